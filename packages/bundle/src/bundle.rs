@@ -72,7 +72,7 @@
 use crate::graph::{GraphError, GraphManager};
 use crate::resolver::{Resolver, ResolverError};
 use paperclip_parser::ast::*;
-use paperclip_parser::get_document_id;
+use paperclip_parser::{document_path, get_document_id, DocumentIdMode};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -163,8 +163,14 @@ pub struct Bundle {
     /// Deduplicated assets with source file tracking
     assets: HashMap<String, (AssetReference, HashSet<PathBuf>)>,
 
-    /// Document IDs for each file (CRC32 of file path)
+    /// Document IDs for each file (CRC32 of the document path)
     document_ids: HashMap<PathBuf, String>,
+
+    /// Root that document paths are made relative to (canonicalized)
+    project_root: Option<PathBuf>,
+
+    /// Whether IDs hash the root-relative or the absolute path
+    document_id_mode: DocumentIdMode,
 }
 
 impl Bundle {
@@ -176,6 +182,38 @@ impl Bundle {
             resolver: Resolver::new(),
             assets: HashMap::new(),
             document_ids: HashMap::new(),
+            project_root: None,
+            document_id_mode: DocumentIdMode::default(),
+        }
+    }
+
+    /// Derive document IDs from paths relative to `project_root`
+    pub fn with_project_root(mut self, project_root: &Path) -> Self {
+        self.set_project_root(project_root);
+        self
+    }
+
+    /// Choose between root-relative (default) and legacy absolute document IDs
+    pub fn with_document_id_mode(mut self, mode: DocumentIdMode) -> Self {
+        self.document_id_mode = mode;
+        self
+    }
+
+    /// Set the root that document IDs are relative to. Documents added later pick it up.
+    pub fn set_project_root(&mut self, project_root: &Path) {
+        let canonical = project_root
+            .canonicalize()
+            .unwrap_or_else(|_| project_root.to_path_buf());
+        self.project_root = Some(canonical);
+    }
+
+    /// Path that identifies `path` - pass it to `parse_with_path` so span IDs line up
+    /// with the bundle's document ID. Without a project root this is the absolute path.
+    pub fn document_path(&self, path: &Path) -> String {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        match &self.project_root {
+            Some(root) => document_path(&canonical, root, self.document_id_mode),
+            None => canonical.to_string_lossy().into_owned(),
         }
     }
 
@@ -184,7 +222,7 @@ impl Bundle {
         // Canonicalize the path to ensure consistent lookups
         // (resolves symlinks like /var -> /private/var on macOS)
         let canonical_path = path.canonicalize().unwrap_or(path);
-        let document_id = get_document_id(&canonical_path.to_string_lossy());
+        let document_id = get_document_id(&self.document_path(&canonical_path));
         self.document_ids.insert(canonical_path.clone(), document_id);
         self.documents.insert(canonical_path, document);
     }
@@ -355,6 +393,13 @@ impl Bundle {
         self.unique_assets().collect()
     }
 
+    /// Document an import alias refers to from `file` (`theme` -> `/app/theme.pc`)
+    pub fn resolve_alias(&self, file: &Path, alias: &str) -> Option<&Path> {
+        self.resolver
+            .resolve_alias(file, alias)
+            .map(PathBuf::as_path)
+    }
+
     /// Look up a style declaration by name across the bundle
     /// Supports namespaced references like "theme.fontRegular"
     /// Searches in the given file and its imports
@@ -493,4 +538,34 @@ mod tests {
         assert!(doc_id.is_some());
         assert!(!doc_id.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_document_id_relative_to_project_root() {
+        let source = "public component Test { render div {} }";
+
+        let ids: Vec<String> = ["/checkout-a", "/checkout-b"]
+            .iter()
+            .map(|root| {
+                let mut bundle = Bundle::new().with_project_root(Path::new(root));
+                let path = PathBuf::from(format!("{}/src/test.pc", root));
+                assert_eq!(bundle.document_path(&path), "/src/test.pc");
+
+                let doc = parse_with_path(source, &bundle.document_path(&path)).unwrap();
+                bundle.add_document(path.clone(), doc);
+                bundle.get_document_id(&path).unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(ids[0], ids[1]);
+
+        // Legacy mode keeps hashing the absolute path
+        let mut bundle = Bundle::new()
+            .with_project_root(Path::new("/checkout-a"))
+            .with_document_id_mode(DocumentIdMode::Absolute);
+        let path = PathBuf::from("/checkout-a/src/test.pc");
+        bundle.add_document(path.clone(), parse_with_path(source, "/x.pc").unwrap());
+        assert_eq!(
+            bundle.get_document_id(&path),
+            Some(get_document_id("/checkout-a/src/test.pc").as_str())
+        );
+    }
 }
@@ -238,7 +238,7 @@ mod tests {
         doc.styles.push(StyleDecl {
             name: "myStyle".to_string(),
             public: true,
-            properties: HashMap::new(),
+            properties: Properties::new(),
             extends: Vec::new(),
             span: Span::new(0, 0, "test".to_string()),
         });
@@ -265,7 +265,7 @@ mod tests {
         theme_doc.styles.push(StyleDecl {
             name: "fontBold".to_string(),
             public: true,
-            properties: HashMap::new(),
+            properties: Properties::new(),
             extends: Vec::new(),
             span: Span::new(0, 0, "test".to_string()),
         });
@@ -289,6 +289,9 @@ mod tests {
         doc.tokens.push(TokenDecl {
             name: "primaryColor".to_string(),
             value: "#blue".to_string(),
+            expression: TokenValue::Literal {
+                value: "#blue".to_string(),
+            },
             public: true,
             span: Span::new(0, 0, "test".to_string()),
         });
@@ -317,6 +320,7 @@ mod tests {
             body: None,
             slots: Vec::new(),
             overrides: Vec::new(),
+            raw_css: Vec::new(),
             span: Span::new(0, 0, "test".to_string()),
         });
         documents.insert(file.clone(), doc);
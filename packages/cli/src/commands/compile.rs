@@ -1,15 +1,33 @@
 use crate::config::Config;
 use anyhow::{anyhow, Result};
-use clap::Args;
+use clap::{Args, ValueEnum};
 use colored::Colorize;
-use paperclip_compiler_css::compile_to_css;
+use paperclip_compiler_css::{
+    compile_bundle_to_css, compile_bundle_to_css_with_source_map, compile_bundle_to_split_css,
+    CompileOptions as CssOptions,
+};
 use paperclip_compiler_html::{compile_to_html, CompileOptions as HtmlOptions};
 use paperclip_compiler_react::{compile_definitions, compile_to_react, CompileOptions};
-use paperclip_parser::parse;
+use paperclip_bundle::Bundle;
+use paperclip_evaluator::{
+    ClassNameStrategy, ClassNames, CssManifest, ResolvedToken, StyleUsage, TokenResolver,
+    TreeShakeReport,
+};
+use paperclip_parser::parse_with_path;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
 
+/// Where `--source-map` puts the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SourceMapMode {
+    /// Embedded in the stylesheet as a data URL
+    Inline,
+    /// Written next to the stylesheet as `.css.map`
+    File,
+}
+
 #[derive(Debug, Args)]
 pub struct CompileArgs {
     /// Directory to compile (defaults to current directory)
@@ -32,6 +50,23 @@ pub struct CompileArgs {
     #[arg(long)]
     pub typescript: bool,
 
+    /// Class name strategy (hashed, readable, css-modules)
+    #[arg(long, default_value = "hashed")]
+    pub class_names: ClassNameStrategy,
+
+    /// Emit one stylesheet per public component plus a shared sheet and a
+    /// `css-manifest.json` (React output imports the split sheets)
+    #[arg(long)]
+    pub split_css: bool,
+
+    /// Generate a source map for CSS output (inline or file)
+    #[arg(long, value_enum)]
+    pub source_map: Option<SourceMapMode>,
+
+    /// Drop tokens, styles and components nothing in the build uses (CSS only)
+    #[arg(long)]
+    pub production: bool,
+
     /// Watch for file changes
     #[arg(short, long)]
     pub watch: bool,
@@ -40,11 +75,32 @@ pub struct CompileArgs {
 pub fn compile(args: CompileArgs, cwd: &str) -> Result<()> {
     let config = Config::load(cwd)?;
     let src_dir = config.get_src_dir(cwd);
+    let mut css_options = CssOptions {
+        class_name_strategy: args.class_names,
+        class_names: None,
+        targets: config.browser_targets()?,
+        cascade_layers: config.css_layers,
+        transforms: config.css_transforms(),
+        style_usage: None,
+    };
 
     if !src_dir.exists() {
         return Err(anyhow!("Source directory does not exist: {:?}", src_dir));
     }
 
+    // A CSS Modules build exposes one class mapping per file, which split sheets would break up
+    if args.split_css && args.class_names == ClassNameStrategy::CssModules {
+        return Err(anyhow!("--split-css doesn't support css-modules class names"));
+    }
+
+    if args.source_map.is_some() && (args.target != "css" || args.split_css) {
+        return Err(anyhow!("--source-map is only supported with --target css (without --split-css)"));
+    }
+
+    if args.production && args.target != "css" {
+        return Err(anyhow!("--production is only supported with --target css"));
+    }
+
     println!("{}", "🔨 Compiling Paperclip files...".bright_blue().bold());
 
     // Find all .pc files
@@ -57,15 +113,43 @@ pub fn compile(args: CompileArgs, cwd: &str) -> Result<()> {
 
     println!("Found {} files", pc_files.len());
 
+    let project = Project::load(&config, cwd, src_dir, &pc_files, args.class_names);
+    css_options.class_names = Some(project.class_names.clone());
+    if args.production {
+        // Every compiled file is an entry, so anything reachable from one is kept
+        let entries: Vec<PathBuf> = pc_files.iter().map(|file| project.entry(file)).collect();
+        css_options.style_usage = Some(StyleUsage::from_entries(&project.bundle, &entries));
+    }
+
+    // Readable names that clashed were suffixed - worth knowing before shipping
+    for collision in project.class_names.collisions() {
+        eprintln!(
+            "  {} class name {} is already taken, using {}",
+            "⚠".yellow(),
+            collision.name,
+            collision.renamed_to
+        );
+    }
+
     // Compile each file
     let mut success_count = 0;
     let mut error_count = 0;
+    let mut css_manifests = BTreeMap::new();
 
     for pc_file in &pc_files {
-        match compile_file(pc_file, &args, &src_dir, cwd) {
+        let document_path = config.document_path(cwd, pc_file);
+        match compile_file(
+            pc_file,
+            &document_path,
+            &args,
+            &css_options,
+            &project,
+            cwd,
+            &mut css_manifests,
+        ) {
             Ok(output_path) => {
                 success_count += 1;
-                let relative_path = pc_file.strip_prefix(&src_dir).unwrap_or(pc_file);
+                let relative_path = pc_file.strip_prefix(&project.src_dir).unwrap_or(pc_file);
                 println!(
                     "  {} {} → {}",
                     "✓".green(),
@@ -75,7 +159,7 @@ pub fn compile(args: CompileArgs, cwd: &str) -> Result<()> {
             }
             Err(e) => {
                 error_count += 1;
-                let relative_path = pc_file.strip_prefix(&src_dir).unwrap_or(pc_file);
+                let relative_path = pc_file.strip_prefix(&project.src_dir).unwrap_or(pc_file);
                 eprintln!(
                     "  {} {} - {}",
                     "✗".red(),
@@ -86,6 +170,15 @@ pub fn compile(args: CompileArgs, cwd: &str) -> Result<()> {
         }
     }
 
+    // One manifest for the whole build, keyed by source file, so bundler
+    // plugins can map a component to its sheets
+    if !css_manifests.is_empty() {
+        let manifest_file = output_dir(&args, cwd).join("css-manifest.json");
+        fs::create_dir_all(output_dir(&args, cwd))?;
+        fs::write(&manifest_file, serde_json::to_string_pretty(&css_manifests)?)?;
+        println!("  {} {}", "✓".green(), manifest_file.display());
+    }
+
     println!();
     if error_count == 0 {
         println!(
@@ -110,6 +203,67 @@ pub fn compile(args: CompileArgs, cwd: &str) -> Result<()> {
     Ok(())
 }
 
+/// Every file being compiled, so cross-file references (imported tokens)
+/// resolve the same way the evaluator sees them
+struct Project {
+    src_dir: PathBuf,
+    bundle: Bundle,
+    /// Class names assigned across every file, shared by all targets
+    class_names: ClassNames,
+}
+
+impl Project {
+    /// Parse `pc_files` into one bundle. Files that don't parse are left out
+    /// here and reported when they're compiled.
+    fn load(
+        config: &Config,
+        cwd: &str,
+        src_dir: PathBuf,
+        pc_files: &[PathBuf],
+        strategy: ClassNameStrategy,
+    ) -> Self {
+        let project_root = config.get_project_root(cwd);
+        let mut bundle = Bundle::new()
+            .with_project_root(&project_root)
+            .with_document_id_mode(config.document_id_mode());
+        for pc_file in pc_files {
+            let Ok(source) = fs::read_to_string(pc_file) else {
+                continue;
+            };
+            if let Ok(document) = parse_with_path(&source, &config.document_path(cwd, pc_file)) {
+                bundle.add_document(pc_file.clone(), document);
+            }
+        }
+        if let Err(e) = bundle.build_dependencies(&project_root) {
+            eprintln!("  {} {}", "⚠".yellow(), e);
+        }
+        let class_names = ClassNames::for_bundle(&bundle, strategy);
+        Self {
+            src_dir,
+            bundle,
+            class_names,
+        }
+    }
+
+    /// `file` as the bundle keys it
+    fn entry(&self, file: &Path) -> PathBuf {
+        file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+    }
+
+    /// Tokens declared in `file`, resolved against everything it imports
+    fn tokens(&self, file: &Path) -> Result<HashMap<String, ResolvedToken>> {
+        let file = self.entry(file);
+        let resolved = TokenResolver::for_bundle(&self.bundle, &file)
+            .resolve_scope()
+            .map_err(|e| anyhow!(e.to_string()))?;
+        Ok(resolved
+            .iter()
+            .filter(|(path, _, _)| *path == file)
+            .map(|(_, name, token)| (name.to_string(), token.clone()))
+            .collect())
+    }
+}
+
 fn find_pc_files(dir: &Path) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
@@ -127,12 +281,20 @@ fn find_pc_files(dir: &Path) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
-fn compile_file(file_path: &Path, args: &CompileArgs, src_dir: &Path, cwd: &str) -> Result<String> {
+fn compile_file(
+    file_path: &Path,
+    document_path: &str,
+    args: &CompileArgs,
+    css_options: &CssOptions,
+    project: &Project,
+    cwd: &str,
+    css_manifests: &mut BTreeMap<String, CssManifest>,
+) -> Result<String> {
     // Read source file
     let source = fs::read_to_string(file_path)?;
 
-    // Parse
-    let document = parse(&source).map_err(|e| {
+    // Parse with the root-relative path so class names don't depend on the checkout
+    let document = parse_with_path(&source, document_path).map_err(|e| {
         // Use pretty error formatting
         use paperclip_parser::error::pretty;
         let file_name = file_path
@@ -142,18 +304,86 @@ fn compile_file(file_path: &Path, args: &CompileArgs, src_dir: &Path, cwd: &str)
         anyhow!("\n{}", pretty::format_error(&e, file_name, &source))
     })?;
 
+    let relative_path = file_path.strip_prefix(&project.src_dir).unwrap_or(file_path);
+    let stem = file_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("styles");
+
+    if args.target == "css" && args.split_css {
+        return write_split_css(
+            file_path,
+            relative_path,
+            project,
+            args,
+            css_options,
+            cwd,
+            css_manifests,
+        );
+    }
+
+    let extension = match args.target.as_str() {
+        "react" => "jsx",
+        "css" if args.class_names == ClassNameStrategy::CssModules => "module.css",
+        "css" => "css",
+        "html" => "html",
+        _ => "txt",
+    };
+    let output_file = output_dir(args, cwd)
+        .join(relative_path)
+        .with_extension(extension);
+
+    if args.source_map.is_some() {
+        return write_css_with_source_map(
+            &source,
+            file_path,
+            relative_path,
+            project,
+            &output_file,
+            args,
+            css_options,
+        );
+    }
+
     // Compile based on target
     let output = match args.target.as_str() {
         "react" => {
+            // Import exactly the sheets `--target css --split-css` writes
+            let css_manifest = if args.split_css {
+                let output = compile_bundle_to_split_css(
+                    &project.bundle,
+                    &project.entry(file_path),
+                    stem,
+                    css_options,
+                )
+                .map_err(|e| anyhow!(e.to_string()))?;
+                Some(output.manifest)
+            } else {
+                None
+            };
             let options = CompileOptions {
                 use_typescript: args.typescript,
                 include_css_imports: true,
+                class_name_strategy: args.class_names,
+                class_names: Some(project.class_names.clone()),
+                css_manifest,
+                tokens: project.tokens(file_path)?,
             };
             compile_to_react(&document, options).map_err(|e| anyhow!(e))?
         }
-        "css" => compile_to_css(&document).map_err(|e| anyhow!(e.to_string()))?,
+        "css" => {
+            let output =
+                compile_bundle_to_css(&project.bundle, &project.entry(file_path), css_options)
+                    .map_err(|e| anyhow!(e.to_string()))?;
+            report_tree_shaking(relative_path, &output.tree_shake_report);
+            output.css
+        }
         "html" => {
-            let options = HtmlOptions::default();
+            let options = HtmlOptions {
+                class_name_strategy: args.class_names,
+                class_names: Some(project.class_names.clone()),
+                ..HtmlOptions::default()
+            };
             compile_to_html(&document, options).map_err(|e| anyhow!(e))?
         }
         other => {
@@ -166,23 +396,6 @@ fn compile_file(file_path: &Path, args: &CompileArgs, src_dir: &Path, cwd: &str)
         println!("{}", output);
         Ok("stdout".to_string())
     } else {
-        // Determine output path
-        let relative_path = file_path.strip_prefix(src_dir).unwrap_or(file_path);
-        let out_dir = if let Some(ref out) = args.out_dir {
-            PathBuf::from(cwd).join(out)
-        } else {
-            PathBuf::from(cwd).join("dist")
-        };
-
-        let extension = match args.target.as_str() {
-            "react" => "jsx",
-            "css" => "css",
-            "html" => "html",
-            _ => "txt",
-        };
-
-        let output_file = out_dir.join(relative_path).with_extension(extension);
-
         // Create output directory
         if let Some(parent) = output_file.parent() {
             fs::create_dir_all(parent)?;
@@ -202,3 +415,141 @@ fn compile_file(file_path: &Path, args: &CompileArgs, src_dir: &Path, cwd: &str)
         Ok(output_file.display().to_string())
     }
 }
+
+fn output_dir(args: &CompileArgs, cwd: &str) -> PathBuf {
+    match &args.out_dir {
+        Some(out) => PathBuf::from(cwd).join(out),
+        None => PathBuf::from(cwd).join("dist"),
+    }
+}
+
+/// Production builds list what tree shaking dropped from each file
+fn report_tree_shaking(relative_path: &Path, report: &TreeShakeReport) {
+    if !report.is_empty() {
+        eprintln!(
+            "  {} {} removed {} tokens, {} styles, {} components",
+            "✂".dimmed(),
+            relative_path.display(),
+            report.removed_tokens.len(),
+            report.removed_styles.len(),
+            report.removed_components.len()
+        );
+    }
+}
+
+/// Write a document's stylesheet along with a source map back to the .pc file
+fn write_css_with_source_map(
+    source: &str,
+    file_path: &Path,
+    relative_path: &Path,
+    project: &Project,
+    output_file: &Path,
+    args: &CompileArgs,
+    css_options: &CssOptions,
+) -> Result<String> {
+    // Sources are resolved relative to the map, which sits next to the CSS
+    let output_parent = output_file.parent().unwrap_or(Path::new(""));
+    let source_name = relative_to(file_path, output_parent);
+    let output = compile_bundle_to_css_with_source_map(
+        &project.bundle,
+        &project.entry(file_path),
+        source,
+        &source_name,
+        css_options,
+    )
+    .map_err(|e| anyhow!(e.to_string()))?;
+    report_tree_shaking(relative_path, &output.tree_shake_report);
+
+    if args.stdout {
+        println!("{}", output.with_inline_source_map());
+        return Ok("stdout".to_string());
+    }
+
+    fs::create_dir_all(output_parent)?;
+    if args.source_map == Some(SourceMapMode::File) {
+        let file_name = output_file
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        let map_name = format!("{}.map", file_name);
+        fs::write(output_file, output.with_source_map_url(&map_name))?;
+        fs::write(output_file.with_file_name(&map_name), &output.source_map)?;
+    } else {
+        fs::write(output_file, output.with_inline_source_map())?;
+    }
+
+    Ok(output_file.display().to_string())
+}
+
+/// `path` relative to the directory `base`, with `/` separators
+fn relative_to(path: &Path, base: &Path) -> String {
+    let path: Vec<Component> = path.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let common = path
+        .iter()
+        .zip(&base)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); base.len() - common];
+    parts.extend(
+        path[common..]
+            .iter()
+            .map(|part| part.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.join("/")
+}
+
+/// Write a document's shared and per-component sheets, recording its manifest
+fn write_split_css(
+    file_path: &Path,
+    relative_path: &Path,
+    project: &Project,
+    args: &CompileArgs,
+    css_options: &CssOptions,
+    cwd: &str,
+    css_manifests: &mut BTreeMap<String, CssManifest>,
+) -> Result<String> {
+    let stem = relative_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("styles");
+    let output = compile_bundle_to_split_css(
+        &project.bundle,
+        &project.entry(file_path),
+        stem,
+        css_options,
+    )
+    .map_err(|e| anyhow!(e.to_string()))?;
+    report_tree_shaking(relative_path, &output.tree_shake_report);
+
+    if args.stdout {
+        for sheet in &output.sheets {
+            println!("/* {} */\n{}", sheet.file_name, sheet.css);
+        }
+        return Ok("stdout".to_string());
+    }
+
+    let sheet_dir = match relative_path.parent() {
+        Some(parent) => output_dir(args, cwd).join(parent),
+        None => output_dir(args, cwd),
+    };
+    fs::create_dir_all(&sheet_dir)?;
+    for sheet in &output.sheets {
+        fs::write(sheet_dir.join(&sheet.file_name), &sheet.css)?;
+    }
+
+    let key = relative_path.to_string_lossy().replace('\\', "/");
+    css_manifests.insert(key, output.manifest);
+
+    let first_sheet = output
+        .sheets
+        .first()
+        .map_or(sheet_dir.clone(), |sheet| sheet_dir.join(&sheet.file_name));
+    Ok(format!(
+        "{} ({} sheets)",
+        first_sheet.display(),
+        output.sheets.len()
+    ))
+}
+
@@ -81,6 +81,7 @@ pub fn init(args: InitArgs, cwd: &str) -> Result<()> {
             emit,
             out_dir: Some("dist".to_string()),
         }],
+        ..Config::default()
     };
 
     // Write config file
@@ -1,7 +1,10 @@
+use crate::config::Config;
 use anyhow::Result;
 use clap::Args;
 use colored::Colorize;
-use paperclip_linter::{lint_document, DiagnosticLevel, LintOptions};
+use paperclip_linter::{
+    lint_document, DiagnosticLevel, GlobalStylesRule, LintOptions, RuleRegistry,
+};
 use paperclip_parser::{parse_with_path, ParseError};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -21,7 +24,9 @@ pub struct LintArgs {
     pub format: String,
 }
 
-pub fn lint(args: LintArgs, _cwd: &str) -> Result<()> {
+pub fn lint(args: LintArgs, cwd: &str) -> Result<()> {
+    let config = Config::load(cwd)?;
+
     println!("🔍 {} Paperclip Linter", "Starting".green().bold());
     println!("   Input: {}", args.input.display());
     println!();
@@ -33,7 +38,7 @@ pub fn lint(args: LintArgs, _cwd: &str) -> Result<()> {
 
     if args.input.is_file() {
         let (file_diagnostics, file_errors, file_warnings) =
-            lint_file(&args.input, args.verbose, &args.format)?;
+            lint_file(&args.input, &config, args.verbose, &args.format)?;
         total_diagnostics += file_diagnostics;
         total_errors += file_errors;
         total_warnings += file_warnings;
@@ -46,7 +51,7 @@ pub fn lint(args: LintArgs, _cwd: &str) -> Result<()> {
 
         for file in pc_files {
             let (file_diagnostics, file_errors, file_warnings) =
-                lint_file(&file, args.verbose, &args.format)?;
+                lint_file(&file, &config, args.verbose, &args.format)?;
             total_diagnostics += file_diagnostics;
             total_errors += file_errors;
             total_warnings += file_warnings;
@@ -90,7 +95,12 @@ pub fn lint(args: LintArgs, _cwd: &str) -> Result<()> {
     Ok(())
 }
 
-fn lint_file(file_path: &Path, verbose: bool, format: &str) -> Result<(usize, usize, usize)> {
+fn lint_file(
+    file_path: &Path,
+    config: &Config,
+    verbose: bool,
+    format: &str,
+) -> Result<(usize, usize, usize)> {
     let source = fs::read_to_string(file_path)?;
 
     // Parse the file
@@ -108,7 +118,15 @@ fn lint_file(file_path: &Path, verbose: bool, format: &str) -> Result<(usize, us
     };
 
     // Run the linter
-    let diagnostics = lint_document(&document, LintOptions::default());
+    let mut registry = RuleRegistry::new();
+    registry.set_rule(Box::new(GlobalStylesRule::new(
+        config.global_style_files.clone(),
+    )));
+    let options = LintOptions {
+        registry: Some(registry),
+        path: Some(file_path.to_path_buf()),
+    };
+    let diagnostics = lint_document(&document, options);
 
     if diagnostics.is_empty() {
         if verbose {
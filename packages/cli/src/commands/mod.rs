@@ -2,8 +2,10 @@ pub mod compile;
 pub mod designer;
 pub mod init;
 pub mod lint;
+pub mod snapshot;
 
 pub use compile::{compile, CompileArgs};
 pub use designer::{designer, DesignerArgs};
 pub use init::{init, InitArgs};
 pub use lint::{lint, LintArgs};
+pub use snapshot::{snapshot, SnapshotArgs};
@@ -0,0 +1,196 @@
+use crate::config::Config;
+use anyhow::{anyhow, Result};
+use clap::Args;
+use colored::Colorize;
+use paperclip_bundle::Bundle;
+use paperclip_evaluator::{Evaluator, SnapshotOptions};
+use paperclip_parser::parse_with_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+    /// Input .pc file or directory
+    pub input: PathBuf,
+
+    /// Write missing snapshots and rewrite drifted ones instead of failing
+    #[arg(short, long)]
+    pub update: bool,
+
+    /// Include semantic IDs in snapshots
+    #[arg(long)]
+    pub semantic_ids: bool,
+}
+
+enum Outcome {
+    Matched,
+    Written,
+    Drifted,
+    /// No `.pc.snap` yet (written with `--update`)
+    Missing,
+}
+
+pub fn snapshot(args: SnapshotArgs, cwd: &str) -> Result<()> {
+    let config = Config::load(cwd)?;
+    let project_root = config.get_project_root(cwd);
+    let options = SnapshotOptions {
+        semantic_ids: args.semantic_ids,
+    };
+
+    let files = if args.input.is_file() {
+        vec![args.input.clone()]
+    } else if args.input.is_dir() {
+        find_pc_files(&args.input)
+    } else {
+        return Err(anyhow!(
+            "Input path does not exist: {}",
+            args.input.display()
+        ));
+    };
+
+    println!("📸 {} Paperclip snapshots", "Checking".green().bold());
+    println!();
+
+    let mut failed = 0;
+    for file in &files {
+        // A file that doesn't evaluate fails on its own; the rest still run
+        let outcome = render_snapshot(file, &project_root, &config, &options)
+            .and_then(|snapshot| check_snapshot(file, &snapshot, args.update));
+
+        match outcome {
+            Ok(Outcome::Matched) => println!("   {} {}", "✓".green(), file.display()),
+            Ok(Outcome::Written) => println!("   {} {} (written)", "✎".blue(), file.display()),
+            Ok(Outcome::Drifted) => {
+                println!("   {} {}", "✗".red(), file.display());
+                failed += 1;
+            }
+            Ok(Outcome::Missing) => {
+                println!("   {} {} (no snapshot)", "✗".red(), file.display());
+                failed += 1;
+            }
+            Err(err) => {
+                println!("   {} {} - {}", "✗".red(), file.display(), err.to_string().red());
+                failed += 1;
+            }
+        }
+    }
+
+    println!();
+    if failed > 0 {
+        return Err(anyhow!(
+            "{} of {} snapshots failed (rerun with --update to accept changes)",
+            failed,
+            files.len()
+        ));
+    }
+
+    println!("✨ {} {} snapshots up to date", "Done".green().bold(), files.len());
+    Ok(())
+}
+
+/// Snapshots live next to their source: `card.pc` -> `card.pc.snap`
+fn snapshot_path(file: &Path) -> PathBuf {
+    let mut path = file.as_os_str().to_owned();
+    path.push(".snap");
+    PathBuf::from(path)
+}
+
+fn check_snapshot(file: &Path, snapshot: &str, update: bool) -> Result<Outcome> {
+    let snap_path = snapshot_path(file);
+
+    match fs::read_to_string(&snap_path) {
+        Ok(existing) if existing == snapshot => Ok(Outcome::Matched),
+        Ok(existing) if !update => {
+            print_drift(&existing, snapshot);
+            Ok(Outcome::Drifted)
+        }
+        // A missing golden would otherwise pass silently in CI
+        Err(_) if !update => Ok(Outcome::Missing),
+        _ => {
+            fs::write(&snap_path, snapshot)?;
+            Ok(Outcome::Written)
+        }
+    }
+}
+
+/// Evaluate a file (and everything it imports) and print its snapshot
+fn render_snapshot(
+    file: &Path,
+    project_root: &Path,
+    config: &Config,
+    options: &SnapshotOptions,
+) -> Result<String> {
+    let entry = file.canonicalize()?;
+    let bundle = load_bundle(&entry, project_root, config)?;
+
+    // Root-relative IDs keep snapshots identical across checkouts
+    let mut evaluator = Evaluator::with_document_id(&bundle.document_path(&entry));
+    let vdom = evaluator
+        .evaluate_bundle(&bundle, &entry)
+        .map_err(|err| anyhow!("{:?}", err))?;
+
+    Ok(vdom.to_snapshot(options))
+}
+
+fn load_bundle(entry: &Path, project_root: &Path, config: &Config) -> Result<Bundle> {
+    let mut bundle = Bundle::new()
+        .with_project_root(project_root)
+        .with_document_id_mode(config.document_id_mode());
+    let mut pending = vec![entry.to_path_buf()];
+
+    while let Some(path) = pending.pop() {
+        if bundle.get_document(&path).is_some() {
+            continue;
+        }
+
+        let source = fs::read_to_string(&path)?;
+        let document = parse_with_path(&source, &bundle.document_path(&path))
+            .map_err(|err| anyhow!("Failed to parse {}: {:?}", path.display(), err))?;
+        bundle.add_document(path.clone(), document);
+        bundle
+            .build_dependencies(project_root)
+            .map_err(|err| anyhow!("{:?}", err))?;
+
+        pending.extend(bundle.get_dependencies(&path).unwrap_or_default().iter().cloned());
+    }
+
+    Ok(bundle)
+}
+
+/// Print the lines that differ between the stored and current snapshot
+fn print_drift(expected: &str, actual: &str) {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let prefix = expected
+        .iter()
+        .zip(&actual)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    for line in &expected[prefix..expected.len() - suffix] {
+        println!("     {}", format!("- {}", line).red());
+    }
+    for line in &actual[prefix..actual.len() - suffix] {
+        println!("     {}", format!("+ {}", line).green());
+    }
+}
+
+fn find_pc_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path().to_path_buf())
+        .filter(|path| path.is_file() && path.extension().map(|e| e == "pc").unwrap_or(false))
+        .collect();
+    files.sort();
+    files
+}
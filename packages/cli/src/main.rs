@@ -3,7 +3,10 @@ mod config;
 
 use clap::{Parser, Subcommand};
 use colored::Colorize;
-use commands::{compile, designer, init, lint, CompileArgs, DesignerArgs, InitArgs, LintArgs};
+use commands::{
+    compile, designer, init, lint, snapshot, CompileArgs, DesignerArgs, InitArgs, LintArgs,
+    SnapshotArgs,
+};
 
 /// Paperclip CLI - Visual component builder for the AI age
 #[derive(Parser, Debug)]
@@ -25,6 +28,9 @@ enum Command {
     /// Lint .pc files for common issues
     Lint(LintArgs),
 
+    /// Check evaluated output against .pc.snap golden files
+    Snapshot(SnapshotArgs),
+
     /// Start the visual designer (coming soon)
     Designer(DesignerArgs),
 
@@ -175,6 +181,7 @@ fn main() {
         Command::Init(args) => init(args, &cwd),
         Command::Compile(args) => compile(args, &cwd),
         Command::Lint(args) => lint(args, &cwd),
+        Command::Snapshot(args) => snapshot(args, &cwd),
         Command::Designer(args) => designer(args, &cwd),
 
         #[cfg(feature = "vision")]
@@ -0,0 +1,53 @@
+/// End-to-end tests for `paperclip snapshot`
+use paperclip_cli::commands::{snapshot, SnapshotArgs};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn run(dir: &Path, update: bool) -> anyhow::Result<()> {
+    snapshot(
+        SnapshotArgs {
+            input: dir.join("src"),
+            update,
+            semantic_ids: false,
+        },
+        dir.to_str().unwrap(),
+    )
+}
+
+#[test]
+fn test_snapshot_lifecycle() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    let button = src.join("button.pc");
+    fs::write(&button, "public component Button { render button { text \"Go\" } }").unwrap();
+    let snap = src.join("button.pc.snap");
+
+    // No golden yet: fails without writing one
+    assert!(run(temp_dir.path(), false).is_err());
+    assert!(!snap.exists());
+
+    // --update writes it, after which the check passes
+    run(temp_dir.path(), true).unwrap();
+    assert!(fs::read_to_string(&snap).unwrap().contains("Go"));
+    run(temp_dir.path(), false).unwrap();
+
+    // Drift fails and leaves the golden alone
+    fs::write(&button, "public component Button { render button { text \"Stop\" } }").unwrap();
+    assert!(run(temp_dir.path(), false).is_err());
+    assert!(fs::read_to_string(&snap).unwrap().contains("Go"));
+}
+
+#[test]
+fn test_snapshot_reports_broken_files_and_continues() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("a_broken.pc"), "component {").unwrap();
+    fs::write(src.join("b_card.pc"), "public component Card { render div }").unwrap();
+
+    assert!(run(temp_dir.path(), true).is_err());
+    assert!(!src.join("a_broken.pc.snap").exists());
+    assert!(src.join("b_card.pc.snap").exists());
+}
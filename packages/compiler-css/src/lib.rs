@@ -1,5 +1,14 @@
-use paperclip_evaluator::{CssEvaluator, CssResult};
+use base64::prelude::{Engine, BASE64_STANDARD};
+use paperclip_bundle::Bundle;
+use paperclip_evaluator::css_prefixer::prefix_css_document;
+use paperclip_evaluator::{
+    BrowserTargets, ClassNameStrategy, ClassNames, ComponentStylesheets, CssError, CssEvaluator, CssManifest, CssResult,
+    CssTransforms, StyleUsage, TreeShakeReport, VirtualCssDocument,
+};
 use paperclip_parser::ast::Document;
+use paperclip_sourcemap::{byte_offset_to_line_col, SourceMapBuilder};
+use std::collections::HashMap;
+use std::path::Path;
 
 /// Compile a Paperclip document to CSS
 pub fn compile_to_css(document: &Document) -> CssResult<String> {
@@ -15,6 +24,276 @@ pub fn compile_to_css_with_path(document: &Document, path: &str) -> CssResult<St
     Ok(css_doc.to_css())
 }
 
+/// Options for CSS compilation
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions {
+    /// How generated class names are formed (must match the markup compilers)
+    pub class_name_strategy: ClassNameStrategy,
+    /// Names assigned across every file of the build (`ClassNames::for_bundle`),
+    /// so readable names stay unique project-wide. Assigned from the compiled
+    /// document alone when `None`.
+    pub class_names: Option<ClassNames>,
+    /// Browsers to add vendor prefixes and fallbacks for
+    pub targets: BrowserTargets,
+    /// Wrap output in named `@layer`s so priority doesn't depend on rule order
+    pub cascade_layers: bool,
+    /// Passes run over every rule before prefixing (px to rem, logical properties, ...)
+    pub transforms: CssTransforms,
+    /// What the build's entry documents reach (production builds). Tokens,
+    /// styles and components outside it are dropped by the `compile_bundle_*`
+    /// functions.
+    pub style_usage: Option<StyleUsage>,
+}
+
+/// Compile with a document path and options
+pub fn compile_to_css_with_options(
+    document: &Document,
+    path: &str,
+    options: &CompileOptions,
+) -> CssResult<String> {
+    let mut evaluator = evaluator(path, options);
+    let css_doc = evaluator.evaluate(document)?;
+    Ok(render_css(css_doc, options))
+}
+
+/// CSS for one bundle entry and what dead style elimination left out
+#[derive(Debug, Clone, PartialEq)]
+pub struct BundleCss {
+    pub css: String,
+    pub tree_shake_report: TreeShakeReport,
+}
+
+/// Compile `entry`'s own styles, resolving what it imports from `bundle`.
+/// Keeps only what `options.style_usage` reaches when set.
+pub fn compile_bundle_to_css(
+    bundle: &Bundle,
+    entry: &Path,
+    options: &CompileOptions,
+) -> CssResult<BundleCss> {
+    let mut evaluator = evaluator(&bundle.document_path(entry), options);
+    let css_doc = evaluator.evaluate_bundle_split(bundle, entry)?.merged();
+    Ok(BundleCss {
+        css: render_css(css_doc, options),
+        tree_shake_report: evaluator.tree_shake_report().clone(),
+    })
+}
+
+/// Compiled CSS with a v3 source map pointing each rule back to the .pc
+/// declaration it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssWithSourceMap {
+    pub css: String,
+    /// Source map JSON
+    pub source_map: String,
+    /// What dead style elimination left out (bundle builds)
+    pub tree_shake_report: TreeShakeReport,
+}
+
+impl CssWithSourceMap {
+    /// CSS with the source map embedded as a data URL
+    pub fn with_inline_source_map(&self) -> String {
+        format!(
+            "{}/*# sourceMappingURL=data:application/json;base64,{} */\n",
+            self.css,
+            BASE64_STANDARD.encode(&self.source_map)
+        )
+    }
+
+    /// CSS pointing at a source map written next to it as `map_file`
+    pub fn with_source_map_url(&self, map_file: &str) -> String {
+        format!("{}/*# sourceMappingURL={} */\n", self.css, map_file)
+    }
+}
+
+/// Compile with a source map. `source` is the text `document` was parsed
+/// from; the map refers to it as `source_name` (usually a path relative to
+/// the CSS file) and embeds its content.
+pub fn compile_to_css_with_source_map(
+    document: &Document,
+    path: &str,
+    source: &str,
+    source_name: &str,
+    options: &CompileOptions,
+) -> CssResult<CssWithSourceMap> {
+    let mut evaluator = evaluator(path, options);
+    let css_doc = evaluator.evaluate(document)?;
+    with_source_map(css_doc, source, source_name, options)
+}
+
+/// `compile_bundle_to_css` with a source map (see `compile_to_css_with_source_map`)
+pub fn compile_bundle_to_css_with_source_map(
+    bundle: &Bundle,
+    entry: &Path,
+    source: &str,
+    source_name: &str,
+    options: &CompileOptions,
+) -> CssResult<CssWithSourceMap> {
+    let mut evaluator = evaluator(&bundle.document_path(entry), options);
+    let css_doc = evaluator.evaluate_bundle_split(bundle, entry)?.merged();
+    let mut output = with_source_map(css_doc, source, source_name, options)?;
+    output.tree_shake_report = evaluator.tree_shake_report().clone();
+    Ok(output)
+}
+
+fn with_source_map(
+    css_doc: VirtualCssDocument,
+    source: &str,
+    source_name: &str,
+    options: &CompileOptions,
+) -> CssResult<CssWithSourceMap> {
+    let css_doc = prepare_css(css_doc, options);
+    let (css, mappings) = css_doc.to_css_with_mappings(options.cascade_layers);
+
+    let mut builder = SourceMapBuilder::new(source_name, source);
+    for mapping in mappings {
+        let (src_line, src_col) = byte_offset_to_line_col(source, mapping.span.start);
+        builder.add_mapping(mapping.line, mapping.column, src_line, src_col, None);
+    }
+    let source_map = builder.to_json().map_err(|error| CssError::EvaluationError {
+        message: format!("Failed to write source map: {}", error),
+    })?;
+
+    Ok(CssWithSourceMap {
+        css,
+        source_map,
+        tree_shake_report: TreeShakeReport::default(),
+    })
+}
+
+/// A stylesheet produced by `compile_to_split_css`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stylesheet {
+    /// File name, relative to the directory the document compiles to
+    pub file_name: String,
+    pub css: String,
+}
+
+/// Per-component stylesheets and the manifest describing them
+#[derive(Debug, Clone)]
+pub struct SplitCssOutput {
+    /// Shared sheet first (unless it's empty), then one per public component
+    pub sheets: Vec<Stylesheet>,
+    pub manifest: CssManifest,
+    /// What dead style elimination left out (bundle builds)
+    pub tree_shake_report: TreeShakeReport,
+}
+
+/// Compile one stylesheet per public component plus a shared sheet (tokens,
+/// globals, mixins), named after `stem`: `button.css`, `button.Button.css`
+pub fn compile_to_split_css(
+    document: &Document,
+    path: &str,
+    stem: &str,
+    options: &CompileOptions,
+) -> CssResult<SplitCssOutput> {
+    let mut evaluator = evaluator(path, options);
+    let split = evaluator.evaluate_split(document)?;
+    let mut manifest = CssManifest::for_document(document, stem);
+    if split.shared.rules.is_empty() {
+        if let Some(shared) = manifest.shared.take() {
+            for sheets in manifest.components.values_mut() {
+                sheets.retain(|sheet| *sheet != shared);
+            }
+        }
+    }
+    Ok(split_output(split, manifest, stem, options))
+}
+
+/// `compile_to_split_css` for a bundle document (see `compile_bundle_to_css`).
+/// The manifest also lists the sheets of imported files each component
+/// needs (see `CssManifest::for_bundle`).
+pub fn compile_bundle_to_split_css(
+    bundle: &Bundle,
+    entry: &Path,
+    stem: &str,
+    options: &CompileOptions,
+) -> CssResult<SplitCssOutput> {
+    let mut evaluator = evaluator(&bundle.document_path(entry), options);
+    let split = evaluator.evaluate_bundle_split(bundle, entry)?;
+
+    // Imported files' sheets are written when they're compiled - evaluate
+    // them the same way to know whether their shared sheet is empty
+    let mut shared_rules = HashMap::new();
+    let mut manifest = CssManifest::for_bundle(bundle, entry, stem, |path| {
+        if path == entry {
+            return !split.shared.rules.is_empty();
+        }
+        *shared_rules
+            .entry(path.to_path_buf())
+            .or_insert_with(|| has_shared_rules(bundle, path, options))
+    });
+    // Tree-shaken components have no sheet to list
+    manifest
+        .components
+        .retain(|name, _| split.components.iter().any(|(kept, _)| kept == name));
+    let mut output = split_output(split, manifest, stem, options);
+    output.tree_shake_report = evaluator.tree_shake_report().clone();
+    Ok(output)
+}
+
+/// Whether `path`'s shared sheet has rules (kept when evaluation fails, so
+/// the error surfaces when that file is compiled)
+fn has_shared_rules(bundle: &Bundle, path: &Path, options: &CompileOptions) -> bool {
+    evaluator(&bundle.document_path(path), options)
+        .evaluate_bundle_split(bundle, path)
+        .map_or(true, |split| !split.shared.rules.is_empty())
+}
+
+fn split_output(
+    split: ComponentStylesheets,
+    manifest: CssManifest,
+    stem: &str,
+    options: &CompileOptions,
+) -> SplitCssOutput {
+    let mut sheets = Vec::new();
+    if let Some(shared) = &manifest.shared {
+        sheets.push(Stylesheet {
+            file_name: shared.clone(),
+            css: render_css(split.shared, options),
+        });
+    }
+    for (component_name, css_doc) in split.components {
+        sheets.push(Stylesheet {
+            file_name: CssManifest::component_sheet(stem, &component_name),
+            css: render_css(css_doc, options),
+        });
+    }
+
+    SplitCssOutput {
+        sheets,
+        manifest,
+        tree_shake_report: TreeShakeReport::default(),
+    }
+}
+
+fn evaluator(path: &str, options: &CompileOptions) -> CssEvaluator {
+    let mut evaluator = CssEvaluator::with_document_id(path);
+    evaluator = match &options.class_names {
+        Some(class_names) => evaluator.with_class_names(class_names.clone()),
+        None => evaluator.with_class_name_strategy(options.class_name_strategy),
+    };
+    if let Some(usage) = &options.style_usage {
+        evaluator = evaluator.with_style_usage(usage.clone());
+    }
+    evaluator
+}
+
+fn render_css(css_doc: VirtualCssDocument, options: &CompileOptions) -> String {
+    let css_doc = prepare_css(css_doc, options);
+    if options.cascade_layers {
+        css_doc.to_layered_css()
+    } else {
+        css_doc.to_css()
+    }
+}
+
+/// Run transforms and prefixing over evaluated rules
+fn prepare_css(mut css_doc: VirtualCssDocument, options: &CompileOptions) -> VirtualCssDocument {
+    options.transforms.apply_to_document(&mut css_doc);
+    prefix_css_document(&mut css_doc, &options.targets);
+    css_doc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +420,564 @@ public component Card {
         assert!(css.contains("font-size: 18px"));
         assert!(css.contains("color: #666"));
     }
+
+    #[test]
+    fn test_compile_with_browser_targets() {
+        let source = r#"
+public component Toolbar {
+    render div {
+        style {
+            position: sticky
+            user-select: none
+            backdrop-filter: blur(8px)
+            appearance: none
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let options = CompileOptions {
+            targets: BrowserTargets::parse(&["safari >= 12", "firefox >= 68"]).unwrap(),
+            ..CompileOptions::default()
+        };
+        let css = compile_to_css_with_options(&document, "/toolbar.pc", &options)
+            .expect("Failed to compile CSS");
+
+        println!("Generated CSS:\n{}", css);
+
+        assert!(css.contains("-webkit-user-select: none;"));
+        assert!(css.contains("-moz-user-select: none;"));
+        assert!(css.contains("-webkit-backdrop-filter: blur("));
+        assert!(css.contains("-webkit-appearance: none;"));
+        assert!(css.contains("-moz-appearance: none;"));
+
+        // The fallback comes first so `sticky` wins where it's supported
+        let fallback = css.find("position: -webkit-sticky;").unwrap();
+        let modern = css.find("position: sticky;").unwrap();
+        assert!(fallback < modern);
+
+        // Without targets nothing is prefixed
+        let css = compile_to_css_with_options(&document, "/toolbar.pc", &CompileOptions::default())
+            .unwrap();
+        assert!(!css.contains("-webkit-"));
+    }
+
+    #[test]
+    fn test_compile_with_cascade_layers() {
+        let source = r#"
+public component Button {
+    variant primary trigger {
+        ":hover"
+    }
+    render button {
+        style variant primary {
+            color: white
+        }
+        style {
+            color: black
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let options = CompileOptions {
+            cascade_layers: true,
+            ..CompileOptions::default()
+        };
+        let css = compile_to_css_with_options(&document, "/button.pc", &options)
+            .expect("Failed to compile CSS");
+
+        println!("Generated CSS:\n{}", css);
+
+        assert!(css.starts_with("@layer tokens, globals, mixins, components, variants, overrides;"));
+        let base = css.find("@layer components {").unwrap();
+        let variant = css.find("@layer variants {").unwrap();
+        assert!(base < variant);
+        assert!(css[variant..].contains("color: white;"));
+
+        // Off by default
+        let css = compile_to_css_with_options(&document, "/button.pc", &CompileOptions::default())
+            .unwrap();
+        assert!(!css.contains("@layer"));
+    }
+
+    #[test]
+    fn test_container_and_supports_triggers() {
+        let source = r#"
+trigger wide {
+    "@container card (min-width: 400px)"
+}
+trigger grid {
+    "@supports (display: grid)"
+}
+public component Card {
+    variant roomy trigger { wide }
+    variant tiled trigger { grid }
+    render div {
+        style {
+            container-type: inline-size
+            container-name: card
+        }
+        style variant roomy {
+            padding: 24px
+        }
+        style variant roomy + tiled {
+            display: grid
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let css = compile_to_css(&document).expect("Failed to compile CSS");
+
+        println!("Generated CSS:\n{}", css);
+
+        assert!(css.contains("container-type: inline-size;"));
+        assert!(css.contains("container-name: card;"));
+        assert!(css.contains("@container card (min-width: 400px) {\n  ._Card-div-"));
+        // Combined variants nest one at-rule inside the other
+        assert!(css.contains(
+            "@container card (min-width: 400px) {\n  @supports (display: grid) {\n    ._Card-div-"
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_trigger_at_rule() {
+        let source = r#"
+public component Card {
+    variant wide trigger { "@font-face" }
+    render div {
+        style variant wide {
+            padding: 24px
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let error = compile_to_css(&document).unwrap_err();
+        assert!(error.to_string().contains("'@font-face'"));
+    }
+
+    #[test]
+    fn test_compile_to_split_css() {
+        let source = r#"
+public component Button {
+    render button {
+        style {
+            padding: 8px
+        }
+    }
+}
+
+public component Card {
+    render div {
+        style {
+            border: 1px solid #ddd
+        }
+        Button()
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let output = compile_to_split_css(
+            &document,
+            "/cards.pc",
+            "cards",
+            &CompileOptions {
+                cascade_layers: true,
+                ..CompileOptions::default()
+            },
+        )
+        .expect("Failed to compile CSS");
+
+        // Nothing shared, so there's no cards.css
+        let file_names: Vec<&str> = output.sheets.iter().map(|s| s.file_name.as_str()).collect();
+        assert_eq!(file_names, vec!["cards.Button.css", "cards.Card.css"]);
+        assert!(output.sheets[0].css.contains("padding: 8px"));
+        assert!(!output.sheets[0].css.contains("border"));
+        assert!(output.sheets[1].css.contains("border: 1px solid #ddd"));
+        // Each sheet declares layer order, so load order doesn't matter
+        assert!(output
+            .sheets
+            .iter()
+            .all(|sheet| sheet.css.starts_with("@layer tokens, globals")));
+
+        assert_eq!(output.manifest.shared, None);
+        assert_eq!(
+            output.manifest.components["Card"],
+            vec!["cards.Button.css", "cards.Card.css"]
+        );
+    }
+
+    #[test]
+    fn test_repeated_compiles_are_identical() {
+        let source = r#"
+public token radiusBase 4px
+public token error #D32F2F
+public token spacing 8px
+
+public style danger {
+    color: var(error)
+    border-radius: var(radiusBase)
+    padding: var(spacing)
+}
+
+public component Alert {
+    variant compact
+    render div {
+        style extends danger {
+            margin: 0
+            font-weight: bold
+            display: flex
+        }
+        style variant compact {
+            padding: 2px
+            margin: 1px
+        }
+    }
+}
+"#;
+
+        let compile = || {
+            let document = parse(source).expect("Failed to parse");
+            compile_to_css_with_options(&document, "/alert.pc", &CompileOptions::default())
+                .expect("Failed to compile CSS")
+        };
+        let first = compile();
+        for _ in 0..20 {
+            assert_eq!(compile(), first);
+        }
+
+        // Tokens and declarations come out in the order they're written
+        let position = |needle: &str| first.find(needle).unwrap();
+        assert!(position("--radiusBase") < position("--error"));
+        assert!(position("--error") < position("--spacing"));
+        assert!(position("margin: 0") < position("font-weight: bold"));
+        assert!(position("font-weight: bold") < position("display: flex"));
+    }
+
+    #[test]
+    fn test_compile_with_transforms() {
+        let source = r#"
+public component Button {
+    render button {
+        style {
+            margin-left: 8px
+            border: 1px solid black
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let transforms = CssTransforms::new()
+            .with(paperclip_evaluator::PxToRem {
+                exclude: vec!["border".to_string()],
+                ..Default::default()
+            })
+            .with(paperclip_evaluator::LogicalProperties);
+        let css = compile_to_css_with_options(
+            &document,
+            "/button.pc",
+            &CompileOptions {
+                transforms,
+                ..CompileOptions::default()
+            },
+        )
+        .expect("Failed to compile CSS");
+
+        println!("Generated CSS:\n{}", css);
+
+        assert!(css.contains("margin-inline-start: 0.5rem;"));
+        assert!(css.contains("border: 1px solid black;"));
+        assert!(!css.contains("margin-left"));
+    }
+
+    #[test]
+    fn test_source_map_points_selectors_at_pc_source() {
+        let source = r#"public token brand #3366FF
+
+public style base {
+    color: var(brand)
+}
+
+public component Button {
+    variant primary
+    render button {
+        style {
+            padding: 8px
+        }
+        style variant primary {
+            color: white
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let output = compile_to_css_with_source_map(
+            &document,
+            "/button.pc",
+            source,
+            "button.pc",
+            &CompileOptions {
+                class_name_strategy: ClassNameStrategy::Readable,
+                cascade_layers: true,
+                ..CompileOptions::default()
+            },
+        )
+        .expect("Failed to compile CSS");
+
+        println!("Generated CSS:\n{}", output.css);
+
+        let map = sourcemap::SourceMap::from_slice(output.source_map.as_bytes()).unwrap();
+        assert_eq!(map.get_source(0), Some("button.pc"));
+        assert_eq!(map.get_source_contents(0), Some(source));
+
+        // (line, column) of `needle` in the .pc source
+        let position = |needle: &str| byte_offset_to_line_col(source, source.find(needle).unwrap());
+        // Where the selector of the rule declaring `declaration` maps to
+        let source_of = |declaration: &str| {
+            let css = &output.css;
+            let rule_start = css[..css.find(declaration).unwrap()].rfind('{').unwrap();
+            let line_start = css[..rule_start].rfind('\n').map_or(0, |newline| newline + 1);
+            let selector = &css[line_start..rule_start];
+            let (line, _) = byte_offset_to_line_col(css, line_start);
+            let column = (selector.len() - selector.trim_start().len()) as u32;
+
+            let token = map.lookup_token(line, column).unwrap();
+            assert_eq!((token.get_dst_line(), token.get_dst_col()), (line, column));
+            (token.get_src_line(), token.get_src_col())
+        };
+
+        assert_eq!(source_of("#3366FF"), position("public token brand"));
+        assert_eq!(source_of("color: var(--base-color"), position("public style base"));
+        assert_eq!(source_of("padding: 8px"), position("style {"));
+        assert_eq!(source_of("color: white"), position("style variant primary"));
+    }
+
+    #[test]
+    fn test_class_names_are_unique_across_build() {
+        use paperclip_bundle::Bundle;
+        use paperclip_parser::parse_with_path;
+        use std::path::PathBuf;
+
+        let source = "public component Card { render div { style { color: red } } }";
+        let first = parse_with_path(source, "/a.pc").unwrap();
+        let second = parse_with_path(source, "/b.pc").unwrap();
+        let mut bundle = Bundle::new();
+        bundle.add_document(PathBuf::from("/a.pc"), first.clone());
+        bundle.add_document(PathBuf::from("/b.pc"), second.clone());
+
+        let options = CompileOptions {
+            class_name_strategy: ClassNameStrategy::Readable,
+            class_names: Some(ClassNames::for_bundle(&bundle, ClassNameStrategy::Readable)),
+            ..CompileOptions::default()
+        };
+        let first_css = compile_to_css_with_options(&first, "/a.pc", &options).unwrap();
+        let second_css = compile_to_css_with_options(&second, "/b.pc", &options).unwrap();
+
+        assert!(first_css.contains(".Card__div {"));
+        assert!(second_css.contains(".Card__div-2 {"));
+    }
+
+    #[test]
+    fn test_production_build_drops_unused_styles() {
+        use paperclip_bundle::Bundle;
+        use paperclip_evaluator::StyleUsage;
+        use paperclip_parser::parse_with_path;
+        use std::path::PathBuf;
+
+        let source = r#"
+            public token used red
+            public token unused blue
+            public style loud { font-weight: bold }
+            public component Card { render div { style { color: var(used) } } }
+        "#;
+        let entry = PathBuf::from("/a.pc");
+        let mut bundle = Bundle::new();
+        bundle.add_document(entry.clone(), parse_with_path(source, "/a.pc").unwrap());
+
+        let options = CompileOptions {
+            style_usage: Some(StyleUsage::from_entries(&bundle, &[&entry])),
+            ..CompileOptions::default()
+        };
+        let output = compile_bundle_to_css(&bundle, &entry, &options).unwrap();
+
+        assert!(output.css.contains("red"));
+        assert!(!output.css.contains("blue"));
+        assert!(!output.css.contains("bold"));
+        assert_eq!(output.tree_shake_report.removed_tokens, vec!["unused"]);
+        assert_eq!(output.tree_shake_report.removed_styles, vec!["loud"]);
+    }
+
+    #[test]
+    fn test_production_build_shakes_the_development_output() {
+        use paperclip_bundle::{Bundle, MockFileSystem};
+        use paperclip_evaluator::StyleUsage;
+        use paperclip_parser::parse_with_path;
+        use std::path::PathBuf;
+
+        let files = [
+            (
+                "/app/theme.pc",
+                r#"
+                public token brand #3366FF
+                public style usedMixin { color: var(brand) }
+                "#,
+            ),
+            (
+                "/app/app.pc",
+                r#"
+                import "./theme.pc" as theme
+                public token unused #000
+                public component Card {
+                    render div {
+                        style extends theme.usedMixin { padding: 4px }
+                    }
+                }
+                "#,
+            ),
+        ];
+        let mut bundle = Bundle::new();
+        let mut mock_fs = MockFileSystem::new();
+        for (path, source) in files {
+            mock_fs.add_file(PathBuf::from(path));
+            bundle.add_document(PathBuf::from(path), parse_with_path(source, path).unwrap());
+        }
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+        let entry = PathBuf::from("/app/app.pc");
+
+        let dev_options = CompileOptions::default();
+        let prod_options = CompileOptions {
+            style_usage: Some(StyleUsage::from_entries(&bundle, &[&entry])),
+            ..CompileOptions::default()
+        };
+        let dev = compile_bundle_to_css(&bundle, &entry, &dev_options).unwrap();
+        let prod = compile_bundle_to_css(&bundle, &entry, &prod_options).unwrap();
+
+        println!("Development CSS:\n{}\nProduction CSS:\n{}", dev.css, prod.css);
+
+        // Same rules minus the unused token - the mixin's color comes along in both
+        assert!(dev.css.contains("--unused"));
+        assert!(!prod.css.contains("--unused"));
+        let lines = |css: &str| {
+            let mut lines: Vec<String> = css.lines().map(str::to_string).collect();
+            lines.sort();
+            lines
+        };
+        let dev_lines = lines(&dev.css);
+        assert!(lines(&prod.css).iter().all(|line| dev_lines.contains(line)));
+        for css in [&dev.css, &prod.css] {
+            assert!(css.contains("padding: 4px"));
+            assert!(css.contains("color: var(--usedMixin-color"));
+            // The theme's own sheet declares its tokens
+            assert!(!css.contains("  --brand"));
+        }
+        assert_eq!(prod.tree_shake_report.removed_tokens, vec!["unused"]);
+
+        // Production combines with split and source-mapped output
+        let split = compile_bundle_to_split_css(&bundle, &entry, "app", &prod_options).unwrap();
+        // The unused token was all app.css had
+        assert_eq!(split.manifest.shared, None);
+        assert_eq!(split.sheets.len(), 1);
+        assert!(split.sheets[0].css.contains("padding: 4px"));
+        assert_eq!(split.tree_shake_report, prod.tree_shake_report);
+        let mapped = compile_bundle_to_css_with_source_map(
+            &bundle,
+            &entry,
+            files[1].1,
+            "app.pc",
+            &prod_options,
+        )
+        .unwrap();
+        assert_eq!(lines(&mapped.css), lines(&prod.css));
+        assert_eq!(mapped.tree_shake_report, prod.tree_shake_report);
+    }
+
+    #[test]
+    fn test_bundle_split_manifest_lists_imported_sheets() {
+        use paperclip_bundle::{Bundle, MockFileSystem};
+        use paperclip_parser::parse_with_path;
+        use std::path::PathBuf;
+
+        let files = [
+            (
+                "/app/theme/theme.pc",
+                r#"
+                public token brand #3366FF
+                public style usedMixin { color: var(brand) }
+                "#,
+            ),
+            (
+                "/app/ui/button.pc",
+                "public component Button { render button { style { padding: 8px } } }",
+            ),
+            (
+                "/app/pages/app.pc",
+                r#"
+                import "theme/theme.pc" as theme
+                import "ui/button.pc" as ui
+                public component Card {
+                    render div {
+                        style extends theme.usedMixin { padding: 4px }
+                        Button()
+                    }
+                }
+                "#,
+            ),
+        ];
+        let mut bundle = Bundle::new();
+        let mut mock_fs = MockFileSystem::new();
+        for (path, source) in files {
+            mock_fs.add_file(PathBuf::from(path));
+            bundle.add_document(PathBuf::from(path), parse_with_path(source, path).unwrap());
+        }
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let output = compile_bundle_to_split_css(
+            &bundle,
+            &PathBuf::from("/app/pages/app.pc"),
+            "app",
+            &CompileOptions::default(),
+        )
+        .unwrap();
+
+        // Nothing but the component's own rules - no empty app.css
+        assert_eq!(output.manifest.shared, None);
+        let file_names: Vec<&str> = output.sheets.iter().map(|s| s.file_name.as_str()).collect();
+        assert_eq!(file_names, vec!["app.Card.css"]);
+        // button.pc has no shared rules either, so only theme.css is loaded for it
+        assert_eq!(
+            output.manifest.components["Card"],
+            vec!["../theme/theme.css", "../ui/button.Button.css", "app.Card.css"]
+        );
+    }
+
+    #[test]
+    fn test_source_map_comments() {
+        let output = CssWithSourceMap {
+            css: ".a {\n}\n".to_string(),
+            source_map: "{}".to_string(),
+            tree_shake_report: TreeShakeReport::default(),
+        };
+
+        assert_eq!(
+            output.with_source_map_url("button.css.map"),
+            ".a {\n}\n/*# sourceMappingURL=button.css.map */\n"
+        );
+        assert_eq!(
+            output.with_inline_source_map(),
+            ".a {\n}\n/*# sourceMappingURL=data:application/json;base64,e30= */\n"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use paperclip_evaluator::{ClassNameStrategy, ClassNames};
 use paperclip_parser::ast::*;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -34,6 +35,11 @@ pub struct CompileOptions {
     pub inline_styles: bool,
     /// Use class names (requires separate CSS compilation)
     pub use_classes: bool,
+    /// How generated class names are formed (must match the CSS build)
+    pub class_name_strategy: ClassNameStrategy,
+    /// Names assigned across every file of the build (`ClassNames::for_bundle`),
+    /// so they match the CSS build. Assigned from the document alone when `None`.
+    pub class_names: Option<ClassNames>,
     /// Pretty print HTML
     pub pretty: bool,
     /// Indentation string
@@ -45,6 +51,8 @@ impl Default for CompileOptions {
         Self {
             inline_styles: true,
             use_classes: false,
+            class_name_strategy: ClassNameStrategy::default(),
+            class_names: None,
             pretty: true,
             indent: "  ".to_string(),
         }
@@ -55,6 +63,9 @@ struct Context {
     options: CompileOptions,
     depth: usize,
     buffer: String,
+    class_names: ClassNames,
+    /// Component being compiled (scopes hashed class names)
+    component: Option<String>,
 }
 
 impl Context {
@@ -63,6 +74,8 @@ impl Context {
             options,
             depth: 0,
             buffer: String::new(),
+            class_names: ClassNames::hashed(),
+            component: None,
         }
     }
 
@@ -108,6 +121,10 @@ pub fn compile_to_html(
     options: CompileOptions,
 ) -> Result<String, CompileError> {
     let mut ctx = Context::new(options);
+    ctx.class_names = match &ctx.options.class_names {
+        Some(class_names) => class_names.clone(),
+        None => ClassNames::for_document(document, ctx.options.class_name_strategy),
+    };
 
     // Add DOCTYPE
     ctx.add_line("<!DOCTYPE html>");
@@ -159,6 +176,8 @@ fn compile_head(_document: &Document, ctx: &mut Context) {
 }
 
 fn compile_component_as_html(component: &Component, ctx: &mut Context) -> Result<(), CompileError> {
+    ctx.component = Some(component.name.clone());
+
     // Add component as a section with ID
     ctx.add_line(&format!(
         "<section id=\"{}\" class=\"paperclip-component\">",
@@ -184,8 +203,8 @@ fn compile_element(element: &Element, ctx: &mut Context) -> Result<(), CompileEr
             attributes,
             styles,
             children,
-            span: _,
-        } => compile_tag(tag_name, attributes, styles, children, ctx),
+            span,
+        } => compile_tag(tag_name, attributes, styles, children, span, ctx),
 
         Element::Text { content, .. } => {
             compile_text_content(content, ctx);
@@ -246,6 +265,7 @@ fn compile_element(element: &Element, ctx: &mut Context) -> Result<(), CompileEr
 
         Element::Insert {
             slot_name: _,
+            params: _,
             content,
             span: _,
         } => {
@@ -264,6 +284,7 @@ fn compile_tag(
     attributes: &HashMap<String, Expression>,
     styles: &[StyleBlock],
     children: &[Element],
+    span: &Span,
     ctx: &mut Context,
 ) -> Result<(), CompileError> {
     // Opening tag
@@ -272,12 +293,31 @@ fn compile_tag(
     }
     ctx.add(&format!("<{}", name));
 
+    // The class the CSS output gives this element, ahead of any explicit class
+    let generated_class = (ctx.options.use_classes && !styles.is_empty()).then(|| {
+        ctx.class_names
+            .class_name(Some(name), &span.id, ctx.component.as_deref())
+    });
+
     // Add attributes
     for (attr_name, expr) in attributes {
+        if attr_name == "class" && generated_class.is_some() {
+            continue;
+        }
         ctx.add(" ");
         compile_attribute(attr_name, expr, ctx)?;
     }
 
+    if let Some(class_name) = generated_class {
+        ctx.add(" class=\"");
+        ctx.add(&escape_html(&class_name));
+        if let Some(expr) = attributes.get("class") {
+            ctx.add(" ");
+            compile_attribute_value(expr, ctx);
+        }
+        ctx.add("\"");
+    }
+
     // Add inline styles
     if ctx.options.inline_styles && !styles.is_empty() {
         ctx.add(" style=\"");
@@ -332,7 +372,12 @@ fn compile_tag(
 fn compile_attribute(name: &str, expr: &Expression, ctx: &mut Context) -> Result<(), CompileError> {
     ctx.add(name);
     ctx.add("=\"");
+    compile_attribute_value(expr, ctx);
+    ctx.add("\"");
+    Ok(())
+}
 
+fn compile_attribute_value(expr: &Expression, ctx: &mut Context) {
     match expr {
         Expression::Literal { value, .. } => {
             ctx.add(&escape_html(value));
@@ -348,9 +393,6 @@ fn compile_attribute(name: &str, expr: &Expression, ctx: &mut Context) -> Result
             ctx.add("[dynamic]");
         }
     }
-
-    ctx.add("\"");
-    Ok(())
 }
 
 fn compile_text_content(expr: &Expression, ctx: &mut Context) {
@@ -1,4 +1,5 @@
 use crate::{compile_to_html, CompileOptions};
+use paperclip_evaluator::ClassNameStrategy;
 use paperclip_parser::parse;
 
 #[test]
@@ -186,3 +187,29 @@ public component Message {
     assert!(html.contains("&amp;"));
     assert!(html.contains("friends"));
 }
+
+#[test]
+fn test_dynamic_class_is_merged_with_generated_class() {
+    let source = r#"
+public component Button {
+    render button(class=kind) {
+        style {
+            color: red
+        }
+        text "Click"
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let options = CompileOptions {
+        use_classes: true,
+        class_name_strategy: ClassNameStrategy::Readable,
+        ..Default::default()
+    };
+    let html = compile_to_html(&document, options).expect("Failed to compile");
+
+    println!("Generated HTML:\n{}", html);
+
+    assert!(html.contains("class=\"Button__button [dynamic]\""));
+}
@@ -53,6 +53,7 @@ public component Card {
             let options = CompileOptions {
                 use_typescript: true,
                 include_css_imports: true,
+                ..CompileOptions::default()
             };
 
             // Generate React code
@@ -1,4 +1,5 @@
 use crate::context::{CompileOptions, CompilerContext};
+use paperclip_evaluator::{ClassNameStrategy, ClassNames, ResolvedToken, TokenResolver};
 use paperclip_parser::ast::*;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -34,6 +35,10 @@ pub fn compile_to_react(
     options: CompileOptions,
 ) -> Result<String, CompileError> {
     let ctx = CompilerContext::new(options);
+    ctx.set_class_names(match &ctx.options.class_names {
+        Some(class_names) => class_names.clone(),
+        None => ClassNames::for_document(document, ctx.options.class_name_strategy),
+    });
 
     // Generate imports
     compile_imports(&document, &ctx);
@@ -47,9 +52,14 @@ pub fn compile_to_react(
     }
 
     // Export public tokens
+    let mut token_resolver = TokenResolver::new(&document.tokens);
     for token in &document.tokens {
         if token.public {
-            compile_token(token, &ctx);
+            let resolved = match ctx.options.tokens.get(&token.name) {
+                Some(resolved) => Some(resolved.clone()),
+                None => token_resolver.resolve(&token.name).ok(),
+            };
+            compile_token(token, resolved, &ctx);
         }
     }
 
@@ -60,13 +70,42 @@ pub fn compile_to_react(
         }
     }
 
+    // CSS Modules consumers get the local -> final class mapping
+    if ctx.options.class_name_strategy == ClassNameStrategy::CssModules {
+        ctx.add_line("export { styles as classNames };");
+    }
+
     Ok(ctx.get_output())
 }
 
 fn compile_imports(document: &Document, ctx: &CompilerContext) {
-    // Import CSS file if enabled
-    if ctx.options.include_css_imports {
-        ctx.add_line("import \"./styles.css\";");
+    // Import CSS file if enabled (CSS Modules always need the class mapping)
+    if ctx.options.class_name_strategy == ClassNameStrategy::CssModules {
+        ctx.add_line("import styles from \"./styles.module.css\";");
+    } else if ctx.options.include_css_imports {
+        match &ctx.options.css_manifest {
+            // Split CSS: each public component's sheets, in the order it declares
+            // them. A side-effect import loads with the whole module, so every
+            // component exported here brings its sheets along; splitting only
+            // pays off across files (or via css-manifest.json in a bundler).
+            Some(manifest) => {
+                let mut imported = Vec::new();
+                let sheets = document
+                    .components
+                    .iter()
+                    .filter_map(|component| manifest.components.get(&component.name))
+                    .flatten();
+                for sheet in manifest.shared.iter().chain(sheets) {
+                    if !imported.contains(&sheet) {
+                        // Imported files' sheets are already relative (`../theme.css`)
+                        let prefix = if sheet.starts_with("../") { "" } else { "./" };
+                        ctx.add_line(&format!("import \"{}{}\";", prefix, sheet));
+                        imported.push(sheet);
+                    }
+                }
+            }
+            None => ctx.add_line("import \"./styles.css\";"),
+        }
     }
 
     // Import React
@@ -81,9 +120,50 @@ fn compile_imports(document: &Document, ctx: &CompilerContext) {
         }
     }
 
+    // Import the real code behind script-bound components
+    for component in &document.components {
+        if let Some(script) = react_script(component) {
+            let local = live_component_name(&component.name);
+            let specifier = module_specifier(&script.src);
+            match script.name.as_deref() {
+                Some("default") => {
+                    ctx.add_line(&format!("import {} from \"{}\";", local, specifier))
+                }
+                name => ctx.add_line(&format!(
+                    "import {{ {} as {} }} from \"{}\";",
+                    name.unwrap_or(&component.name),
+                    local,
+                    specifier
+                )),
+            }
+        }
+    }
+
     ctx.add("\n");
 }
 
+/// Script directive of a component bound to React code. Other targets can't
+/// be mounted here, so those components compile from their markup.
+fn react_script(component: &Component) -> Option<&ScriptDirective> {
+    component
+        .script
+        .as_ref()
+        .filter(|script| script.target == "react")
+}
+
+/// Script source as a module specifier. TS/JS extensions are dropped so the
+/// bundler resolves the compiled module (`./counter.tsx` -> `./counter`).
+fn module_specifier(src: &str) -> &str {
+    [".tsx", ".ts", ".jsx", ".js"]
+        .iter()
+        .find_map(|ext| src.strip_suffix(ext))
+        .unwrap_or(src)
+}
+
+fn live_component_name(component_name: &str) -> String {
+    format!("_{}Live", component_name)
+}
+
 fn compile_utilities(ctx: &CompilerContext) {
     // Add utility functions that React components may need
     ctx.add_line("// Utility function to merge class names");
@@ -91,29 +171,62 @@ fn compile_utilities(ctx: &CompilerContext) {
     ctx.add("\n");
 }
 
-fn compile_token(token: &TokenDecl, ctx: &CompilerContext) {
-    ctx.add_line(&format!(
-        "export const {} = \"{}\";",
-        token.name, token.value
-    ));
+fn compile_token(token: &TokenDecl, resolved: Option<ResolvedToken>, ctx: &CompilerContext) {
+    let value = match (resolved, &token.expression) {
+        (Some(resolved), _) if resolved.unresolved.is_empty() => {
+            format!("\"{}\"", resolved.value)
+        }
+        // Alias to an imported token - reference the imported module's export
+        (_, TokenValue::Reference { name }) if name.contains('.') => name.clone(),
+        // Depends on imported tokens that weren't available - the expression
+        // itself isn't a usable value
+        (resolved, _) => {
+            let missing = resolved
+                .map(|resolved| resolved.unresolved.join(", "))
+                .unwrap_or_else(|| token.value.clone());
+            format!("undefined /* unresolved: {} */", missing)
+        }
+    };
+    ctx.add_line(&format!("export const {} = {};", token.name, value));
 }
 
 fn compile_style_export(style: &StyleDecl, ctx: &CompilerContext) {
-    // Generate a class name based on the style name
-    let class_name = format!("pc-{}", style.name.to_lowercase());
+    // The same class the CSS output gives the style
+    let class_name = ctx.class_name(Some(&style.name), &style.span.id);
     ctx.add_line(&format!(
-        "export const {} = \"{}\";",
-        style.name, class_name
+        "export const {} = {};",
+        style.name,
+        ctx.class_ref(&class_name)
     ));
 }
 
 fn compile_component(component: &Component, ctx: &CompilerContext) -> Result<(), CompileError> {
     let component_name = &component.name;
+    ctx.set_component(Some(component_name));
+    ctx.set_toggled_variants(
+        component
+            .variants
+            .iter()
+            .filter(|variant| variant.triggers.is_empty())
+            .map(|variant| variant.name.clone())
+            .collect(),
+    );
 
     // Start component function
     ctx.add_line(&format!("const _{} = (props, ref) => {{", component_name));
     ctx.indent();
 
+    if react_script(component).is_some() {
+        // Bound to real code - the markup only exists for the designer
+        ctx.add_line(&format!(
+            "return <{} {{...props}} ref={{ref}} />;",
+            live_component_name(component_name)
+        ));
+        ctx.dedent();
+        ctx.add_line("};");
+        return finish_component(component, ctx);
+    }
+
     // Extract variants from props if any
     if !component.variants.is_empty() {
         compile_variant_extraction(component, ctx);
@@ -123,6 +236,14 @@ fn compile_component(component: &Component, ctx: &CompilerContext) -> Result<(),
     if !component.slots.is_empty() {
         compile_slot_extraction(component, ctx);
     }
+    ctx.set_slot_params(
+        component
+            .slots
+            .iter()
+            .filter(|slot| slot.is_scoped())
+            .map(|slot| (slot.name.clone(), slot.params.clone()))
+            .collect(),
+    );
 
     // Render the component body
     if let Some(body) = &component.body {
@@ -138,6 +259,13 @@ fn compile_component(component: &Component, ctx: &CompilerContext) -> Result<(),
     ctx.dedent();
     ctx.add_line("};");
 
+    finish_component(component, ctx)
+}
+
+/// Display name, memo/forwardRef wrapper and export for a compiled `_Name`
+fn finish_component(component: &Component, ctx: &CompilerContext) -> Result<(), CompileError> {
+    let component_name = &component.name;
+
     // Set display name
     ctx.add_line(&format!(
         "_{}.displayName = \"{}\";",
@@ -184,8 +312,8 @@ fn compile_element(
             attributes,
             styles,
             children,
-            span: _,
-        } => compile_tag(tag_name, attributes, styles, children, ctx, is_root),
+            span,
+        } => compile_tag(tag_name, attributes, styles, children, span, ctx, is_root),
 
         Element::Text { content, .. } => {
             compile_text_content(content, ctx);
@@ -214,23 +342,32 @@ fn compile_element(
         } => compile_repeat(item_name, collection, body, ctx),
 
         Element::SlotInsert { name, span: _ } => {
-            ctx.add(&format!("{{{}}}", name));
+            match ctx.slot_params(name) {
+                // Scoped slots are render props: call them with the slot's parameters
+                Some(params) => {
+                    ctx.add(&format!("{{{}?.(", name));
+                    for (i, param) in params.iter().enumerate() {
+                        if i > 0 {
+                            ctx.add(", ");
+                        }
+                        compile_variable(param, ctx);
+                    }
+                    ctx.add(")}");
+                }
+                None => ctx.add(&format!("{{{}}}", name)),
+            }
             Ok(())
         }
 
         Element::Insert {
-            slot_name,
+            slot_name: _,
+            params: _,
             content,
             span: _,
         } => {
-            // Insert directive - compile content as fragment
-            // This would typically be passed to the component instance
-            ctx.add("<>");
-            for child in content {
-                compile_element(child, ctx, false)?;
-            }
-            ctx.add("</>");
-            Ok(())
+            // Insert directive outside an instance - compile content as fragment
+            // (inside an instance it's compiled to a prop by `compile_instance`)
+            compile_fragment(content, ctx)
         }
     }
 }
@@ -240,6 +377,7 @@ fn compile_tag(
     attributes: &HashMap<String, Expression>,
     styles: &[StyleBlock],
     children: &[Element],
+    span: &Span,
     ctx: &CompilerContext,
     is_root: bool,
 ) -> Result<(), CompileError> {
@@ -250,20 +388,23 @@ fn compile_tag(
         ctx.add(" ref={ref}");
     }
 
-    // Compile attributes
+    let classes = generated_classes(name, styles, span, ctx);
+
+    // Compile attributes (an explicit class is merged with the generated ones)
     for (attr_name, expr) in attributes {
+        if attr_name == "class" && !classes.is_empty() {
+            continue;
+        }
         ctx.add(" ");
         compile_attribute(attr_name, expr, ctx)?;
     }
 
-    // Add className if there are styles
-    if !styles.is_empty() {
+    if !classes.is_empty() {
         ctx.add(" className={cx(");
-        for (i, _style) in styles.iter().enumerate() {
-            if i > 0 {
-                ctx.add(", ");
-            }
-            ctx.add(&format!("\"pc-style-{}\"", i));
+        ctx.add(&classes.join(", "));
+        if let Some(expr) = attributes.get("class") {
+            ctx.add(", ");
+            compile_expression(expr, ctx)?;
         }
         ctx.add(")}");
     }
@@ -285,6 +426,38 @@ fn compile_tag(
     Ok(())
 }
 
+/// Class expressions for a styled element: its generated class, plus a
+/// modifier for each trigger-less variant its styles depend on
+fn generated_classes(
+    tag: &str,
+    styles: &[StyleBlock],
+    span: &Span,
+    ctx: &CompilerContext,
+) -> Vec<String> {
+    if styles.is_empty() {
+        return Vec::new();
+    }
+
+    let class_name = ctx.class_name(Some(tag), &span.id);
+    let mut classes = vec![ctx.class_ref(&class_name)];
+
+    let mut toggled: Vec<&str> = Vec::new();
+    for variant in styles.iter().flat_map(|style| &style.variants) {
+        if ctx.is_toggled_variant(variant) && !toggled.contains(&variant.as_str()) {
+            toggled.push(variant);
+        }
+    }
+    for variant in toggled {
+        classes.push(format!(
+            "{} && {}",
+            variant,
+            ctx.class_ref(&ctx.modifier(&class_name, variant))
+        ));
+    }
+
+    classes
+}
+
 fn compile_attribute(
     name: &str,
     expr: &Expression,
@@ -340,11 +513,42 @@ fn compile_instance(
         compile_attribute(prop_name, expr, ctx)?;
     }
 
-    if children.is_empty() {
+    // Inserts become slot props; scoped ones are render-prop functions
+    let mut rest = Vec::new();
+    for child in children {
+        let Element::Insert {
+            slot_name,
+            params,
+            content,
+            ..
+        } = child
+        else {
+            rest.push(child);
+            continue;
+        };
+
+        ctx.add(&format!(" {}={{", slot_name));
+        if params.is_empty() {
+            compile_fragment(content, ctx)?;
+        } else {
+            ctx.add(&format!("({}) => (", params.join(", ")));
+            for param in params {
+                ctx.push_local(param);
+            }
+            compile_fragment(content, ctx)?;
+            for _ in params {
+                ctx.pop_local();
+            }
+            ctx.add(")");
+        }
+        ctx.add("}");
+    }
+
+    if rest.is_empty() {
         ctx.add(" />");
     } else {
         ctx.add(">");
-        for child in children {
+        for child in rest {
             compile_element(child, ctx, false)?;
         }
         ctx.add(&format!("</{}>", name));
@@ -353,6 +557,15 @@ fn compile_instance(
     Ok(())
 }
 
+fn compile_fragment(content: &[Element], ctx: &CompilerContext) -> Result<(), CompileError> {
+    ctx.add("<>");
+    for child in content {
+        compile_element(child, ctx, false)?;
+    }
+    ctx.add("</>");
+    Ok(())
+}
+
 fn compile_conditional(
     condition: &Expression,
     then_branch: &[Element],
@@ -406,6 +619,7 @@ fn compile_repeat(
     ctx.add("{");
     compile_expression(collection, ctx)?;
     ctx.add(&format!("?.map(({}, index) => (", item_name));
+    ctx.push_local(item_name);
 
     // Wrap in fragment if multiple elements
     if body.len() > 1 {
@@ -427,6 +641,7 @@ fn compile_repeat(
         ctx.add("</React.Fragment>");
     }
 
+    ctx.pop_local();
     ctx.add("))}");
     Ok(())
 }
@@ -442,9 +657,7 @@ fn compile_expression(expr: &Expression, ctx: &CompilerContext) -> Result<(), Co
         Expression::Boolean { value, .. } => {
             ctx.add(&value.to_string());
         }
-        Expression::Variable { name, .. } => {
-            ctx.add(&format!("props.{}", name));
-        }
+        Expression::Variable { name, .. } => compile_variable(name, ctx),
         Expression::Member {
             object, property, ..
         } => {
@@ -497,6 +710,15 @@ fn compile_expression(expr: &Expression, ctx: &CompilerContext) -> Result<(), Co
     Ok(())
 }
 
+/// Locals (repeat items, scoped slot params) are in scope; anything else is a prop
+fn compile_variable(name: &str, ctx: &CompilerContext) {
+    if ctx.is_local(name) {
+        ctx.add(name);
+    } else {
+        ctx.add(&format!("props.{}", name));
+    }
+}
+
 fn compile_operator(op: &BinaryOp, ctx: &CompilerContext) {
     let op_str = match op {
         BinaryOp::Add => "+",
@@ -1,4 +1,6 @@
+use paperclip_evaluator::{ClassNameStrategy, ClassNames, CssManifest, ResolvedToken};
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 /// Options for React compilation
@@ -8,6 +10,16 @@ pub struct CompileOptions {
     pub use_typescript: bool,
     /// Whether to include CSS imports
     pub include_css_imports: bool,
+    /// How generated class names are formed (must match the CSS build)
+    pub class_name_strategy: ClassNameStrategy,
+    /// Names assigned across every file of the build (`ClassNames::for_bundle`),
+    /// so they match the CSS build. Assigned from the document alone when `None`.
+    pub class_names: Option<ClassNames>,
+    /// Per-component stylesheets to import instead of a single `styles.css`
+    pub css_manifest: Option<CssManifest>,
+    /// The document's tokens resolved against its imports, by name. Tokens
+    /// missing here are resolved from the document alone.
+    pub tokens: HashMap<String, ResolvedToken>,
 }
 
 impl Default for CompileOptions {
@@ -15,6 +27,10 @@ impl Default for CompileOptions {
         Self {
             use_typescript: false,
             include_css_imports: true,
+            class_name_strategy: ClassNameStrategy::default(),
+            class_names: None,
+            css_manifest: None,
+            tokens: HashMap::new(),
         }
     }
 }
@@ -23,6 +39,16 @@ impl Default for CompileOptions {
 pub struct CompilerContext {
     buffer: Rc<RefCell<String>>,
     indent_level: Rc<RefCell<usize>>,
+    /// Names bound locally (repeat items) that must not be read from `props`
+    locals: Rc<RefCell<Vec<String>>>,
+    /// Parameters of the current component's scoped slots, by slot name
+    slot_params: Rc<RefCell<HashMap<String, Vec<String>>>>,
+    /// Class names shared with the CSS output
+    class_names: Rc<RefCell<ClassNames>>,
+    /// Component being compiled (scopes hashed class names)
+    component: Rc<RefCell<Option<String>>>,
+    /// The current component's variants without triggers, toggled by class
+    toggled_variants: Rc<RefCell<Vec<String>>>,
     pub options: CompileOptions,
 }
 
@@ -31,6 +57,11 @@ impl CompilerContext {
         Self {
             buffer: Rc::new(RefCell::new(String::new())),
             indent_level: Rc::new(RefCell::new(0)),
+            locals: Rc::new(RefCell::new(Vec::new())),
+            slot_params: Rc::new(RefCell::new(HashMap::new())),
+            class_names: Rc::new(RefCell::new(ClassNames::hashed())),
+            component: Rc::new(RefCell::new(None)),
+            toggled_variants: Rc::new(RefCell::new(Vec::new())),
             options,
         }
     }
@@ -61,6 +92,64 @@ impl CompilerContext {
         }
     }
 
+    pub fn push_local(&self, name: &str) {
+        self.locals.borrow_mut().push(name.to_string());
+    }
+
+    pub fn pop_local(&self) {
+        self.locals.borrow_mut().pop();
+    }
+
+    pub fn is_local(&self, name: &str) -> bool {
+        self.locals.borrow().iter().any(|local| local == name)
+    }
+
+    pub fn set_slot_params(&self, slot_params: HashMap<String, Vec<String>>) {
+        *self.slot_params.borrow_mut() = slot_params;
+    }
+
+    /// Parameters of a scoped slot, or `None` for a plain slot
+    pub fn slot_params(&self, name: &str) -> Option<Vec<String>> {
+        self.slot_params.borrow().get(name).cloned()
+    }
+
+    pub fn set_class_names(&self, class_names: ClassNames) {
+        *self.class_names.borrow_mut() = class_names;
+    }
+
+    pub fn set_component(&self, name: Option<&str>) {
+        *self.component.borrow_mut() = name.map(str::to_string);
+    }
+
+    pub fn set_toggled_variants(&self, variants: Vec<String>) {
+        *self.toggled_variants.borrow_mut() = variants;
+    }
+
+    pub fn is_toggled_variant(&self, name: &str) -> bool {
+        self.toggled_variants.borrow().iter().any(|variant| variant == name)
+    }
+
+    /// Class name generated for an element, as the CSS output names it
+    pub fn class_name(&self, tag: Option<&str>, span_id: &str) -> String {
+        self.class_names
+            .borrow()
+            .class_name(tag, span_id, self.component.borrow().as_deref())
+    }
+
+    /// Class toggled by a variant without triggers
+    pub fn modifier(&self, class_name: &str, variant: &str) -> String {
+        self.class_names.borrow().modifier(class_name, variant)
+    }
+
+    /// JS expression for a generated class: a string literal, or a lookup
+    /// in the imported stylesheet for CSS Modules
+    pub fn class_ref(&self, class_name: &str) -> String {
+        match self.options.class_name_strategy {
+            ClassNameStrategy::CssModules => format!("styles[\"{}\"]", class_name),
+            _ => format!("\"{}\"", class_name),
+        }
+    }
+
     pub fn get_output(&self) -> String {
         self.buffer.borrow().clone()
     }
@@ -69,6 +158,11 @@ impl CompilerContext {
         Self {
             buffer: Rc::new(RefCell::new(String::new())),
             indent_level: self.indent_level.clone(),
+            locals: self.locals.clone(),
+            slot_params: self.slot_params.clone(),
+            class_names: self.class_names.clone(),
+            component: self.component.clone(),
+            toggled_variants: self.toggled_variants.clone(),
             options: self.options.clone(),
         }
     }
@@ -162,6 +162,51 @@ public component Layout {
         assert!(result.contains("footer?: React.ReactNode;")); // Optional because has default
     }
 
+    #[test]
+    fn test_compile_with_scoped_slot() {
+        let source = r#"
+public component List {
+    slot row(item)
+
+    render ul {
+        repeat item in items {
+            row
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let result = compile_definitions(&document, CompileOptions::default())
+            .expect("Failed to compile definitions");
+
+        println!("Generated definitions:\n{}", result);
+
+        assert!(result.contains("row: (item: any) => React.ReactNode;"));
+    }
+
+    #[test]
+    fn test_compile_recursive_prop_types() {
+        let source = r#"
+public component TreeNode {
+    render div {
+        text {node.label}
+        repeat child in node.children {
+            TreeNode(node=child)
+        }
+    }
+}
+"#;
+
+        let document = parse(source).expect("Failed to parse");
+        let result = compile_definitions(&document, CompileOptions::default())
+            .expect("Failed to compile definitions");
+
+        println!("Generated definitions:\n{}", result);
+
+        assert!(result.contains("\"children\": Array<TreeNodeProps[\"node\"]>;"));
+    }
+
     #[test]
     fn test_compile_with_tokens() {
         let source = r#"
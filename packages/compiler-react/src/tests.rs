@@ -1,5 +1,7 @@
 use crate::{compile_to_react, CompileOptions};
 use paperclip_parser::parse;
+use paperclip_evaluator::{ClassNameStrategy, CssManifest, ResolvedToken};
+use std::collections::HashMap;
 
 fn normalize_whitespace(s: &str) -> String {
     s.split_whitespace().collect::<Vec<_>>().join(" ")
@@ -138,6 +140,54 @@ public component Button {
     assert!(result.contains("export const primaryColor = \"#3366FF\""));
 }
 
+#[test]
+fn test_public_token_alias_and_math() {
+    let source = r#"
+import "./theme.pc" as theme
+
+token spacing 8px
+public token spacingLarge spacing * 2
+public token overlay alpha(#000000, 0.5)
+public token brand theme.primary
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let result = compile_to_react(&document, CompileOptions::default()).expect("Failed to compile");
+
+    assert!(result.contains("export const spacingLarge = \"16px\";"));
+    assert!(result.contains("export const overlay = \"rgba(0, 0, 0, 0.5)\";"));
+    assert!(result.contains("export const brand = theme.primary;"));
+}
+
+#[test]
+fn test_token_math_on_imported_token() {
+    let source = r#"
+import "./theme.pc" as theme
+
+public token gutter theme.spacing * 2
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+
+    // Without the imported document the expression isn't exported as a value
+    let result = compile_to_react(&document, CompileOptions::default()).expect("Failed to compile");
+    assert!(result.contains("export const gutter = undefined /* unresolved: theme.spacing */;"));
+
+    // Resolved against the bundle it's a literal
+    let gutter = ResolvedToken {
+        value: "16px".to_string(),
+        css_value: "calc(var(--spacing-abc-1) * 2)".to_string(),
+        var_name: "--gutter-abc-2".to_string(),
+        unresolved: vec![],
+    };
+    let options = CompileOptions {
+        tokens: HashMap::from([("gutter".to_string(), gutter)]),
+        ..CompileOptions::default()
+    };
+    let result = compile_to_react(&document, options).expect("Failed to compile");
+    assert!(result.contains("export const gutter = \"16px\";"));
+}
+
 #[test]
 fn test_conditional_rendering() {
     let source = r#"
@@ -182,3 +232,296 @@ public component List {
     assert!(result.contains(".map"));
     assert!(result.contains("item"));
 }
+
+#[test]
+fn test_recursive_component() {
+    let source = r#"
+public component TreeNode {
+    render div {
+        text {node.label}
+        repeat child in {node.children} {
+            TreeNode(node=child)
+        }
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let result = compile_to_react(&document, CompileOptions::default()).expect("Failed to compile");
+
+    println!("Generated code:\n{}", result);
+
+    // Repeat items are locals, not props
+    assert!(result.contains("props.node.children?.map((child, index) => ("));
+    assert!(result.contains("<TreeNode node={child} />"));
+    assert!(!result.contains("props.child"));
+}
+
+#[test]
+fn test_scoped_slot_render_prop() {
+    let source = r#"
+public component List {
+    slot row(item)
+    slot header
+
+    render ul {
+        header
+        repeat item in {items} {
+            row
+        }
+    }
+}
+
+public component App {
+    render List(items=users) {
+        insert header {
+            text "People"
+        }
+        insert row(user) {
+            li {
+                text {user.name}
+            }
+        }
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let result = compile_to_react(&document, CompileOptions::default()).expect("Failed to compile");
+
+    println!("Generated code:\n{}", result);
+
+    // The slot is called with the repeat item
+    assert!(result.contains("{row?.(item)}"));
+    assert!(result.contains("{header}"));
+
+    // Inserts become props; the scoped one is a function of its params
+    let normalized = normalize_whitespace(&result);
+    assert!(normalized.contains("header={<>People</>}"));
+    assert!(normalized.contains("row={(user) => (<><li>{user.name}</li></>)}"));
+    assert!(!result.contains("props.user.name"));
+}
+
+#[test]
+fn test_script_bound_component_renders_real_code() {
+    let source = r#"
+public component Counter {
+    script(src: "./counter.tsx", target: "react")
+
+    render button {
+        text "0"
+    }
+}
+
+public component Chart {
+    script(src: "./chart.tsx", target: "react", name: "default")
+
+    render div {
+        text "Chart"
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let result = compile_to_react(&document, CompileOptions::default()).expect("Failed to compile");
+
+    println!("Generated code:\n{}", result);
+
+    assert!(result.contains("import { Counter as _CounterLive } from \"./counter\";"));
+    assert!(result.contains("import _ChartLive from \"./chart\";"));
+    assert!(result.contains("return <_CounterLive {...props} ref={ref} />;"));
+    assert!(result.contains("const Counter = React.memo(React.forwardRef(_Counter));"));
+
+    // The placeholder markup isn't shipped
+    assert!(!result.contains("<button"));
+}
+
+#[test]
+fn test_script_imports_drop_source_extensions() {
+    let source = r#"
+public component A {
+    script(src: "./a.ts", target: "react")
+    render div
+}
+
+public component B {
+    script(src: "../lib/b.jsx", target: "react")
+    render div
+}
+
+public component C {
+    script(src: "./c.js", target: "react")
+    render div
+}
+
+public component D {
+    script(src: "@acme/widgets", target: "react", name: "Widget")
+    render div
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let result = compile_to_react(&document, CompileOptions::default()).expect("Failed to compile");
+
+    assert!(result.contains("import { A as _ALive } from \"./a\";"));
+    assert!(result.contains("import { B as _BLive } from \"../lib/b\";"));
+    assert!(result.contains("import { C as _CLive } from \"./c\";"));
+    assert!(result.contains("import { Widget as _DLive } from \"@acme/widgets\";"));
+    assert!(!result.contains(".ts\""));
+}
+
+#[test]
+fn test_readable_class_names() {
+    let source = r#"
+public component Button {
+    variant primary
+    render button {
+        style {
+            padding: 8px
+        }
+        style variant primary {
+            color: blue
+        }
+        text "Click"
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let options = CompileOptions {
+        class_name_strategy: ClassNameStrategy::Readable,
+        ..CompileOptions::default()
+    };
+    let result = compile_to_react(&document, options).expect("Failed to compile");
+
+    println!("Generated code:\n{}", result);
+
+    assert!(result.contains("\"Button__button\""));
+    assert!(result.contains("primary && \"Button__button--primary\""));
+    assert!(!result.contains("pc-style-"));
+}
+
+#[test]
+fn test_css_modules_class_names() {
+    let source = r#"
+public component Card {
+    render div {
+        style {
+            padding: 16px
+        }
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let options = CompileOptions {
+        class_name_strategy: ClassNameStrategy::CssModules,
+        ..CompileOptions::default()
+    };
+    let result = compile_to_react(&document, options).expect("Failed to compile");
+
+    println!("Generated code:\n{}", result);
+
+    assert!(result.contains("import styles from \"./styles.module.css\";"));
+    assert!(result.contains("styles[\"Card__div\"]"));
+    assert!(result.contains("export { styles as classNames };"));
+}
+
+#[test]
+fn test_split_css_imports() {
+    let source = r#"
+public component Button {
+    render button {
+        style {
+            padding: 8px
+        }
+    }
+}
+
+public component Card {
+    render div {
+        Button()
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let options = CompileOptions {
+        css_manifest: Some(CssManifest::for_document(&document, "cards")),
+        ..CompileOptions::default()
+    };
+    let result = compile_to_react(&document, options).expect("Failed to compile");
+
+    println!("Generated code:\n{}", result);
+
+    assert!(result.contains("import \"./cards.css\";\nimport \"./cards.Button.css\";\nimport \"./cards.Card.css\";"));
+    assert!(!result.contains("styles.css"));
+}
+
+#[test]
+fn test_split_css_imports_follow_component_order() {
+    let source = r#"
+public component Toolbar {
+    render div {
+        style {
+            display: flex
+        }
+    }
+}
+
+public component Avatar {
+    render img {
+        style {
+            border-radius: 50%
+        }
+    }
+}
+
+component Badge {
+    render span
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let options = CompileOptions {
+        css_manifest: Some(CssManifest::for_document(&document, "header")),
+        ..CompileOptions::default()
+    };
+    let result = compile_to_react(&document, options).expect("Failed to compile");
+
+    assert!(result.contains(
+        "import \"./header.css\";\nimport \"./header.Toolbar.css\";\nimport \"./header.Avatar.css\";\nimport React"
+    ));
+    assert!(!result.contains("header.Badge.css"));
+}
+
+#[test]
+fn test_split_css_imports_sheets_from_other_files() {
+    let source = r#"
+public component Card {
+    render div {
+        style {
+            padding: 4px
+        }
+    }
+}
+"#;
+
+    let document = parse(source).expect("Failed to parse");
+    let manifest = CssManifest {
+        shared: None,
+        components: [(
+            "Card".to_string(),
+            vec!["../theme/theme.css".to_string(), "app.Card.css".to_string()],
+        )]
+        .into(),
+    };
+    let options = CompileOptions {
+        css_manifest: Some(manifest),
+        ..CompileOptions::default()
+    };
+    let result = compile_to_react(&document, options).expect("Failed to compile");
+
+    assert!(result.contains("import \"../theme/theme.css\";\nimport \"./app.Card.css\";\nimport React"));
+    assert!(!result.contains("app.css"));
+}
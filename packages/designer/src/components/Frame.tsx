@@ -176,6 +176,26 @@ function renderNode(node: VNode, doc: Document): Node | null {
     return el;
   }
 
+  if (node.liveComponent) {
+    // Script-bound component: a layout-neutral host that a hybrid client can
+    // mount the real export into. Until then it shows the static render.
+    const live = node.liveComponent;
+    const el = doc.createElement("div");
+    el.style.display = "contents";
+    el.setAttribute("data-pc-id", live.semanticId);
+    el.setAttribute("data-pc-live-src", live.src);
+    el.setAttribute("data-pc-live-export", live.exportName);
+    el.setAttribute("data-pc-live-target", live.target);
+    el.setAttribute("data-pc-live-props", live.propsJson);
+    for (const child of live.children ?? []) {
+      const childEl = renderNode(child, doc);
+      if (childEl) {
+        el.appendChild(childEl);
+      }
+    }
+    return el;
+  }
+
   if (node.text) {
     return doc.createTextNode(node.text.content);
   }
@@ -10,6 +10,7 @@ import {
   VNode,
   VDocument,
   VDocPatch,
+  RootMetadata,
 } from "@paperclip/proto";
 import {
   DesignerEvent,
@@ -71,6 +72,7 @@ interface RawNode {
 interface RawDocument {
   nodes?: RawNode[];
   styles?: Array<{ selector: string; properties: Record<string, string> }>;
+  roots?: Partial<RootMetadata>[];
 }
 
 // ============================================================================
@@ -174,8 +176,11 @@ function transformDocument(raw: RawDocument): VDocument {
     styles: (raw.styles || []).map(s => ({
       selector: s.selector,
       properties: s.properties || {},
+      atRules: [],
     })),
     components: [],  // Component metadata is not included in raw server responses
+    // The server omits empty annotation lists
+    roots: (raw.roots ?? []).map(root => ({ annotations: [], ...root })),
   };
 }
 
@@ -228,6 +233,14 @@ function transformPatch(raw: RawPatch): VDocPatch {
       variantData.node = transformNode(variantData.node as RawNode);
     }
 
+    // MoveChild indices (from_index -> fromIndex)
+    if (variantData && "from_index" in variantData) {
+      variantData.fromIndex = variantData.from_index;
+      variantData.toIndex = variantData.to_index;
+      delete variantData.from_index;
+      delete variantData.to_index;
+    }
+
     const result: Record<string, unknown> = {};
     result[fieldName] = variantData;
 
@@ -242,18 +255,22 @@ function transformPatch(raw: RawPatch): VDocPatch {
 // Patch Application
 // ============================================================================
 
+// Children of a node: elements and live components (static fallback) have them
+function childrenOf(node: VNode | null | undefined): VNode[] | undefined {
+  return node?.element?.children ?? node?.liveComponent?.children;
+}
+
 // Get node at path in proto VDocument
 function getNodeAtPath(doc: VDocument, path: number[]): VNode | null {
   if (path.length === 0) return null;
 
   let current: VNode | undefined = doc.nodes[path[0]];
   for (let i = 1; i < path.length && current; i++) {
-    // Oneof check: if it's an element, traverse children
-    if (current.element) {
-      current = current.element.children[path[i]];
-    } else {
+    const children = childrenOf(current);
+    if (!children) {
       return null;
     }
+    current = children[path[i]];
   }
   return current || null;
 }
@@ -262,6 +279,7 @@ function getNodeAtPath(doc: VDocument, path: number[]): VNode | null {
 function applyPatch(doc: VDocument, patch: VDocPatch): VDocument {
   // Deep clone for immutability
   const newDoc: VDocument = JSON.parse(JSON.stringify(doc));
+  const roots = newDoc.roots;
 
   // Simple oneof checking - no getPatchType() function!
   if (patch.replaceNode) {
@@ -271,9 +289,9 @@ function applyPatch(doc: VDocument, patch: VDocPatch): VDocument {
     } else if (newNode && path.length > 1) {
       const parentPath = path.slice(0, -1);
       const index = path[path.length - 1];
-      const parent = getNodeAtPath(newDoc, parentPath);
-      if (parent?.element) {
-        parent.element.children[index] = newNode;
+      const siblings = childrenOf(getNodeAtPath(newDoc, parentPath));
+      if (siblings) {
+        siblings[index] = newNode;
       }
     }
   } else if (patch.updateText) {
@@ -298,24 +316,44 @@ function applyPatch(doc: VDocument, patch: VDocPatch): VDocument {
     const { path, node, index } = patch.createNode;
     if (node && path.length === 0) {
       newDoc.nodes.splice(index, 0, node);
+      roots.splice(index, 0, { annotations: [] });
     } else if (node) {
-      const parent = getNodeAtPath(newDoc, path);
-      if (parent?.element) {
-        parent.element.children.splice(index, 0, node);
+      childrenOf(getNodeAtPath(newDoc, path))?.splice(index, 0, node);
+    }
+  } else if (patch.moveChild) {
+    // `path` is the positional parent path
+    const { path, fromIndex, toIndex } = patch.moveChild;
+    const siblings =
+      path.length === 0 ? newDoc.nodes : childrenOf(getNodeAtPath(newDoc, path));
+    if (siblings && fromIndex < siblings.length) {
+      const [child] = siblings.splice(fromIndex, 1);
+      siblings.splice(toIndex, 0, child);
+      if (path.length === 0) {
+        const [root] = roots.splice(fromIndex, 1);
+        roots.splice(toIndex, 0, root ?? { annotations: [] });
       }
     }
   } else if (patch.removeNode) {
     const { path } = patch.removeNode;
     if (path.length === 1) {
       newDoc.nodes.splice(path[0], 1);
+      roots.splice(path[0], 1);
     } else if (path.length > 1) {
       const parentPath = path.slice(0, -1);
       const index = path[path.length - 1];
-      const parent = getNodeAtPath(newDoc, parentPath);
-      if (parent?.element) {
-        parent.element.children.splice(index, 1);
-      }
+      childrenOf(getNodeAtPath(newDoc, parentPath))?.splice(index, 1);
     }
+  } else if (patch.updateLiveProps) {
+    // New props re-render the mounted component without remounting it
+    const { path, propsJson } = patch.updateLiveProps;
+    const node = getNodeAtPath(newDoc, path);
+    if (node?.liveComponent) {
+      node.liveComponent.propsJson = propsJson;
+    }
+  } else if (patch.updateFrame) {
+    // Frame moves only touch root metadata, never node attributes
+    const { index, metadata } = patch.updateFrame;
+    roots[index] = metadata ?? { annotations: [] };
   }
 
   return newDoc;
@@ -353,8 +391,23 @@ function extractFrameFromMetadata(
 }
 
 function extractFramesFromDocument(doc: VDocument): Frame[] {
+  const roots = doc.roots;
   return doc.nodes.map((node, index) => {
+    const frame = roots[index]?.frame;
+
     // Oneof check: if it's an element, extract frame data
+    if (node.element && frame) {
+      return {
+        id: node.element.sourceId ?? node.element.semanticId ?? `frame-${index}`,
+        bounds: {
+          x: frame.x,
+          y: frame.y,
+          width: frame.width ?? 1024,
+          height: frame.height ?? 768,
+        },
+      };
+    }
+
     if (node.element) {
       const attrs = node.element.attributes ?? {};
       const metadata = node.element.metadata as Record<string, unknown> | undefined;
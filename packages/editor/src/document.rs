@@ -148,6 +148,7 @@ impl Document {
             nodes: vec![],
             styles: vec![],
             components: vec![],
+            roots: vec![],
         })
     }
 
@@ -21,13 +21,21 @@
 //! - Atomic replacement (not character diff)
 //! - Last write wins if concurrent edits
 //! - No merge attempts
+//! - Clears any rich text runs
+//!
+//! ### UpdateRichText
+//! - Replaces all runs of a text node at once (no per-run edits)
+//! - Plain content is rebuilt from the runs
+//! - Last write wins if concurrent edits
 //!
 //! ### Delete
 //! - Removes node and all descendants
 //! - Concurrent moves to deleted nodes fail
 //! - Concurrent edits of deleted nodes are no-ops
 
-use paperclip_parser::ast::{Annotation, AnnotationValue, Document, DocComment, Element, Span};
+use paperclip_parser::ast::{
+    Annotation, AnnotationValue, Document, DocComment, Element, Span, TextRun,
+};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -44,6 +52,9 @@ pub enum Mutation {
     /// Update text content of a text node (atomic replacement)
     UpdateText { node_id: String, content: String },
 
+    /// Replace the inline formatting runs of a text node (atomic replacement)
+    UpdateRichText { node_id: String, runs: Vec<TextRun> },
+
     /// Set an inline style property
     SetInlineStyle {
         node_id: String,
@@ -142,6 +153,10 @@ impl Mutation {
                 Self::apply_update_text(doc, node_id, content)
             }
 
+            Mutation::UpdateRichText { node_id, runs } => {
+                Self::apply_update_rich_text(doc, node_id, runs)
+            }
+
             Mutation::SetInlineStyle {
                 node_id,
                 property,
@@ -227,6 +242,7 @@ impl Mutation {
         match elem {
             Element::Text {
                 content: ref mut expr,
+                runs,
                 span,
                 ..
             } => {
@@ -234,6 +250,31 @@ impl Mutation {
                     value: content.to_string(),
                     span: span.clone(),
                 };
+                runs.clear();
+                Ok(())
+            }
+            _ => Err(MutationError::NotText),
+        }
+    }
+
+    fn apply_update_rich_text(
+        doc: &mut Document,
+        node_id: &str,
+        new_runs: &[TextRun],
+    ) -> Result<(), MutationError> {
+        let elem = doc
+            .find_element_mut(node_id)
+            .ok_or_else(|| MutationError::NodeNotFound(node_id.to_string()))?;
+
+        match elem {
+            Element::Text {
+                content,
+                runs,
+                span,
+                ..
+            } => {
+                *content = TextRun::plain_content(new_runs, span);
+                *runs = new_runs.to_vec();
                 Ok(())
             }
             _ => Err(MutationError::NotText),
@@ -260,7 +301,7 @@ impl Mutation {
                     styles.push(paperclip_parser::ast::StyleBlock {
                         variants: vec![],
                         extends: vec![],
-                        properties: std::collections::HashMap::new(),
+                        properties: paperclip_parser::ast::Properties::new(),
                         span: span.clone(),
                     });
                 }
@@ -345,7 +386,7 @@ impl Mutation {
             Element::Tag { styles, .. } => {
                 // Remove property from inline styles
                 if let Some(style_block) = styles.get_mut(0) {
-                    style_block.properties.remove(property);
+                    style_block.properties.shift_remove(property);
                 }
                 Ok(())
             }
@@ -616,8 +657,15 @@ impl Mutation {
                     .find_element(node_id)
                     .ok_or_else(|| MutationError::NodeNotFound(node_id.clone()))?;
 
-                if let Element::Text { content, .. } = elem {
+                if let Element::Text { content, runs, .. } = elem {
                     use paperclip_parser::ast::Expression;
+                    if !runs.is_empty() {
+                        return Ok(Mutation::UpdateRichText {
+                            node_id: node_id.clone(),
+                            runs: runs.clone(),
+                        });
+                    }
+
                     let old_content = match content {
                         Expression::Literal { value, .. } => value.clone(),
                         _ => String::new(),
@@ -632,6 +680,35 @@ impl Mutation {
                 }
             }
 
+            Mutation::UpdateRichText { node_id, .. } => {
+                // Capture current runs, falling back to plain text
+                let elem = doc
+                    .find_element(node_id)
+                    .ok_or_else(|| MutationError::NodeNotFound(node_id.clone()))?;
+
+                match elem {
+                    Element::Text { runs, .. } if !runs.is_empty() => {
+                        Ok(Mutation::UpdateRichText {
+                            node_id: node_id.clone(),
+                            runs: runs.clone(),
+                        })
+                    }
+                    Element::Text { content, .. } => {
+                        use paperclip_parser::ast::Expression;
+                        let old_content = match content {
+                            Expression::Literal { value, .. } => value.clone(),
+                            _ => String::new(),
+                        };
+
+                        Ok(Mutation::UpdateText {
+                            node_id: node_id.clone(),
+                            content: old_content,
+                        })
+                    }
+                    _ => Err(MutationError::NotText),
+                }
+            }
+
             Mutation::SetInlineStyle {
                 node_id, property, ..
             } => {
@@ -984,7 +1061,7 @@ impl Mutation {
                 Ok(())
             }
 
-            Mutation::UpdateText { node_id, .. } => {
+            Mutation::UpdateText { node_id, .. } | Mutation::UpdateRichText { node_id, .. } => {
                 let elem = doc
                     .find_element(node_id)
                     .ok_or_else(|| MutationError::NodeNotFound(node_id.clone()))?;
@@ -1138,6 +1215,69 @@ mod tests {
         assert!(mutation.validate(&doc).is_err());
     }
 
+    #[test]
+    fn test_update_rich_text_replaces_runs_atomically() {
+        let source = r#"component Test { render div { text "Hello" } }"#;
+        let mut doc = paperclip_parser::parse(source).unwrap();
+        let text_id = doc.components[0].body.as_ref().unwrap().children().unwrap()[0]
+            .span()
+            .id
+            .clone();
+
+        let runs_source = r#"component R { render div { text { "Hello " strong "world" } } }"#;
+        let runs = match &paperclip_parser::parse(runs_source).unwrap().components[0]
+            .body
+            .as_ref()
+            .unwrap()
+            .children()
+            .unwrap()[0]
+        {
+            Element::Text { runs, .. } => runs.clone(),
+            _ => panic!("Expected text"),
+        };
+
+        let mutation = Mutation::UpdateRichText {
+            node_id: text_id.clone(),
+            runs,
+        };
+        let inverse = mutation.to_inverse(&doc).unwrap();
+        mutation.apply(&mut doc).unwrap();
+
+        match doc.find_element(&text_id) {
+            Some(Element::Text { runs, content, .. }) => {
+                assert_eq!(runs.len(), 2);
+                assert_eq!(runs[1].tag.as_deref(), Some("strong"));
+                assert!(matches!(
+                    content,
+                    paperclip_parser::ast::Expression::Template { parts, .. } if parts.len() == 2
+                ));
+            }
+            other => panic!("Expected text, got {:?}", other),
+        }
+
+        // Undo restores plain text
+        assert!(matches!(inverse, Mutation::UpdateText { ref content, .. } if content == "Hello"));
+        inverse.apply(&mut doc).unwrap();
+        assert!(matches!(
+            doc.find_element(&text_id),
+            Some(Element::Text { runs, .. }) if runs.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_update_rich_text_rejects_non_text() {
+        let source = "component Test { render div {} }";
+        let doc = paperclip_parser::parse(source).unwrap();
+        let div_id = doc.components[0].body.as_ref().unwrap().span().id.clone();
+
+        let mutation = Mutation::UpdateRichText {
+            node_id: div_id,
+            runs: Vec::new(),
+        };
+
+        assert_eq!(mutation.validate(&doc), Err(MutationError::NotText));
+    }
+
     // ==================== Annotation Mutation Tests ====================
 
     #[test]
@@ -361,6 +361,64 @@ fn diff_identical_vdocuments(c: &mut Criterion) {
     });
 }
 
+fn edit_to_patch_200_components(c: &mut Criterion) {
+    use paperclip_bundle::Bundle;
+    use paperclip_evaluator::{diff_vdocument, EvalCache};
+    use paperclip_parser::parse_with_path;
+    use std::path::PathBuf;
+
+    // 200 components, half of which render another one; the edit touches a leaf
+    let source = |label: &str| {
+        (0..200)
+            .map(|i| {
+                let text = if i == 100 { label } else { "Component" };
+                let child = if i % 2 == 1 {
+                    format!("Comp{}()", i - 1)
+                } else {
+                    String::new()
+                };
+                format!(
+                    "component Comp{} {{\n  render div {{\n    text \"{} {}\"\n    {}\n  }}\n}}\n",
+                    i, text, i, child
+                )
+            })
+            .collect::<String>()
+    };
+    let sources = [source("Before"), source("After")];
+    let path = PathBuf::from("/bench/workspace.pc");
+
+    let evaluate = |source: &str, cache: &mut EvalCache| {
+        let doc = parse_with_path(source, "/bench/workspace.pc").unwrap();
+        let mut bundle = Bundle::new();
+        bundle.add_document(path.clone(), doc);
+        Evaluator::with_document_id("/bench/workspace.pc")
+            .evaluate_bundle_cached(&bundle, &path, cache)
+            .unwrap()
+    };
+
+    let initial = evaluate(&sources[0], &mut EvalCache::new());
+
+    c.bench_function("edit_to_patch_200_components_cold", |b| {
+        let mut edit = 0;
+        b.iter(|| {
+            edit += 1;
+            let vdoc = evaluate(&sources[edit % 2], &mut EvalCache::new());
+            diff_vdocument(black_box(&initial), black_box(&vdoc))
+        })
+    });
+
+    c.bench_function("edit_to_patch_200_components_cached", |b| {
+        let mut cache = EvalCache::new();
+        evaluate(&sources[0], &mut cache);
+        let mut edit = 0;
+        b.iter(|| {
+            edit += 1;
+            let vdoc = evaluate(&sources[edit % 2], &mut cache);
+            diff_vdocument(black_box(&initial), black_box(&vdoc))
+        })
+    });
+}
+
 criterion_group!(
     benches,
     evaluate_simple_component,
@@ -372,6 +430,7 @@ criterion_group!(
     evaluate_many_siblings,
     evaluate_with_many_styles,
     diff_large_vdocument,
-    diff_identical_vdocuments
+    diff_identical_vdocuments,
+    edit_to_patch_200_components
 );
 criterion_main!(benches);
@@ -11,7 +11,7 @@ use paperclip_evaluator::{
     VirtualDomDocument,
 };
 use paperclip_evaluator::css_differ::{diff_css_rules, CssDiff};
-use paperclip_parser::parse_with_path;
+use paperclip_parser::{document_path, parse_with_path, DocumentIdMode};
 use std::fs;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
@@ -23,6 +23,8 @@ use notify::{RecommendedWatcher, RecursiveMode, Watcher, Config};
 #[derive(Debug, Clone)]
 struct PreviewState {
     file_path: PathBuf,
+    /// Path IDs are generated from, relative to the directory the server runs in
+    document_path: String,
     last_vdom: Option<VirtualDomDocument>,
     previous_css: Vec<paperclip_evaluator::vdom::CssRule>,
     version: u64,
@@ -30,7 +32,12 @@ struct PreviewState {
 
 impl PreviewState {
     fn new(file_path: PathBuf) -> Self {
+        let file = file_path.canonicalize().unwrap_or_else(|_| file_path.clone());
+        let root = std::env::current_dir()
+            .and_then(|dir| dir.canonicalize())
+            .unwrap_or_default();
         Self {
+            document_path: document_path(&file, &root, DocumentIdMode::default()),
             file_path,
             last_vdom: None,
             previous_css: Vec::new(),
@@ -43,11 +50,11 @@ impl PreviewState {
         let source = fs::read_to_string(&self.file_path)
             .map_err(|e| format!("Failed to read file: {}", e))?;
 
-        let document = parse_with_path(&source, self.file_path.to_str().unwrap())
+        let document = parse_with_path(&source, &self.document_path)
             .map_err(|e| format!("Parse error: {:?}", e))?;
 
         // Evaluate to VDOM
-        let mut evaluator = Evaluator::with_document_id(self.file_path.to_str().unwrap());
+        let mut evaluator = Evaluator::with_document_id(&self.document_path);
         let new_vdom = evaluator.evaluate(&document)
             .map_err(|e| format!("Evaluation error: {:?}", e))?;
 
@@ -450,29 +457,28 @@ const PREVIEW_HTML: &str = r#"<!DOCTYPE html>
             });
         }
 
+        // Cascade layers, lowest priority first (see css_layers.rs)
+        const CSS_LAYERS = ['tokens', 'globals', 'mixins', 'components', 'variants', 'overrides'];
+
         function applyCssPatches(patches) {
             // Apply CSS patches incrementally
             for (const patch of patches) {
-                const key = `${patch.selector || ''}|${patch.media_query || ''}`;
-
                 if (patch.type === 'Add') {
                     // Add new rule
                     currentCssRules.push(patch.rule);
                 } else if (patch.type === 'Update') {
                     // Update existing rule
-                    const index = currentCssRules.findIndex(r =>
-                        r.selector === patch.selector &&
-                        (r.media_query || '') === (patch.media_query || '')
-                    );
+                    const index = currentCssRules.findIndex(r => ruleKey(r) === ruleKey(patch));
                     if (index >= 0) {
-                        currentCssRules[index].properties = patch.properties;
+                        const properties = currentCssRules[index].properties;
+                        Object.assign(properties, patch.properties);
+                        for (const name of patch.removed || []) {
+                            delete properties[name];
+                        }
                     }
                 } else if (patch.type === 'Remove') {
                     // Remove rule
-                    const index = currentCssRules.findIndex(r =>
-                        r.selector === patch.selector &&
-                        (r.media_query || '') === (patch.media_query || '')
-                    );
+                    const index = currentCssRules.findIndex(r => ruleKey(r) === ruleKey(patch));
                     if (index >= 0) {
                         currentCssRules.splice(index, 1);
                     }
@@ -503,43 +509,44 @@ const PREVIEW_HTML: &str = r#"<!DOCTYPE html>
             const styleTag = document.createElement('style');
             styleTag.id = 'paperclip-styles';
 
-            // Group rules by media query
-            const regularRules = [];
-            const mediaRules = {};
-
-            for (const rule of currentCssRules) {
-                if (rule.media_query) {
-                    if (!mediaRules[rule.media_query]) {
-                        mediaRules[rule.media_query] = [];
-                    }
-                    mediaRules[rule.media_query].push(rule);
-                } else {
-                    regularRules.push(rule);
+            // Wrap each cascade layer so added rules can't outrank variants
+            let cssText = `@layer ${CSS_LAYERS.join(', ');`;
+            for (const layer of CSS_LAYERS) {
+                const rules = currentCssRules.filter(rule => (rule.layer || 'components') === layer);
+                if (rules.length > 0) {
+                    cssText += `\n\n@layer ${layer} {\n${rulesToCss(rules)}\n}`;
                 }
             }
 
-            // Convert regular rules to CSS text
-            let cssText = regularRules.map(rule => {
-                const properties = Object.entries(rule.properties || {})
-                    .map(([key, value]) => `  ${key}: ${value};`)
-                    .join('\n');
-                return `${rule.selector} {\n${properties}\n}`;
-            }).join('\n\n');
+            styleTag.textContent = cssText;
+            document.head.appendChild(styleTag);
+        }
 
-            // Add media query rules
-            for (const [mediaQuery, rules] of Object.entries(mediaRules)) {
-                const mediaRulesText = rules.map(rule => {
-                    const properties = Object.entries(rule.properties || {})
-                        .map(([key, value]) => `  ${key}: ${value};`)
-                        .join('\n');
-                    return `  ${rule.selector} {\n  ${properties}\n  }`;
-                }).join('\n\n  ');
+        // Rules are keyed by (layer, selector, at_rules) - see css_differ.rs
+        function ruleKey(item) {
+            const atRules = (item.at_rules || []).map(atRuleText);
+            return JSON.stringify([item.layer || 'components', item.selector, ...atRules]);
+        }
 
-                cssText += `\n\n${mediaQuery} {\n${mediaRulesText}\n}`;
-            }
+        function atRuleText(atRule) {
+            return atRule.prelude ? `@${atRule.name} ${atRule.prelude}` : `@${atRule.name}`;
+        }
 
-            styleTag.textContent = cssText;
-            document.head.appendChild(styleTag);
+        function rulesToCss(rules) {
+            return rules.map(rule => {
+                // Nest the rule in its at-rules, outermost first
+                const atRules = rule.at_rules || [];
+                const pad = depth => '  '.repeat(depth);
+                const properties = Object.entries(rule.properties || {})
+                    .map(([key, value]) => `${pad(atRules.length + 1)}${key}: ${value};`)
+                    .join('\n');
+
+                let css = `${pad(atRules.length)}${rule.selector} {\n${properties}\n${pad(atRules.length)}}`;
+                for (let depth = atRules.length - 1; depth >= 0; depth--) {
+                    css = `${pad(depth)}${atRuleText(atRules[depth])} {\n${css}\n${pad(depth)}}`;
+                }
+                return css;
+            }).join('\n\n');
         }
 
         function renderNode(node) {
@@ -0,0 +1,411 @@
+//! # Class Names
+//!
+//! Decides the class names generated for styled elements. Every consumer
+//! (`CssEvaluator`, the VDOM evaluator, the React and HTML compilers) looks
+//! names up here, so a selector and the element it targets always agree.
+//!
+//! ## Strategies
+//!
+//! - **Hashed** (default): `_Button-div-<document id>-<n>`, unique by construction
+//! - **Readable**: BEM-like `Button__container`, with variant modifiers
+//!   `Button__container--disabled`. Names are unique across a whole bundle.
+//! - **CssModules**: the same local names, unique per file, meant to be fed
+//!   through a CSS Modules loader. `ClassNames::exports` maps each element to
+//!   its local name.
+//!
+//! Readable and CSS Modules names come from the element name when it has one
+//! (`div container`), the tag otherwise. When two elements want the same name
+//! the later one (in document order, documents sorted by path) gets a numeric
+//! suffix and the clash is reported in `ClassNames::collisions`.
+//!
+//! ## Usage
+//!
+//! ```rust
+//! use paperclip_evaluator::{ClassNameStrategy, ClassNames};
+//! use paperclip_parser::parse_with_path;
+//!
+//! let doc = parse_with_path(
+//!     "component Button { render div container { style { color: red } } }",
+//!     "/button.pc",
+//! )
+//! .unwrap();
+//! let names = ClassNames::for_document(&doc, ClassNameStrategy::Readable);
+//! assert_eq!(names.exports()["Button.container"], "Button__container");
+//! ```
+
+use crate::utils::get_style_namespace;
+use paperclip_bundle::Bundle;
+use paperclip_parser::ast::{Document, Element};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// How generated class names are formed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClassNameStrategy {
+    /// Opaque names derived from document and AST IDs
+    #[default]
+    Hashed,
+    /// BEM-like `Component__element--variant` names
+    Readable,
+    /// Readable local names for a CSS Modules pipeline
+    CssModules,
+}
+
+impl ClassNameStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClassNameStrategy::Hashed => "hashed",
+            ClassNameStrategy::Readable => "readable",
+            ClassNameStrategy::CssModules => "css-modules",
+        }
+    }
+}
+
+impl fmt::Display for ClassNameStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ClassNameStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hashed" => Ok(ClassNameStrategy::Hashed),
+            "readable" => Ok(ClassNameStrategy::Readable),
+            "css-modules" => Ok(ClassNameStrategy::CssModules),
+            other => Err(format!(
+                "Unknown class name strategy '{}' (expected hashed, readable or css-modules)",
+                other
+            )),
+        }
+    }
+}
+
+/// Two elements that wanted the same readable name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClassNameCollision {
+    /// The contested name, kept by the first element
+    pub name: String,
+    /// Span ID of the element that kept the name
+    pub kept_by: String,
+    /// Span ID of the element that was renamed
+    pub renamed: String,
+    /// Name given to the renamed element
+    pub renamed_to: String,
+}
+
+/// Class names for the styled elements of a document or bundle
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClassNames {
+    strategy: ClassNameStrategy,
+    /// Span ID -> assigned name (empty for `Hashed`, which needs no table)
+    names: HashMap<String, String>,
+    /// `Component.element` -> name, for CSS Modules consumers
+    exports: BTreeMap<String, String>,
+    collisions: Vec<ClassNameCollision>,
+}
+
+impl ClassNames {
+    /// Hashed names, no table needed
+    pub fn hashed() -> Self {
+        Self::default()
+    }
+
+    /// Empty table for `strategy`, filled by `for_document`/`for_bundle` once
+    /// the source is known
+    pub(crate) fn unassigned(strategy: ClassNameStrategy) -> Self {
+        Self {
+            strategy,
+            ..Self::default()
+        }
+    }
+
+    /// Assign names for a single document
+    pub fn for_document(doc: &Document, strategy: ClassNameStrategy) -> Self {
+        let mut names = Self::unassigned(strategy);
+        if strategy != ClassNameStrategy::Hashed {
+            names.assign_document(doc, &mut HashMap::new());
+        }
+        names
+    }
+
+    /// Assign names for every document in a bundle. Readable names are unique
+    /// across the bundle; CSS Modules names only within their file.
+    pub fn for_bundle(bundle: &Bundle, strategy: ClassNameStrategy) -> Self {
+        let mut names = Self::unassigned(strategy);
+        if strategy == ClassNameStrategy::Hashed {
+            return names;
+        }
+
+        let mut paths: Vec<_> = bundle.documents().keys().collect();
+        paths.sort();
+
+        let mut taken = HashMap::new();
+        for path in paths {
+            if strategy == ClassNameStrategy::CssModules {
+                taken.clear();
+            }
+            names.assign_document(&bundle.documents()[path], &mut taken);
+        }
+        names
+    }
+
+    pub fn strategy(&self) -> ClassNameStrategy {
+        self.strategy
+    }
+
+    /// Class name for an element. `tag` and `component` only matter for
+    /// hashed names (and elements missing from the table).
+    pub fn class_name(&self, tag: Option<&str>, span_id: &str, component: Option<&str>) -> String {
+        match self.names.get(span_id) {
+            Some(name) => name.clone(),
+            None => get_style_namespace(tag, span_id, component),
+        }
+    }
+
+    /// Class toggled on by a variant without triggers: the bare variant name
+    /// for hashed output, a BEM modifier otherwise
+    pub fn modifier(&self, class_name: &str, variant: &str) -> String {
+        match self.strategy {
+            ClassNameStrategy::Hashed => variant.to_string(),
+            _ => format!("{}--{}", class_name, variant),
+        }
+    }
+
+    /// `Component.element` (or `style.name` for style declarations) to class name
+    pub fn exports(&self) -> &BTreeMap<String, String> {
+        &self.exports
+    }
+
+    /// Readable names that clashed and were suffixed
+    pub fn collisions(&self) -> &[ClassNameCollision] {
+        &self.collisions
+    }
+
+    fn assign_document(&mut self, doc: &Document, taken: &mut HashMap<String, String>) {
+        for style in &doc.styles {
+            self.assign(
+                format!("style.{}", style.name),
+                style.name.clone(),
+                &style.span.id,
+                taken,
+            );
+        }
+        for component in &doc.components {
+            if let Some(body) = &component.body {
+                self.assign_element(body, Some(&component.name), taken);
+            }
+        }
+        for render in &doc.renders {
+            self.assign_element(render, None, taken);
+        }
+    }
+
+    fn assign_element(
+        &mut self,
+        element: &Element,
+        component: Option<&str>,
+        taken: &mut HashMap<String, String>,
+    ) {
+        match element {
+            Element::Tag {
+                tag_name,
+                name,
+                children,
+                span,
+                ..
+            } => {
+                self.assign_part(name.as_deref().unwrap_or(tag_name), &span.id, component, taken);
+                for child in children {
+                    self.assign_element(child, component, taken);
+                }
+            }
+            Element::Text {
+                styles, runs, span, ..
+            } => {
+                if !styles.is_empty() || !runs.is_empty() {
+                    self.assign_part("span", &span.id, component, taken);
+                }
+                for run in runs {
+                    if let Some(tag) = &run.tag {
+                        self.assign_part(tag, &run.span.id, component, taken);
+                    }
+                }
+            }
+            Element::Instance { children, .. } => {
+                for child in children {
+                    self.assign_element(child, component, taken);
+                }
+            }
+            Element::Conditional {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                for child in then_branch.iter().chain(else_branch.iter().flatten()) {
+                    self.assign_element(child, component, taken);
+                }
+            }
+            Element::Repeat { body, .. } => {
+                for child in body {
+                    self.assign_element(child, component, taken);
+                }
+            }
+            Element::Insert { content, .. } => {
+                for child in content {
+                    self.assign_element(child, component, taken);
+                }
+            }
+            Element::SlotInsert { .. } => {}
+        }
+    }
+
+    fn assign_part(
+        &mut self,
+        part: &str,
+        span_id: &str,
+        component: Option<&str>,
+        taken: &mut HashMap<String, String>,
+    ) {
+        let (export, wanted) = match component {
+            Some(component) => (
+                format!("{}.{}", component, part),
+                format!("{}__{}", component, part),
+            ),
+            None => (part.to_string(), part.to_string()),
+        };
+        self.assign(export, wanted, span_id, taken);
+    }
+
+    fn assign(
+        &mut self,
+        export: String,
+        wanted: String,
+        span_id: &str,
+        taken: &mut HashMap<String, String>,
+    ) {
+        let mut name = wanted.clone();
+        if let Some(kept_by) = taken.get(&wanted).cloned() {
+            let mut n = 2;
+            while taken.contains_key(&name) {
+                name = format!("{}-{}", wanted, n);
+                n += 1;
+            }
+            self.collisions.push(ClassNameCollision {
+                name: wanted,
+                kept_by,
+                renamed: span_id.to_string(),
+                renamed_to: name.clone(),
+            });
+        }
+
+        taken.insert(name.clone(), span_id.to_string());
+        self.names.insert(span_id.to_string(), name.clone());
+        // The first element keeps the plain export key
+        let mut key = export.clone();
+        let mut n = 2;
+        while self.exports.contains_key(&key) {
+            key = format!("{}-{}", export, n);
+            n += 1;
+        }
+        self.exports.insert(key, name);
+    }
+}
+
+impl Hash for ClassNames {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.strategy.hash(state);
+        let mut names: Vec<_> = self.names.iter().collect();
+        names.sort();
+        names.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paperclip_parser::parse_with_path;
+    use std::path::PathBuf;
+
+    const SOURCE: &str = r#"
+        component Button {
+            render button container {
+                style {
+                    padding: 8px
+                }
+                div {
+                    text "One"
+                }
+                div {
+                    text "Two"
+                }
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_hashed_names_match_namespace() {
+        let doc = parse_with_path(SOURCE, "/button.pc").unwrap();
+        let names = ClassNames::for_document(&doc, ClassNameStrategy::Hashed);
+        assert_eq!(
+            names.class_name(Some("button"), "abc", Some("Button")),
+            get_style_namespace(Some("button"), "abc", Some("Button"))
+        );
+        assert!(names.exports().is_empty());
+    }
+
+    #[test]
+    fn test_readable_names_are_bem_like() {
+        let doc = parse_with_path(SOURCE, "/button.pc").unwrap();
+        let names = ClassNames::for_document(&doc, ClassNameStrategy::Readable);
+
+        let Some(Element::Tag { span, children, .. }) = &doc.components[0].body else {
+            panic!("Expected button");
+        };
+        assert_eq!(names.class_name(None, &span.id, None), "Button__container");
+        assert_eq!(names.modifier("Button__container", "disabled"), "Button__container--disabled");
+
+        // The second unnamed div is suffixed and reported
+        let Element::Tag { span: second, .. } = &children[1] else {
+            panic!("Expected div");
+        };
+        assert_eq!(names.class_name(None, &second.id, None), "Button__div-2");
+        assert_eq!(names.collisions().len(), 1);
+        assert_eq!(names.collisions()[0].name, "Button__div");
+        assert_eq!(names.exports()["Button.div-2"], "Button__div-2");
+    }
+
+    #[test]
+    fn test_collisions_across_bundle() {
+        let source = "public component Card { render div { style { color: red } } }";
+        let mut bundle = Bundle::new();
+        bundle.add_document(PathBuf::from("/a.pc"), parse_with_path(source, "/a.pc").unwrap());
+        bundle.add_document(PathBuf::from("/b.pc"), parse_with_path(source, "/b.pc").unwrap());
+
+        let readable = ClassNames::for_bundle(&bundle, ClassNameStrategy::Readable);
+        assert_eq!(readable.collisions().len(), 1);
+        assert_eq!(readable.collisions()[0].renamed_to, "Card__div-2");
+
+        // CSS Modules scope names to their file
+        let modules = ClassNames::for_bundle(&bundle, ClassNameStrategy::CssModules);
+        assert!(modules.collisions().is_empty());
+    }
+
+    #[test]
+    fn test_strategy_round_trips() {
+        for strategy in [
+            ClassNameStrategy::Hashed,
+            ClassNameStrategy::Readable,
+            ClassNameStrategy::CssModules,
+        ] {
+            assert_eq!(strategy.as_str().parse::<ClassNameStrategy>(), Ok(strategy));
+        }
+        assert!("bem".parse::<ClassNameStrategy>().is_err());
+    }
+}
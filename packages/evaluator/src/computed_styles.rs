@@ -0,0 +1,602 @@
+//! # Computed Styles
+//!
+//! Answers "which value wins for each property of this element, and where did
+//! it come from" for the style editing pane.
+//!
+//! Declarations are collected in cascade order, lowest precedence first:
+//!
+//! 1. Base `style` blocks on the element, in source order. Within a block,
+//!    `extends` mixins come before the block's own properties (`Extends`, or
+//!    `Imported` when the mixin lives in another file; `Inline`, or `Token`
+//!    when the value is just a token reference). Mixins are looked up from
+//!    the file that names them (`theme.base` through that file's imports) and
+//!    their own `extends` come before their properties.
+//! 2. `style variant ...` blocks whose variants are all active (`Variant`, or
+//!    `Trigger` when a variant is bound to a named trigger).
+//! 3. `override` blocks in other components whose path targets the element.
+//!
+//! This mirrors the generated CSS, where variant selectors are more specific
+//! than the element's base class.
+
+use crate::css_evaluator::{CssError, CssEvaluator, CssResult};
+use paperclip_bundle::Bundle;
+use paperclip_parser::ast::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where a declaration came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OriginKind {
+    /// Base `style` block on the element
+    Inline,
+    /// Style mixin pulled in with `extends`
+    Extends,
+    /// Inline value that is just a token reference
+    Token,
+    /// `style variant` block
+    Variant,
+    /// `style variant` block whose variant is bound to a named trigger
+    Trigger,
+    /// `override` block in a component that renders the element
+    Override,
+    /// Style mixin imported from another file
+    Imported,
+}
+
+/// A single declaration affecting an element
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComputedProperty {
+    pub name: String,
+    /// Value as written in the source
+    pub value: String,
+    /// Value with token references substituted
+    pub resolved_value: String,
+    /// Style block, mixin or override that declared the value
+    pub source_span: Span,
+    pub origin_kind: OriginKind,
+    /// Index of the winning declaration in the returned list (`None` for the winner)
+    pub overridden_by: Option<usize>,
+}
+
+/// Compute the styles of the element `node_id` (an AST id) in `path`.
+///
+/// Returns every declaration that applies, grouped by property name (sorted),
+/// with the winner first in each group.
+pub fn compute_styles(
+    bundle: &Bundle,
+    path: &Path,
+    node_id: &str,
+    active_variants: &[String],
+) -> CssResult<Vec<ComputedProperty>> {
+    let entry_doc = bundle
+        .get_document(path)
+        .ok_or_else(|| CssError::EvaluationError {
+            message: format!("Document not found: {}", path.display()),
+        })?;
+
+    let mut evaluator = CssEvaluator::with_document_id(&path.to_string_lossy());
+    evaluator.register_bundle_tokens(bundle, path)?;
+    evaluator.register_bundle_triggers(bundle, entry_doc, path);
+
+    // Components visible from the entry file, with the file declaring them
+    let mut owners: Vec<(&Component, &Path)> =
+        entry_doc.components.iter().map(|c| (c, path)).collect();
+    for dep_path in bundle.get_dependencies(path).unwrap_or_default() {
+        if let Some(dep_doc) = bundle.get_document(dep_path) {
+            owners.extend(
+                dep_doc
+                    .components
+                    .iter()
+                    .filter(|c| c.public)
+                    .map(|c| (c, dep_path.as_path())),
+            );
+        }
+    }
+    let components: Vec<&Component> = owners.iter().map(|(c, _)| *c).collect();
+
+    let (styles, component) = find_node(entry_doc, node_id).ok_or_else(|| {
+        CssError::EvaluationError {
+            message: format!("Node '{}' not found in {}", node_id, path.display()),
+        }
+    })?;
+
+    let resolver = Resolver {
+        evaluator: &evaluator,
+        bundle,
+        entry_path: path,
+    };
+    let mut declarations = Vec::new();
+
+    for block in styles.iter().filter(|block| block.variants.is_empty()) {
+        resolver.push_block(block, path, None, &mut declarations)?;
+    }
+
+    for block in styles.iter().filter(|block| {
+        !block.variants.is_empty() && block.variants.iter().all(|v| active_variants.contains(v))
+    }) {
+        let bound_to_trigger = block.variants.iter().any(|name| {
+            component
+                .and_then(|c| c.variants.iter().find(|v| &v.name == name))
+                .is_some_and(|v| v.triggers.iter().any(|t| evaluator.is_trigger(t)))
+        });
+        let kind = if bound_to_trigger {
+            OriginKind::Trigger
+        } else {
+            OriginKind::Variant
+        };
+        resolver.push_block(block, path, Some(kind), &mut declarations)?;
+    }
+
+    for (owner, owner_path) in &owners {
+        for override_def in &owner.overrides {
+            if resolve_override_target(owner, &override_def.path, &components).as_deref()
+                != Some(node_id)
+            {
+                continue;
+            }
+            for block in override_def.styles.iter().filter(|block| {
+                block.variants.iter().all(|v| active_variants.contains(v))
+            }) {
+                resolver.push_block(
+                    block,
+                    owner_path,
+                    Some(OriginKind::Override),
+                    &mut declarations,
+                )?;
+            }
+        }
+    }
+
+    Ok(rank(declarations))
+}
+
+struct Resolver<'a> {
+    evaluator: &'a CssEvaluator,
+    bundle: &'a Bundle,
+    entry_path: &'a Path,
+}
+
+impl<'a> Resolver<'a> {
+    /// Push a block's declarations: extended mixins first, then its own properties.
+    /// `file` is the file declaring the block, which `extends` resolve from.
+    /// `kind` overrides the origin of the block's own properties.
+    fn push_block(
+        &self,
+        block: &StyleBlock,
+        file: &Path,
+        kind: Option<OriginKind>,
+        out: &mut Vec<ComputedProperty>,
+    ) -> CssResult<()> {
+        let mut visited = Vec::new();
+        for extend_ref in &block.extends {
+            self.push_mixin(extend_ref, file, &mut visited, out)?;
+        }
+
+        for (property, value) in sorted(&block.properties) {
+            let origin = match kind {
+                Some(kind) => kind,
+                None if self.is_token_reference(value) => OriginKind::Token,
+                None => OriginKind::Inline,
+            };
+            out.push(self.declaration(property, value, &block.span, origin)?);
+        }
+
+        Ok(())
+    }
+
+    /// Push a mixin's declarations, its own `extends` first. `visited` guards
+    /// against mixins that extend each other.
+    fn push_mixin(
+        &self,
+        extend_ref: &str,
+        file: &Path,
+        visited: &mut Vec<(PathBuf, String)>,
+        out: &mut Vec<ComputedProperty>,
+    ) -> CssResult<()> {
+        let Some((mixin, mixin_path)) = self.find_mixin(extend_ref, file) else {
+            return Ok(());
+        };
+        let key = (mixin_path.clone(), mixin.name.clone());
+        if visited.contains(&key) {
+            return Ok(());
+        }
+        visited.push(key);
+
+        for nested in &mixin.extends {
+            self.push_mixin(nested, &mixin_path, visited, out)?;
+        }
+
+        let origin = if mixin_path == self.entry_path {
+            OriginKind::Extends
+        } else {
+            OriginKind::Imported
+        };
+        for (property, value) in sorted(&mixin.properties) {
+            out.push(self.declaration(property, value, &mixin.span, origin)?);
+        }
+        Ok(())
+    }
+
+    /// `theme.base` through `file`'s import aliases, a bare name in `file`
+    /// itself and then in the files it imports (as the generated CSS does)
+    fn find_mixin(&self, extend_ref: &str, file: &Path) -> Option<(&'a StyleDecl, PathBuf)> {
+        self.bundle.find_style(extend_ref, file).or_else(|| {
+            if extend_ref.contains('.') {
+                return None;
+            }
+            self.bundle
+                .get_dependencies(file)
+                .unwrap_or_default()
+                .iter()
+                .find_map(|dep_path| {
+                    let style = self
+                        .bundle
+                        .get_document(dep_path)?
+                        .styles
+                        .iter()
+                        .find(|s| s.public && s.name == extend_ref)?;
+                    Some((style, dep_path.clone()))
+                })
+        })
+    }
+
+    fn declaration(
+        &self,
+        name: &str,
+        value: &str,
+        span: &Span,
+        origin_kind: OriginKind,
+    ) -> CssResult<ComputedProperty> {
+        Ok(ComputedProperty {
+            name: name.to_string(),
+            value: value.to_string(),
+            resolved_value: self.evaluator.computed_value(value)?,
+            source_span: span.clone(),
+            origin_kind,
+            overridden_by: None,
+        })
+    }
+
+    /// `{token}` or `var(token)` (possibly namespaced) and nothing else
+    fn is_token_reference(&self, value: &str) -> bool {
+        let value = value.trim();
+        let reference = value
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+            .or_else(|| {
+                value
+                    .strip_prefix("var(")
+                    .and_then(|rest| rest.strip_suffix(')'))
+            })
+            .map(str::trim);
+
+        match reference {
+            Some(reference) if !reference.starts_with("--") => self.evaluator.is_token(reference),
+            _ => false,
+        }
+    }
+}
+
+/// Group declarations by property, winner (last declared) first
+fn rank(declarations: Vec<ComputedProperty>) -> Vec<ComputedProperty> {
+    let mut by_name: BTreeMap<String, Vec<ComputedProperty>> = BTreeMap::new();
+    for declaration in declarations {
+        by_name
+            .entry(declaration.name.clone())
+            .or_default()
+            .push(declaration);
+    }
+
+    let mut ranked = Vec::new();
+    for (_, mut group) in by_name {
+        let winner = ranked.len();
+        group.reverse();
+        for (index, mut declaration) in group.into_iter().enumerate() {
+            declaration.overridden_by = (index > 0).then_some(winner);
+            ranked.push(declaration);
+        }
+    }
+    ranked
+}
+
+fn sorted(properties: &Properties) -> Vec<(&String, &String)> {
+    let mut properties: Vec<_> = properties.iter().collect();
+    properties.sort();
+    properties
+}
+
+/// Find the style blocks of a tag, text node or rich text run by AST id,
+/// along with the component it's rendered in
+fn find_node<'a>(
+    doc: &'a Document,
+    node_id: &str,
+) -> Option<(&'a [StyleBlock], Option<&'a Component>)> {
+    doc.components
+        .iter()
+        .find_map(|component| {
+            let body = component.body.as_ref()?;
+            find_styles(body, node_id).map(|styles| (styles, Some(component)))
+        })
+        .or_else(|| {
+            doc.renders
+                .iter()
+                .find_map(|render| find_styles(render, node_id).map(|styles| (styles, None)))
+        })
+}
+
+fn find_styles<'a>(element: &'a Element, node_id: &str) -> Option<&'a [StyleBlock]> {
+    match element {
+        Element::Tag { styles, span, .. } if span.id == node_id => Some(styles),
+        Element::Text { styles, span, .. } if span.id == node_id => Some(styles),
+        Element::Text { runs, .. } => runs
+            .iter()
+            .find(|run| run.span.id == node_id)
+            .map(|run| run.styles.as_slice()),
+        Element::Tag { children, .. } | Element::Instance { children, .. } => {
+            children.iter().find_map(|child| find_styles(child, node_id))
+        }
+        Element::Conditional {
+            then_branch,
+            else_branch,
+            ..
+        } => then_branch
+            .iter()
+            .chain(else_branch.iter().flatten())
+            .find_map(|child| find_styles(child, node_id)),
+        Element::Repeat { body, .. } => body.iter().find_map(|child| find_styles(child, node_id)),
+        Element::Insert { content, .. } => {
+            content.iter().find_map(|child| find_styles(child, node_id))
+        }
+        Element::SlotInsert { .. } => None,
+    }
+}
+
+/// Resolve an override path (`Card.Button.Icon`) declared in `owner` to the AST
+/// id of the element it styles. Instance segments descend into that component;
+/// the final segment is a named element (or tag), or an instance whose root
+/// element is targeted.
+fn resolve_override_target(
+    owner: &Component,
+    path: &[String],
+    components: &[&Component],
+) -> Option<String> {
+    let mut current = owner;
+    for (index, segment) in path.iter().enumerate() {
+        let last = index + 1 == path.len();
+        match find_named(current.body.as_ref()?, segment)? {
+            Named::Element(id) => return last.then_some(id),
+            Named::Instance(name) => {
+                current = components.iter().find(|c| c.name == name)?;
+                if last {
+                    return current.body.as_ref().map(|body| body.span().id.clone());
+                }
+            }
+        }
+    }
+    None
+}
+
+enum Named {
+    Element(String),
+    Instance(String),
+}
+
+fn find_named(element: &Element, segment: &str) -> Option<Named> {
+    match element {
+        Element::Tag {
+            tag_name,
+            name,
+            span,
+            children,
+            ..
+        } => {
+            if name.as_deref() == Some(segment) || tag_name == segment {
+                return Some(Named::Element(span.id.clone()));
+            }
+            children.iter().find_map(|child| find_named(child, segment))
+        }
+        Element::Instance { name, children, .. } => {
+            if name == segment {
+                return Some(Named::Instance(name.clone()));
+            }
+            children.iter().find_map(|child| find_named(child, segment))
+        }
+        Element::Conditional {
+            then_branch,
+            else_branch,
+            ..
+        } => then_branch
+            .iter()
+            .chain(else_branch.iter().flatten())
+            .find_map(|child| find_named(child, segment)),
+        Element::Repeat { body, .. } => body.iter().find_map(|child| find_named(child, segment)),
+        Element::Insert { content, .. } => {
+            content.iter().find_map(|child| find_named(child, segment))
+        }
+        Element::Text { .. } | Element::SlotInsert { .. } => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paperclip_parser::parse_with_path;
+    use std::path::PathBuf;
+
+    fn bundle_for(source: &str) -> (Bundle, PathBuf) {
+        let path = PathBuf::from("/test.pc");
+        let mut bundle = Bundle::new();
+        bundle.add_document(path.clone(), parse_with_path(source, "/test.pc").unwrap());
+        (bundle, path)
+    }
+
+    fn node_id(bundle: &Bundle, path: &Path, component: &str) -> String {
+        let doc = bundle.get_document(path).unwrap();
+        let component = doc.components.iter().find(|c| c.name == component).unwrap();
+        component.body.as_ref().unwrap().span().id.clone()
+    }
+
+    #[test]
+    fn test_compute_styles_provenance() {
+        let source = r#"
+            token brand #3366FF
+
+            style base {
+                color: black;
+                padding: 4px;
+            }
+
+            component Button {
+                variant hover trigger {
+                    ":hover"
+                }
+
+                render button {
+                    style extends base {
+                        color: var(brand);
+                    }
+                    style variant hover {
+                        color: red;
+                    }
+                }
+            }
+        "#;
+        let (bundle, path) = bundle_for(source);
+        let id = node_id(&bundle, &path, "Button");
+
+        let computed = compute_styles(&bundle, &path, &id, &[]).unwrap();
+        let names: Vec<_> = computed
+            .iter()
+            .map(|p| (p.name.as_str(), p.origin_kind, p.overridden_by))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("color", OriginKind::Token, None),
+                ("color", OriginKind::Extends, Some(0)),
+                ("padding", OriginKind::Extends, None),
+            ]
+        );
+        assert!(computed[0].value.contains("brand"));
+        assert_eq!(computed[0].resolved_value, "#3366FF");
+
+        let hovered = compute_styles(&bundle, &path, &id, &["hover".to_string()]).unwrap();
+        assert_eq!(hovered[0].origin_kind, OriginKind::Variant);
+        assert_eq!(hovered[0].value, "red");
+        assert_eq!(hovered[1].overridden_by, Some(0));
+    }
+
+    #[test]
+    fn test_compute_styles_trigger_and_override() {
+        let source = r#"
+            trigger mobile {
+                "@media screen and (max-width: 400px)"
+            }
+
+            component Icon {
+                variant small trigger { mobile }
+
+                render span {
+                    style {
+                        width: 24px;
+                    }
+                    style variant small {
+                        width: 16px;
+                    }
+                }
+            }
+
+            component Toolbar {
+                render div {
+                    Icon()
+                }
+
+                override Icon {
+                    style {
+                        width: 32px;
+                    }
+                }
+            }
+        "#;
+        let (bundle, path) = bundle_for(source);
+        let id = node_id(&bundle, &path, "Icon");
+
+        let computed = compute_styles(&bundle, &path, &id, &["small".to_string()]).unwrap();
+        let kinds: Vec<_> = computed.iter().map(|p| (p.origin_kind, p.value.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (OriginKind::Override, "32px"),
+                (OriginKind::Trigger, "16px"),
+                (OriginKind::Inline, "24px"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_styles_unknown_node() {
+        let (bundle, path) = bundle_for("component A { render div }");
+        assert!(compute_styles(&bundle, &path, "missing", &[]).is_err());
+    }
+
+    #[test]
+    fn test_compute_styles_resolves_mixins_through_aliases() {
+        let files = [
+            ("/app/light.pc", "public style base { color: white; }"),
+            (
+                "/app/dark.pc",
+                r#"
+                style ink {
+                    border: 1px solid;
+                    color: gray;
+                }
+                public style base extends ink {
+                    color: black;
+                }
+            "#,
+            ),
+            (
+                "/app/card.pc",
+                r#"
+                import "./light.pc" as light
+                import "./dark.pc" as dark
+
+                component Card {
+                    render div {
+                        style extends dark.base {
+                            padding: 4px;
+                        }
+                    }
+                }
+            "#,
+            ),
+        ];
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+        for (path, source) in files {
+            mock_fs.add_file(PathBuf::from(path));
+            bundle.add_document(PathBuf::from(path), parse_with_path(source, path).unwrap());
+        }
+        bundle
+            .build_dependencies_with_fs(Path::new("/app"), &mock_fs)
+            .unwrap();
+        let path = PathBuf::from("/app/card.pc");
+        let id = node_id(&bundle, &path, "Card");
+
+        // `dark.base` rather than light's same-named mixin, after the mixin it extends
+        let computed = compute_styles(&bundle, &path, &id, &[]).unwrap();
+        let values: Vec<_> = computed
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_str(), p.origin_kind))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("border", "1px solid", OriginKind::Imported),
+                ("color", "black", OriginKind::Imported),
+                ("color", "gray", OriginKind::Imported),
+                ("padding", "4px", OriginKind::Inline),
+            ]
+        );
+    }
+}
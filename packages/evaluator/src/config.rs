@@ -0,0 +1,252 @@
+//! Project configuration - `paperclip.config.json`
+//!
+//! Shared by the CLI and the workspace server, so previews and compiled output
+//! read the same settings (class names, targets, CSS transforms).
+
+use crate::css_prefixer::{BrowserTargets, PrefixError};
+use crate::css_transforms::{CssTransformConfig, CssTransforms};
+use paperclip_parser::{document_path, DocumentIdMode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub const DEFAULT_CONFIG_NAME: &str = "paperclip.config.json";
+
+/// Paperclip configuration file format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Config {
+    /// Source directory containing .pc files
+    #[serde(default = "default_src_dir")]
+    pub src_dir: String,
+
+    /// Module directories for imports
+    #[serde(default)]
+    pub module_dirs: Vec<String>,
+
+    /// Compiler output options
+    #[serde(default)]
+    pub compiler_options: Vec<CompilerOption>,
+
+    /// Files allowed to declare `global style` rules (path suffixes)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub global_style_files: Vec<String>,
+
+    /// Root that document IDs are relative to (defaults to the config directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_root: Option<String>,
+
+    /// Derive document IDs from absolute paths, as before (not portable across checkouts)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub legacy_document_ids: bool,
+
+    /// Browsers compiled CSS is prefixed for (e.g. "safari >= 14")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+
+    /// Wrap compiled CSS in named `@layer`s (tokens, globals, mixins, components, variants, overrides)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub css_layers: bool,
+
+    /// Transforms run over generated CSS, in order (px-to-rem, logical-properties, rtl-flip)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub css_transforms: Vec<CssTransformConfig>,
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("Failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+}
+
+fn default_src_dir() -> String {
+    "src".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompilerOption {
+    /// Output formats to emit (e.g., "react", "html", "css")
+    pub emit: Vec<String>,
+
+    /// Optional output directory
+    #[serde(rename = "outDir", skip_serializing_if = "Option::is_none")]
+    pub out_dir: Option<String>,
+}
+
+impl Config {
+    /// Load config from a directory
+    pub fn load(cwd: &str) -> Result<Self, ConfigError> {
+        let config_path = PathBuf::from(cwd).join(DEFAULT_CONFIG_NAME);
+
+        if config_path.exists() {
+            let content =
+                std::fs::read_to_string(&config_path).map_err(|source| ConfigError::Io {
+                    path: config_path.clone(),
+                    source,
+                })?;
+            let config: Config =
+                serde_json::from_str(&content).map_err(|source| ConfigError::Parse {
+                    path: config_path,
+                    source,
+                })?;
+            Ok(config)
+        } else {
+            // Return default config if none exists
+            Ok(Config::default())
+        }
+    }
+
+    /// Get absolute path to source directory
+    pub fn get_src_dir(&self, cwd: &str) -> PathBuf {
+        PathBuf::from(cwd).join(&self.src_dir)
+    }
+
+    /// Get absolute path to the project root
+    pub fn get_project_root(&self, cwd: &str) -> PathBuf {
+        match &self.project_root {
+            Some(root) => PathBuf::from(cwd).join(root),
+            None => PathBuf::from(cwd),
+        }
+    }
+
+    pub fn document_id_mode(&self) -> DocumentIdMode {
+        if self.legacy_document_ids {
+            DocumentIdMode::Absolute
+        } else {
+            DocumentIdMode::Relative
+        }
+    }
+
+    /// Parsed `targets`
+    pub fn browser_targets(&self) -> Result<BrowserTargets, PrefixError> {
+        BrowserTargets::parse(&self.targets)
+    }
+
+    /// Pipeline built from `cssTransforms`
+    pub fn css_transforms(&self) -> CssTransforms {
+        CssTransforms::from_config(&self.css_transforms)
+    }
+
+    /// Path that `file` is identified by when generating IDs and class names
+    pub fn document_path(&self, cwd: &str, file: &Path) -> String {
+        let root = self.get_project_root(cwd);
+        let root = root.canonicalize().unwrap_or(root);
+        let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+        document_path(&file, &root, self.document_id_mode())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            src_dir: default_src_dir(),
+            module_dirs: vec![],
+            compiler_options: vec![CompilerOption {
+                emit: vec!["react".to_string()],
+                out_dir: None,
+            }],
+            global_style_files: vec![],
+            project_root: None,
+            legacy_document_ids: false,
+            targets: vec![],
+            css_layers: false,
+            css_transforms: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let json = r#"{
+            "srcDir": "components",
+            "moduleDirs": ["node_modules"],
+            "compilerOptions": [
+                { "emit": ["react", "css"], "outDir": "dist" }
+            ]
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.src_dir, "components");
+        assert_eq!(config.module_dirs, vec!["node_modules"]);
+        assert_eq!(config.compiler_options.len(), 1);
+        assert_eq!(config.compiler_options[0].emit, vec!["react", "css"]);
+        assert_eq!(config.compiler_options[0].out_dir, Some("dist".to_string()));
+        assert!(config.global_style_files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_global_style_files() {
+        let json = r#"{ "globalStyleFiles": ["styles/reset.pc"] }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.global_style_files, vec!["styles/reset.pc"]);
+    }
+
+    #[test]
+    fn test_parse_project_root() {
+        let json = r#"{ "projectRoot": "..", "legacyDocumentIds": true }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.get_project_root("/repo/app"), PathBuf::from("/repo/app/.."));
+        assert_eq!(config.document_id_mode(), DocumentIdMode::Absolute);
+        assert_eq!(Config::default().document_id_mode(), DocumentIdMode::Relative);
+    }
+
+    #[test]
+    fn test_document_path_is_relative_to_project_root() {
+        let config = Config::default();
+        let path = config.document_path("/repo", Path::new("/repo/src/button.pc"));
+        assert_eq!(path, "/src/button.pc");
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let json = r#"{ "targets": ["safari >= 14", "firefox >= 90"] }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.browser_targets().unwrap().targets().len(), 2);
+
+        let config: Config = serde_json::from_str(r#"{ "targets": ["ie 11"] }"#).unwrap();
+        assert!(config.browser_targets().is_err());
+    }
+
+    #[test]
+    fn test_parse_css_transforms() {
+        let json = r#"{
+            "cssTransforms": [
+                { "name": "px-to-rem", "rootSize": 10, "exclude": ["border*"] },
+                { "name": "rtl-flip" }
+            ]
+        }"#;
+
+        let config: Config = serde_json::from_str(json).unwrap();
+        assert_eq!(config.css_transforms().names(), vec!["px-to-rem", "rtl-flip"]);
+
+        let json = r#"{ "cssTransforms": [{ "name": "minify-everything" }] }"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
+
+        let json = r#"{ "cssTransforms": [{ "name": "px-to-rem", "rootSize": 0 }] }"#;
+        assert!(serde_json::from_str::<Config>(json).is_err());
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = Config::default();
+        assert_eq!(config.src_dir, "src");
+        assert_eq!(config.module_dirs.len(), 0);
+        assert_eq!(config.compiler_options[0].emit, vec!["react"]);
+    }
+}
@@ -0,0 +1,169 @@
+//! At-rule stacks - the grouping at-rules a CSS rule is nested in
+//!
+//! A rule can sit inside several at-rules, outermost first. A variant that
+//! combines a `@supports` trigger with a `@container` trigger produces
+//!
+//! ```css
+//! @supports (display: grid) {
+//!   @container card (min-width: 400px) {
+//!     .card { ... }
+//!   }
+//! }
+//! ```
+//!
+//! which is the stack `[@supports (display: grid), @container card (min-width: 400px)]`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Conditional at-rules that triggers may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AtRuleKind {
+    Media,
+    Container,
+    Supports,
+    Layer,
+}
+
+impl AtRuleKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AtRuleKind::Media => "media",
+            AtRuleKind::Container => "container",
+            AtRuleKind::Supports => "supports",
+            AtRuleKind::Layer => "layer",
+        }
+    }
+}
+
+/// A grouping at-rule, e.g. `@container card (min-width: 400px)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct AtRule {
+    /// At-keyword without the `@`, as written (`media`, `-webkit-keyframes`)
+    pub name: String,
+    /// Everything between the keyword and the block, trimmed
+    pub prelude: String,
+}
+
+impl AtRule {
+    pub fn new(name: impl Into<String>, prelude: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            prelude: prelude.into(),
+        }
+    }
+
+    /// `@media <prelude>`
+    pub fn media(prelude: impl Into<String>) -> Self {
+        Self::new("media", prelude)
+    }
+
+    /// Parse at-rule text such as `@supports (display: grid)`
+    pub fn parse(text: &str) -> Option<Self> {
+        let rest = text.trim().strip_prefix('@')?;
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '(')
+            .unwrap_or(rest.len());
+        let (name, prelude) = rest.split_at(end);
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self::new(name, prelude.trim()))
+    }
+
+    /// Kind of conditional at-rule, `None` for anything else (`@keyframes`, ...)
+    pub fn kind(&self) -> Option<AtRuleKind> {
+        match self.name.to_ascii_lowercase().as_str() {
+            "media" => Some(AtRuleKind::Media),
+            "container" => Some(AtRuleKind::Container),
+            "supports" => Some(AtRuleKind::Supports),
+            "layer" => Some(AtRuleKind::Layer),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AtRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.prelude.is_empty() {
+            write!(f, "@{}", self.name)
+        } else {
+            write!(f, "@{} {}", self.name, self.prelude)
+        }
+    }
+}
+
+/// Open every at-rule in `stack` (outermost first) starting at `indent`.
+/// Returns the indent for the rule nested inside.
+pub(crate) fn open_at_rules(css: &mut String, stack: &[AtRule], indent: &str) -> String {
+    let mut inner = indent.to_string();
+    for at_rule in stack {
+        css.push_str(&inner);
+        css.push_str(&at_rule.to_string());
+        css.push_str(" {\n");
+        inner.push_str("  ");
+    }
+    inner
+}
+
+/// Close the blocks opened by `open_at_rules`
+pub(crate) fn close_at_rules(css: &mut String, stack: &[AtRule], indent: &str) {
+    for depth in (0..stack.len()).rev() {
+        css.push_str(indent);
+        css.push_str(&"  ".repeat(depth));
+        css.push_str("}\n");
+    }
+}
+
+/// Single-level stack as legacy `media_query` text, for clients that predate stacks
+pub fn legacy_media_query(stack: &[AtRule]) -> Option<String> {
+    match stack {
+        [at_rule] => Some(at_rule.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_at_rule() {
+        let at_rule = AtRule::parse("@container card (min-width: 400px)").unwrap();
+        assert_eq!(at_rule.name, "container");
+        assert_eq!(at_rule.prelude, "card (min-width: 400px)");
+        assert_eq!(at_rule.kind(), Some(AtRuleKind::Container));
+        assert_eq!(at_rule.to_string(), "@container card (min-width: 400px)");
+
+        let at_rule = AtRule::parse("@supports(display: grid)").unwrap();
+        assert_eq!(at_rule.kind(), Some(AtRuleKind::Supports));
+        assert_eq!(at_rule.prelude, "(display: grid)");
+
+        assert_eq!(AtRule::parse("@keyframes spin").unwrap().kind(), None);
+        assert_eq!(AtRule::parse(".card"), None);
+        assert_eq!(AtRule::parse("@"), None);
+    }
+
+    #[test]
+    fn test_nested_stack() {
+        let stack = vec![
+            AtRule::parse("@supports (display: grid)").unwrap(),
+            AtRule::media("screen"),
+        ];
+        let mut css = String::new();
+        let inner = open_at_rules(&mut css, &stack, "");
+        css.push_str(&inner);
+        css.push_str(".a {}\n");
+        close_at_rules(&mut css, &stack, "");
+
+        assert_eq!(
+            css,
+            "@supports (display: grid) {\n  @media screen {\n    .a {}\n  }\n}\n"
+        );
+        assert_eq!(legacy_media_query(&stack), None);
+        assert_eq!(
+            legacy_media_query(&stack[1..]).as_deref(),
+            Some("@media screen")
+        );
+    }
+}
@@ -1,11 +1,19 @@
 //! CSS diffing - compute incremental updates for hot reload
 
+use crate::css_at_rules::AtRule;
+use crate::css_layers::CssLayer;
 use crate::vdom::CssRule;
+use paperclip_parser::ast::Properties;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// A CSS patch operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Rules are addressed by `(layer, selector, at_rules)` rather than position,
+/// so patches stay valid when rules are reordered (e.g. by `optimize_css_rules`).
+/// The layer is part of the key because the same selector can appear in more
+/// than one layer (token and mixin custom properties both live on `:root`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum CssPatch {
     /// Add a new CSS rule
@@ -13,17 +21,27 @@ pub enum CssPatch {
         rule: CssRule,
     },
 
-    /// Update an existing rule's properties
+    /// Update some properties of an existing rule
     Update {
         selector: String,
-        media_query: Option<String>,
-        properties: HashMap<String, String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        at_rules: Vec<AtRule>,
+        #[serde(default)]
+        layer: CssLayer,
+        /// Properties that were added or changed
+        properties: Properties,
+        /// Properties that were removed (sorted)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        removed: Vec<String>,
     },
 
     /// Remove a CSS rule
     Remove {
         selector: String,
-        media_query: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        at_rules: Vec<AtRule>,
+        #[serde(default)]
+        layer: CssLayer,
     },
 }
 
@@ -49,55 +67,96 @@ impl CssDiff {
     }
 }
 
+type RuleKey<'a> = (CssLayer, &'a str, &'a [AtRule]);
+
+fn rule_key(rule: &CssRule) -> RuleKey<'_> {
+    (rule.layer, rule.selector.as_str(), rule.at_rules.as_slice())
+}
+
+/// Whether `rule` is the one a patch keyed by `(layer, selector, at_rules)` targets
+fn is_keyed(rule: &CssRule, layer: CssLayer, selector: &str, at_rules: &[AtRule]) -> bool {
+    rule_key(rule) == (layer, selector, at_rules)
+}
+
 /// Compute CSS diff between old and new rules
+///
+/// Patches are deterministic: removals in old rule order, then updates and
+/// additions in new rule order. When a key appears more than once the last
+/// rule wins, matching the cascade.
 pub fn diff_css_rules(old_rules: &[CssRule], new_rules: &[CssRule]) -> CssDiff {
     let mut diff = CssDiff::new();
 
-    // Index old rules by (selector, media_query)
-    let mut old_map: HashMap<(String, Option<String>), &CssRule> = HashMap::new();
+    // Index rules by (layer, selector, at_rules)
+    let old_map: HashMap<RuleKey, &CssRule> =
+        old_rules.iter().map(|rule| (rule_key(rule), rule)).collect();
+    let new_map: HashMap<RuleKey, &CssRule> =
+        new_rules.iter().map(|rule| (rule_key(rule), rule)).collect();
+
+    // Find removed rules
+    let mut seen = HashSet::new();
     for rule in old_rules {
-        let key = (rule.selector.clone(), rule.media_query.clone());
-        old_map.insert(key, rule);
+        let key = rule_key(rule);
+        if !new_map.contains_key(&key) && seen.insert(key) {
+            diff.patches.push(CssPatch::Remove {
+                selector: rule.selector.clone(),
+                at_rules: rule.at_rules.clone(),
+                layer: rule.layer,
+            });
+        }
     }
 
-    // Index new rules by (selector, media_query)
-    let mut new_map: HashMap<(String, Option<String>), &CssRule> = HashMap::new();
+    // Find added and updated rules
+    let mut seen = HashSet::new();
     for rule in new_rules {
-        let key = (rule.selector.clone(), rule.media_query.clone());
-        new_map.insert(key, rule);
-    }
+        let key = rule_key(rule);
+        if !seen.insert(key) {
+            continue;
+        }
+        let new_rule = new_map[&key];
 
-    // Find added and updated rules
-    for (key, new_rule) in &new_map {
-        if let Some(old_rule) = old_map.get(key) {
-            // Rule exists - check if properties changed
-            if old_rule.properties != new_rule.properties {
-                diff.patches.push(CssPatch::Update {
-                    selector: new_rule.selector.clone(),
-                    media_query: new_rule.media_query.clone(),
-                    properties: new_rule.properties.clone(),
-                });
+        match old_map.get(&key) {
+            Some(old_rule) => {
+                if let Some(patch) = diff_properties(old_rule, new_rule) {
+                    diff.patches.push(patch);
+                }
             }
-            // If properties are the same, no patch needed
-        } else {
-            // New rule
-            diff.patches.push(CssPatch::Add {
-                rule: (*new_rule).clone(),
-            });
+            None => diff.patches.push(CssPatch::Add {
+                rule: new_rule.clone(),
+            }),
         }
     }
 
-    // Find removed rules
-    for (key, _old_rule) in &old_map {
-        if !new_map.contains_key(key) {
-            diff.patches.push(CssPatch::Remove {
-                selector: key.0.clone(),
-                media_query: key.1.clone(),
-            });
-        }
+    diff
+}
+
+/// Per-property update for a rule present on both sides, `None` if unchanged
+fn diff_properties(old_rule: &CssRule, new_rule: &CssRule) -> Option<CssPatch> {
+    let properties: Properties = new_rule
+        .properties
+        .iter()
+        .filter(|(name, value)| old_rule.properties.get(*name) != Some(*value))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    let mut removed: Vec<String> = old_rule
+        .properties
+        .keys()
+        .filter(|name| !new_rule.properties.contains_key(*name))
+        .cloned()
+        .collect();
+    removed.sort();
+
+    if properties.is_empty() && removed.is_empty() {
+        return None;
     }
 
-    diff
+    Some(CssPatch::Update {
+        selector: new_rule.selector.clone(),
+        at_rules: new_rule.at_rules.clone(),
+        layer: new_rule.layer,
+        properties,
+        removed,
+    })
 }
 
 /// Apply CSS patches to a rule set (for testing)
@@ -105,28 +164,38 @@ pub fn apply_css_patches(rules: &mut Vec<CssRule>, patches: &[CssPatch]) {
     for patch in patches {
         match patch {
             CssPatch::Add { rule } => {
-                rules.push(rule.clone());
+                // Keep layer order - a new base rule goes before existing variants
+                let index = rules
+                    .iter()
+                    .position(|existing| existing.layer > rule.layer)
+                    .unwrap_or(rules.len());
+                rules.insert(index, rule.clone());
             }
             CssPatch::Update {
                 selector,
-                media_query,
+                at_rules,
+                layer,
                 properties,
+                removed,
             } => {
                 // Find and update the rule
-                if let Some(existing) = rules.iter_mut().find(|r| {
-                    &r.selector == selector && &r.media_query == media_query
-                }) {
-                    existing.properties = properties.clone();
+                if let Some(existing) = rules
+                    .iter_mut()
+                    .find(|r| is_keyed(r, *layer, selector, at_rules))
+                {
+                    existing.properties.extend(properties.clone());
+                    for name in removed {
+                        existing.properties.shift_remove(name);
+                    }
                 }
             }
             CssPatch::Remove {
                 selector,
-                media_query,
+                at_rules,
+                layer,
             } => {
                 // Remove the rule
-                rules.retain(|r| {
-                    &r.selector != selector || &r.media_query != media_query
-                });
+                rules.retain(|r| !is_keyed(r, *layer, selector, at_rules));
             }
         }
     }
@@ -142,7 +211,9 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
 
@@ -157,7 +228,9 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
 
@@ -172,7 +245,9 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
         let new = vec![];
@@ -188,14 +263,18 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
         let new = vec![
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "blue".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
 
@@ -210,21 +289,27 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
 
         let patches = vec![
             CssPatch::Update {
                 selector: ".foo".to_string(),
-                media_query: None,
+                at_rules: vec![],
+                layer: CssLayer::Components,
                 properties: [("color".to_string(), "blue".to_string())].into(),
+                removed: vec![],
             },
             CssPatch::Add {
                 rule: CssRule {
                     selector: ".bar".to_string(),
                     properties: [("background".to_string(), "green".to_string())].into(),
-                    media_query: None,
+                    at_rules: vec![],
+                    global: false,
+                    layer: CssLayer::Components,
                 }
             }
         ];
@@ -242,19 +327,25 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
         let new = vec![
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "blue".to_string())].into(),
-                media_query: Some("@media screen".to_string()),
+                at_rules: vec![AtRule::media("screen")],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
 
@@ -263,4 +354,116 @@ mod tests {
         assert_eq!(diff.patch_count(), 1);
         assert!(matches!(diff.patches[0], CssPatch::Add { .. }));
     }
+
+    #[test]
+    fn test_one_property_edit_sends_one_property() {
+        let rule = |color: &str| CssRule {
+            selector: ".card".to_string(),
+            properties: [
+                ("color".to_string(), color.to_string()),
+                ("padding".to_string(), "8px".to_string()),
+                ("margin".to_string(), "0".to_string()),
+            ]
+            .into(),
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
+        };
+
+        let diff = diff_css_rules(&[rule("red")], &[rule("blue")]);
+
+        assert_eq!(
+            diff.patches,
+            vec![CssPatch::Update {
+                selector: ".card".to_string(),
+                at_rules: vec![],
+                layer: CssLayer::Components,
+                properties: [("color".to_string(), "blue".to_string())].into(),
+                removed: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_removed_property_and_reorder() {
+        let foo = CssRule {
+            selector: ".foo".to_string(),
+            properties: [
+                ("color".to_string(), "red".to_string()),
+                ("margin".to_string(), "0".to_string()),
+            ]
+            .into(),
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
+        };
+        let bar = CssRule {
+            selector: ".bar".to_string(),
+            properties: [("color".to_string(), "red".to_string())].into(),
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
+        };
+        let mut foo_without_margin = foo.clone();
+        foo_without_margin.properties.shift_remove("margin");
+
+        // Reordering alone is not a change
+        let diff = diff_css_rules(&[foo.clone(), bar.clone()], &[bar.clone(), foo.clone()]);
+        assert!(diff.is_empty());
+
+        let diff = diff_css_rules(&[foo, bar.clone()], &[bar, foo_without_margin.clone()]);
+        assert_eq!(
+            diff.patches,
+            vec![CssPatch::Update {
+                selector: ".foo".to_string(),
+                at_rules: vec![],
+                layer: CssLayer::Components,
+                properties: Properties::new(),
+                removed: vec!["margin".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_same_selector_in_different_layers() {
+        let rule = |selector: &str, layer: CssLayer, name: &str, value: &str| CssRule {
+            selector: selector.to_string(),
+            properties: [(name.to_string(), value.to_string())].into(),
+            at_rules: vec![],
+            global: false,
+            layer,
+        };
+        let rules = |color: &str| {
+            vec![
+                rule(":root", CssLayer::Tokens, "--brand", color),
+                rule(":root", CssLayer::Mixins, "--base-padding", "8px"),
+                rule(".card", CssLayer::Components, "color", "var(--brand)"),
+                rule(".card", CssLayer::Variants, "color", "white"),
+            ]
+        };
+
+        // Both `:root` rules are kept apart
+        let diff = diff_css_rules(&[], &rules("red"));
+        assert_eq!(diff.patch_count(), 4);
+        assert!(diff
+            .patches
+            .iter()
+            .all(|patch| matches!(patch, CssPatch::Add { .. })));
+
+        let diff = diff_css_rules(&rules("red"), &rules("blue"));
+        assert_eq!(
+            diff.patches,
+            vec![CssPatch::Update {
+                selector: ":root".to_string(),
+                at_rules: vec![],
+                layer: CssLayer::Tokens,
+                properties: [("--brand".to_string(), "blue".to_string())].into(),
+                removed: vec![],
+            }]
+        );
+
+        let mut applied = rules("red");
+        apply_css_patches(&mut applied, &diff.patches);
+        assert_eq!(applied, rules("blue"));
+    }
 }
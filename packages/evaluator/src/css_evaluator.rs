@@ -1,16 +1,23 @@
-use crate::utils::get_style_namespace;
+use crate::raw_css::{keyframes_name, keyframes_names, parse_raw_css, scope_animation_names};
+use crate::token_resolver::{ResolvedToken, TokenResolver, TokenScope};
+use crate::class_names::{ClassNameStrategy, ClassNames};
+use crate::css_at_rules::{close_at_rules, open_at_rules, AtRule};
+use crate::css_layers::{layer_order_statement, CssLayer};
+use crate::css_splitter::ComponentStylesheets;
+use crate::css_tree_shaker::{StyleUsage, TreeShakeReport};
 use paperclip_bundle::Bundle;
+use indexmap::IndexMap;
 use paperclip_parser::ast::*;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tracing::{debug, info, instrument};
 
 pub type CssResult<T> = Result<T, CssError>;
 
-/// Extract the style name from a potentially namespaced reference.
+/// Extract the style or token name from a potentially namespaced reference.
 /// "colors.primary" -> "primary", "myStyle" -> "myStyle"
-fn extract_style_name(extend_ref: &str) -> &str {
+pub(crate) fn extract_style_name(extend_ref: &str) -> &str {
     extend_ref.rsplit('.').next().unwrap_or(extend_ref)
 }
 
@@ -21,14 +28,78 @@ pub enum CssError {
 
     #[error("Token '{name}' not found")]
     TokenNotFound { name: String },
+
+    #[error("Token cycle detected: {cycle}")]
+    TokenCycle { cycle: String },
+
+    #[error("Invalid value for token '{name}': {message}")]
+    InvalidTokenValue { name: String, message: String },
+
+    #[error("Unsupported at-rule in trigger '{trigger}' (expected @media, @container, @supports or @layer)")]
+    UnsupportedTrigger { trigger: String },
+
+    #[error("Invalid CSS block at line {line}, column {column}: {message}")]
+    InvalidRawCss {
+        message: String,
+        line: u32,
+        column: u32,
+    },
+}
+
+/// One way a variant can be activated
+enum TriggerCondition {
+    /// Selector applied to the element (`:hover`, `.dark`, `[data-open]`)
+    Selector(String),
+    /// Conditional at-rule the rule is nested in (`@container card (min-width: 400px)`)
+    AtRule(AtRule),
+}
+
+impl TriggerCondition {
+    /// Add this condition to a rule's selector and at-rule stack
+    fn apply(&self, selector: &str, at_rules: &[AtRule]) -> (String, Vec<AtRule>) {
+        match self {
+            TriggerCondition::Selector(trigger) => {
+                let selector = if trigger.starts_with('.') || trigger.starts_with(':') {
+                    format!("{}{}", selector, trigger)
+                } else {
+                    format!("{} {}", selector, trigger)
+                };
+                (selector, at_rules.to_vec())
+            }
+            TriggerCondition::AtRule(at_rule) => {
+                let mut at_rules = at_rules.to_vec();
+                at_rules.push(at_rule.clone());
+                (selector.to_string(), at_rules)
+            }
+        }
+    }
 }
 
 /// CSS rule with selector and properties
 #[derive(Debug, Clone, PartialEq)]
 pub struct CssRule {
     pub selector: String,
-    pub properties: HashMap<String, String>,
-    pub media_query: Option<String>,
+    /// Declarations in the order they're written out
+    pub properties: Properties,
+    /// Enclosing at-rules, outermost first
+    pub at_rules: Vec<AtRule>,
+    /// Unscoped rule from a `global style` declaration
+    pub global: bool,
+    /// Cascade layer - decides priority between generated rules
+    pub layer: CssLayer,
+    /// The .pc node the rule was generated from (for source maps)
+    pub span: Option<Span>,
+}
+
+/// Where a rule's selector was written in generated CSS
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssMapping {
+    /// 0-indexed line in the generated CSS
+    pub line: u32,
+    /// 0-indexed column (in characters) in the generated CSS
+    pub column: u32,
+    /// The .pc node the rule was generated from
+    pub span: Span,
 }
 
 /// CSS document - collection of CSS rules
@@ -46,29 +117,114 @@ impl VirtualCssDocument {
         self.rules.push(rule);
     }
 
-    /// Convert to CSS text
+    /// Rules in cascade layer order (source order within a layer)
+    pub fn layered_rules(&self) -> Vec<&CssRule> {
+        let mut rules: Vec<&CssRule> = self.rules.iter().collect();
+        rules.sort_by_key(|rule| rule.layer);
+        rules
+    }
+
+    /// Convert to CSS text in source order. Without `@layer` blocks, moving
+    /// rules between layers would change which one wins.
     pub fn to_css(&self) -> String {
+        self.write_css(false).0
+    }
+
+    /// Convert to CSS text with each layer wrapped in a named `@layer` block,
+    /// so priority holds even if the stylesheet is concatenated out of order
+    pub fn to_layered_css(&self) -> String {
+        self.write_css(true).0
+    }
+
+    /// Convert to CSS text (layered or not), along with where each rule that
+    /// carries a span was written
+    pub fn to_css_with_mappings(&self, layered: bool) -> (String, Vec<CssMapping>) {
+        let (css, written) = self.write_css(layered);
+
+        let mut mappings = Vec::with_capacity(written.len());
+        let mut positions = written.into_iter().peekable();
+        let (mut line, mut column) = (0, 0);
+        for (offset, ch) in css.char_indices() {
+            while let Some((_, span)) = positions.next_if(|(rule_offset, _)| *rule_offset == offset) {
+                mappings.push(CssMapping {
+                    line,
+                    column,
+                    span: span.clone(),
+                });
+            }
+            if ch == '\n' {
+                line += 1;
+                column = 0;
+            } else {
+                column += 1;
+            }
+        }
+
+        (css, mappings)
+    }
+
+    /// Write every rule, returning the byte offset of each spanned rule's selector
+    fn write_css(&self, layered: bool) -> (String, Vec<(usize, &Span)>) {
         let mut css = String::new();
+        let mut written = Vec::new();
 
-        for rule in &self.rules {
-            css.push_str(&rule.selector);
-            css.push_str(" {\n");
+        if !layered {
+            for rule in &self.rules {
+                let offset = write_rule(&mut css, rule, "");
+                written.extend(rule.span.as_ref().map(|span| (offset, span)));
+            }
+            return (css, written);
+        }
 
-            for (key, value) in &rule.properties {
-                css.push_str("  ");
-                css.push_str(key);
-                css.push_str(": ");
-                css.push_str(value);
-                css.push_str(";\n");
+        let rules = self.layered_rules();
+        css.push_str(&layer_order_statement());
+        for layer in CssLayer::ALL {
+            let layer_rules: Vec<&CssRule> =
+                rules.iter().copied().filter(|rule| rule.layer == layer).collect();
+            if layer_rules.is_empty() {
+                continue;
             }
 
-            css.push_str("}\n\n");
+            css.push('\n');
+            css.push_str(&format!("@layer {} {{\n", layer));
+            for rule in layer_rules {
+                let offset = write_rule(&mut css, rule, "  ");
+                written.extend(rule.span.as_ref().map(|span| (offset, span)));
+            }
+            css.push_str("}\n");
         }
 
-        css
+        (css, written)
     }
 }
 
+/// Append a rule (nested in its at-rules, if any) at the given indent,
+/// returning the byte offset its selector starts at
+fn write_rule(css: &mut String, rule: &CssRule, indent: &str) -> usize {
+    let inner = open_at_rules(css, &rule.at_rules, indent);
+
+    css.push_str(&inner);
+    let selector_offset = css.len();
+    css.push_str(&rule.selector);
+    css.push_str(" {\n");
+
+    for (key, value) in &rule.properties {
+        css.push_str(&inner);
+        css.push_str("  ");
+        css.push_str(key);
+        css.push_str(": ");
+        css.push_str(value);
+        css.push_str(";\n");
+    }
+
+    css.push_str(&inner);
+    css.push_str("}\n");
+    close_at_rules(css, &rule.at_rules, indent);
+    css.push('\n');
+
+    selector_offset
+}
+
 impl Default for VirtualCssDocument {
     fn default() -> Self {
         Self::new()
@@ -77,9 +233,58 @@ impl Default for VirtualCssDocument {
 
 /// CSS Evaluator - extracts styles from PC components
 pub struct CssEvaluator {
+    token_decls: Vec<TokenDecl>,
+    /// Computed values of the tokens visible from the entry document, by name
     tokens: HashMap<String, String>,
+    /// Resolved tokens keyed by (document, name)
+    resolved_tokens: TokenScope<ResolvedToken>,
+    /// Document token references are currently resolved from
+    token_scope: PathBuf,
+    /// `@keyframes` declared by the current document -> emitted (scoped) names
+    keyframes: HashMap<String, String>,
+    /// Problems that didn't stop evaluation (unresolved token references)
+    warnings: Vec<CssError>,
     triggers: HashMap<String, Vec<String>>,  // trigger name -> selectors
     document_id: String,
+    class_names: ClassNames,
+    /// `class_names` came from the caller (a whole build) and is kept as-is
+    preassigned_class_names: bool,
+    /// Reachable styles for dead style elimination (`None` keeps everything)
+    usage: Option<StyleUsage>,
+    tree_shake_report: TreeShakeReport,
+    /// Emit imported files' global styles along with the entry's own
+    imported_globals: bool,
+}
+
+/// Styles `extends` can reach from a bundle document: its own and the public
+/// styles of the files it imports
+fn bundle_styles(bundle: &Bundle, doc: &Document, path: &Path) -> Vec<StyleDecl> {
+    let mut all_styles = doc.styles.clone();
+    if let Some(deps) = bundle.get_dependencies(path) {
+        for dep_path in deps {
+            if let Some(dep_doc) = bundle.get_document(dep_path) {
+                for style in &dep_doc.styles {
+                    if style.public {
+                        all_styles.push(style.clone());
+                    }
+                }
+            }
+        }
+    }
+    all_styles
+}
+
+/// `@keyframes` declared in a document's raw CSS blocks -> their emitted names
+fn document_keyframes(doc: &Document, document_id: &str) -> HashMap<String, String> {
+    doc.raw_css
+        .iter()
+        .chain(doc.components.iter().flat_map(|c| &c.raw_css))
+        .flat_map(|block| keyframes_names(&block.source))
+        .map(|name| {
+            let scoped = keyframes_name(&name, document_id);
+            (name, scoped)
+        })
+        .collect()
 }
 
 impl CssEvaluator {
@@ -88,33 +293,210 @@ impl CssEvaluator {
     }
 
     pub fn with_document_id(path: &str) -> Self {
-        let document_id = paperclip_parser::get_document_id(path);
+        Self::from_document_id(paperclip_parser::get_document_id(path))
+    }
+
+    /// Evaluator for a document whose ID (not path) is already known
+    pub(crate) fn from_document_id(document_id: String) -> Self {
         Self {
+            token_decls: Vec::new(),
             tokens: HashMap::new(),
+            resolved_tokens: TokenScope::default(),
+            token_scope: PathBuf::new(),
+            keyframes: HashMap::new(),
+            warnings: Vec::new(),
             triggers: HashMap::new(),
             document_id,
+            class_names: ClassNames::hashed(),
+            preassigned_class_names: false,
+            usage: None,
+            tree_shake_report: TreeShakeReport::default(),
+            imported_globals: false,
         }
     }
 
+    /// Choose how generated class names are formed (default: hashed)
+    pub fn with_class_name_strategy(mut self, strategy: ClassNameStrategy) -> Self {
+        self.class_names = ClassNames::unassigned(strategy);
+        self.preassigned_class_names = false;
+        self
+    }
+
+    /// Use names already assigned across a whole build (`ClassNames::for_bundle`)
+    /// instead of assigning them from the evaluated document, so collision
+    /// suffixes match the other files' output
+    pub fn with_class_names(mut self, class_names: ClassNames) -> Self {
+        self.class_names = class_names;
+        self.preassigned_class_names = true;
+        self
+    }
+
+    /// Also emit the global styles of every file the entry imports. By default
+    /// each file's global styles are only emitted by that file's own stylesheet,
+    /// so a build loading several stylesheets gets them once; previews load the
+    /// entry's stylesheet alone and need them inlined.
+    pub fn with_imported_globals(mut self) -> Self {
+        self.imported_globals = true;
+        self
+    }
+
+    /// Only emit tokens, styles and components in `usage` when evaluating
+    /// bundle documents (production builds)
+    pub fn with_style_usage(mut self, usage: StyleUsage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
     pub fn document_id(&self) -> &str {
         &self.document_id
     }
 
-    /// Get the registered tokens (for testing/debugging)
+    /// Class names assigned by the last evaluation
+    pub fn class_names(&self) -> &ClassNames {
+        &self.class_names
+    }
+
+    /// What the last bundle evaluation dropped as unused
+    pub fn tree_shake_report(&self) -> &TreeShakeReport {
+        &self.tree_shake_report
+    }
+
+    /// Get the registered tokens with their computed values (for testing/debugging)
     pub fn tokens(&self) -> &HashMap<String, String> {
         &self.tokens
     }
 
+    /// Problems the last evaluation worked around, such as token references
+    /// that couldn't be found and were passed through
+    pub fn warnings(&self) -> &[CssError] {
+        &self.warnings
+    }
+
+    /// Whether `reference` (`primary`, `theme.primary`) names a token visible
+    /// from the current document
+    pub(crate) fn is_token(&self, reference: &str) -> bool {
+        self.resolved_tokens
+            .get(reference, &self.token_scope)
+            .is_some()
+    }
+
+    /// Register a token declaration (later registrations shadow earlier ones)
+    fn register_token(&mut self, token: &TokenDecl) {
+        self.token_decls.retain(|t| t.name != token.name);
+        self.token_decls.push(token.clone());
+    }
+
+    /// Resolve all registered tokens (aliases, math, color functions)
+    fn resolve_tokens(&mut self) -> CssResult<()> {
+        let resolved = TokenResolver::new(&self.token_decls).resolve_scope()?;
+        self.set_resolved_tokens(resolved, PathBuf::new());
+        Ok(())
+    }
+
+    /// Use `resolved` for lookups from `entry`, reporting references that
+    /// couldn't be found
+    fn set_resolved_tokens(&mut self, resolved: TokenScope<ResolvedToken>, entry: PathBuf) {
+        // Imported tokens first so the entry's own shadow them
+        let mut visible: Vec<(&Path, &str, &ResolvedToken)> = resolved
+            .iter()
+            .filter(|(path, name, _)| *path == entry || resolved.is_public(path, name))
+            .collect();
+        visible.sort_by_key(|(path, name, _)| (*path == entry, *path, *name));
+        self.tokens = visible
+            .into_iter()
+            .map(|(_, name, token)| (name.to_string(), token.value.clone()))
+            .collect();
+
+        let mut unresolved: Vec<&String> = resolved
+            .iter()
+            .flat_map(|(_, _, token)| &token.unresolved)
+            .collect();
+        unresolved.sort();
+        unresolved.dedup();
+        self.warnings = unresolved
+            .into_iter()
+            .map(|name| CssError::TokenNotFound { name: name.clone() })
+            .collect();
+
+        self.resolved_tokens = resolved;
+        self.token_scope = entry;
+    }
+
+    /// Resolve token references and keyframes names as the bundle document
+    /// `path` sees them while running `f`
+    fn with_document_scope<T>(
+        &mut self,
+        bundle: &Bundle,
+        path: &Path,
+        f: impl FnOnce(&mut Self) -> T,
+    ) -> T {
+        let keyframes = match bundle.get_document(path) {
+            Some(doc) => document_keyframes(doc, self.bundle_document_id(bundle, path)),
+            None => HashMap::new(),
+        };
+        let previous_keyframes = std::mem::replace(&mut self.keyframes, keyframes);
+        let previous_scope = std::mem::replace(&mut self.token_scope, path.to_path_buf());
+        let result = f(self);
+        self.token_scope = previous_scope;
+        self.keyframes = previous_keyframes;
+        result
+    }
+
+    /// ID of a bundle document (what its span IDs and class names are prefixed with)
+    fn bundle_document_id<'a>(&'a self, bundle: &'a Bundle, path: &Path) -> &'a str {
+        bundle.get_document_id(path).unwrap_or(&self.document_id)
+    }
+
+    /// Create a :root rule declaring custom properties for the given tokens
+    fn token_root_rule<'a>(
+        &self,
+        tokens: impl IntoIterator<Item = (&'a Path, &'a TokenDecl)>,
+    ) -> Option<CssRule> {
+        let mut span = None;
+        let mut properties = Properties::new();
+        for (path, token) in tokens {
+            if let Some(resolved) = self.resolved_tokens.declared(path, &token.name) {
+                span.get_or_insert_with(|| token.span.clone());
+                properties.insert(resolved.var_name.clone(), resolved.css_value.clone());
+            }
+        }
+
+        if properties.is_empty() {
+            return None;
+        }
+
+        Some(CssRule {
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Tokens,
+            selector: ":root".to_string(),
+            properties,
+            span,
+        })
+    }
+
     /// Evaluate a document to CSS
-    #[instrument(skip(self, doc), fields(components = doc.components.len(), tokens = doc.tokens.len()))]
     pub fn evaluate(&mut self, doc: &Document) -> CssResult<VirtualCssDocument> {
+        Ok(self.evaluate_split(doc)?.merged())
+    }
+
+    /// Evaluate a document to one stylesheet per public component, plus a
+    /// shared sheet for everything the components rely on
+    #[instrument(skip(self, doc), fields(components = doc.components.len(), tokens = doc.tokens.len()))]
+    pub fn evaluate_split(&mut self, doc: &Document) -> CssResult<ComponentStylesheets> {
         info!("Starting CSS evaluation");
 
+        if !self.preassigned_class_names {
+            self.class_names = ClassNames::for_document(doc, self.class_names.strategy());
+        }
+        self.keyframes = document_keyframes(doc, &self.document_id);
+
         // Register tokens
         for token in &doc.tokens {
             debug!(token_name = %token.name, token_value = %token.value, "Registering CSS token");
-            self.tokens.insert(token.name.clone(), token.value.clone());
+            self.register_token(token);
         }
+        self.resolve_tokens()?;
 
         // Register triggers
         for trigger in &doc.triggers {
@@ -122,40 +504,132 @@ impl CssEvaluator {
             self.triggers.insert(trigger.name.clone(), trigger.selectors.clone());
         }
 
-        let mut css_doc = VirtualCssDocument::new();
+        let stylesheets = self.emit_stylesheets(doc, None, &doc.styles)?;
+
+        info!(
+            rules = stylesheets.total_rules(),
+            "CSS evaluation complete"
+        );
+        Ok(stylesheets)
+    }
+
+    /// Evaluate one bundle document to its own stylesheets, like
+    /// `evaluate_split`, with token references, triggers and `extends`
+    /// resolved through its imports. Imported declarations aren't copied in -
+    /// they're in their own files' sheets. With a `StyleUsage`, unreachable
+    /// tokens, styles and components are left out.
+    #[instrument(skip(self, bundle), fields(entry = %entry_path.display()))]
+    pub fn evaluate_bundle_split(
+        &mut self,
+        bundle: &Bundle,
+        entry_path: &Path,
+    ) -> CssResult<ComponentStylesheets> {
+        info!("Starting bundle document CSS evaluation");
+
+        if !self.preassigned_class_names {
+            self.class_names = ClassNames::for_bundle(bundle, self.class_names.strategy());
+        }
+
+        let entry_doc =
+            bundle
+                .get_document(entry_path)
+                .ok_or_else(|| CssError::EvaluationError {
+                    message: format!("Entry document not found: {}", entry_path.display()),
+                })?;
+        if let Some(doc_id) = bundle.get_document_id(entry_path) {
+            self.document_id = doc_id.to_string();
+        }
+        self.keyframes = document_keyframes(entry_doc, &self.document_id);
+
+        self.register_bundle_tokens(bundle, entry_path)?;
+        self.register_bundle_triggers(bundle, entry_doc, entry_path);
+        self.tree_shake_report = TreeShakeReport::default();
+
+        let all_styles = bundle_styles(bundle, entry_doc, entry_path);
+        let stylesheets = self.emit_stylesheets(entry_doc, Some(entry_path), &all_styles)?;
+
+        self.tree_shake_report.normalize();
+        info!(
+            rules = stylesheets.total_rules(),
+            removed_tokens = self.tree_shake_report.removed_tokens.len(),
+            removed_styles = self.tree_shake_report.removed_styles.len(),
+            removed_components = self.tree_shake_report.removed_components.len(),
+            "Bundle document CSS evaluation complete"
+        );
+        Ok(stylesheets)
+    }
+
+    /// Rules declared by `doc`: a shared sheet (tokens, globals, raw CSS,
+    /// mixins, renders) and one per public component. `bundle_path` is the
+    /// document's path in a bundle - tree shaking only applies there.
+    fn emit_stylesheets(
+        &mut self,
+        doc: &Document,
+        bundle_path: Option<&Path>,
+        all_styles: &[StyleDecl],
+    ) -> CssResult<ComponentStylesheets> {
+        let mut stylesheets = ComponentStylesheets::default();
+        let css_doc = &mut stylesheets.shared;
+        let path = bundle_path.unwrap_or(Path::new(""));
+
+        // Token custom properties
+        let mut tokens = Vec::new();
+        for token in &doc.tokens {
+            if bundle_path.is_none() || self.keep_token(token) {
+                tokens.push((path, token));
+            }
+        }
+        if let Some(rule) = self.token_root_rule(tokens) {
+            css_doc.add_rule(rule);
+        }
+
+        // Global (unscoped) styles
+        for global in &doc.global_styles {
+            css_doc.add_rule(self.evaluate_global_style(global)?);
+        }
+
+        // Raw CSS blocks (document and component level)
+        for rule in self.evaluate_document_raw_css(doc, &self.document_id)? {
+            css_doc.add_rule(rule);
+        }
 
         // Extract global styles with CSS variables
         for style_decl in &doc.styles {
+            if bundle_path.is_some() && !self.keep_style(style_decl) {
+                continue;
+            }
             debug!(style_name = %style_decl.name, "Processing global style");
-            let rules = self.evaluate_style_decl(style_decl, &doc.styles)?;
+            let rules = self.evaluate_style_decl(style_decl, all_styles)?;
             for rule in rules {
                 css_doc.add_rule(rule);
             }
         }
 
-        // Extract component styles
-        for component in &doc.components {
-            if component.public {
-                debug!(component_name = %component.name, "Processing component styles");
-                let rules =
-                    self.evaluate_component_styles(&component.name, component, &doc.styles)?;
-                for rule in rules {
-                    css_doc.add_rule(rule);
-                }
-            }
-        }
-
         // Extract top-level render styles
         for render in &doc.renders {
             let mut rules = Vec::new();
-            self.extract_element_styles(render, None, &mut rules, &doc.styles, &[])?;
+            self.extract_element_styles(render, None, &mut rules, all_styles, &[])?;
             for rule in rules {
                 css_doc.add_rule(rule);
             }
         }
 
-        info!(rules = css_doc.rules.len(), "CSS evaluation complete");
-        Ok(css_doc)
+        // Extract component styles, one sheet each
+        for component in &doc.components {
+            if !component.public {
+                continue;
+            }
+            if bundle_path.is_some() && !self.keep_component(path, component) {
+                continue;
+            }
+            debug!(component_name = %component.name, "Processing component styles");
+            let rules = self.evaluate_component_styles(&component.name, component, all_styles)?;
+            stylesheets
+                .components
+                .push((component.name.clone(), VirtualCssDocument { rules }));
+        }
+
+        Ok(stylesheets)
     }
 
     /// Evaluate a bundle to CSS (supports cross-file imports)
@@ -167,6 +641,10 @@ impl CssEvaluator {
     ) -> CssResult<VirtualCssDocument> {
         info!("Starting bundle CSS evaluation");
 
+        if !self.preassigned_class_names {
+            self.class_names = ClassNames::for_bundle(bundle, self.class_names.strategy());
+        }
+
         // Get entry document
         let entry_doc =
             bundle
@@ -179,45 +657,37 @@ impl CssEvaluator {
         if let Some(doc_id) = bundle.get_document_id(entry_path) {
             self.document_id = doc_id.to_string();
         }
+        self.keyframes = document_keyframes(entry_doc, &self.document_id);
 
-        // Register tokens from entry file
-        for token in &entry_doc.tokens {
-            debug!(token_name = %token.name, token_value = %token.value, "Registering CSS token");
-            self.tokens.insert(token.name.clone(), token.value.clone());
-        }
+        let imported_tokens = self.register_bundle_tokens(bundle, entry_path)?;
+        self.register_bundle_triggers(bundle, entry_doc, entry_path);
+        self.tree_shake_report = TreeShakeReport::default();
 
-        // Register tokens from imported files
-        if let Some(deps) = bundle.get_dependencies(entry_path) {
-            for dep_path in deps {
-                if let Some(dep_doc) = bundle.get_document(dep_path) {
-                    for token in &dep_doc.tokens {
-                        if token.public {
-                            debug!(token_name = %token.name, from_file = %dep_path.display(), "Registering imported token");
-                            self.tokens.insert(token.name.clone(), token.value.clone());
-                        }
-                    }
-                }
-            }
+        let mut css_doc = VirtualCssDocument::new();
+
+        // Token custom properties (imported tokens keep theme overrides working)
+        let tokens: Vec<(&Path, &TokenDecl)> = imported_tokens
+            .into_iter()
+            .chain(entry_doc.tokens.iter().map(|token| (entry_path, token)))
+            .filter(|(_, token)| self.keep_token(token))
+            .collect();
+        if let Some(rule) = self.token_root_rule(tokens) {
+            css_doc.add_rule(rule);
         }
 
-        let mut css_doc = VirtualCssDocument::new();
+        // Global styles (the entry's own, or the whole import graph's for
+        // previews) - each file contributes once, dependencies first
+        let mut visited = HashSet::new();
+        self.collect_global_styles(bundle, entry_path, entry_path, &mut visited, &mut css_doc)?;
 
         // Collect all styles (entry + imported) for extends resolution
-        let mut all_styles = entry_doc.styles.clone();
-        if let Some(deps) = bundle.get_dependencies(entry_path) {
-            for dep_path in deps {
-                if let Some(dep_doc) = bundle.get_document(dep_path) {
-                    for style in &dep_doc.styles {
-                        if style.public {
-                            all_styles.push(style.clone());
-                        }
-                    }
-                }
-            }
-        }
+        let all_styles = bundle_styles(bundle, entry_doc, entry_path);
 
         // Extract global styles from entry file
         for style_decl in &entry_doc.styles {
+            if !self.keep_style(style_decl) {
+                continue;
+            }
             debug!(style_name = %style_decl.name, "Processing global style");
             let rules = self.evaluate_style_decl(style_decl, &all_styles)?;
             for rule in rules {
@@ -231,9 +701,11 @@ impl CssEvaluator {
             for dep_path in deps {
                 if let Some(dep_doc) = bundle.get_document(dep_path) {
                     for style_decl in &dep_doc.styles {
-                        if style_decl.public {
+                        if style_decl.public && self.keep_style(style_decl) {
                             debug!(style_name = %style_decl.name, from_file = %dep_path.display(), "Processing imported global style");
-                            let rules = self.evaluate_style_decl(style_decl, &all_styles)?;
+                            let rules = self.with_document_scope(bundle, dep_path, |evaluator| {
+                                evaluator.evaluate_style_decl(style_decl, &all_styles)
+                            })?;
                             for rule in rules {
                                 css_doc.add_rule(rule);
                             }
@@ -245,7 +717,7 @@ impl CssEvaluator {
 
         // Extract component styles from entry file
         for component in &entry_doc.components {
-            if component.public {
+            if component.public && self.keep_component(entry_path, component) {
                 debug!(component_name = %component.name, "Processing component styles");
                 let rules =
                     self.evaluate_component_styles(&component.name, component, &all_styles)?;
@@ -255,13 +727,218 @@ impl CssEvaluator {
             }
         }
 
+        self.tree_shake_report.normalize();
         info!(
             rules = css_doc.rules.len(),
+            removed_tokens = self.tree_shake_report.removed_tokens.len(),
+            removed_styles = self.tree_shake_report.removed_styles.len(),
+            removed_components = self.tree_shake_report.removed_components.len(),
             "Bundle CSS evaluation complete"
         );
         Ok(css_doc)
     }
 
+    /// Whether a token's custom property is emitted, recording it as removed if not
+    fn keep_token(&mut self, token: &TokenDecl) -> bool {
+        let keep = self.usage.as_ref().is_none_or(|usage| usage.uses_token(&token.name));
+        if !keep {
+            self.tree_shake_report.removed_tokens.push(token.name.clone());
+        }
+        keep
+    }
+
+    /// Whether a style declaration is emitted, recording it as removed if not
+    fn keep_style(&mut self, style: &StyleDecl) -> bool {
+        let keep = self.usage.as_ref().is_none_or(|usage| usage.uses_style(&style.name));
+        if !keep {
+            self.tree_shake_report.removed_styles.push(style.name.clone());
+        }
+        keep
+    }
+
+    /// Whether a component's rules are emitted, recording it as removed if not
+    fn keep_component(&mut self, path: &Path, component: &Component) -> bool {
+        let keep = self
+            .usage
+            .as_ref()
+            .is_none_or(|usage| usage.uses_component(path, &component.name));
+        if !keep {
+            self.tree_shake_report
+                .removed_components
+                .push(component.name.clone());
+        }
+        keep
+    }
+
+    /// Resolve the tokens of `entry_path` and everything it imports, directly
+    /// or not. Public imported tokens are returned for the `:root` rule, since
+    /// the entry's `var()` chains can reach any of them.
+    pub(crate) fn register_bundle_tokens<'a>(
+        &mut self,
+        bundle: &'a Bundle,
+        entry_path: &Path,
+    ) -> CssResult<Vec<(&'a Path, &'a TokenDecl)>> {
+        let decls = TokenScope::for_bundle(bundle, entry_path);
+        let mut imported_tokens: Vec<(&'a Path, &'a TokenDecl)> = Vec::new();
+        for (path, name, token) in decls.iter() {
+            if path != entry_path && decls.is_public(path, name) {
+                debug!(token_name = %name, from_file = %path.display(), "Registering imported token");
+                if let Some(dep_path) = bundle.documents().get_key_value(path).map(|(p, _)| p) {
+                    imported_tokens.push((dep_path.as_path(), *token));
+                }
+            }
+        }
+        imported_tokens.sort_by_key(|(path, token)| (*path, token.span.start));
+
+        let resolved = TokenResolver::with_scope(decls).resolve_scope()?;
+        self.set_resolved_tokens(resolved, entry_path.to_path_buf());
+
+        Ok(imported_tokens)
+    }
+
+    /// Register triggers declared in the entry file and public triggers it imports
+    pub(crate) fn register_bundle_triggers(
+        &mut self,
+        bundle: &Bundle,
+        entry_doc: &Document,
+        entry_path: &Path,
+    ) {
+        if let Some(deps) = bundle.get_dependencies(entry_path) {
+            for dep_path in deps {
+                if let Some(dep_doc) = bundle.get_document(dep_path) {
+                    for trigger in dep_doc.triggers.iter().filter(|t| t.public) {
+                        self.triggers
+                            .insert(trigger.name.clone(), trigger.selectors.clone());
+                    }
+                }
+            }
+        }
+
+        for trigger in &entry_doc.triggers {
+            self.triggers
+                .insert(trigger.name.clone(), trigger.selectors.clone());
+        }
+    }
+
+    /// Whether `name` is a registered trigger
+    pub(crate) fn is_trigger(&self, name: &str) -> bool {
+        self.triggers.contains_key(name)
+    }
+
+    /// Emit global styles and raw CSS for a file and (first) everything it imports
+    fn collect_global_styles(
+        &mut self,
+        bundle: &Bundle,
+        entry_path: &Path,
+        path: &Path,
+        visited: &mut HashSet<PathBuf>,
+        css_doc: &mut VirtualCssDocument,
+    ) -> CssResult<()> {
+        if !visited.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+
+        if let Some(deps) = bundle.get_dependencies(path) {
+            for dep_path in deps {
+                self.collect_global_styles(bundle, entry_path, dep_path, visited, css_doc)?;
+            }
+        }
+
+        if let Some(doc) = bundle.get_document(path) {
+            let globals: &[GlobalStyle] = if path == entry_path || self.imported_globals {
+                &doc.global_styles
+            } else {
+                &[]
+            };
+            let document_id = self.bundle_document_id(bundle, path).to_string();
+            let rules = self.with_document_scope(bundle, path, |evaluator| -> CssResult<Vec<CssRule>> {
+                let mut rules = Vec::new();
+                for global in globals {
+                    debug!(selector = %global.selector, from_file = %path.display(), "Processing global style");
+                    rules.push(evaluator.evaluate_global_style(global)?);
+                }
+
+                // Raw CSS is scoped to its own document, so it's safe to carry
+                // along with the files that render its components
+                rules.extend(evaluator.evaluate_document_raw_css(doc, &document_id)?);
+                Ok(rules)
+            })?;
+            for rule in rules {
+                css_doc.add_rule(rule);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate every raw CSS block in a document (document level first)
+    fn evaluate_document_raw_css(
+        &self,
+        doc: &Document,
+        document_id: &str,
+    ) -> CssResult<Vec<CssRule>> {
+        let mut rules = Vec::new();
+        let blocks = doc
+            .raw_css
+            .iter()
+            .chain(doc.components.iter().flat_map(|c| &c.raw_css));
+
+        for block in blocks {
+            rules.extend(self.evaluate_raw_css(block, document_id)?);
+        }
+
+        Ok(rules)
+    }
+
+    /// Validate a raw CSS block and scope its selectors and keyframes to the
+    /// declaring document `document_id`
+    fn evaluate_raw_css(&self, block: &RawCssBlock, document_id: &str) -> CssResult<Vec<CssRule>> {
+        let raw_rules = parse_raw_css(&block.source, document_id).map_err(
+            |error| CssError::InvalidRawCss {
+                message: error.message,
+                line: error.line,
+                column: error.column,
+            },
+        )?;
+
+        raw_rules
+            .into_iter()
+            .map(|raw_rule| {
+                let mut properties = Properties::new();
+                for (property, value) in raw_rule.declarations {
+                    let value = self.resolve_property(&property, &value)?;
+                    properties.insert(property, value);
+                }
+
+                Ok(CssRule {
+                    selector: raw_rule.selector,
+                    properties,
+                    at_rules: raw_rule.at_rules,
+                    global: false,
+                    layer: CssLayer::Overrides,
+                    span: Some(block.span.clone()),
+                })
+            })
+            .collect()
+    }
+
+    /// Evaluate a global style - the selector is emitted as-is (no scoping)
+    fn evaluate_global_style(&self, global: &GlobalStyle) -> CssResult<CssRule> {
+        let mut properties = Properties::new();
+        for (property, value) in &global.properties {
+            properties.insert(property.clone(), self.resolve_property(property, value)?);
+        }
+
+        Ok(CssRule {
+            selector: global.selector.clone(),
+            properties,
+            at_rules: vec![],
+            global: true,
+            layer: CssLayer::Globals,
+            span: Some(global.span.clone()),
+        })
+    }
+
     /// Evaluate a style declaration to CSS rules (with CSS variables)
     fn evaluate_style_decl(
         &mut self,
@@ -271,30 +948,33 @@ impl CssEvaluator {
         let mut rules = Vec::new();
 
         // Generate CSS custom properties (variables) for this style
-        let mut variables = HashMap::new();
+        let mut variables = Properties::new();
         for (property, value) in &style_decl.properties {
             let var_name = format!("--{}-{}-{}", style_decl.name, property, style_decl.span.id);
-            let resolved_value = self.resolve_value(value)?;
+            let resolved_value = self.resolve_property(property, value)?;
             variables.insert(var_name, resolved_value);
         }
 
         // Create :root rule with CSS variables if we have properties
         if !variables.is_empty() {
             rules.push(CssRule {
-                            media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Mixins,
                 selector: ":root".to_string(),
                 properties: variables,
+                span: Some(style_decl.span.clone()),
             });
         }
 
         // Create class rule that uses the variables
-        let class_name = get_style_namespace(
+        let class_name = self.class_names.class_name(
             Some(&style_decl.name),
             &style_decl.span.id,
             None, // Not in component context
         );
 
-        let mut class_properties = HashMap::new();
+        let mut class_properties = Properties::new();
 
         // Handle extends - pull in CSS variables from extended styles
         for extend_ref in &style_decl.extends {
@@ -307,7 +987,7 @@ impl CssEvaluator {
                         "--{}-{}-{}",
                         extended_style.name, property, extended_style.span.id
                     );
-                    let resolved_value = self.resolve_value(value)?;
+                    let resolved_value = self.resolve_property(property, value)?;
                     // Reference the variable with fallback
                     let var_value = format!("var({}, {})", var_name, resolved_value);
                     class_properties.insert(property.clone(), var_value);
@@ -318,16 +998,19 @@ impl CssEvaluator {
         // Add local properties (can override extended properties)
         for (property, value) in &style_decl.properties {
             let var_name = format!("--{}-{}-{}", style_decl.name, property, style_decl.span.id);
-            let resolved_value = self.resolve_value(value)?;
+            let resolved_value = self.resolve_property(property, value)?;
             // Use var() with fallback
             let var_value = format!("var({}, {})", var_name, resolved_value);
             class_properties.insert(property.clone(), var_value);
         }
 
         rules.push(CssRule {
-                            media_query: None,
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Mixins,
             selector: format!(".{}", class_name),
             properties: class_properties,
+            span: Some(style_decl.span.clone()),
         });
 
         Ok(rules)
@@ -370,16 +1053,20 @@ impl CssEvaluator {
             } => {
                 // Generate class name using AST ID
                 let class_name =
-                    get_style_namespace(Some(tag_name.as_str()), &span.id, component_name);
+                    self.class_names
+                        .class_name(Some(tag_name.as_str()), &span.id, component_name);
 
                 // Collect styles from style blocks
                 if !styles.is_empty() {
                     // Separate base styles from variant styles
-                    let mut base_properties = HashMap::new();
-                    let mut variant_styles: HashMap<Vec<String>, HashMap<String, String>> = HashMap::new();
+                    let mut base_properties = Properties::new();
+                    let mut variant_styles: IndexMap<Vec<String>, Properties> = IndexMap::new();
+                    // First style block contributing to each rule, for source maps
+                    let mut base_span = None;
+                    let mut variant_spans: HashMap<&[String], &Span> = HashMap::new();
 
                     for style_block in styles {
-                        let mut properties = HashMap::new();
+                        let mut properties = Properties::new();
 
                         // Handle extends - pull in CSS variables from extended styles
                         for extend_ref in &style_block.extends {
@@ -394,7 +1081,7 @@ impl CssEvaluator {
                                         "--{}-{}-{}",
                                         extended_style.name, property, extended_style.span.id
                                     );
-                                    let resolved_value = self.resolve_value(value)?;
+                                    let resolved_value = self.resolve_property(property, value)?;
                                     // Reference the variable with fallback
                                     let var_value =
                                         format!("var({}, {})", var_name, resolved_value);
@@ -405,20 +1092,24 @@ impl CssEvaluator {
 
                         // Add local properties (can override extended properties)
                         for (key, value) in &style_block.properties {
-                            let resolved_value = self.resolve_value(value)?;
+                            let resolved_value = self.resolve_property(key, value)?;
                             properties.insert(key.clone(), resolved_value);
                         }
 
                         // Categorize as base or variant styles
                         if style_block.variants.is_empty() {
                             // Base styles
+                            base_span.get_or_insert(&style_block.span);
                             for (key, value) in properties {
                                 base_properties.insert(key, value);
                             }
                         } else {
                             // Variant styles
+                            variant_spans
+                                .entry(style_block.variants.as_slice())
+                                .or_insert(&style_block.span);
                             variant_styles.entry(style_block.variants.clone())
-                                .or_insert_with(HashMap::new)
+                                .or_default()
                                 .extend(properties);
                         }
                     }
@@ -426,9 +1117,12 @@ impl CssEvaluator {
                     // Generate base CSS rule
                     if !base_properties.is_empty() {
                         rules.push(CssRule {
-                            media_query: None,
+                            at_rules: vec![],
+                            global: false,
+                            layer: CssLayer::Components,
                             selector: format!(".{}", class_name),
                             properties: base_properties,
+                            span: base_span.cloned(),
                         });
                     }
 
@@ -438,93 +1132,30 @@ impl CssEvaluator {
                             continue;
                         }
 
-                        // Resolve trigger selectors for these variants
-                        let mut trigger_selectors = Vec::new();
-
+                        // Every variant in the combination must be active: take one
+                        // condition from each, chaining selectors and nesting at-rules
+                        let mut combinations = vec![(format!(".{}", class_name), Vec::new())];
                         for variant_name in &variant_names {
-                            // Find the variant definition
-                            if let Some(variant_def) = component_variants.iter().find(|v| &v.name == variant_name) {
-                                // Resolve all trigger references in this variant
-                                for trigger_ref in &variant_def.triggers {
-                                    // Check if it's a trigger reference or inline selector
-                                    if let Some(trigger_defs) = self.triggers.get(trigger_ref) {
-                                        // It's a trigger reference - use its selectors
-                                        trigger_selectors.extend(trigger_defs.clone());
-                                    } else {
-                                        // It's an inline selector
-                                        trigger_selectors.push(trigger_ref.clone());
-                                    }
-                                }
-                            } else {
-                                // Variant not found, use name as class
-                                trigger_selectors.push(format!(".{}", variant_name));
-                            }
-                        }
-
-                        // Separate media queries from selectors
-                        let mut media_queries = Vec::new();
-                        let mut regular_selectors = Vec::new();
-
-                        for trigger_selector in &trigger_selectors {
-                            if trigger_selector.starts_with('@') {
-                                media_queries.push(trigger_selector.clone());
-                            } else {
-                                regular_selectors.push(trigger_selector.clone());
-                            }
-                        }
-
-                        // Generate rules for regular selectors
-                        if !regular_selectors.is_empty() {
-                            for selector in &regular_selectors {
-                                let final_selector = if selector.starts_with('.') || selector.starts_with(':') {
-                                    format!(".{}{}", class_name, selector)
-                                } else {
-                                    format!(".{} {}", class_name, selector)
-                                };
-
-                                rules.push(CssRule {
-                                    selector: final_selector,
-                                    properties: properties.clone(),
-                                    media_query: None,
-                                });
-                            }
+                            let conditions =
+                                self.variant_conditions(variant_name, component_variants, &class_name)?;
+                            combinations = combinations
+                                .iter()
+                                .flat_map(|(selector, at_rules)| {
+                                    conditions
+                                        .iter()
+                                        .map(move |condition| condition.apply(selector, at_rules))
+                                })
+                                .collect();
                         }
 
-                        // Generate rules wrapped in media queries
-                        for media_query in &media_queries {
+                        for (selector, at_rules) in combinations {
                             rules.push(CssRule {
-                                selector: format!(".{}", class_name),
+                                selector,
                                 properties: properties.clone(),
-                                media_query: Some(media_query.clone()),
-                            });
-                        }
-
-                        // If both exist, generate combined rule (media query + selector)
-                        if !media_queries.is_empty() && !regular_selectors.is_empty() {
-                            for media_query in &media_queries {
-                                for selector in &regular_selectors {
-                                    let final_selector = if selector.starts_with('.') || selector.starts_with(':') {
-                                        format!(".{}{}", class_name, selector)
-                                    } else {
-                                        format!(".{} {}", class_name, selector)
-                                    };
-
-                                    rules.push(CssRule {
-                                        selector: final_selector,
-                                        properties: properties.clone(),
-                                        media_query: Some(media_query.clone()),
-                                    });
-                                }
-                            }
-                        }
-
-                        // If no trigger selectors resolved, fall back to variant name
-                        if trigger_selectors.is_empty() {
-                            let variant_classes = variant_names.join(".");
-                            rules.push(CssRule {
-                                selector: format!(".{}.{}", class_name, variant_classes),
-                                properties,
-                                media_query: None,
+                                at_rules,
+                                global: false,
+                                layer: CssLayer::Variants,
+                                span: variant_spans.get(variant_names.as_slice()).map(|span| (*span).clone()),
                             });
                         }
                     }
@@ -566,26 +1197,16 @@ impl CssEvaluator {
                 }
             }
 
-            Element::Text { styles, span, .. } => {
+            Element::Text {
+                styles, runs, span, ..
+            } => {
                 // Handle text with styles (wrapped in span at evaluation time)
-                if !styles.is_empty() {
-                    let class_name =
-                        get_style_namespace(Some("span"), &span.id, component_name);
-
-                    let mut base_properties = HashMap::new();
-                    for style_block in styles {
-                        for (key, value) in &style_block.properties {
-                            let resolved_value = self.resolve_value(value)?;
-                            base_properties.insert(key.clone(), resolved_value);
-                        }
-                    }
+                self.extract_text_styles("span", styles, span, component_name, rules)?;
 
-                    if !base_properties.is_empty() {
-                        rules.push(CssRule {
-                            media_query: None,
-                            selector: format!(".{}", class_name),
-                            properties: base_properties,
-                        });
+                // Tagged rich text runs are classed by their own span id
+                for run in runs {
+                    if let Some(tag) = &run.tag {
+                        self.extract_text_styles(tag, &run.styles, &run.span, component_name, rules)?;
                     }
                 }
             }
@@ -605,22 +1226,151 @@ impl CssEvaluator {
         Ok(())
     }
 
+    /// Conditions that activate a variant (any one of them). Variants without
+    /// triggers are toggled by a modifier class.
+    fn variant_conditions(
+        &self,
+        variant_name: &str,
+        component_variants: &[Variant],
+        class_name: &str,
+    ) -> CssResult<Vec<TriggerCondition>> {
+        let mut triggers = Vec::new();
+        if let Some(variant_def) = component_variants.iter().find(|v| v.name == variant_name) {
+            for trigger_ref in &variant_def.triggers {
+                // Trigger reference, or an inline selector
+                match self.triggers.get(trigger_ref) {
+                    Some(selectors) => triggers.extend(selectors.iter().cloned()),
+                    None => triggers.push(trigger_ref.clone()),
+                }
+            }
+        }
+
+        if triggers.is_empty() {
+            let modifier = self.class_names.modifier(class_name, variant_name);
+            return Ok(vec![TriggerCondition::Selector(format!(".{}", modifier))]);
+        }
+
+        triggers
+            .into_iter()
+            .map(|trigger| {
+                if !trigger.starts_with('@') {
+                    return Ok(TriggerCondition::Selector(trigger));
+                }
+                match AtRule::parse(&trigger) {
+                    Some(at_rule) if at_rule.kind().is_some() => Ok(TriggerCondition::AtRule(at_rule)),
+                    _ => Err(CssError::UnsupportedTrigger { trigger }),
+                }
+            })
+            .collect()
+    }
+
+    /// Extract the base rule for a styled text node or rich text run
+    fn extract_text_styles(
+        &self,
+        tag: &str,
+        styles: &[StyleBlock],
+        span: &Span,
+        component_name: Option<&str>,
+        rules: &mut Vec<CssRule>,
+    ) -> CssResult<()> {
+        if styles.is_empty() {
+            return Ok(());
+        }
+
+        let class_name = self.class_names.class_name(Some(tag), &span.id, component_name);
+
+        let mut base_properties = Properties::new();
+        for style_block in styles {
+            for (key, value) in &style_block.properties {
+                let resolved_value = self.resolve_property(key, value)?;
+                base_properties.insert(key.clone(), resolved_value);
+            }
+        }
+
+        if !base_properties.is_empty() {
+            rules.push(CssRule {
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
+                selector: format!(".{}", class_name),
+                properties: base_properties,
+                span: styles.first().map(|style_block| style_block.span.clone()),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a property value, pointing animations at the current
+    /// document's scoped `@keyframes`
+    fn resolve_property(&self, property: &str, value: &str) -> CssResult<String> {
+        let value = self.resolve_value(value)?;
+        Ok(scope_animation_names(property, &value, &self.keyframes))
+    }
+
     /// Resolve value (handle token references)
-    fn resolve_value(&self, value: &str) -> CssResult<String> {
+    pub(crate) fn resolve_value(&self, value: &str) -> CssResult<String> {
         // Check if value references a token
         if value.starts_with('{') && value.ends_with('}') {
             let token_name = &value[1..value.len() - 1];
-            if let Some(token_value) = self.tokens.get(token_name) {
-                Ok(token_value.clone())
+            if let Some(token) = self.resolved_tokens.get(token_name, &self.token_scope) {
+                Ok(token.value.clone())
             } else {
                 Err(CssError::TokenNotFound {
                     name: token_name.to_string(),
                 })
             }
+        } else if value.contains("var(") {
+            Ok(self.resolve_token_vars(value, true))
         } else {
             Ok(value.to_string())
         }
     }
+
+    /// Resolve a value to what it computes to, with token references replaced
+    /// by the token values themselves
+    pub(crate) fn computed_value(&self, value: &str) -> CssResult<String> {
+        if value.contains("var(") {
+            Ok(self.resolve_token_vars(value, false))
+        } else {
+            self.resolve_value(value)
+        }
+    }
+
+    /// Rewrite `var(token)` / `var(theme.token)` to the token's custom property,
+    /// keeping the computed value as fallback (or to the bare value if `keep_var`
+    /// is false). `var(--custom)` is left untouched.
+    fn resolve_token_vars(&self, value: &str, keep_var: bool) -> String {
+        let mut output = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(start) = rest.find("var(") {
+            let args_start = start + "var(".len();
+            let Some(len) = rest[args_start..].find(')') else {
+                break;
+            };
+            let reference: String = rest[args_start..args_start + len]
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+
+            output.push_str(&rest[..start]);
+            match self.resolved_tokens.get(&reference, &self.token_scope) {
+                Some(token) if !reference.starts_with("--") => {
+                    if keep_var {
+                        output.push_str(&format!("var({}, {})", token.var_name, token.value));
+                    } else {
+                        output.push_str(&token.value);
+                    }
+                }
+                _ => output.push_str(&rest[start..args_start + len + 1]),
+            }
+            rest = &rest[args_start + len + 1..];
+        }
+
+        output.push_str(rest);
+        output
+    }
 }
 
 impl Default for CssEvaluator {
@@ -714,14 +1464,17 @@ mod tests {
     fn test_css_document_to_css() {
         let mut css_doc = VirtualCssDocument::new();
 
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("color".to_string(), "red".to_string());
         properties.insert("font-size".to_string(), "16px".to_string());
 
         css_doc.add_rule(CssRule {
-                media_query: None,
+                at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
             selector: ".button".to_string(),
             properties,
+            span: None,
         });
 
         let css_text = css_doc.to_css();
@@ -731,6 +1484,26 @@ mod tests {
         assert!(css_text.contains("font-size: 16px"));
     }
 
+    #[test]
+    fn test_css_mappings_follow_selectors() {
+        let source = "public component Card {\n    render div {\n        style {\n            color: red\n        }\n    }\n}\n";
+        let doc = parse_with_path(source, "/card.pc").expect("Failed to parse");
+        let css_doc = CssEvaluator::with_document_id("/card.pc")
+            .evaluate(&doc)
+            .expect("Failed to evaluate");
+
+        let (css, mappings) = css_doc.to_css_with_mappings(true);
+        assert_eq!(css, css_doc.to_layered_css());
+        assert_eq!(mappings.len(), 1);
+
+        let selector_line = css.lines().nth(mappings[0].line as usize).unwrap();
+        assert_eq!(
+            &selector_line[mappings[0].column as usize..],
+            format!("{} {{", css_doc.rules[0].selector)
+        );
+        assert_eq!(mappings[0].span.start, source.find("style {").unwrap());
+    }
+
     #[test]
     fn test_evaluate_global_styles() {
         let source = r#"
@@ -870,6 +1643,157 @@ mod tests {
         assert!(has_nested_selectors, "Should have nested selectors");
     }
 
+    #[test]
+    fn test_token_alias_keeps_var_chain() {
+        let source = r#"
+            token primaryColor #3366FF
+            token buttonBg primaryColor
+            token gap 8px * 2
+
+            public component Button {
+                render button {
+                    style {
+                        background: var(buttonBg)
+                        padding: var(gap)
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = CssEvaluator::with_document_id("/test.pc");
+        let css_doc = evaluator.evaluate(&doc).expect("Failed to evaluate");
+
+        let primary_var = format!("--primaryColor-{}", doc.tokens[0].span.id);
+        let button_var = format!("--buttonBg-{}", doc.tokens[1].span.id);
+
+        let root = css_doc
+            .rules
+            .iter()
+            .find(|r| r.selector == ":root")
+            .expect("Should have :root token rule");
+        assert_eq!(root.properties.get(&primary_var), Some(&"#3366FF".to_string()));
+        assert_eq!(
+            root.properties.get(&button_var),
+            Some(&format!("var({}, #3366FF)", primary_var))
+        );
+
+        assert_eq!(evaluator.tokens().get("gap"), Some(&"16px".to_string()));
+
+        let button_rule = css_doc
+            .rules
+            .iter()
+            .find(|r| r.selector.contains("button"))
+            .expect("Should have button rule");
+        assert_eq!(
+            button_rule.properties.get("background"),
+            Some(&format!("var({}, #3366FF)", button_var))
+        );
+        assert!(button_rule.properties["padding"].ends_with(", 16px)"));
+    }
+
+    #[test]
+    fn test_token_cycle_is_reported() {
+        let source = r#"
+            token a b
+            token b a
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = CssEvaluator::with_document_id("/test.pc");
+        let err = evaluator.evaluate(&doc).unwrap_err();
+
+        assert!(matches!(err, CssError::TokenCycle { .. }));
+        assert_eq!(err.to_string(), "Token cycle detected: a -> b -> a");
+    }
+
+    #[test]
+    fn test_missing_imported_token_passes_through() {
+        let source = r#"
+            import "./theme.pc" as theme
+
+            token gutter 8px
+            token b theme.x
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = CssEvaluator::with_document_id("/test.pc");
+        let css_doc = evaluator.evaluate(&doc).expect("Document should still evaluate");
+
+        assert_eq!(evaluator.tokens().get("gutter"), Some(&"8px".to_string()));
+        let warnings: Vec<String> = evaluator.warnings().iter().map(|w| w.to_string()).collect();
+        assert_eq!(warnings, vec!["Token 'theme.x' not found".to_string()]);
+        assert!(css_doc.rules.iter().any(|rule| rule.selector == ":root"));
+    }
+
+    #[test]
+    fn test_raw_css_at_rules_in_css_output() {
+        let source = r#"
+            css """
+            @container (min-width: 400px) {
+                .card { padding: 24px; }
+            }
+            @font-face {
+                font-family: Brand;
+                src: url(brand.woff2);
+            }
+            """
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = CssEvaluator::with_document_id("/test.pc");
+        let css = evaluator.evaluate(&doc).expect("Failed to evaluate").to_css();
+
+        assert!(css.contains("@container (min-width: 400px) {\n  .card[class*="));
+        assert!(css.contains("    padding: 24px;\n  }\n}\n"));
+        assert!(css.contains("@font-face {\n"));
+        assert!(css.contains("  src: url(brand.woff2);\n"));
+    }
+
+    #[test]
+    fn test_global_styles_are_unscoped() {
+        let source = r#"
+            token brand #3366FF
+
+            global {
+                body { margin: 0 }
+                "a:hover" { color: var(brand) }
+            }
+
+            public component Button {
+                render button {
+                    style { color: red }
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = CssEvaluator::with_document_id("/test.pc");
+        let css_doc = evaluator.evaluate(&doc).expect("Failed to evaluate");
+
+        let body = css_doc
+            .rules
+            .iter()
+            .find(|r| r.selector == "body")
+            .expect("Should have unscoped body rule");
+        assert!(body.global);
+        assert_eq!(body.properties.get("margin"), Some(&"0".to_string()));
+
+        let link = css_doc
+            .rules
+            .iter()
+            .find(|r| r.selector == "a:hover")
+            .expect("Should have unscoped a:hover rule");
+        assert!(link.properties["color"].starts_with("var(--brand-"));
+
+        // Component rules stay scoped
+        assert!(css_doc
+            .rules
+            .iter()
+            .filter(|r| r.selector.contains("Button"))
+            .all(|r| !r.global));
+    }
+
     #[test]
     fn test_document_id_in_class_names() {
         let source = r#"
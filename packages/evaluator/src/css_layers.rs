@@ -0,0 +1,100 @@
+//! CSS cascade layers - deterministic style priority
+//!
+//! Every generated rule belongs to a layer. Layers are declared up front in
+//! priority order, so a variant beats a base style because of the layer it
+//! lives in, not because it happens to come later in the stylesheet:
+//!
+//! ```css
+//! @layer tokens, globals, mixins, components, variants, overrides;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Cascade layer of a generated rule, lowest priority first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CssLayer {
+    /// `:root` custom properties for design tokens
+    Tokens,
+    /// `global style` declarations
+    Globals,
+    /// Custom properties and classes for `style` declarations
+    Mixins,
+    /// Base element styles
+    #[default]
+    Components,
+    /// Variant and trigger styles
+    Variants,
+    /// Raw CSS blocks - hand-written escape hatches win over generated styles
+    Overrides,
+}
+
+impl CssLayer {
+    /// All layers in priority order (lowest first)
+    pub const ALL: [CssLayer; 6] = [
+        CssLayer::Tokens,
+        CssLayer::Globals,
+        CssLayer::Mixins,
+        CssLayer::Components,
+        CssLayer::Variants,
+        CssLayer::Overrides,
+    ];
+
+    /// Layer name as it appears in `@layer`
+    pub fn name(&self) -> &'static str {
+        match self {
+            CssLayer::Tokens => "tokens",
+            CssLayer::Globals => "globals",
+            CssLayer::Mixins => "mixins",
+            CssLayer::Components => "components",
+            CssLayer::Variants => "variants",
+            CssLayer::Overrides => "overrides",
+        }
+    }
+}
+
+impl fmt::Display for CssLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for CssLayer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CssLayer::ALL
+            .into_iter()
+            .find(|layer| layer.name() == s)
+            .ok_or_else(|| format!("Unknown CSS layer '{}'", s))
+    }
+}
+
+/// The `@layer` statement that fixes layer priority
+pub fn layer_order_statement() -> String {
+    let names: Vec<&str> = CssLayer::ALL.iter().map(|layer| layer.name()).collect();
+    format!("@layer {};\n", names.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_order_statement() {
+        assert_eq!(
+            layer_order_statement(),
+            "@layer tokens, globals, mixins, components, variants, overrides;\n"
+        );
+    }
+
+    #[test]
+    fn test_layer_priority() {
+        assert!(CssLayer::Variants > CssLayer::Components);
+        assert!(CssLayer::Overrides > CssLayer::Variants);
+        assert_eq!("variants".parse::<CssLayer>(), Ok(CssLayer::Variants));
+        assert!("base".parse::<CssLayer>().is_err());
+    }
+}
@@ -75,9 +75,9 @@ pub fn minify_css_rules(rules: &mut [CssRule]) {
             *value = minify_css_value(value);
         }
 
-        // Minify media query if present
-        if let Some(ref mut mq) = rule.media_query {
-            *mq = mq.trim().to_string();
+        // Minify at-rule preludes
+        for at_rule in &mut rule.at_rules {
+            at_rule.prelude = at_rule.prelude.trim().to_string();
         }
     }
 }
@@ -93,6 +93,7 @@ pub fn calculate_compression_ratio(original_size: usize, minified_size: usize) -
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::css_layers::CssLayer;
     use std::collections::HashMap;
 
     #[test]
@@ -132,7 +133,9 @@ mod tests {
                     ("margin".to_string(), "10px  20px".to_string()),
                     ("color".to_string(), "#ffffff".to_string()),
                 ].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             }
         ];
 
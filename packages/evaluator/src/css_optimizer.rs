@@ -1,23 +1,34 @@
 //! CSS optimizer - deduplicates and merges CSS rules for better performance
 
+use crate::css_at_rules::AtRule;
+use crate::css_layers::CssLayer;
 use crate::vdom::CssRule;
+use paperclip_parser::ast::Properties;
 use std::collections::HashMap;
 
 /// Optimize a list of CSS rules by:
 /// 1. Deduplicating identical rules
-/// 2. Merging rules with same selector + media query
+/// 2. Merging rules with same selector + media query (within a layer)
 /// 3. Removing empty rules
+///
+/// Output is ordered by cascade layer, so variants follow base styles even
+/// without `@layer` support.
 pub fn optimize_css_rules(rules: Vec<CssRule>) -> Vec<CssRule> {
     if rules.is_empty() {
         return rules;
     }
 
-    // Group rules by (selector, media_query)
-    let mut grouped: HashMap<(String, Option<String>), HashMap<String, String>> = HashMap::new();
+    // Group rules by (layer, selector, at_rules)
+    let mut grouped: HashMap<(CssLayer, String, Vec<AtRule>), Properties> =
+        HashMap::new();
+    let mut global_keys = std::collections::HashSet::new();
 
     for rule in rules {
-        let key = (rule.selector.clone(), rule.media_query.clone());
-        let props = grouped.entry(key).or_insert_with(HashMap::new);
+        let key = (rule.layer, rule.selector.clone(), rule.at_rules.clone());
+        if rule.global {
+            global_keys.insert(key.clone());
+        }
+        let props = grouped.entry(key).or_default();
 
         // Merge properties (later properties override earlier ones)
         for (prop_name, prop_value) in rule.properties {
@@ -27,24 +38,27 @@ pub fn optimize_css_rules(rules: Vec<CssRule>) -> Vec<CssRule> {
 
     // Convert back to rules
     let mut optimized = Vec::new();
-    for ((selector, media_query), properties) in grouped {
+    for ((layer, selector, at_rules), properties) in grouped {
         if !properties.is_empty() {
+            let global = global_keys.contains(&(layer, selector.clone(), at_rules.clone()));
             optimized.push(CssRule {
                 selector,
                 properties,
-                media_query,
+                at_rules,
+                global,
+                layer,
             });
         }
     }
 
     // Sort for deterministic output (helps with testing and caching)
     optimized.sort_by(|a, b| {
-        // Sort by media query presence first (no media query first)
-        match (&a.media_query, &b.media_query) {
-            (None, Some(_)) => std::cmp::Ordering::Less,
-            (Some(_), None) => std::cmp::Ordering::Greater,
-            _ => a.selector.cmp(&b.selector),
-        }
+        // Layer first, then unconditional rules before at-rules (shallowest first)
+        a.layer
+            .cmp(&b.layer)
+            .then_with(|| a.at_rules.len().cmp(&b.at_rules.len()))
+            .then_with(|| a.selector.cmp(&b.selector))
+            .then_with(|| a.at_rules.cmp(&b.at_rules))
     });
 
     optimized
@@ -60,12 +74,16 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -81,12 +99,16 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("background".to_string(), "blue".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -104,12 +126,16 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "blue".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -125,12 +151,16 @@ mod tests {
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".foo".to_string(),
                 properties: [("color".to_string(), "blue".to_string())].into(),
-                media_query: Some("@media screen".to_string()),
+                at_rules: vec![AtRule::media("screen")],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -144,13 +174,17 @@ mod tests {
         let rules = vec![
             CssRule {
                 selector: ".foo".to_string(),
-                properties: HashMap::new(),
-                media_query: None,
+                properties: Properties::new(),
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".bar".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -165,12 +199,16 @@ mod tests {
             CssRule {
                 selector: ".zebra".to_string(),
                 properties: [("color".to_string(), "red".to_string())].into(),
-                media_query: Some("@media screen".to_string()),
+                at_rules: vec![AtRule::media("screen")],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: ".apple".to_string(),
                 properties: [("color".to_string(), "blue".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -0,0 +1,437 @@
+//! Vendor prefixing - add `-webkit-`/`-moz-` declarations for the configured browser targets
+//!
+//! Works from an embedded compatibility table, so builds never hit the network. Targets
+//! use a small subset of browserslist syntax: `"safari >= 14"` or `"firefox 90"`.
+
+use crate::css_evaluator::{CssRule as EvaluatedCssRule, VirtualCssDocument};
+use crate::vdom::CssRule;
+use paperclip_parser::ast::Properties;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use Browser::*;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum PrefixError {
+    #[error("Invalid browser target '{query}': {message}")]
+    InvalidTarget { query: String, message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Browser {
+    Chrome,
+    Edge,
+    Firefox,
+    Safari,
+    IosSafari,
+    Samsung,
+    Opera,
+}
+
+impl FromStr for Browser {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "chrome" => Ok(Browser::Chrome),
+            "edge" => Ok(Browser::Edge),
+            "firefox" | "ff" => Ok(Browser::Firefox),
+            "safari" => Ok(Browser::Safari),
+            "ios" | "ios_saf" => Ok(Browser::IosSafari),
+            "samsung" => Ok(Browser::Samsung),
+            "opera" => Ok(Browser::Opera),
+            other => Err(format!("unknown browser '{}'", other)),
+        }
+    }
+}
+
+/// Browser version (`major.minor`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(pub u16, pub u16);
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid version '{}'", s);
+        let (major, minor) = s.split_once('.').unwrap_or((s, "0"));
+        Ok(Version(
+            major.parse().map_err(|_| invalid())?,
+            minor.parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.0, self.1)
+    }
+}
+
+/// Oldest version of a browser that must be supported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BrowserTarget {
+    pub browser: Browser,
+    pub version: Version,
+}
+
+impl FromStr for BrowserTarget {
+    type Err = PrefixError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let invalid = |message: String| PrefixError::InvalidTarget {
+            query: query.to_string(),
+            message,
+        };
+
+        let parts: Vec<&str> = query.split_whitespace().collect();
+        let (browser, version) = match parts.as_slice() {
+            [browser, ">=", version] | [browser, version] => (browser, version),
+            _ => return Err(invalid("expected '<browser> >= <version>'".to_string())),
+        };
+
+        Ok(BrowserTarget {
+            browser: browser.parse().map_err(invalid)?,
+            version: version.parse().map_err(invalid)?,
+        })
+    }
+}
+
+/// Browsers the generated CSS has to work in. Empty targets add no prefixes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BrowserTargets {
+    targets: Vec<BrowserTarget>,
+}
+
+impl BrowserTargets {
+    pub fn new(targets: Vec<BrowserTarget>) -> Self {
+        Self { targets }
+    }
+
+    /// Parse queries like `["chrome >= 90", "safari >= 14"]`
+    pub fn parse<S: AsRef<str>>(queries: &[S]) -> Result<Self, PrefixError> {
+        let targets = queries
+            .iter()
+            .map(|query| query.as_ref().parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Self { targets })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.targets.is_empty()
+    }
+
+    pub fn targets(&self) -> &[BrowserTarget] {
+        &self.targets
+    }
+
+    /// Whether some target is `browser` older than `since` (`None`: no version is new enough)
+    fn needs(&self, browser: Browser, since: Option<Version>) -> bool {
+        self.targets.iter().any(|target| {
+            target.browser == browser && since.is_none_or(|since| target.version < since)
+        })
+    }
+}
+
+/// `property` needs `prefix` in `browser` before `unprefixed_since`
+struct PropertyPrefix {
+    property: &'static str,
+    prefix: &'static str,
+    browser: Browser,
+    unprefixed_since: Option<Version>,
+}
+
+const fn prop(
+    property: &'static str,
+    prefix: &'static str,
+    browser: Browser,
+    unprefixed_since: Option<Version>,
+) -> PropertyPrefix {
+    PropertyPrefix {
+        property,
+        prefix,
+        browser,
+        unprefixed_since,
+    }
+}
+
+const WEBKIT: &str = "-webkit-";
+const MOZ: &str = "-moz-";
+
+#[rustfmt::skip]
+const PROPERTY_PREFIXES: &[PropertyPrefix] = &[
+    prop("user-select", WEBKIT, Safari, None),
+    prop("user-select", WEBKIT, IosSafari, None),
+    prop("user-select", WEBKIT, Chrome, Some(Version(54, 0))),
+    prop("user-select", WEBKIT, Edge, Some(Version(79, 0))),
+    prop("user-select", WEBKIT, Opera, Some(Version(41, 0))),
+    prop("user-select", WEBKIT, Samsung, Some(Version(6, 0))),
+    prop("user-select", MOZ, Firefox, Some(Version(69, 0))),
+
+    prop("backdrop-filter", WEBKIT, Safari, Some(Version(18, 0))),
+    prop("backdrop-filter", WEBKIT, IosSafari, Some(Version(18, 0))),
+
+    prop("appearance", WEBKIT, Chrome, Some(Version(84, 0))),
+    prop("appearance", WEBKIT, Edge, Some(Version(84, 0))),
+    prop("appearance", WEBKIT, Opera, Some(Version(70, 0))),
+    prop("appearance", WEBKIT, Samsung, Some(Version(14, 0))),
+    prop("appearance", WEBKIT, Safari, Some(Version(15, 4))),
+    prop("appearance", WEBKIT, IosSafari, Some(Version(15, 4))),
+    prop("appearance", MOZ, Firefox, Some(Version(80, 0))),
+
+    prop("text-size-adjust", WEBKIT, Safari, None),
+    prop("text-size-adjust", WEBKIT, IosSafari, None),
+
+    prop("hyphens", WEBKIT, Safari, Some(Version(17, 0))),
+    prop("hyphens", WEBKIT, IosSafari, Some(Version(17, 0))),
+    prop("hyphens", MOZ, Firefox, Some(Version(43, 0))),
+
+    prop("mask-image", WEBKIT, Chrome, Some(Version(120, 0))),
+    prop("mask-image", WEBKIT, Edge, Some(Version(120, 0))),
+    prop("mask-image", WEBKIT, Safari, Some(Version(15, 4))),
+    prop("mask-image", WEBKIT, IosSafari, Some(Version(15, 4))),
+
+    prop("clip-path", WEBKIT, Chrome, Some(Version(55, 0))),
+    prop("clip-path", WEBKIT, Safari, Some(Version(13, 1))),
+    prop("clip-path", WEBKIT, IosSafari, Some(Version(13, 0))),
+
+    prop("box-decoration-break", WEBKIT, Chrome, Some(Version(130, 0))),
+    prop("box-decoration-break", WEBKIT, Edge, Some(Version(130, 0))),
+    prop("box-decoration-break", WEBKIT, Safari, None),
+    prop("box-decoration-break", WEBKIT, IosSafari, None),
+
+    prop("tab-size", MOZ, Firefox, Some(Version(91, 0))),
+];
+
+/// `value` of `properties` falls back to `fallback` in `browser` before `supported_since`
+struct ValueFallback {
+    properties: &'static [&'static str],
+    value: &'static str,
+    fallback: &'static str,
+    browser: Browser,
+    supported_since: Version,
+}
+
+const SIZES: &[&str] = &[
+    "width",
+    "min-width",
+    "max-width",
+    "height",
+    "min-height",
+    "max-height",
+];
+
+const VALUE_FALLBACKS: &[ValueFallback] = &[
+    ValueFallback {
+        properties: &["position"],
+        value: "sticky",
+        fallback: "-webkit-sticky",
+        browser: Safari,
+        supported_since: Version(13, 0),
+    },
+    ValueFallback {
+        properties: &["position"],
+        value: "sticky",
+        fallback: "-webkit-sticky",
+        browser: IosSafari,
+        supported_since: Version(13, 0),
+    },
+    ValueFallback {
+        properties: SIZES,
+        value: "fit-content",
+        fallback: "-moz-fit-content",
+        browser: Firefox,
+        supported_since: Version(94, 0),
+    },
+];
+
+/// Add prefixed copies of properties the targets don't support unprefixed.
+/// Prefixed properties the author already wrote are left alone.
+pub fn prefix_properties(properties: &mut Properties, targets: &BrowserTargets) {
+    if targets.is_empty() {
+        return;
+    }
+
+    let mut prefixed = Vec::new();
+    for entry in PROPERTY_PREFIXES {
+        let Some(value) = properties.get(entry.property) else {
+            continue;
+        };
+        if targets.needs(entry.browser, entry.unprefixed_since) {
+            prefixed.push((format!("{}{}", entry.prefix, entry.property), value.clone()));
+        }
+    }
+
+    for (name, value) in prefixed {
+        properties.entry(name).or_insert(value);
+    }
+}
+
+/// Declarations to emit before `properties` for targets that don't understand their values
+pub fn value_fallbacks(
+    properties: &Properties,
+    targets: &BrowserTargets,
+) -> Properties {
+    let mut fallbacks = Properties::new();
+    if targets.is_empty() {
+        return fallbacks;
+    }
+
+    for entry in VALUE_FALLBACKS {
+        if !targets.needs(entry.browser, Some(entry.supported_since)) {
+            continue;
+        }
+        for property in entry.properties {
+            if properties.get(*property).map(|v| v.trim()) == Some(entry.value) {
+                fallbacks.insert(property.to_string(), entry.fallback.to_string());
+            }
+        }
+    }
+
+    fallbacks
+}
+
+/// Prefix compiled CSS. Value fallbacks go in a copy of the rule placed right before it,
+/// so browsers that drop the modern value keep the fallback. Run after rules are merged.
+pub fn prefix_css_document(document: &mut VirtualCssDocument, targets: &BrowserTargets) {
+    if targets.is_empty() {
+        return;
+    }
+
+    let mut rules = Vec::with_capacity(document.rules.len());
+    for mut rule in document.rules.drain(..) {
+        let fallbacks = value_fallbacks(&rule.properties, targets);
+        if !fallbacks.is_empty() {
+            rules.push(EvaluatedCssRule {
+                selector: rule.selector.clone(),
+                properties: fallbacks,
+                at_rules: rule.at_rules.clone(),
+                global: rule.global,
+                layer: rule.layer,
+                span: rule.span.clone(),
+            });
+        }
+        prefix_properties(&mut rule.properties, targets);
+        rules.push(rule);
+    }
+    document.rules = rules;
+}
+
+/// Prefix preview styles. Only properties are prefixed: rules are keyed by selector for
+/// diffing, so there's no room for fallback copies.
+pub fn prefix_css_rules(rules: &mut [CssRule], targets: &BrowserTargets) {
+    for rule in rules {
+        prefix_properties(&mut rule.properties, targets);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css_layers::CssLayer;
+
+    fn props(entries: &[(&str, &str)]) -> Properties {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let targets = BrowserTargets::parse(&["safari >= 15.4", "firefox 90"]).unwrap();
+        assert_eq!(
+            targets.targets(),
+            &[
+                BrowserTarget {
+                    browser: Safari,
+                    version: Version(15, 4)
+                },
+                BrowserTarget {
+                    browser: Firefox,
+                    version: Version(90, 0)
+                },
+            ]
+        );
+
+        let err = BrowserTargets::parse(&["netscape >= 4"]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid browser target 'netscape >= 4': unknown browser 'netscape'"
+        );
+        assert!(BrowserTargets::parse(&["last 2 versions"]).is_err());
+    }
+
+    #[test]
+    fn test_user_select() {
+        let targets = BrowserTargets::parse(&["safari >= 16", "firefox >= 60"]).unwrap();
+        let mut properties = props(&[("user-select", "none")]);
+        prefix_properties(&mut properties, &targets);
+
+        assert_eq!(properties.get("-webkit-user-select").map(String::as_str), Some("none"));
+        assert_eq!(properties.get("-moz-user-select").map(String::as_str), Some("none"));
+
+        // Firefox 69+ reads the standard property
+        let targets = BrowserTargets::parse(&["firefox >= 100"]).unwrap();
+        let mut properties = props(&[("user-select", "none")]);
+        prefix_properties(&mut properties, &targets);
+        assert_eq!(properties.len(), 1);
+    }
+
+    #[test]
+    fn test_backdrop_filter() {
+        let mut properties = props(&[("backdrop-filter", "blur(4px)")]);
+        let old_safari = BrowserTargets::parse(&["safari >= 17"]).unwrap();
+        prefix_properties(&mut properties, &old_safari);
+        assert_eq!(
+            properties.get("-webkit-backdrop-filter").map(String::as_str),
+            Some("blur(4px)")
+        );
+
+        let mut properties = props(&[("backdrop-filter", "blur(4px)")]);
+        let modern = BrowserTargets::parse(&["safari >= 18", "chrome >= 76"]).unwrap();
+        prefix_properties(&mut properties, &modern);
+        assert!(!properties.contains_key("-webkit-backdrop-filter"));
+    }
+
+    #[test]
+    fn test_appearance_keeps_author_prefix() {
+        let targets = BrowserTargets::parse(&["chrome >= 80", "firefox >= 78"]).unwrap();
+        let mut properties = props(&[("appearance", "none"), ("-webkit-appearance", "button")]);
+        prefix_properties(&mut properties, &targets);
+
+        assert_eq!(properties.get("-webkit-appearance").map(String::as_str), Some("button"));
+        assert_eq!(properties.get("-moz-appearance").map(String::as_str), Some("none"));
+    }
+
+    #[test]
+    fn test_no_targets_is_a_no_op() {
+        let mut properties = props(&[("user-select", "none"), ("position", "sticky")]);
+        prefix_properties(&mut properties, &BrowserTargets::default());
+        assert_eq!(properties.len(), 2);
+        assert!(value_fallbacks(&properties, &BrowserTargets::default()).is_empty());
+    }
+
+    #[test]
+    fn test_value_fallback_rule_precedes_rule() {
+        let targets = BrowserTargets::parse(&["safari >= 12"]).unwrap();
+        let mut document = VirtualCssDocument::new();
+        document.add_rule(EvaluatedCssRule {
+            selector: ".header".to_string(),
+            properties: props(&[("position", "sticky"), ("top", "0")]),
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
+            span: None,
+        });
+
+        prefix_css_document(&mut document, &targets);
+
+        assert_eq!(document.rules.len(), 2);
+        assert_eq!(document.rules[0].properties, props(&[("position", "-webkit-sticky")]));
+        assert_eq!(
+            document.rules[1].properties.get("position").map(String::as_str),
+            Some("sticky")
+        );
+    }
+}
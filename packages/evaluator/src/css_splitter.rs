@@ -1,7 +1,19 @@
 //! CSS splitting - separate vendor/global styles from component styles
+//!
+//! Two flavours:
+//! - `split_css_rules` buckets a VDOM's rules by how they should load
+//!   (global, critical, component, deferred)
+//! - `ComponentStylesheets` gives each public component its own stylesheet
+//!   next to a shared one, and `CssManifest` records which sheets a component
+//!   needs so bundlers can code-split styles along with components
 
+use crate::css_evaluator::VirtualCssDocument;
 use crate::vdom::CssRule;
+use paperclip_bundle::Bundle;
+use paperclip_parser::ast::{Component, Document, Element};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 
 /// Split CSS rules into categories for better caching and loading
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +71,11 @@ pub fn split_css_rules(rules: Vec<CssRule>) -> SplitCss {
 
 /// Determine if a style is global (tokens, CSS variables, resets)
 fn is_global_style(rule: &CssRule) -> bool {
+    // Declared with `global style`
+    if rule.global {
+        return true;
+    }
+
     // CSS variables and token definitions
     if rule.selector.starts_with(":root") || rule.selector.contains("--") {
         return true;
@@ -79,8 +96,8 @@ fn is_global_style(rule: &CssRule) -> bool {
 
 /// Determine if a style is critical (above the fold)
 fn is_critical_style(rule: &CssRule) -> bool {
-    // No media query = visible by default = critical
-    if rule.media_query.is_none() {
+    // No media query or other at-rule = visible by default = critical
+    if rule.at_rules.is_empty() {
         // Check for common above-the-fold components
         let selector_lower = rule.selector.to_lowercase();
 
@@ -115,41 +132,390 @@ pub fn merge_split_css(split: &SplitCss) -> Vec<CssRule> {
     merged
 }
 
+/// A document's CSS with each public component in its own stylesheet
+#[derive(Debug, Clone, Default)]
+pub struct ComponentStylesheets {
+    /// Rules every component relies on - tokens, global styles, raw CSS,
+    /// style mixins and top-level renders
+    pub shared: VirtualCssDocument,
+    /// Rules of each public component, in declaration order
+    pub components: Vec<(String, VirtualCssDocument)>,
+}
+
+impl ComponentStylesheets {
+    /// Total number of rules across all sheets
+    pub fn total_rules(&self) -> usize {
+        self.shared.rules.len()
+            + self
+                .components
+                .iter()
+                .map(|(_, sheet)| sheet.rules.len())
+                .sum::<usize>()
+    }
+
+    /// Everything back in one document, shared rules first
+    pub fn merged(self) -> VirtualCssDocument {
+        let mut merged = self.shared;
+        for (_, sheet) in self.components {
+            merged.rules.extend(sheet.rules);
+        }
+        merged
+    }
+}
+
+/// Which stylesheets each public component of a document needs.
+/// File names are relative to the directory the document compiles to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CssManifest {
+    /// Tokens, globals and mixins - needed by every component. `None` when
+    /// the document has none, in which case no shared sheet is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shared: Option<String>,
+    /// Public component -> sheets to load, in order (shared sheets first,
+    /// then the components it renders, then its own)
+    pub components: BTreeMap<String, Vec<String>>,
+}
+
+impl CssManifest {
+    /// Manifest for a document whose sheets are named after `stem`:
+    /// `button.css` (shared) and `button.Button.css` per component
+    pub fn for_document(doc: &Document, stem: &str) -> Self {
+        let shared = format!("{}.css", stem);
+        let by_name: HashMap<&str, &Component> = doc
+            .components
+            .iter()
+            .map(|component| (component.name.as_str(), component))
+            .collect();
+
+        let components = doc
+            .components
+            .iter()
+            .filter(|component| component.public)
+            .map(|component| {
+                let mut sheets = vec![shared.clone()];
+                collect_component_sheets(component, &by_name, stem, &mut HashSet::new(), &mut sheets);
+                (component.name.clone(), sheets)
+            })
+            .collect();
+
+        Self {
+            shared: Some(shared),
+            components,
+        }
+    }
+
+    /// Manifest for a bundle document, including the sheets of what it
+    /// imports: the shared sheets of every file it imports (dependencies
+    /// first) and the sheets of imported components it renders. Imported
+    /// sheets are named after their file's stem and referenced relative to
+    /// `entry` (`../theme/theme.css`), so output has to mirror the source tree.
+    /// `has_shared_sheet` tells whether a file's shared sheet has any rules;
+    /// empty ones are left out.
+    pub fn for_bundle(
+        bundle: &Bundle,
+        entry: &Path,
+        stem: &str,
+        mut has_shared_sheet: impl FnMut(&Path) -> bool,
+    ) -> Self {
+        let Some(doc) = bundle.get_document(entry) else {
+            return Self::default();
+        };
+        let entry_dir = entry.parent().unwrap_or(Path::new(""));
+        let shared = has_shared_sheet(entry).then(|| format!("{}.css", stem));
+
+        let mut shared_sheets: Vec<String> = imports_of(bundle, entry)
+            .into_iter()
+            .filter(|path| has_shared_sheet(path))
+            .map(|path| relative_sheet(&path, &format!("{}.css", file_stem(&path)), entry_dir))
+            .collect();
+        shared_sheets.extend(shared.clone());
+
+        let components = doc
+            .components
+            .iter()
+            .filter(|component| component.public)
+            .map(|component| {
+                let mut sheets = shared_sheets.clone();
+                let mut walk = BundleSheets {
+                    bundle,
+                    entry,
+                    entry_dir,
+                    stem,
+                    visited: HashSet::new(),
+                };
+                walk.collect(entry, component, &mut sheets);
+                (component.name.clone(), sheets)
+            })
+            .collect();
+
+        Self { shared, components }
+    }
+
+    /// Sheet holding a single component's rules
+    pub fn component_sheet(stem: &str, component_name: &str) -> String {
+        format!("{}.{}.css", stem, component_name)
+    }
+
+    /// Every sheet in the manifest, shared first, without duplicates
+    pub fn sheets(&self) -> Vec<&str> {
+        let mut sheets: Vec<&str> = self.shared.iter().map(String::as_str).collect();
+        for sheet in self.components.values().flatten() {
+            if !sheets.contains(&sheet.as_str()) {
+                sheets.push(sheet);
+            }
+        }
+        sheets
+    }
+}
+
+/// Append the sheets of the public components `component` renders (same
+/// document, depth first) and then its own
+fn collect_component_sheets<'a>(
+    component: &'a Component,
+    by_name: &HashMap<&str, &'a Component>,
+    stem: &str,
+    visited: &mut HashSet<&'a str>,
+    sheets: &mut Vec<String>,
+) {
+    if !visited.insert(&component.name) {
+        return;
+    }
+
+    let mut instances = Vec::new();
+    if let Some(body) = &component.body {
+        collect_instances(body, &mut instances);
+    }
+    for slot in &component.slots {
+        for element in &slot.default_content {
+            collect_instances(element, &mut instances);
+        }
+    }
+
+    for name in instances {
+        if let Some(rendered) = by_name.get(name) {
+            collect_component_sheets(rendered, by_name, stem, visited, sheets);
+        }
+    }
+
+    // Private components have no rules of their own
+    if component.public {
+        sheets.push(CssManifest::component_sheet(stem, &component.name));
+    }
+}
+
+/// `collect_component_sheets` across the files of a bundle
+struct BundleSheets<'a> {
+    bundle: &'a Bundle,
+    entry: &'a Path,
+    entry_dir: &'a Path,
+    stem: &'a str,
+    visited: HashSet<(PathBuf, String)>,
+}
+
+impl<'a> BundleSheets<'a> {
+    fn collect(&mut self, path: &Path, component: &'a Component, sheets: &mut Vec<String>) {
+        if !self
+            .visited
+            .insert((path.to_path_buf(), component.name.clone()))
+        {
+            return;
+        }
+
+        let mut instances = Vec::new();
+        if let Some(body) = &component.body {
+            collect_instances(body, &mut instances);
+        }
+        for slot in &component.slots {
+            for element in &slot.default_content {
+                collect_instances(element, &mut instances);
+            }
+        }
+
+        for name in instances {
+            if let Some((rendered, rendered_path)) = self.resolve(name, path) {
+                self.collect(&rendered_path, rendered, sheets);
+            }
+        }
+
+        if component.public {
+            let sheet = if path == self.entry {
+                CssManifest::component_sheet(self.stem, &component.name)
+            } else {
+                let file_name = CssManifest::component_sheet(&file_stem(path), &component.name);
+                relative_sheet(path, &file_name, self.entry_dir)
+            };
+            if !sheets.contains(&sheet) {
+                sheets.push(sheet);
+            }
+        }
+    }
+
+    /// The component `name` refers to from `path` - its own, or a public
+    /// one it imports (by bare name, nearest import first)
+    fn resolve(&self, name: &str, path: &Path) -> Option<(&'a Component, PathBuf)> {
+        if let Some(found) = self.bundle.find_component(name, path) {
+            return Some(found);
+        }
+
+        let mut visited = HashSet::from([path.to_path_buf()]);
+        let mut queue: VecDeque<PathBuf> = VecDeque::from([path.to_path_buf()]);
+        while let Some(current) = queue.pop_front() {
+            for dep in self.bundle.get_dependencies(&current).unwrap_or_default() {
+                if !visited.insert(dep.clone()) {
+                    continue;
+                }
+                let component = self.bundle.get_document(dep).and_then(|doc| {
+                    doc.components
+                        .iter()
+                        .find(|component| component.public && component.name == name)
+                });
+                if let Some(component) = component {
+                    return Some((component, dep.clone()));
+                }
+                queue.push_back(dep.clone());
+            }
+        }
+        None
+    }
+}
+
+/// Files `entry` imports, directly or not, each after its own imports
+fn imports_of(bundle: &Bundle, entry: &Path) -> Vec<PathBuf> {
+    fn visit(bundle: &Bundle, path: &Path, visited: &mut HashSet<PathBuf>, order: &mut Vec<PathBuf>) {
+        for dep in bundle.get_dependencies(path).unwrap_or_default() {
+            if visited.insert(dep.clone()) {
+                visit(bundle, dep, visited, order);
+                order.push(dep.clone());
+            }
+        }
+    }
+
+    let mut visited = HashSet::from([entry.to_path_buf()]);
+    let mut order = Vec::new();
+    visit(bundle, entry, &mut visited, &mut order);
+    order
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "styles".to_string())
+}
+
+/// Sheet `file_name`, written next to the output of `path`, as seen from
+/// the output directory of a file in `from_dir`
+fn relative_sheet(path: &Path, file_name: &str, from_dir: &Path) -> String {
+    let dir: Vec<_> = path.parent().unwrap_or(Path::new("")).components().collect();
+    let from: Vec<_> = from_dir.components().collect();
+    let common = dir.iter().zip(&from).take_while(|(a, b)| a == b).count();
+
+    let mut parts = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        dir[common..]
+            .iter()
+            .map(|part| part.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.push(file_name.to_string());
+    parts.join("/")
+}
+
+/// Names of the components instantiated in an element tree
+fn collect_instances<'a>(element: &'a Element, names: &mut Vec<&'a str>) {
+    let children: Vec<&Element> = match element {
+        Element::Instance { name, children, .. } => {
+            names.push(name);
+            children.iter().collect()
+        }
+        Element::Tag { children, .. } => children.iter().collect(),
+        Element::Conditional {
+            then_branch,
+            else_branch,
+            ..
+        } => then_branch
+            .iter()
+            .chain(else_branch.iter().flatten())
+            .collect(),
+        Element::Repeat { body, .. } => body.iter().collect(),
+        Element::Insert { content, .. } => content.iter().collect(),
+        Element::Text { .. } | Element::SlotInsert { .. } => Vec::new(),
+    };
+
+    for child in children {
+        collect_instances(child, names);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use crate::css_at_rules::AtRule;
+    use crate::css_layers::CssLayer;
+    use paperclip_parser::ast::Properties;
 
     #[test]
     fn test_identify_global_styles() {
         let rule = CssRule {
             selector: ":root".to_string(),
             properties: [("--primary-color".to_string(), "blue".to_string())].into(),
-            media_query: None,
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
         };
         assert!(is_global_style(&rule));
 
         let rule = CssRule {
             selector: "body".to_string(),
             properties: [("margin".to_string(), "0".to_string())].into(),
-            media_query: None,
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
         };
         assert!(is_global_style(&rule));
     }
 
+    #[test]
+    fn test_declared_global_styles_are_global() {
+        // Selectors that the heuristics wouldn't catch
+        let rules = vec![
+            CssRule {
+                selector: "h1".to_string(),
+                properties: [("font-size".to_string(), "32px".to_string())].into(),
+                at_rules: vec![],
+                global: true,
+                layer: CssLayer::Components,
+            },
+            CssRule {
+                selector: "a:hover".to_string(),
+                properties: [("color".to_string(), "red".to_string())].into(),
+                at_rules: vec![AtRule::media("screen")],
+                global: true,
+                layer: CssLayer::Components,
+            },
+        ];
+
+        let split = split_css_rules(rules);
+        assert_eq!(split.global.len(), 2);
+        assert!(split.critical.is_empty());
+        assert!(split.components.is_empty());
+    }
+
     #[test]
     fn test_identify_critical_styles() {
         let rule = CssRule {
             selector: "._Navigation-nav-123".to_string(),
-            properties: HashMap::new(),
-            media_query: None,
+            properties: Properties::new(),
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
         };
         assert!(is_critical_style(&rule));
 
         let rule = CssRule {
             selector: "._Footer-div-456".to_string(),
-            properties: HashMap::new(),
-            media_query: Some("@media screen".to_string()),
+            properties: Properties::new(),
+            at_rules: vec![AtRule::media("screen")],
+            global: false,
+            layer: CssLayer::Components,
         };
         assert!(!is_critical_style(&rule)); // Has media query
     }
@@ -160,17 +526,23 @@ mod tests {
             CssRule {
                 selector: ":root".to_string(),
                 properties: [("--color".to_string(), "blue".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: "._Header-div-123".to_string(),
                 properties: [("padding".to_string(), "10px".to_string())].into(),
-                media_query: None,
+                at_rules: vec![],
+                global: false,
+                layer: CssLayer::Components,
             },
             CssRule {
                 selector: "._Footer-div-456".to_string(),
-                properties: HashMap::new(),
-                media_query: Some("@media screen".to_string()),
+                properties: Properties::new(),
+                at_rules: vec![AtRule::media("screen")],
+                global: false,
+                layer: CssLayer::Components,
             },
         ];
 
@@ -180,4 +552,77 @@ mod tests {
         assert_eq!(split.critical.len(), 1);
         assert_eq!(split.components.len(), 1);
     }
+
+    const CARDS: &str = r#"
+        token brand #3366FF
+        style base {
+            color: var(brand)
+        }
+        component Icon {
+            render span {
+                style {
+                    width: 16px
+                }
+            }
+        }
+        public component Button {
+            render button {
+                style extends base {
+                    padding: 8px
+                }
+                Icon()
+            }
+        }
+        public component Card {
+            render div {
+                style {
+                    border: 1px solid
+                }
+                Button()
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_evaluate_split_per_component() {
+        let doc = paperclip_parser::parse_with_path(CARDS, "/cards.pc").unwrap();
+        let sheets = crate::CssEvaluator::new().evaluate_split(&doc).unwrap();
+
+        let shared = sheets.shared.to_css();
+        assert!(shared.contains("--brand-"));
+        assert!(shared.contains("--base-color-"));
+        assert!(!shared.contains("padding: 8px"));
+
+        let names: Vec<&str> = sheets.components.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["Button", "Card"]);
+        assert!(sheets.components[0].1.to_css().contains("padding: 8px"));
+        assert!(!sheets.components[0].1.to_css().contains("border"));
+        assert!(sheets.components[1].1.to_css().contains("border: 1px solid"));
+
+        let total = sheets.total_rules();
+        assert_eq!(sheets.merged().rules.len(), total);
+    }
+
+    #[test]
+    fn test_manifest_lists_rendered_components() {
+        let doc = paperclip_parser::parse_with_path(CARDS, "/cards.pc").unwrap();
+        let manifest = CssManifest::for_document(&doc, "cards");
+
+        assert_eq!(manifest.shared.as_deref(), Some("cards.css"));
+        assert_eq!(
+            manifest.components["Button"],
+            vec!["cards.css", "cards.Button.css"]
+        );
+        // Card renders Button, so it needs Button's sheet too
+        assert_eq!(
+            manifest.components["Card"],
+            vec!["cards.css", "cards.Button.css", "cards.Card.css"]
+        );
+        assert!(!manifest.components.contains_key("Icon"));
+        assert_eq!(
+            manifest.sheets(),
+            vec!["cards.css", "cards.Button.css", "cards.Card.css"]
+        );
+    }
 }
+
@@ -0,0 +1,587 @@
+//! CSS transforms - rewrite generated declarations before they're emitted
+//!
+//! A `CssTransforms` pipeline runs its `CssTransform` passes, in order, over
+//! every rule. The same pipeline is applied to preview styles
+//! (`Evaluator::with_css_transforms`) and compiled CSS, so what the designer
+//! shows is what ships. Built-in passes:
+//!
+//! - `PxToRem`: `16px` -> `1rem` for a configurable root size, with excluded
+//!   properties left alone
+//! - `LogicalProperties`: physical left/right properties and keywords to their
+//!   logical equivalents (`margin-left` -> `margin-inline-start`)
+//! - `RtlFlip`: mirror left and right for a right-to-left stylesheet
+//!
+//! Passes are listed in `paperclip.config.json`:
+//!
+//! ```json
+//! "cssTransforms": [
+//!   { "name": "px-to-rem", "rootSize": 16, "exclude": ["border*"] },
+//!   { "name": "logical-properties" }
+//! ]
+//! ```
+//!
+//! Transforms only see declarations - selectors and at-rule preludes
+//! (`@media (min-width: 768px)`) are never rewritten.
+
+use crate::computed_styles::ComputedProperty;
+use crate::css_evaluator::VirtualCssDocument;
+use crate::vdom::CssRule;
+use paperclip_parser::ast::Properties;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// A pass over the declarations of each generated rule
+pub trait CssTransform: fmt::Debug + Send + Sync {
+    /// Name the pass is configured by
+    fn name(&self) -> &'static str;
+
+    /// Rewrite one rule's declarations in place
+    fn transform(&self, properties: &mut Properties);
+}
+
+/// Ordered list of transforms applied to generated CSS
+#[derive(Debug, Clone, Default)]
+pub struct CssTransforms {
+    passes: Vec<Arc<dyn CssTransform>>,
+}
+
+impl CssTransforms {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the built-in passes listed in config
+    pub fn from_config(configs: &[CssTransformConfig]) -> Self {
+        let mut transforms = Self::new();
+        for config in configs {
+            transforms.passes.push(config.build());
+        }
+        transforms
+    }
+
+    /// Append a pass (runs after the ones already added)
+    pub fn with(mut self, pass: impl CssTransform + 'static) -> Self {
+        self.passes.push(Arc::new(pass));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Names of the passes, in the order they run
+    pub fn names(&self) -> Vec<&'static str> {
+        self.passes.iter().map(|pass| pass.name()).collect()
+    }
+
+    /// Run every pass over one rule's declarations
+    pub fn apply(&self, properties: &mut Properties) {
+        for pass in &self.passes {
+            pass.transform(properties);
+        }
+    }
+
+    /// Transform compiled CSS
+    pub fn apply_to_document(&self, document: &mut VirtualCssDocument) {
+        for rule in &mut document.rules {
+            self.apply(&mut rule.properties);
+        }
+    }
+
+    /// Transform preview styles
+    pub fn apply_to_rules(&self, rules: &mut [CssRule]) {
+        for rule in rules {
+            self.apply(&mut rule.properties);
+        }
+    }
+
+    /// Transform computed styles. Each declaration is rewritten on its own,
+    /// keeping its source span and place in the cascade.
+    pub fn apply_to_computed(&self, properties: &mut [ComputedProperty]) {
+        if self.is_empty() {
+            return;
+        }
+        for property in properties {
+            let mut declaration =
+                Properties::from([(property.name.clone(), property.resolved_value.clone())]);
+            self.apply(&mut declaration);
+            if let Some((name, value)) = declaration.into_iter().next() {
+                property.name = name;
+                property.resolved_value = value;
+            }
+        }
+    }
+}
+
+/// Passes and their settings, so cached preview output can depend on them
+impl Hash for CssTransforms {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for pass in &self.passes {
+            pass.name().hash(state);
+            format!("{:?}", pass).hash(state);
+        }
+    }
+}
+
+/// A built-in transform as listed under `cssTransforms` in config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "kebab-case")]
+pub enum CssTransformConfig {
+    PxToRem(PxToRem),
+    LogicalProperties,
+    RtlFlip,
+}
+
+impl CssTransformConfig {
+    pub fn build(&self) -> Arc<dyn CssTransform> {
+        match self {
+            CssTransformConfig::PxToRem(pass) => Arc::new(pass.clone()),
+            CssTransformConfig::LogicalProperties => Arc::new(LogicalProperties),
+            CssTransformConfig::RtlFlip => Arc::new(RtlFlip),
+        }
+    }
+}
+
+/// Convert `px` lengths to `rem`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PxToRem {
+    /// Pixels per rem - the root font size (must be positive)
+    #[serde(
+        default = "default_root_size",
+        deserialize_with = "deserialize_root_size"
+    )]
+    pub root_size: f64,
+    /// Properties kept in px. A trailing `*` matches a prefix (`border*`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+}
+
+fn default_root_size() -> f64 {
+    16.0
+}
+
+fn deserialize_root_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let root_size = f64::deserialize(deserializer)?;
+    if root_size > 0.0 {
+        Ok(root_size)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "px-to-rem rootSize must be greater than 0, got {}",
+            root_size
+        )))
+    }
+}
+
+impl Default for PxToRem {
+    fn default() -> Self {
+        Self {
+            root_size: default_root_size(),
+            exclude: Vec::new(),
+        }
+    }
+}
+
+impl PxToRem {
+    fn is_excluded(&self, property: &str) -> bool {
+        self.exclude.iter().any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => property.starts_with(prefix),
+            None => property == pattern,
+        })
+    }
+
+    /// Rewrite every px length in a value, leaving strings and `url()` alone
+    fn convert(&self, value: &str) -> String {
+        let chars: Vec<char> = value.chars().collect();
+        let mut output = String::with_capacity(value.len());
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+
+            // Copy quoted strings and url(...) verbatim
+            let verbatim_end = if c == '"' || c == '\'' {
+                chars[i + 1..].iter().position(|&q| q == c).map(|p| i + p + 2)
+            } else if starts_with_ignore_case(&chars[i..], "url(") {
+                chars[i..].iter().position(|&p| p == ')').map(|p| i + p + 1)
+            } else {
+                None
+            };
+            if let Some(end) = verbatim_end {
+                output.extend(&chars[i..end]);
+                i = end;
+                continue;
+            }
+
+            let token_start = i == 0
+                || !(chars[i - 1].is_alphanumeric() || matches!(chars[i - 1], '-' | '_' | '.' | '#'));
+            let starts_number = c.is_ascii_digit()
+                || (matches!(c, '-' | '.')
+                    && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit() || *n == '.'));
+
+            if token_start && starts_number {
+                let start = i;
+                i += 1;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let number: String = chars[start..i].iter().collect();
+
+                let is_px = starts_with_ignore_case(&chars[i..], "px")
+                    && !chars
+                        .get(i + 2)
+                        .is_some_and(|n| n.is_alphanumeric() || *n == '%');
+                match number.parse::<f64>() {
+                    Ok(px) if is_px => {
+                        output.push_str(&format_rem(px / self.root_size));
+                        i += 2;
+                    }
+                    _ => output.push_str(&number),
+                }
+                continue;
+            }
+
+            output.push(c);
+            i += 1;
+        }
+
+        output
+    }
+}
+
+impl CssTransform for PxToRem {
+    fn name(&self) -> &'static str {
+        "px-to-rem"
+    }
+
+    fn transform(&self, properties: &mut Properties) {
+        for (property, value) in properties.iter_mut() {
+            if value.contains("px") && !self.is_excluded(property) {
+                *value = self.convert(value);
+            }
+        }
+    }
+}
+
+fn starts_with_ignore_case(chars: &[char], prefix: &str) -> bool {
+    chars.len() >= prefix.len()
+        && chars
+            .iter()
+            .zip(prefix.chars())
+            .all(|(a, b)| a.eq_ignore_ascii_case(&b))
+}
+
+fn format_rem(rem: f64) -> String {
+    let rounded = (rem * 10_000.0).round() / 10_000.0;
+    if rounded == 0.0 {
+        "0".to_string()
+    } else {
+        format!("{}rem", rounded)
+    }
+}
+
+/// Physical left/right properties and their logical equivalents
+const LOGICAL_PROPERTIES: &[(&str, &str)] = &[
+    ("margin-left", "margin-inline-start"),
+    ("margin-right", "margin-inline-end"),
+    ("padding-left", "padding-inline-start"),
+    ("padding-right", "padding-inline-end"),
+    ("border-left", "border-inline-start"),
+    ("border-right", "border-inline-end"),
+    ("border-left-width", "border-inline-start-width"),
+    ("border-right-width", "border-inline-end-width"),
+    ("border-left-style", "border-inline-start-style"),
+    ("border-right-style", "border-inline-end-style"),
+    ("border-left-color", "border-inline-start-color"),
+    ("border-right-color", "border-inline-end-color"),
+    ("border-top-left-radius", "border-start-start-radius"),
+    ("border-top-right-radius", "border-start-end-radius"),
+    ("border-bottom-left-radius", "border-end-start-radius"),
+    ("border-bottom-right-radius", "border-end-end-radius"),
+    ("left", "inset-inline-start"),
+    ("right", "inset-inline-end"),
+];
+
+/// Convert physical left/right properties and keywords to logical ones, so
+/// layouts follow the document's writing direction
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LogicalProperties;
+
+impl CssTransform for LogicalProperties {
+    fn name(&self) -> &'static str {
+        "logical-properties"
+    }
+
+    fn transform(&self, properties: &mut Properties) {
+        // Renamed in place, so declarations keep their order
+        let original = std::mem::take(properties);
+        for (property, value) in &original {
+            let logical = LOGICAL_PROPERTIES
+                .iter()
+                .find(|(physical, _)| physical == property)
+                .map(|(_, logical)| *logical);
+            match logical {
+                // A logical declaration written by hand wins
+                Some(logical) if original.contains_key(logical) => {}
+                Some(logical) => {
+                    properties.insert(logical.to_string(), value.clone());
+                }
+                None => {
+                    properties.insert(property.clone(), value.clone());
+                }
+            }
+        }
+
+        for (property, value) in properties.iter_mut() {
+            let keyword = match (property.as_str(), value.trim()) {
+                ("float" | "clear", "left") => "inline-start",
+                ("float" | "clear", "right") => "inline-end",
+                ("text-align", "left") => "start",
+                ("text-align", "right") => "end",
+                _ => continue,
+            };
+            *value = keyword.to_string();
+        }
+    }
+}
+
+/// Mirror left and right - properties, keywords and four-sided shorthands -
+/// for a right-to-left stylesheet. Horizontal offsets inside values
+/// (`box-shadow`, `transform`) aren't mirrored.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RtlFlip;
+
+impl CssTransform for RtlFlip {
+    fn name(&self) -> &'static str {
+        "rtl-flip"
+    }
+
+    fn transform(&self, properties: &mut Properties) {
+        let flipped: Properties = properties
+            .drain(..)
+            .map(|(property, value)| {
+                let value = flip_value(&property, &value);
+                (flip_property(&property), value)
+            })
+            .collect();
+        *properties = flipped;
+    }
+}
+
+/// `margin-left` -> `margin-right`, `border-top-left-radius` -> `border-top-right-radius`
+fn flip_property(property: &str) -> String {
+    if property.starts_with("--") {
+        return property.to_string();
+    }
+    property
+        .split('-')
+        .map(|segment| match segment {
+            "left" => "right",
+            "right" => "left",
+            other => other,
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn flip_value(property: &str, value: &str) -> String {
+    match property {
+        "float" | "clear" | "text-align" => match value.trim() {
+            "left" => "right".to_string(),
+            "right" => "left".to_string(),
+            _ => value.to_string(),
+        },
+        // top right bottom left -> top left bottom right
+        "margin" | "padding" | "inset" | "border-width" | "border-style" | "border-color" => {
+            let mut parts = split_top_level(value);
+            if parts.len() == 4 {
+                parts.swap(1, 3);
+                parts.join(" ")
+            } else {
+                value.to_string()
+            }
+        }
+        // top-left top-right bottom-right bottom-left, mirrored horizontally
+        "border-radius" if !value.contains('/') => {
+            let parts = split_top_level(value);
+            match parts.as_slice() {
+                [a, b] => format!("{} {}", b, a),
+                [a, b, c] => format!("{} {} {} {}", b, a, b, c),
+                [a, b, c, d] => format!("{} {} {} {}", b, a, d, c),
+                _ => value.to_string(),
+            }
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Split a value on whitespace outside parentheses (`calc(1px + 2px) 4px`)
+fn split_top_level(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = None;
+
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    parts.push(&value[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    if let Some(s) = start {
+        parts.push(&value[s..]);
+    }
+
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn props(entries: &[(&str, &str)]) -> Properties {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_px_to_rem() {
+        let pass = PxToRem {
+            root_size: 16.0,
+            exclude: vec!["border*".to_string()],
+        };
+        let mut properties = props(&[
+            ("padding", "8px 24px"),
+            ("width", "calc(100% - 12px)"),
+            ("margin", "-4px 0px"),
+            ("border", "1px solid red"),
+            ("background", "url(\"icon-16px.png\") 2px"),
+            ("font-family", "\"Px 16px\""),
+        ]);
+        pass.transform(&mut properties);
+
+        assert_eq!(properties["padding"], "0.5rem 1.5rem");
+        assert_eq!(properties["width"], "calc(100% - 0.75rem)");
+        assert_eq!(properties["margin"], "-0.25rem 0");
+        assert_eq!(properties["border"], "1px solid red");
+        assert_eq!(properties["background"], "url(\"icon-16px.png\") 0.125rem");
+        assert_eq!(properties["font-family"], "\"Px 16px\"");
+    }
+
+    #[test]
+    fn test_logical_properties() {
+        let mut properties = props(&[
+            ("margin-left", "4px"),
+            ("padding-right", "8px"),
+            ("border-top-left-radius", "2px"),
+            ("left", "0"),
+            ("float", "left"),
+            ("text-align", "right"),
+            ("margin-top", "1px"),
+        ]);
+        LogicalProperties.transform(&mut properties);
+
+        assert_eq!(
+            properties,
+            props(&[
+                ("margin-inline-start", "4px"),
+                ("padding-inline-end", "8px"),
+                ("border-start-start-radius", "2px"),
+                ("inset-inline-start", "0"),
+                ("float", "inline-start"),
+                ("text-align", "end"),
+                ("margin-top", "1px"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rtl_flip() {
+        let mut properties = props(&[
+            ("margin-left", "4px"),
+            ("right", "0"),
+            ("border-top-left-radius", "2px"),
+            ("float", "left"),
+            ("padding", "1px 2px 3px calc(4px + 1em)"),
+            ("border-radius", "1px 2px 3px 4px"),
+            ("--left-gap", "4px"),
+        ]);
+        RtlFlip.transform(&mut properties);
+
+        assert_eq!(
+            properties,
+            props(&[
+                ("margin-right", "4px"),
+                ("left", "0"),
+                ("border-top-right-radius", "2px"),
+                ("float", "right"),
+                ("padding", "1px calc(4px + 1em) 3px 2px"),
+                ("border-radius", "2px 1px 4px 3px"),
+                ("--left-gap", "4px"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_pipeline_from_config() {
+        let configs: Vec<CssTransformConfig> = serde_json::from_str(
+            r#"[{ "name": "px-to-rem", "rootSize": 10 }, { "name": "logical-properties" }]"#,
+        )
+        .unwrap();
+        let transforms = CssTransforms::from_config(&configs);
+        assert_eq!(transforms.names(), vec!["px-to-rem", "logical-properties"]);
+
+        let mut properties = props(&[("margin-left", "15px")]);
+        transforms.apply(&mut properties);
+        assert_eq!(properties, props(&[("margin-inline-start", "1.5rem")]));
+    }
+
+    #[test]
+    fn test_root_size_must_be_positive() {
+        for root_size in ["0", "-16"] {
+            let json = format!(r#"[{{ "name": "px-to-rem", "rootSize": {} }}]"#, root_size);
+            let error = serde_json::from_str::<Vec<CssTransformConfig>>(&json).unwrap_err();
+            assert!(error.to_string().contains("rootSize must be greater than 0"));
+        }
+    }
+
+    #[test]
+    fn test_computed_styles() {
+        use crate::computed_styles::OriginKind;
+        use paperclip_parser::ast::Span;
+
+        let declaration = |name: &str, value: &str, overridden_by| ComputedProperty {
+            name: name.to_string(),
+            value: value.to_string(),
+            resolved_value: value.to_string(),
+            source_span: Span::new(0, 0, "style".to_string()),
+            origin_kind: OriginKind::Inline,
+            overridden_by,
+        };
+        let mut properties = vec![
+            declaration("padding-left", "24px", None),
+            declaration("padding-left", "8px", Some(0)),
+        ];
+        CssTransforms::new()
+            .with(PxToRem::default())
+            .with(LogicalProperties)
+            .apply_to_computed(&mut properties);
+
+        assert_eq!(properties[0].name, "padding-inline-start");
+        assert_eq!(properties[0].resolved_value, "1.5rem");
+        assert_eq!(properties[0].value, "24px");
+        assert_eq!(properties[1].resolved_value, "0.5rem");
+        assert_eq!(properties[1].overridden_by, Some(0));
+    }
+}
@@ -0,0 +1,424 @@
+//! Dead style elimination for production CSS
+//!
+//! `CssEvaluator::evaluate_bundle` emits every public token and style a file
+//! imports, so an app that pulls in a design system ships all of it. For
+//! production builds, `StyleUsage` walks the bundle from a set of entry
+//! documents and records what is actually reachable:
+//!
+//! - every component and top-level render in an entry document
+//! - components instantiated (transitively) by those
+//! - styles their style blocks extend, and the styles those extend
+//! - tokens referenced by reachable styles, global styles, raw CSS and other
+//!   reachable tokens
+//!
+//! Handing the usage to `CssEvaluator::with_style_usage` drops everything
+//! else; `CssEvaluator::tree_shake_report` lists what was removed.
+//!
+//! Styles and tokens are matched by name, the way the evaluator resolves them,
+//! so a name that's reachable keeps every declaration carrying it.
+
+use crate::css_evaluator::extract_style_name;
+use paperclip_bundle::Bundle;
+use paperclip_parser::ast::{Component, Document, Element, StyleBlock, StyleDecl, TokenValue};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// What a set of entry documents reaches in a bundle
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleUsage {
+    /// (document path, component name)
+    components: HashSet<(PathBuf, String)>,
+    styles: HashSet<String>,
+    tokens: HashSet<String>,
+}
+
+impl StyleUsage {
+    /// Walk `bundle` from `entries` and record every reachable component,
+    /// style and token
+    pub fn from_entries<P: AsRef<Path>>(bundle: &Bundle, entries: &[P]) -> Self {
+        let entries: Vec<&Path> = entries.iter().map(|p| p.as_ref()).collect();
+        let mut walker = UsageWalker::new(bundle, &entries);
+
+        for entry in &entries {
+            if let Some(doc) = bundle.get_document(entry) {
+                for component in &doc.components {
+                    walker.visit_component(entry, component);
+                }
+                for render in &doc.renders {
+                    walker.visit_element(entry, render);
+                }
+            }
+        }
+        walker.finish()
+    }
+
+    pub fn uses_component(&self, path: &Path, name: &str) -> bool {
+        self.components
+            .contains(&(path.to_path_buf(), name.to_string()))
+    }
+
+    pub fn uses_style(&self, name: &str) -> bool {
+        self.styles.contains(name)
+    }
+
+    pub fn uses_token(&self, name: &str) -> bool {
+        self.tokens.contains(name)
+    }
+}
+
+/// What dead style elimination left out of the last evaluation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TreeShakeReport {
+    /// Tokens whose custom properties were dropped from `:root`
+    pub removed_tokens: Vec<String>,
+    /// Styles whose custom properties and mixin class were dropped
+    pub removed_styles: Vec<String>,
+    /// Components whose rules were dropped
+    pub removed_components: Vec<String>,
+}
+
+impl TreeShakeReport {
+    pub fn is_empty(&self) -> bool {
+        self.removed_tokens.is_empty()
+            && self.removed_styles.is_empty()
+            && self.removed_components.is_empty()
+    }
+
+    /// Sort and dedupe (a name can be declared in more than one file)
+    pub(crate) fn normalize(&mut self) {
+        for names in [
+            &mut self.removed_tokens,
+            &mut self.removed_styles,
+            &mut self.removed_components,
+        ] {
+            names.sort();
+            names.dedup();
+        }
+    }
+}
+
+/// Token names referenced by a CSS value - `{token}`, `var(token)` and
+/// `var(theme.token)`. `var(--custom)` isn't a token reference.
+pub(crate) fn token_references(value: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+
+    if let Some(name) = value
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        names.push(extract_style_name(name.trim()));
+    }
+
+    let mut rest = value;
+    while let Some(start) = rest.find("var(") {
+        let args = &rest[start + "var(".len()..];
+        let Some(end) = args.find([',', ')']) else {
+            break;
+        };
+        let reference = args[..end].trim();
+        if !reference.is_empty() && !reference.starts_with("--") {
+            names.push(extract_style_name(reference));
+        }
+        rest = &args[end..];
+    }
+
+    names
+}
+
+struct UsageWalker<'a> {
+    bundle: &'a Bundle,
+    /// Style and token declarations visible from the entries, by name
+    style_decls: HashMap<&'a str, Vec<&'a StyleDecl>>,
+    token_decls: HashMap<&'a str, Vec<&'a TokenValue>>,
+    pending_tokens: VecDeque<String>,
+    usage: StyleUsage,
+}
+
+impl<'a> UsageWalker<'a> {
+    fn new(bundle: &'a Bundle, entries: &[&Path]) -> Self {
+        let mut walker = Self {
+            bundle,
+            style_decls: HashMap::new(),
+            token_decls: HashMap::new(),
+            pending_tokens: VecDeque::new(),
+            usage: StyleUsage::default(),
+        };
+
+        for doc in import_graph(bundle, entries) {
+            for style in &doc.styles {
+                walker
+                    .style_decls
+                    .entry(style.name.as_str())
+                    .or_default()
+                    .push(style);
+            }
+            for token in &doc.tokens {
+                walker
+                    .token_decls
+                    .entry(token.name.as_str())
+                    .or_default()
+                    .push(&token.expression);
+            }
+
+            // Global styles and raw CSS are always emitted, so their tokens are roots
+            for global in &doc.global_styles {
+                for value in global.properties.values() {
+                    walker.use_tokens_in(value);
+                }
+            }
+            let raw_css = doc
+                .raw_css
+                .iter()
+                .chain(doc.components.iter().flat_map(|c| &c.raw_css));
+            for block in raw_css {
+                walker.use_tokens_in(&block.source);
+            }
+        }
+
+        walker
+    }
+
+    fn finish(mut self) -> StyleUsage {
+        while let Some(name) = self.pending_tokens.pop_front() {
+            let expressions = self.token_decls.get(name.as_str()).cloned();
+            for expression in expressions.into_iter().flatten() {
+                self.visit_token_value(expression);
+            }
+        }
+        self.usage
+    }
+
+    fn visit_component(&mut self, path: &Path, component: &'a Component) {
+        if !self
+            .usage
+            .components
+            .insert((path.to_path_buf(), component.name.clone()))
+        {
+            return;
+        }
+
+        if let Some(body) = &component.body {
+            self.visit_element(path, body);
+        }
+        for slot in &component.slots {
+            self.visit_elements(path, &slot.default_content);
+        }
+        for override_decl in &component.overrides {
+            self.visit_style_blocks(&override_decl.styles);
+        }
+    }
+
+    fn visit_elements(&mut self, path: &Path, elements: &'a [Element]) {
+        for element in elements {
+            self.visit_element(path, element);
+        }
+    }
+
+    fn visit_element(&mut self, path: &Path, element: &'a Element) {
+        match element {
+            Element::Tag {
+                styles, children, ..
+            } => {
+                self.visit_style_blocks(styles);
+                self.visit_elements(path, children);
+            }
+            Element::Text { styles, runs, .. } => {
+                self.visit_style_blocks(styles);
+                for run in runs {
+                    self.visit_style_blocks(&run.styles);
+                }
+            }
+            Element::Instance { name, children, .. } => {
+                let bundle = self.bundle;
+                if let Some((component, component_path)) = bundle.find_component(name, path) {
+                    self.visit_component(&component_path, component);
+                }
+                self.visit_elements(path, children);
+            }
+            Element::Conditional {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.visit_elements(path, then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.visit_elements(path, else_branch);
+                }
+            }
+            Element::Repeat { body, .. } => self.visit_elements(path, body),
+            Element::Insert { content, .. } => self.visit_elements(path, content),
+            Element::SlotInsert { .. } => {}
+        }
+    }
+
+    fn visit_style_blocks(&mut self, styles: &[StyleBlock]) {
+        for style in styles {
+            for extend_ref in &style.extends {
+                self.use_style(extract_style_name(extend_ref));
+            }
+            for value in style.properties.values() {
+                self.use_tokens_in(value);
+            }
+        }
+    }
+
+    fn use_style(&mut self, name: &str) {
+        if !self.usage.styles.insert(name.to_string()) {
+            return;
+        }
+
+        let decls = self.style_decls.get(name).cloned();
+        for decl in decls.into_iter().flatten() {
+            for extend_ref in &decl.extends {
+                self.use_style(extract_style_name(extend_ref));
+            }
+            for value in decl.properties.values() {
+                self.use_tokens_in(value);
+            }
+        }
+    }
+
+    fn use_tokens_in(&mut self, value: &str) {
+        for name in token_references(value) {
+            self.use_token(name);
+        }
+    }
+
+    fn use_token(&mut self, name: &str) {
+        if self.usage.tokens.insert(name.to_string()) {
+            self.pending_tokens.push_back(name.to_string());
+        }
+    }
+
+    fn visit_token_value(&mut self, value: &TokenValue) {
+        match value {
+            TokenValue::Literal { value } => self.use_tokens_in(value),
+            TokenValue::Reference { name } => self.use_token(extract_style_name(name)),
+            TokenValue::Binary { left, right, .. } => {
+                self.visit_token_value(left);
+                self.visit_token_value(right);
+            }
+            TokenValue::Call { arguments, .. } => {
+                for argument in arguments {
+                    self.visit_token_value(argument);
+                }
+            }
+        }
+    }
+}
+
+/// Every document reachable through imports from `entries`
+fn import_graph<'a>(bundle: &'a Bundle, entries: &[&Path]) -> Vec<&'a Document> {
+    let mut visited = HashSet::new();
+    let mut queue: VecDeque<PathBuf> = entries.iter().map(|p| p.to_path_buf()).collect();
+    let mut docs = Vec::new();
+
+    while let Some(path) = queue.pop_front() {
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        if let Some(doc) = bundle.get_document(&path) {
+            docs.push(doc);
+        }
+        if let Some(deps) = bundle.get_dependencies(&path) {
+            queue.extend(deps.iter().cloned());
+        }
+    }
+
+    docs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paperclip_bundle::MockFileSystem;
+    use paperclip_parser::parse_with_path;
+
+    fn bundle(files: &[(&str, &str)]) -> Bundle {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = MockFileSystem::new();
+        for (path, source) in files {
+            mock_fs.add_file(PathBuf::from(path));
+            bundle.add_document(PathBuf::from(path), parse_with_path(source, path).unwrap());
+        }
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+        bundle
+    }
+
+    #[test]
+    fn test_token_references() {
+        assert_eq!(token_references("{spacing}"), vec!["spacing"]);
+        assert_eq!(
+            token_references("1px solid var(theme.border)"),
+            vec!["border"]
+        );
+        assert_eq!(
+            token_references("var(--local, var(accent))"),
+            vec!["accent"]
+        );
+        assert!(token_references("16px").is_empty());
+    }
+
+    #[test]
+    fn test_reachability_follows_extends_and_aliases() {
+        let bundle = bundle(&[
+            (
+                "/app/ds.pc",
+                r#"
+                public token brand #3366FF
+                public token accent brand
+                public token unused #000
+                public style base {
+                    color: var(accent)
+                }
+                public style fancy extends base {
+                    padding: 8px
+                }
+                public style loud {
+                    font-weight: bold
+                }
+                public component Unused {
+                    render div {
+                        style extends loud
+                    }
+                }
+                "#,
+            ),
+            (
+                "/app/main.pc",
+                r#"
+                import "./ds.pc" as ds
+                component Button {
+                    render button {
+                        style extends ds.fancy {
+                            margin: 0
+                        }
+                    }
+                }
+                public component App {
+                    render div {
+                        Button()
+                    }
+                }
+                "#,
+            ),
+        ]);
+
+        let usage = StyleUsage::from_entries(&bundle, &["/app/main.pc"]);
+
+        assert!(usage.uses_component(Path::new("/app/main.pc"), "App"));
+        assert!(usage.uses_component(Path::new("/app/main.pc"), "Button"));
+        assert!(!usage.uses_component(Path::new("/app/ds.pc"), "Unused"));
+
+        assert!(usage.uses_style("fancy"));
+        assert!(usage.uses_style("base"));
+        assert!(!usage.uses_style("loud"));
+
+        assert!(usage.uses_token("accent"));
+        assert!(usage.uses_token("brand"));
+        assert!(!usage.uses_token("unused"));
+    }
+}
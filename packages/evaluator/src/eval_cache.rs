@@ -0,0 +1,380 @@
+//! # Evaluation Cache
+//!
+//! Memoizes the VNode tree of each top-level component and render between
+//! evaluations so an edit only re-evaluates what it touched.
+//!
+//! ## Cache Keys
+//!
+//! An entry is keyed by a structural hash of:
+//!
+//! - The component (or render) AST, ignoring span offsets so edits elsewhere in
+//!   the file don't shift every key. Span *ids* are included because VNodes
+//!   carry them (`source_id`, class names).
+//! - The keys of every component it instantiates, transitively and across
+//!   files, so editing an imported component dirties its users.
+//! - The evaluation environment: document id, tokens, globals, CSS transforms
+//!   (applied to inline styles) and the instance key counters (which leak
+//!   into semantic IDs).
+//!
+//! Frames are applied after the cached evaluation, so moving a frame on the
+//! canvas doesn't dirty anything. Style declarations only feed the CSS
+//! evaluator, so they don't take part in DOM keys.
+//!
+//! Results containing `VNode::Error` are never cached: errors carry source
+//! offsets, which the keys deliberately ignore.
+
+use crate::vdom::VNode;
+use paperclip_parser::ast::{Component, Element};
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{Hash, Hasher};
+
+/// Memoized VNodes for top-level components and renders
+#[derive(Debug, Default)]
+pub struct EvalCache {
+    /// (document id, slot) → entry. Slots are component names or `render:{index}`.
+    entries: HashMap<(String, String), CacheEntry>,
+    stats: CacheStats,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    key: u64,
+    vnode: VNode,
+    /// Instance key counters bumped while evaluating, replayed on a hit
+    counter_delta: Vec<(String, usize)>,
+}
+
+/// Hit/miss counts since the cache was created or last reset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl EvalCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up a memoized VNode, returning it with the counter increments to replay
+    pub fn get(
+        &mut self,
+        document_id: &str,
+        slot: &str,
+        key: u64,
+    ) -> Option<(VNode, Vec<(String, usize)>)> {
+        let hit = self
+            .entries
+            .get(&(document_id.to_string(), slot.to_string()))
+            .filter(|entry| entry.key == key)
+            .map(|entry| (entry.vnode.clone(), entry.counter_delta.clone()));
+
+        if hit.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+        hit
+    }
+
+    /// Store a freshly evaluated VNode (skipped if it contains errors)
+    pub fn insert(
+        &mut self,
+        document_id: &str,
+        slot: &str,
+        key: u64,
+        vnode: &VNode,
+        counter_delta: Vec<(String, usize)>,
+    ) {
+        let entry_key = (document_id.to_string(), slot.to_string());
+        if contains_error(vnode) {
+            self.entries.remove(&entry_key);
+            return;
+        }
+
+        self.entries.insert(
+            entry_key,
+            CacheEntry {
+                key,
+                vnode: vnode.clone(),
+                counter_delta,
+            },
+        );
+    }
+
+    /// Drop all entries for a document (e.g. when the file is deleted)
+    pub fn invalidate_document(&mut self, document_id: &str) {
+        self.entries.retain(|(doc_id, _), _| doc_id != document_id);
+    }
+
+    /// Drop entries for slots that no longer exist in a document
+    pub fn retain_slots(&mut self, document_id: &str, slots: &BTreeSet<String>) {
+        self.entries
+            .retain(|(doc_id, slot), _| doc_id != document_id || slots.contains(slot));
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Hash a serializable AST fragment, ignoring span offsets
+pub fn structural_hash<T: Serialize + ?Sized>(value: &T) -> u64 {
+    let mut json = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+    strip_span_offsets(&mut json);
+
+    let mut hasher = DefaultHasher::new();
+    json.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Remove `start`/`end` from span objects (`{ start, end, id }`)
+fn strip_span_offsets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            if map.len() == 3
+                && map.contains_key("id")
+                && map.get("start").is_some_and(|v| v.is_u64())
+                && map.get("end").is_some_and(|v| v.is_u64())
+            {
+                map.remove("start");
+                map.remove("end");
+            }
+            for child in map.values_mut() {
+                strip_span_offsets(child);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                strip_span_offsets(item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Computes component keys, memoized per evaluation
+pub(crate) struct KeyBuilder<'a> {
+    components: &'a HashMap<String, Component>,
+    memo: HashMap<String, u64>,
+    /// Component -> hash of its own structure and the components it instantiates
+    own: HashMap<String, (u64, BTreeSet<String>)>,
+}
+
+impl<'a> KeyBuilder<'a> {
+    pub(crate) fn new(components: &'a HashMap<String, Component>) -> Self {
+        Self {
+            components,
+            memo: HashMap::new(),
+            own: HashMap::new(),
+        }
+    }
+
+    /// Key for a component: its own structure plus every component it
+    /// reaches. Recursive components share their cycle's members, so the key
+    /// doesn't depend on which of them is keyed first.
+    pub(crate) fn component_key(&mut self, name: &str) -> u64 {
+        if let Some(key) = self.memo.get(name) {
+            return *key;
+        }
+
+        let mut reachable = BTreeSet::new();
+        let mut pending = vec![name.to_string()];
+        while let Some(next) = pending.pop() {
+            if !reachable.insert(next.clone()) {
+                continue;
+            }
+            if let Some((_, instances)) = self.own_key(&next) {
+                pending.extend(instances.iter().cloned());
+            }
+        }
+
+        let mut hasher = DefaultHasher::new();
+        name.hash(&mut hasher);
+        for component in &reachable {
+            component.hash(&mut hasher);
+            match self.own_key(component) {
+                Some((own, _)) => own.hash(&mut hasher),
+                None => structural_hash(&("missing", component)).hash(&mut hasher),
+            }
+        }
+        let key = hasher.finish();
+
+        self.memo.insert(name.to_string(), key);
+        key
+    }
+
+    /// Hash of a component's own structure, with the names it instantiates
+    fn own_key(&mut self, name: &str) -> Option<&(u64, BTreeSet<String>)> {
+        if !self.own.contains_key(name) {
+            let component = self.components.get(name)?;
+            let own = structural_hash(&(
+                &component.name,
+                &component.script,
+                &component.variants,
+                &component.slots,
+                &component.overrides,
+                &component.body,
+            ));
+
+            let mut instances = BTreeSet::new();
+            if let Some(body) = &component.body {
+                collect_instances(body, &mut instances);
+            }
+            for slot in &component.slots {
+                for element in &slot.default_content {
+                    collect_instances(element, &mut instances);
+                }
+            }
+            self.own.insert(name.to_string(), (own, instances));
+        }
+        self.own.get(name)
+    }
+
+    /// Key for a top-level render element
+    pub(crate) fn element_key(&mut self, element: &Element) -> u64 {
+        let mut instances = BTreeSet::new();
+        collect_instances(element, &mut instances);
+
+        let mut hasher = DefaultHasher::new();
+        structural_hash(element).hash(&mut hasher);
+        for instance in &instances {
+            instance.hash(&mut hasher);
+            self.component_key(instance).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Names of all component instances in an element tree
+fn collect_instances(element: &Element, out: &mut BTreeSet<String>) {
+    match element {
+        Element::Instance { name, children, .. } => {
+            out.insert(name.clone());
+            for child in children {
+                collect_instances(child, out);
+            }
+        }
+        Element::Tag { children, .. } => {
+            for child in children {
+                collect_instances(child, out);
+            }
+        }
+        Element::Conditional {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            for child in then_branch.iter().chain(else_branch.iter().flatten()) {
+                collect_instances(child, out);
+            }
+        }
+        Element::Repeat { body, .. } => {
+            for child in body {
+                collect_instances(child, out);
+            }
+        }
+        Element::Insert { content, .. } => {
+            for child in content {
+                collect_instances(child, out);
+            }
+        }
+        Element::Text { .. } | Element::SlotInsert { .. } => {}
+    }
+}
+
+fn contains_error(vnode: &VNode) -> bool {
+    match vnode {
+        VNode::Error { .. } => true,
+        VNode::Element { children, .. } => children.iter().any(contains_error),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paperclip_parser::parse_with_path;
+
+    fn components(source: &str) -> HashMap<String, Component> {
+        parse_with_path(source, "/test.pc")
+            .unwrap()
+            .components
+            .into_iter()
+            .map(|c| (c.name.clone(), c))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_ignores_span_offsets() {
+        let before = components("component A { render div { text \"a\" } }");
+        let after = components("\n\n  component A { render div { text \"a\" } }");
+
+        assert_eq!(
+            KeyBuilder::new(&before).component_key("A"),
+            KeyBuilder::new(&after).component_key("A")
+        );
+    }
+
+    #[test]
+    fn test_key_follows_instances() {
+        let source = |label: &str| {
+            format!(
+                "component B {{ render span {{ text \"{}\" }} }}\ncomponent A {{ render div {{ B() }} }}\ncomponent C {{ render div {{ text \"c\" }} }}",
+                label
+            )
+        };
+        let before = components(&source("one"));
+        let after = components(&source("two"));
+
+        let mut keys_before = KeyBuilder::new(&before);
+        let mut keys_after = KeyBuilder::new(&after);
+        assert_ne!(keys_before.component_key("A"), keys_after.component_key("A"));
+        assert_eq!(keys_before.component_key("C"), keys_after.component_key("C"));
+    }
+
+    #[test]
+    fn test_key_covers_mutual_recursion() {
+        let source = |label: &str| {
+            format!(
+                "component A {{ render div {{ text \"{}\" if show {{ B() }} }} }}\ncomponent B {{ render span {{ if show {{ A() }} }} }}",
+                label
+            )
+        };
+        let before = components(&source("one"));
+        let after = components(&source("two"));
+
+        // Whichever component is keyed first, editing A dirties B
+        for order in [["A", "B"], ["B", "A"]] {
+            let mut keys_before = KeyBuilder::new(&before);
+            let mut keys_after = KeyBuilder::new(&after);
+            for name in order {
+                assert_ne!(
+                    keys_before.component_key(name),
+                    keys_after.component_key(name)
+                );
+            }
+        }
+
+        let mut a_first = KeyBuilder::new(&before);
+        a_first.component_key("A");
+        assert_eq!(
+            a_first.component_key("B"),
+            KeyBuilder::new(&before).component_key("B")
+        );
+    }
+}
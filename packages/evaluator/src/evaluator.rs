@@ -33,15 +33,17 @@
 //!
 //! ## Recursion Protection
 //!
-//! Component recursion is detected and prevented via component stack tracking.
+//! Component recursion is detected via component stack tracking.
 //! See `EvalContext::component_stack` and the cycle detection at component instantiation.
 //!
-//! **Structural recursion** (direct or indirect cycles) is always an error:
+//! **Structural recursion** (a cycle with no `if`/`repeat` between the instances) is
+//! always an error:
 //! ```paperclip
 //! component A { render A() }  // Error: recursive component
 //! ```
 //!
-//! **Data-driven recursion** (bounded by data structures) is allowed:
+//! **Data-driven recursion** (at least one instance in the cycle sits under a
+//! `Conditional` or `Repeat`) is allowed:
 //! ```paperclip
 //! component TreeNode {
 //!   render div {
@@ -53,6 +55,16 @@
 //! }
 //! ```
 //!
+//! Guarded recursion is still capped at `EvalContext::max_recursion_depth` instances of
+//! the same component. Hitting the cap renders a `VNode::Error` in place of the instance
+//! instead of failing the document.
+//!
+//! ## Resource Limits
+//!
+//! Every evaluation runs under an `EvalLimits` budget (node count, nesting depth,
+//! repeat items, output size) and an optional `CancellationToken`, both checked in
+//! `evaluate_element`. See the `limits` module.
+//!
 //! ## Error Recovery Boundaries
 //!
 //! Error recovery is **only allowed at expression and leaf-node boundaries**, not at structural boundaries:
@@ -89,18 +101,26 @@
 //! let vdom = evaluator.evaluate(&doc)?;
 //! ```
 
-use crate::css_evaluator::CssEvaluator;
+use crate::css_evaluator::{CssEvaluator, VirtualCssDocument};
+use crate::eval_cache::{EvalCache, KeyBuilder};
+use crate::limits::{CancellationToken, EvalLimits, EvalUsage};
 use crate::css_minifier::minify_css_rules;
 use crate::css_optimizer::optimize_css_rules;
-use crate::utils::get_style_namespace;
+use crate::css_prefixer::{prefix_css_rules, BrowserTargets};
+use crate::css_transforms::CssTransforms;
+use crate::class_names::{ClassNameStrategy, ClassNames};
 use crate::vdom::{
-    AnnotationMetadata, ComponentMetadata, CssRule, FrameMetadata, VNode, VirtualDomDocument,
+    AnnotationMetadata, ComponentMetadata, CssRule, FrameMetadata, RootMetadata, VNode,
+    VirtualDomDocument,
 };
 use paperclip_bundle::Bundle;
 use paperclip_parser::ast::*;
 use paperclip_semantics::{SemanticID, SemanticSegment};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
 use tracing::{debug, error, info, instrument, warn};
 
@@ -135,6 +155,109 @@ fn is_html_tag(name: &str) -> bool {
     )
 }
 
+/// Convert evaluated CSS to VDOM rules (transformed, optimized, minified and
+/// prefixed for `targets`)
+/// Every file `entry_path` imports, directly or not, nearest first
+fn dependency_closure(bundle: &Bundle, entry_path: &Path) -> Vec<PathBuf> {
+    let mut visited = HashSet::from([entry_path.to_path_buf()]);
+    let mut closure = Vec::new();
+    let mut queue = VecDeque::from([entry_path.to_path_buf()]);
+    while let Some(path) = queue.pop_front() {
+        for dep_path in bundle.get_dependencies(&path).unwrap_or_default() {
+            if visited.insert(dep_path.clone()) {
+                closure.push(dep_path.clone());
+                queue.push_back(dep_path.clone());
+            }
+        }
+    }
+    closure
+}
+
+fn into_vdom_styles(
+    css_doc: VirtualCssDocument,
+    targets: &BrowserTargets,
+    transforms: &CssTransforms,
+) -> Vec<CssRule> {
+    let original_count = css_doc.rules.len();
+    debug!(rules = original_count, "CSS evaluation succeeded");
+
+    // Convert CSS evaluator rules to VDOM rules
+    let mut css_rules = Vec::new();
+    for css_rule in css_doc.rules {
+        css_rules.push(CssRule {
+            selector: css_rule.selector,
+            properties: css_rule.properties,
+            at_rules: css_rule.at_rules,
+            global: css_rule.global,
+            layer: css_rule.layer,
+        });
+    }
+
+    // Same transforms as compiled CSS, so the preview matches what ships
+    transforms.apply_to_rules(&mut css_rules);
+
+    // Optimize CSS rules (deduplicate, merge)
+    debug!(before = original_count, "Optimizing CSS rules");
+    css_rules = optimize_css_rules(css_rules);
+    let optimized_count = css_rules.len();
+    debug!(
+        after = optimized_count,
+        saved = original_count - optimized_count,
+        "CSS optimization complete"
+    );
+
+    // Minify CSS (remove whitespace, shorten values)
+    minify_css_rules(&mut css_rules);
+    debug!("CSS minification complete");
+
+    prefix_css_rules(&mut css_rules, targets);
+
+    css_rules
+}
+
+/// Approximate serialized size of a node (children only if `deep`)
+fn output_size(vnode: &VNode, deep: bool) -> usize {
+    match vnode {
+        VNode::Element {
+            tag,
+            attributes,
+            styles,
+            children,
+            ..
+        } => {
+            let own = tag.len()
+                + attributes
+                    .iter()
+                    .chain(styles.iter())
+                    .map(|(k, v)| k.len() + v.len())
+                    .sum::<usize>();
+            let nested = if deep {
+                children.iter().map(|child| output_size(child, true)).sum()
+            } else {
+                0
+            };
+            own + nested
+        }
+        VNode::LiveComponent {
+            src,
+            export_name,
+            props,
+            children,
+            ..
+        } => {
+            let own = src.len() + export_name.len() + props.len();
+            let nested = if deep {
+                children.iter().map(|child| output_size(child, true)).sum()
+            } else {
+                0
+            };
+            own + nested
+        }
+        VNode::Text { content } | VNode::Comment { content } => content.len(),
+        VNode::Error { message, .. } => message.len(),
+    }
+}
+
 /// Extract component metadata (description, frame, annotations) for designer use
 fn extract_component_metadata(component: &Component) -> ComponentMetadata {
     let mut description = None;
@@ -196,6 +319,30 @@ fn extract_component_metadata(component: &Component) -> ComponentMetadata {
     }
 }
 
+/// Designer metadata for a component's root node
+fn component_root_metadata(component: &Component) -> RootMetadata {
+    let metadata = extract_component_metadata(component);
+    RootMetadata {
+        component: Some(metadata.name),
+        frame: metadata.frame,
+        annotations: metadata.annotations,
+    }
+}
+
+/// Designer metadata for a top-level render's root node
+fn render_root_metadata(frame: Option<&Option<FrameAnnotation>>) -> RootMetadata {
+    RootMetadata {
+        component: None,
+        frame: frame.and_then(Option::as_ref).map(|f| FrameMetadata {
+            x: f.x,
+            y: f.y,
+            width: f.width,
+            height: f.height,
+        }),
+        annotations: Vec::new(),
+    }
+}
+
 /// Get a number parameter from annotation params
 fn get_number_param(params: &[(String, AnnotationValue)], key: &str) -> Option<f64> {
     params.iter().find(|(k, _)| k == key).and_then(|(_, v)| {
@@ -245,6 +392,9 @@ pub enum EvalError {
     #[error("Evaluation error at {span:?}: {message}")]
     EvaluationError { message: String, span: Span },
 
+    #[error("Evaluation cancelled")]
+    Cancelled,
+
     #[error("Recursive component detected: {component}\nCall stack: {}\n{}", call_stack.join(" → "), hint.as_ref().unwrap_or(&String::new()))]
     RecursiveComponent {
         component: String,
@@ -253,6 +403,14 @@ pub enum EvalError {
     },
 }
 
+/// Content a component instance inserts into one of its slots
+#[derive(Clone)]
+struct SlotContent {
+    /// Names bound to a scoped slot's parameters (`insert row(item)`)
+    params: Vec<String>,
+    elements: Vec<Element>,
+}
+
 /// Context for evaluation
 #[derive(Clone)]
 pub struct EvalContext {
@@ -266,11 +424,34 @@ pub struct EvalContext {
     /// Component instance key counters for auto-generating keys
     component_key_counters: HashMap<String, usize>,
     /// Slot content - maps slot name to inserted content
-    slot_content: HashMap<String, Vec<Element>>,
+    slot_content: HashMap<String, SlotContent>,
     /// Component call stack for cycle detection (prevents infinite recursion)
     component_stack: Vec<String>,
+    /// Parallel to `component_stack`: whether each instance was under a conditional/repeat
+    component_guards: Vec<bool>,
+    /// Number of conditionals/repeats enclosing the current position in the component body
+    guard_depth: usize,
+    /// Maximum number of nested instances of the same component
+    max_recursion_depth: usize,
+    /// Resource budget checked in `evaluate_element`
+    limits: EvalLimits,
+    /// Nodes and bytes produced so far (shared with nested component scopes)
+    usage: Arc<EvalUsage>,
+    /// Current element nesting depth
+    depth: usize,
+    /// Set by the caller to abandon this evaluation
+    cancellation: CancellationToken,
+    /// Generated class names (rebuilt from the document or bundle on each evaluation)
+    class_names: Arc<ClassNames>,
+    /// Browsers preview styles are prefixed for (none by default)
+    browser_targets: Arc<BrowserTargets>,
+    /// Transforms applied to preview styles (none by default)
+    css_transforms: Arc<CssTransforms>,
 }
 
+/// Default cap on nested instances of a recursive component
+pub const DEFAULT_MAX_RECURSION_DEPTH: usize = 32;
+
 impl EvalContext {
     pub fn new(document_id: String) -> Self {
         Self {
@@ -283,9 +464,55 @@ impl EvalContext {
             component_key_counters: HashMap::new(),
             slot_content: HashMap::new(),
             component_stack: Vec::new(),
+            component_guards: Vec::new(),
+            guard_depth: 0,
+            max_recursion_depth: DEFAULT_MAX_RECURSION_DEPTH,
+            limits: EvalLimits::default(),
+            usage: Arc::new(EvalUsage::default()),
+            depth: 0,
+            cancellation: CancellationToken::new(),
+            class_names: Arc::new(ClassNames::hashed()),
+            browser_targets: Arc::new(BrowserTargets::default()),
+            css_transforms: Arc::new(CssTransforms::default()),
+        }
+    }
+
+    /// Set the maximum number of nested instances of the same component
+    pub fn set_max_recursion_depth(&mut self, depth: usize) {
+        self.max_recursion_depth = depth;
+    }
+
+    /// Set the resource budget for this evaluation
+    pub fn set_limits(&mut self, limits: EvalLimits) {
+        self.limits = limits;
+    }
+
+    /// Set the token the caller uses to cancel this evaluation
+    pub fn set_cancellation(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    /// Set how generated class names are formed
+    pub fn set_class_name_strategy(&mut self, strategy: ClassNameStrategy) {
+        if strategy != self.class_names.strategy() {
+            self.class_names = Arc::new(ClassNames::unassigned(strategy));
         }
     }
 
+    pub fn class_names(&self) -> &ClassNames {
+        &self.class_names
+    }
+
+    /// Set the browsers preview styles are vendor-prefixed for
+    pub fn set_browser_targets(&mut self, targets: BrowserTargets) {
+        self.browser_targets = Arc::new(targets);
+    }
+
+    /// Set the transforms applied to preview styles
+    pub fn set_css_transforms(&mut self, transforms: CssTransforms) {
+        self.css_transforms = Arc::new(transforms);
+    }
+
     pub fn document_id(&self) -> &str {
         &self.document_id
     }
@@ -365,6 +592,24 @@ impl Value {
             Value::Object(o) => !o.is_empty(),
         }
     }
+
+    /// Convert to JSON (non-finite numbers become `null`)
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::String(s) => serde_json::Value::String(s.clone()),
+            Value::Number(n) => serde_json::Number::from_f64(*n)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::Boolean(b) => serde_json::Value::Bool(*b),
+            Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(Value::to_json).collect())
+            }
+            Value::Object(fields) => serde_json::Value::Object(
+                fields.iter().map(|(k, v)| (k.clone(), v.to_json())).collect(),
+            ),
+            Value::Null => serde_json::Value::Null,
+        }
+    }
 }
 
 /// Evaluator
@@ -384,11 +629,57 @@ impl Evaluator {
         }
     }
 
+    /// Cap nested instances of recursive components (default: `DEFAULT_MAX_RECURSION_DEPTH`)
+    pub fn with_max_recursion_depth(mut self, depth: usize) -> Self {
+        self.context.set_max_recursion_depth(depth);
+        self
+    }
+
+    /// Bound the work a single evaluation may do (default: `EvalLimits::default()`)
+    pub fn with_limits(mut self, limits: EvalLimits) -> Self {
+        self.context.set_limits(limits);
+        self
+    }
+
+    /// Abort with `EvalError::Cancelled` once `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.context.set_cancellation(token);
+        self
+    }
+
+    /// Choose how generated class names are formed (default: hashed)
+    pub fn with_class_name_strategy(mut self, strategy: ClassNameStrategy) -> Self {
+        self.context.set_class_name_strategy(strategy);
+        self
+    }
+
+    /// Vendor-prefix preview styles for `targets` (default: no prefixes)
+    pub fn with_browser_targets(mut self, targets: BrowserTargets) -> Self {
+        self.context.set_browser_targets(targets);
+        self
+    }
+
+    /// Run `transforms` over preview styles (default: none)
+    pub fn with_css_transforms(mut self, transforms: CssTransforms) -> Self {
+        self.context.set_css_transforms(transforms);
+        self
+    }
+
+    fn check_cancelled(&self) -> EvalResult<()> {
+        if self.context.cancellation.is_cancelled() {
+            return Err(EvalError::Cancelled);
+        }
+        Ok(())
+    }
+
     /// Evaluate a document to virtual DOM
     #[instrument(skip(self, doc), fields(components = doc.components.len(), tokens = doc.tokens.len()))]
     pub fn evaluate(&mut self, doc: &Document) -> EvalResult<VirtualDomDocument> {
         info!("Starting document evaluation");
 
+        let strategy = self.context.class_names.strategy();
+        self.context.class_names = Arc::new(ClassNames::for_document(doc, strategy));
+
         // Register tokens
         for token in &doc.tokens {
             debug!(token_name = %token.name, token_value = %token.value, "Registering token");
@@ -413,95 +704,31 @@ impl Evaluator {
         );
 
         for component in &doc.components {
+            self.check_cancelled()?;
             debug!(component_name = %component.name, public = component.public, "Evaluating component");
-            let mut vnode = self.evaluate_component(&component.name)?;
-
-            // Add frame attributes if component has @frame annotation
-            if let Some(frame) = &component.frame {
-                debug!(
-                    component_name = %component.name,
-                    x = frame.x,
-                    y = frame.y,
-                    width = ?frame.width,
-                    height = ?frame.height,
-                    "Adding frame attributes to component"
-                );
-                vnode = vnode
-                    .with_attr("data-frame-x", frame.x.to_string())
-                    .with_attr("data-frame-y", frame.y.to_string());
-                if let Some(w) = frame.width {
-                    vnode = vnode.with_attr("data-frame-width", w.to_string());
-                }
-                if let Some(h) = frame.height {
-                    vnode = vnode.with_attr("data-frame-height", h.to_string());
-                }
-            }
-
-            vdoc.add_node(vnode);
+            let vnode = self.evaluate_component(&component.name)?;
+            vdoc.add_root(vnode, component_root_metadata(component));
         }
 
         // Evaluate top-level renders with their frame annotations
         for (index, render) in doc.renders.iter().enumerate() {
+            self.check_cancelled()?;
             debug!("Evaluating top-level render element at index {}", index);
-            let mut vnode = self.evaluate_element(render)?;
-
-            // Add frame attributes if render has @frame annotation
-            if let Some(Some(frame)) = doc.render_frames.get(index) {
-                debug!(
-                    render_index = index,
-                    x = frame.x,
-                    y = frame.y,
-                    width = ?frame.width,
-                    height = ?frame.height,
-                    "Adding frame attributes to render"
-                );
-                vnode = vnode
-                    .with_attr("data-frame-x", frame.x.to_string())
-                    .with_attr("data-frame-y", frame.y.to_string());
-                if let Some(w) = frame.width {
-                    vnode = vnode.with_attr("data-frame-width", w.to_string());
-                }
-                if let Some(h) = frame.height {
-                    vnode = vnode.with_attr("data-frame-height", h.to_string());
-                }
-            }
-
-            vdoc.add_node(vnode);
+            let vnode = self.evaluate_element(render)?;
+            vdoc.add_root(vnode, render_root_metadata(doc.render_frames.get(index)));
         }
 
         // Evaluate CSS
         debug!("Starting CSS evaluation");
-        let mut css_evaluator = CssEvaluator::with_document_id(&self.context.document_id);
+        let mut css_evaluator = CssEvaluator::from_document_id(self.context.document_id.clone())
+            .with_class_name_strategy(strategy);
         match css_evaluator.evaluate(doc) {
             Ok(css_doc) => {
-                let original_count = css_doc.rules.len();
-                debug!(rules = original_count, "CSS evaluation succeeded");
-
-                // Convert CSS evaluator rules to VDOM rules
-                let mut css_rules = Vec::new();
-                for css_rule in css_doc.rules {
-                    css_rules.push(CssRule {
-                        selector: css_rule.selector,
-                        properties: css_rule.properties,
-                        media_query: css_rule.media_query,
-                    });
-                }
-
-                // Optimize CSS rules (deduplicate, merge)
-                debug!(before = original_count, "Optimizing CSS rules");
-                css_rules = optimize_css_rules(css_rules);
-                let optimized_count = css_rules.len();
-                debug!(
-                    after = optimized_count,
-                    saved = original_count - optimized_count,
-                    "CSS optimization complete"
+                vdoc.styles = into_vdom_styles(
+                    css_doc,
+                    &self.context.browser_targets,
+                    &self.context.css_transforms,
                 );
-
-                // Minify CSS (remove whitespace, shorten values)
-                minify_css_rules(&mut css_rules);
-                debug!("CSS minification complete");
-
-                vdoc.styles = css_rules;
             }
             Err(e) => {
                 warn!(error = %e, "CSS evaluation failed - continuing without styles");
@@ -509,19 +736,36 @@ impl Evaluator {
             }
         }
 
+        // Cancellation inside a body may have been rendered as an error node
+        self.check_cancelled()?;
+
         info!(nodes = vdoc.nodes.len(), styles = vdoc.styles.len(), "Document evaluation complete");
         Ok(vdoc)
     }
 
     /// Evaluate a bundle to virtual DOM (supports cross-file imports)
-    #[instrument(skip(self, bundle), fields(entry = %entry_path.display()))]
     pub fn evaluate_bundle(
         &mut self,
         bundle: &Bundle,
         entry_path: &Path,
+    ) -> EvalResult<VirtualDomDocument> {
+        self.evaluate_bundle_cached(bundle, entry_path, &mut EvalCache::new())
+    }
+
+    /// Evaluate a bundle, reusing VNodes from `cache` for components and renders
+    /// whose structure (and dependencies) haven't changed
+    #[instrument(skip(self, bundle, cache), fields(entry = %entry_path.display()))]
+    pub fn evaluate_bundle_cached(
+        &mut self,
+        bundle: &Bundle,
+        entry_path: &Path,
+        cache: &mut EvalCache,
     ) -> EvalResult<VirtualDomDocument> {
         info!("Starting bundle DOM evaluation");
 
+        let strategy = self.context.class_names.strategy();
+        self.context.class_names = Arc::new(ClassNames::for_bundle(bundle, strategy));
+
         // Get entry document
         let entry_doc = bundle.get_document(entry_path).ok_or_else(|| {
             error!("Entry document not found");
@@ -564,15 +808,18 @@ impl Evaluator {
             self.context.add_component(component.clone());
         }
 
-        // Register components from imported files
-        if let Some(deps) = bundle.get_dependencies(entry_path) {
-            for dep_path in deps {
-                if let Some(dep_doc) = bundle.get_document(dep_path) {
-                    for component in &dep_doc.components {
-                        if component.public {
-                            debug!(component_name = %component.name, from_file = %dep_path.display(), "Registering imported component");
-                            self.context.add_component(component.clone());
-                        }
+        // Register components from imported files, transitively - an imported
+        // component may render one its own file imports. Names already taken
+        // by a direct import aren't shadowed by a transitive one.
+        let direct_count = bundle.get_dependencies(entry_path).map_or(0, |deps| deps.len());
+        for (index, dep_path) in dependency_closure(bundle, entry_path).iter().enumerate() {
+            if let Some(dep_doc) = bundle.get_document(dep_path) {
+                for component in &dep_doc.components {
+                    let shadowed = index >= direct_count
+                        && self.context.components.contains_key(&component.name);
+                    if component.public && !shadowed {
+                        debug!(component_name = %component.name, from_file = %dep_path.display(), "Registering imported component");
+                        self.context.add_component(component.clone());
                     }
                 }
             }
@@ -587,71 +834,135 @@ impl Evaluator {
             "Evaluating all components for preview"
         );
 
+        let components = self.context.components.clone();
+        let mut keys = KeyBuilder::new(&components);
+        let mut slots = BTreeSet::new();
+
         for component in &entry_doc.components {
-            debug!(component_name = %component.name, public = component.public, "Evaluating component");
-            let mut vnode = self.evaluate_component(&component.name)?;
-
-            // Add frame attributes if component has @frame annotation
-            if let Some(frame) = &component.frame {
-                debug!(
-                    component_name = %component.name,
-                    x = frame.x,
-                    y = frame.y,
-                    width = ?frame.width,
-                    height = ?frame.height,
-                    "Adding frame attributes to component"
-                );
-                vnode = vnode
-                    .with_attr("data-frame-x", frame.x.to_string())
-                    .with_attr("data-frame-y", frame.y.to_string());
-                if let Some(w) = frame.width {
-                    vnode = vnode.with_attr("data-frame-width", w.to_string());
-                }
-                if let Some(h) = frame.height {
-                    vnode = vnode.with_attr("data-frame-height", h.to_string());
-                }
-            }
+            self.check_cancelled()?;
+            let key = self.cache_key(keys.component_key(&component.name));
+            slots.insert(component.name.clone());
 
-            // Add component metadata for designer
-            let metadata = extract_component_metadata(component);
-            vdoc.components.push(metadata);
+            let vnode = self.evaluate_memoized(cache, &component.name, key, |evaluator| {
+                debug!(component_name = %component.name, public = component.public, "Evaluating component");
+                evaluator.evaluate_component(&component.name)
+            })?;
 
-            vdoc.add_node(vnode);
+            // Add component metadata for designer
+            vdoc.components.push(extract_component_metadata(component));
+            vdoc.add_root(vnode, component_root_metadata(component));
         }
 
         // Evaluate top-level renders with their frame annotations
         for (index, render) in entry_doc.renders.iter().enumerate() {
-            debug!("Evaluating top-level render element at index {}", index);
-            let mut vnode = self.evaluate_element(render)?;
-
-            // Add frame attributes if render has @frame annotation
-            if let Some(Some(frame)) = entry_doc.render_frames.get(index) {
-                debug!(
-                    render_index = index,
-                    x = frame.x,
-                    y = frame.y,
-                    width = ?frame.width,
-                    height = ?frame.height,
-                    "Adding frame attributes to render"
+            self.check_cancelled()?;
+            let key = self.cache_key(keys.element_key(render));
+            let slot = format!("render:{}", index);
+
+            let vnode = self.evaluate_memoized(cache, &slot, key, |evaluator| {
+                debug!("Evaluating top-level render element at index {}", index);
+                evaluator.evaluate_element(render)
+            })?;
+            slots.insert(slot);
+
+            vdoc.add_root(vnode, render_root_metadata(entry_doc.render_frames.get(index)));
+        }
+
+        // Forget components and renders that were removed
+        cache.retain_slots(&self.context.document_id, &slots);
+
+        // Evaluate CSS across the import graph so stylesheet edits reach the CSSOM.
+        // The preview loads this stylesheet alone, so imported globals come along.
+        debug!("Starting bundle CSS evaluation");
+        let mut css_evaluator = CssEvaluator::new()
+            .with_class_name_strategy(strategy)
+            .with_imported_globals();
+        match css_evaluator.evaluate_bundle(bundle, entry_path) {
+            Ok(css_doc) => {
+                vdoc.styles = into_vdom_styles(
+                    css_doc,
+                    &self.context.browser_targets,
+                    &self.context.css_transforms,
                 );
-                vnode = vnode
-                    .with_attr("data-frame-x", frame.x.to_string())
-                    .with_attr("data-frame-y", frame.y.to_string());
-                if let Some(w) = frame.width {
-                    vnode = vnode.with_attr("data-frame-width", w.to_string());
-                }
-                if let Some(h) = frame.height {
-                    vnode = vnode.with_attr("data-frame-height", h.to_string());
-                }
             }
-
-            vdoc.add_node(vnode);
+            Err(e) => {
+                warn!(error = %e, "CSS evaluation failed - continuing without styles");
+            }
         }
 
-        info!(nodes = vdoc.nodes.len(), "Bundle DOM evaluation complete");
+        // Cancellation inside a body may have been rendered as an error node
+        self.check_cancelled()?;
+
+        info!(nodes = vdoc.nodes.len(), styles = vdoc.styles.len(), "Bundle DOM evaluation complete");
         Ok(vdoc)
     }
 
+    /// Combine an AST key with everything else evaluation output depends on
+    fn cache_key(&self, ast_key: u64) -> u64 {
+        let mut tokens: Vec<_> = self.context.tokens.iter().collect();
+        tokens.sort();
+        let mut counters: Vec<_> = self.context.component_key_counters.iter().collect();
+        counters.sort();
+        let mut variables: Vec<_> = self
+            .context
+            .variables
+            .iter()
+            .map(|(name, value)| (name, format!("{:?}", value)))
+            .collect();
+        variables.sort();
+
+        let mut hasher = DefaultHasher::new();
+        ast_key.hash(&mut hasher);
+        self.context.document_id.hash(&mut hasher);
+        tokens.hash(&mut hasher);
+        counters.hash(&mut hasher);
+        variables.hash(&mut hasher);
+        self.context.max_recursion_depth.hash(&mut hasher);
+        self.context.limits.hash(&mut hasher);
+        self.context.class_names.hash(&mut hasher);
+        // Inline preview styles go through the transforms
+        self.context.css_transforms.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Evaluate a top-level slot through the cache. On a hit, the instance key
+    /// counters the original evaluation bumped are replayed so later semantic
+    /// IDs don't shift.
+    fn evaluate_memoized(
+        &mut self,
+        cache: &mut EvalCache,
+        slot: &str,
+        key: u64,
+        evaluate: impl FnOnce(&mut Self) -> EvalResult<VNode>,
+    ) -> EvalResult<VNode> {
+        let document_id = self.context.document_id.clone();
+
+        if let Some((vnode, counter_delta)) = cache.get(&document_id, slot, key) {
+            debug!(slot, "Evaluation cache hit");
+            for (name, count) in counter_delta {
+                *self.context.component_key_counters.entry(name).or_insert(0) += count;
+            }
+            return Ok(vnode);
+        }
+
+        let counters_before = self.context.component_key_counters.clone();
+        let vnode = evaluate(self)?;
+
+        let mut counter_delta: Vec<_> = self
+            .context
+            .component_key_counters
+            .iter()
+            .filter_map(|(name, after)| {
+                let before = counters_before.get(name).copied().unwrap_or(0);
+                (*after > before).then(|| (name.clone(), after - before))
+            })
+            .collect();
+        counter_delta.sort();
+
+        cache.insert(&document_id, slot, key, &vnode, counter_delta);
+        Ok(vnode)
+    }
+
     /// Evaluate a component by name (for top-level public components)
     fn evaluate_component(&mut self, name: &str) -> EvalResult<VNode> {
         // Generate key and push component segment for top-level component
@@ -695,8 +1006,40 @@ impl Evaluator {
             "Evaluating component with props and children"
         );
 
+        // Recursion is allowed when some instance in the cycle is under a conditional/repeat
+        let guarded = self.context.guard_depth > 0;
+        let previous = self.context.component_stack.iter().rposition(|c| c == name);
+        let cycle_guarded = previous.is_some_and(|index| {
+            guarded || self.context.component_guards[index + 1..].iter().any(|g| *g)
+        });
+
+        if cycle_guarded {
+            let depth = self
+                .context
+                .component_stack
+                .iter()
+                .filter(|c| c.as_str() == name)
+                .count();
+
+            if depth >= self.context.max_recursion_depth {
+                warn!(
+                    component = name,
+                    depth, "Maximum recursion depth reached"
+                );
+                let semantic_id = self.context.get_semantic_id();
+                return Ok(VNode::error(
+                    format!(
+                        "Maximum recursion depth ({}) reached for component '{}'",
+                        self.context.max_recursion_depth, name
+                    ),
+                    None,
+                    semantic_id,
+                ));
+            }
+        }
+
         // Check for circular component dependency
-        if self.context.component_stack.contains(&name.to_string()) {
+        if previous.is_some() && !cycle_guarded {
             // Build full call stack for error message
             let mut call_stack = self.context.component_stack.clone();
             call_stack.push(name.to_string());
@@ -742,6 +1085,7 @@ impl Evaluator {
         // Push component to call stack BEFORE cloning context
         // This ensures the cloned context includes the updated stack
         self.context.component_stack.push(name.to_string());
+        self.context.component_guards.push(guarded);
 
         // Create a new context scope with props as variables
         let mut scoped_evaluator = Evaluator {
@@ -750,6 +1094,7 @@ impl Evaluator {
 
         // Set current component for class name scoping
         scoped_evaluator.context.current_component = Some(name.to_string());
+        scoped_evaluator.context.guard_depth = 0;
 
         // Bind props to variables
         for (key, value) in props {
@@ -758,15 +1103,36 @@ impl Evaluator {
                 .set_variable(key.clone(), value.clone());
         }
 
-        // Register slot content
-        // If children are provided, they become the default slot content
-        // Named slots can be extracted from children if they have slot attributes
-        if !children.is_empty() {
-            // For now, all children go to the default "children" slot
-            scoped_evaluator
-                .context
-                .slot_content
-                .insert("children".to_string(), children.to_vec());
+        // Register slot content: `insert name { ... }` fills a named slot,
+        // everything else goes to the default "children" slot
+        let mut default_children = Vec::new();
+        for child in children {
+            match child {
+                Element::Insert {
+                    slot_name,
+                    params,
+                    content,
+                    ..
+                } => {
+                    scoped_evaluator.context.slot_content.insert(
+                        slot_name.clone(),
+                        SlotContent {
+                            params: params.clone(),
+                            elements: content.clone(),
+                        },
+                    );
+                }
+                other => default_children.push(other.clone()),
+            }
+        }
+        if !default_children.is_empty() {
+            scoped_evaluator.context.slot_content.insert(
+                "children".to_string(),
+                SlotContent {
+                    params: Vec::new(),
+                    elements: default_children,
+                },
+            );
         }
 
         let result = if let Some(body) = &component_body {
@@ -779,12 +1145,156 @@ impl Evaluator {
 
         // Pop component from call stack in SELF context (not scoped)
         self.context.component_stack.pop();
+        self.context.component_guards.pop();
 
         result
     }
 
+    /// Declarations of `styles` as inline preview styles, run through the
+    /// same transforms as the stylesheet
+    fn inline_styles(&self, styles: &[StyleBlock]) -> HashMap<String, String> {
+        let mut properties = Properties::new();
+        for style_block in styles {
+            for (key, value) in &style_block.properties {
+                properties.insert(key.clone(), value.clone());
+            }
+        }
+        self.context.css_transforms.apply(&mut properties);
+        properties.into_iter().collect()
+    }
+
+    /// Evaluate rich text into a `span` whose children are the runs.
+    /// Plain runs become text nodes; tagged runs become inline elements.
+    fn evaluate_rich_text(
+        &mut self,
+        runs: &[TextRun],
+        styles: &[StyleBlock],
+        span: &Span,
+    ) -> EvalResult<VNode> {
+        let semantic_id = self.context.get_semantic_id();
+        let class_name = self.context.class_names.class_name(
+            Some("span"),
+            &span.id,
+            self.context.current_component.as_deref(),
+        );
+
+        let mut vnode = VNode::element("span", semantic_id)
+            .with_source_id(span.id.clone())
+            .with_attr("class", class_name);
+        for (key, value) in self.inline_styles(styles) {
+            vnode = vnode.with_style(key, value);
+        }
+
+        for run in runs {
+            vnode = vnode.with_child(self.evaluate_text_run(run)?);
+        }
+
+        Ok(vnode)
+    }
+
+    /// Evaluate a single rich text run
+    fn evaluate_text_run(&mut self, run: &TextRun) -> EvalResult<VNode> {
+        let text = match self.evaluate_expression(&run.content) {
+            Ok(value) => value.to_string(),
+            Err(err) => {
+                warn!(error = %err, "Expression evaluation failed in text run");
+                let semantic_id = self.context.get_semantic_id();
+                return Ok(VNode::error(
+                    format!("Error: {}", err),
+                    Some(run.span.clone()),
+                    semantic_id,
+                ));
+            }
+        };
+
+        let Some(tag) = &run.tag else {
+            return Ok(VNode::text(text));
+        };
+
+        self.context.push_segment(SemanticSegment::Element {
+            tag: tag.clone(),
+            role: None,
+            ast_id: run.span.id.clone(),
+        });
+        let semantic_id = self.context.get_semantic_id();
+
+        let class_name = self.context.class_names.class_name(
+            Some(tag.as_str()),
+            &run.span.id,
+            self.context.current_component.as_deref(),
+        );
+        let mut vnode = VNode::element(tag, semantic_id)
+            .with_source_id(run.span.id.clone())
+            .with_attr("class", class_name);
+
+        for (key, expr) in &run.attributes {
+            match self.evaluate_expression(expr) {
+                Ok(value) => vnode = vnode.with_attr(key, value.to_string()),
+                Err(err) => {
+                    warn!(attribute = key, error = %err, "Expression evaluation failed in attribute");
+                    vnode = vnode.with_attr(key, format!("[Error: {}]", err));
+                }
+            }
+        }
+        for (key, value) in self.inline_styles(&run.styles) {
+            vnode = vnode.with_style(key, value);
+        }
+
+        self.context.pop_segment();
+
+        Ok(vnode.with_child(VNode::text(text)))
+    }
+
     /// Evaluate an element
     fn evaluate_element(&mut self, element: &Element) -> EvalResult<VNode> {
+        self.check_cancelled()?;
+
+        let limits = self.context.limits;
+        if self.context.depth >= limits.max_depth {
+            return Ok(self.limit_error(
+                format!("Maximum nesting depth ({}) exceeded", limits.max_depth),
+                element,
+            ));
+        }
+        if self.context.usage.add_node() > limits.max_nodes {
+            return Ok(self.limit_error(
+                format!("Maximum node count ({}) exceeded", limits.max_nodes),
+                element,
+            ));
+        }
+
+        self.context.depth += 1;
+        let result = self.evaluate_element_unbounded(element);
+        self.context.depth -= 1;
+        let vnode = result?;
+
+        // Only tags and text produce output of their own; other elements return
+        // nodes that were already counted
+        let bytes = match element {
+            Element::Tag { .. } => output_size(&vnode, false),
+            Element::Text { .. } => output_size(&vnode, true),
+            _ => 0,
+        };
+        if bytes > 0 && self.context.usage.add_output_bytes(bytes) > limits.max_output_bytes {
+            return Ok(self.limit_error(
+                format!("Maximum output size ({} bytes) exceeded", limits.max_output_bytes),
+                element,
+            ));
+        }
+
+        Ok(vnode)
+    }
+
+    fn limit_error(&self, message: String, element: &Element) -> VNode {
+        warn!(%message, "Evaluation limit reached");
+        VNode::error(
+            message,
+            Some(element.span().clone()),
+            self.context.get_semantic_id(),
+        )
+    }
+
+    fn evaluate_element_unbounded(&mut self, element: &Element) -> EvalResult<VNode> {
         match element {
             Element::Tag {
                 tag_name,
@@ -825,7 +1335,7 @@ impl Evaluator {
                     .with_source_id(span.id.clone());  // Map back to AST for mutations
 
                 // Generate and apply class name for CSS synchronization
-                let class_name = get_style_namespace(
+                let class_name = self.context.class_names.class_name(
                     Some(tag_name.as_str()),
                     &span.id,
                     self.context.current_component.as_deref(),
@@ -861,10 +1371,8 @@ impl Evaluator {
                 }
 
                 // Evaluate styles
-                for style_block in styles {
-                    for (key, value) in &style_block.properties {
-                        vnode = vnode.with_style(key, value);
-                    }
+                for (key, value) in self.inline_styles(styles) {
+                    vnode = vnode.with_style(key, value);
                 }
 
                 // Evaluate children
@@ -906,10 +1414,18 @@ impl Evaluator {
                 Ok(vnode)
             }
 
+            Element::Text {
+                runs,
+                styles,
+                span,
+                ..
+            } if !runs.is_empty() => self.evaluate_rich_text(runs, styles, span),
+
             Element::Text {
                 content,
                 styles,
                 span,
+                ..
             } => {
                 match self.evaluate_expression(content) {
                     Ok(value) => {
@@ -920,10 +1436,8 @@ impl Evaluator {
                             // Wrap text in span when it has styles
                             let semantic_id = self.context.get_semantic_id();
                             let mut vnode = VNode::element("span", semantic_id);
-                            for style_block in styles {
-                                for (key, value) in &style_block.properties {
-                                    vnode = vnode.with_style(key, value);
-                                }
+                            for (key, value) in self.inline_styles(styles) {
+                                vnode = vnode.with_style(key, value);
                             }
                             vnode = vnode.with_child(VNode::text(text_value));
                             Ok(vnode)
@@ -946,7 +1460,7 @@ impl Evaluator {
                 name,
                 props,
                 children,
-                span: _span,
+                span,
             } => {
                 // Check if this is an HTML tag (not a component)
                 if is_html_tag(name) {
@@ -1043,12 +1557,37 @@ impl Evaluator {
                 // Expand component - this returns the component's body with props applied
                 // The result is pure DOM elements, not a Component VNode
                 // Pass children as slot content
-                let result = self.evaluate_component_with_props_and_children(
+                let mut result = self.evaluate_component_with_props_and_children(
                     name,
                     &evaluated_props,
                     children,
                 );
 
+                // Script-bound components keep the static render as a fallback
+                // for clients that can't mount the real code
+                let script = self
+                    .context
+                    .components
+                    .get(name)
+                    .and_then(|component| component.script.clone());
+                if let (Some(script), Ok(fallback)) = (script, &result) {
+                    let props: serde_json::Map<String, serde_json::Value> = evaluated_props
+                        .iter()
+                        .filter(|(key, _)| key.as_str() != "key")
+                        .map(|(key, value)| (key.clone(), value.to_json()))
+                        .collect();
+                    result = Ok(VNode::LiveComponent {
+                        src: script.src,
+                        export_name: script.name.unwrap_or_else(|| name.clone()),
+                        target: script.target,
+                        props: serde_json::Value::Object(props).to_string(),
+                        children: vec![fallback.clone()],
+                        semantic_id: self.context.get_semantic_id(),
+                        source_id: Some(span.id.clone()),
+                        key: None,
+                    });
+                }
+
                 // Pop component segment
                 self.context.pop_segment();
 
@@ -1075,7 +1614,10 @@ impl Evaluator {
                     }
                 };
 
-                if condition_value.is_truthy() {
+                // Instances in either branch are guarded by data
+                self.context.guard_depth += 1;
+
+                let result = if condition_value.is_truthy() {
                     // Push ConditionalBranch segment for then branch
                     self.context
                         .push_segment(SemanticSegment::ConditionalBranch {
@@ -1167,7 +1709,10 @@ impl Evaluator {
                     Ok(VNode::Comment {
                         content: "conditional false".to_string(),
                     })
-                }
+                };
+
+                self.context.guard_depth -= 1;
+                result
             }
 
             Element::Repeat {
@@ -1191,11 +1736,30 @@ impl Evaluator {
                     }
                 };
 
+                if let Value::Array(items) = &collection_value {
+                    let max_items = self.context.limits.max_repeat_items;
+                    if items.len() > max_items {
+                        let semantic_id = self.context.get_semantic_id();
+                        return Ok(VNode::error(
+                            format!(
+                                "Repeat over {} items exceeds the limit of {}",
+                                items.len(),
+                                max_items
+                            ),
+                            Some(span.clone()),
+                            semantic_id,
+                        ));
+                    }
+                }
+
                 // Push repeat wrapper semantic ID
                 let semantic_id = self.context.get_semantic_id();
                 let mut wrapper = VNode::element("div", semantic_id);
 
                 if let Value::Array(items) = collection_value {
+                    // Instances in the body are guarded by the collection
+                    self.context.guard_depth += 1;
+
                     for (index, item) in items.iter().enumerate() {
                         // Check if first child has explicit key attribute
                         let explicit_key =
@@ -1268,6 +1832,8 @@ impl Evaluator {
 
                         self.context.pop_segment();
                     }
+
+                    self.context.guard_depth -= 1;
                 }
 
                 Ok(wrapper)
@@ -1289,14 +1855,35 @@ impl Evaluator {
                 };
 
                 // Check if we have inserted content for this slot (clone to avoid borrow issues)
-                let inserted_content = self.context.slot_content.get(name).cloned();
+                let inserted = self.context.slot_content.get(name).cloned();
+
+                if let Some(inserted) = inserted {
+                    // Scoped slots pass values from the render site to the
+                    // inserted content, bound under the insert's own names
+                    let slot_params = self
+                        .context
+                        .components
+                        .get(component_name)
+                        .and_then(|component| component.slots.iter().find(|s| &s.name == name))
+                        .map(|slot| slot.params.clone())
+                        .unwrap_or_default();
+                    let mut shadowed = Vec::new();
+                    for (index, param) in inserted.params.iter().enumerate() {
+                        let value = slot_params
+                            .get(index)
+                            .and_then(|slot_param| self.context.variables.get(slot_param))
+                            .cloned()
+                            .unwrap_or(Value::Null);
+                        shadowed.push((param.clone(), self.context.variables.get(param).cloned()));
+                        self.context.set_variable(param.clone(), value);
+                    }
 
-                if let Some(inserted_content) = inserted_content {
                     // Use inserted content
                     self.context.push_segment(SemanticSegment::Slot {
                         name: name.clone(),
                         variant: paperclip_semantics::SlotVariant::Inserted,
                     });
+                    let inserted_content = inserted.elements;
 
                     // If single child, return it directly
                     // If multiple children, wrap in fragment
@@ -1335,6 +1922,14 @@ impl Evaluator {
                     };
 
                     self.context.pop_segment();
+                    for (param, old_value) in shadowed.into_iter().rev() {
+                        match old_value {
+                            Some(old) => self.context.set_variable(param, old),
+                            None => {
+                                self.context.variables.remove(&param);
+                            }
+                        }
+                    }
                     result
                 } else {
                     // Use default content from slot definition
@@ -1429,6 +2024,7 @@ impl Evaluator {
                 slot_name,
                 content,
                 span,
+                ..
             } => {
                 // Insert directive is used to explicitly provide slot content
                 // This should typically be handled at the component instance level
@@ -1792,6 +2388,55 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_evaluate_rich_text_runs() {
+        let source = r#"
+            public component Notice {
+                render div {
+                    text {
+                        "Read the "
+                        a(href="/terms") "terms" {
+                            style { color: blue }
+                        }
+                        " first"
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = Evaluator::with_document_id("/test.pc");
+        let vdoc = evaluator.evaluate(&doc).expect("Failed to evaluate");
+
+        let VNode::Element { children, .. } = &vdoc.nodes[0] else {
+            panic!("Expected element node");
+        };
+        let VNode::Element { tag, children: runs, .. } = &children[0] else {
+            panic!("Expected rich text span");
+        };
+        assert_eq!(tag, "span");
+        assert_eq!(runs.len(), 3);
+        assert!(matches!(&runs[0], VNode::Text { content } if content == "Read the "));
+        assert!(matches!(&runs[2], VNode::Text { content } if content == " first"));
+
+        match &runs[1] {
+            VNode::Element {
+                tag,
+                attributes,
+                styles,
+                children,
+                ..
+            } => {
+                assert_eq!(tag, "a");
+                assert_eq!(attributes.get("href"), Some(&"/terms".to_string()));
+                assert!(attributes["class"].starts_with("_Notice-a-"));
+                assert_eq!(styles.get("color"), Some(&"blue".to_string()));
+                assert!(matches!(&children[0], VNode::Text { content } if content == "terms"));
+            }
+            other => panic!("Expected link run, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_component_expansion_with_props() {
         let source = r#"
@@ -1851,6 +2496,56 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_script_bound_instance_emits_live_component() {
+        let source = r#"
+            component Counter {
+                script(src: "./counter.tsx", target: "react", name: "LiveCounter")
+                render button {
+                    text {count}
+                }
+            }
+
+            public component App {
+                render div {
+                    Counter(count=3, label="Clicks")
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let mut evaluator = Evaluator::with_document_id("/test.pc");
+        let vdoc = evaluator.evaluate(&doc).expect("Failed to evaluate");
+
+        let VNode::Element { children, .. } = &vdoc.nodes[1] else {
+            panic!("Expected App div");
+        };
+        let VNode::LiveComponent {
+            src,
+            export_name,
+            target,
+            props,
+            children: fallback,
+            semantic_id,
+            ..
+        } = &children[0]
+        else {
+            panic!("Expected live component, got {:?}", children[0]);
+        };
+
+        assert_eq!(src, "./counter.tsx");
+        assert_eq!(export_name, "LiveCounter");
+        assert_eq!(target, "react");
+        assert_eq!(props, r#"{"count":3.0,"label":"Clicks"}"#);
+        assert!(semantic_id.to_selector().contains("::Counter{"));
+
+        // The static render is kept as the fallback
+        let VNode::Element { tag, .. } = &fallback[0] else {
+            panic!("Expected fallback button");
+        };
+        assert_eq!(tag, "button");
+    }
+
     #[test]
     fn test_nested_component_expansion() {
         let source = r#"
@@ -1949,12 +2644,20 @@ component Card {
         let mut evaluator = Evaluator::with_document_id("/test.pc");
         let vdoc = evaluator.evaluate(&doc).expect("Failed to evaluate");
 
-        // Check VDOM has frame attributes
+        // Frame is typed root metadata, not node attributes
+        let root = vdoc.root_metadata(0).expect("Root should have metadata");
+        assert_eq!(root.component.as_deref(), Some("Card"));
+        assert_eq!(
+            root.frame,
+            Some(FrameMetadata {
+                x: 100.0,
+                y: 200.0,
+                width: Some(300.0),
+                height: Some(400.0),
+            })
+        );
         if let VNode::Element { attributes, .. } = &vdoc.nodes[0] {
-            assert_eq!(attributes.get("data-frame-x"), Some(&"100".to_string()));
-            assert_eq!(attributes.get("data-frame-y"), Some(&"200".to_string()));
-            assert_eq!(attributes.get("data-frame-width"), Some(&"300".to_string()));
-            assert_eq!(attributes.get("data-frame-height"), Some(&"400".to_string()));
+            assert!(!attributes.keys().any(|k| k.starts_with("data-frame-")));
         } else {
             panic!("Expected Element node");
         }
@@ -2001,32 +2704,31 @@ div {
         // Should have 2 nodes: one for component, one for render
         assert_eq!(vdoc.nodes.len(), 2, "Should have 2 nodes (component + render)");
 
-        // First node (component) should have frame attributes
-        if let VNode::Element { attributes, .. } = &vdoc.nodes[0] {
-            assert_eq!(
-                attributes.get("data-frame-x"),
-                Some(&"100".to_string()),
-                "Component should have data-frame-x"
-            );
-            assert_eq!(attributes.get("data-frame-y"), Some(&"200".to_string()));
-            assert_eq!(attributes.get("data-frame-width"), Some(&"300".to_string()));
-            assert_eq!(attributes.get("data-frame-height"), Some(&"400".to_string()));
-        } else {
-            panic!("Expected Element node for component");
-        }
+        // Both roots carry their frame in root metadata
+        let component = vdoc.root_metadata(0).expect("Component root metadata");
+        assert_eq!(component.component.as_deref(), Some("Card"));
+        assert_eq!(
+            component.frame,
+            Some(FrameMetadata {
+                x: 100.0,
+                y: 200.0,
+                width: Some(300.0),
+                height: Some(400.0),
+            }),
+            "Component should have frame"
+        );
 
-        // Second node (render) should have frame attributes
-        if let VNode::Element { attributes, .. } = &vdoc.nodes[1] {
-            assert_eq!(
-                attributes.get("data-frame-x"),
-                Some(&"500".to_string()),
-                "Render should have data-frame-x"
-            );
-            assert_eq!(attributes.get("data-frame-y"), Some(&"600".to_string()));
-            assert_eq!(attributes.get("data-frame-width"), Some(&"700".to_string()));
-            assert_eq!(attributes.get("data-frame-height"), Some(&"800".to_string()));
-        } else {
-            panic!("Expected Element node for render");
-        }
+        let render = vdoc.root_metadata(1).expect("Render root metadata");
+        assert_eq!(render.component, None);
+        assert_eq!(
+            render.frame,
+            Some(FrameMetadata {
+                x: 500.0,
+                y: 600.0,
+                width: Some(700.0),
+                height: Some(800.0),
+            }),
+            "Render should have frame"
+        );
     }
 }
@@ -0,0 +1,608 @@
+//! # Static HTML Rendering
+//!
+//! Serializes an evaluated `VirtualDomDocument` to HTML, so static output
+//! matches the preview exactly (expressions, slots, overrides and repeats are
+//! already resolved by the evaluator).
+//!
+//! With `hydration_markers` enabled every element carries a `data-pc-id`
+//! attribute holding its semantic ID (and `data-pc-key` for keyed repeat
+//! items), which lets a client adopt the server-rendered DOM and then apply
+//! streamed `VDocPatch`es to it. Script-bound components are wrapped in a
+//! `display: contents` host carrying `data-pc-live-*` attributes so the
+//! client can mount the real component; without markers only the static
+//! fallback is written.
+//!
+//! Patches address nodes by child index, so the parsed DOM has to line up
+//! with the VDOM: roots are written back to back with nothing else in
+//! `<body>` (whitespace there parses as text nodes), and with markers an
+//! empty comment (`<!---->`) separates adjacent text nodes, which would
+//! otherwise parse as one. Clients drop those comments when hydrating.
+//!
+//! Output is deterministic: attributes, inline styles and rule properties are
+//! written in sorted order.
+//!
+//! ## Usage
+//!
+//! ```rust,ignore
+//! use paperclip_evaluator::{HtmlOptions, VirtualDomDocument};
+//!
+//! let html = vdom.to_html(&HtmlOptions {
+//!     hydration_markers: true,
+//!     ..HtmlOptions::default()
+//! });
+//! ```
+
+use crate::css_at_rules::{close_at_rules, open_at_rules};
+use crate::css_layers::{layer_order_statement, CssLayer};
+use crate::vdom::{CssRule, VNode, VirtualDomDocument};
+use std::collections::BTreeMap;
+
+/// Options for `VirtualDomDocument::to_html`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtmlOptions {
+    /// Emit only the `<style>` block and nodes, without `<html>`/`<head>`/`<body>`
+    pub fragment: bool,
+    /// Add `data-pc-id` / `data-pc-key` attributes for client hydration
+    pub hydration_markers: bool,
+    /// Document `<title>` (ignored for fragments)
+    pub title: String,
+    /// Wrap styles in named `@layer` blocks
+    pub cascade_layers: bool,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            fragment: false,
+            hydration_markers: false,
+            title: "Paperclip Components".to_string(),
+            cascade_layers: false,
+        }
+    }
+}
+
+impl VirtualDomDocument {
+    /// Render the document as HTML
+    pub fn to_html(&self, options: &HtmlOptions) -> String {
+        let mut html = String::new();
+
+        if !options.fragment {
+            html.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
+            html.push_str("<meta charset=\"UTF-8\">\n");
+            html.push_str(
+                "<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n",
+            );
+            html.push_str("<title>");
+            html.push_str(&escape_text(&options.title));
+            html.push_str("</title>\n");
+        }
+
+        if !self.styles.is_empty() {
+            html.push_str("<style>\n");
+            html.push_str(&escape_raw_text(&rules_to_css(&self.styles, options.cascade_layers), "style"));
+            html.push_str("</style>\n");
+        }
+
+        if !options.fragment {
+            html.push_str("</head>\n<body>");
+        }
+
+        render_children(&self.nodes, None, options, &mut html);
+
+        // Even whitespace after `</html>` ends up in `<body>`
+        if !options.fragment {
+            html.push_str("</body></html>");
+        }
+
+        html
+    }
+}
+
+fn render_children(
+    nodes: &[VNode],
+    parent_tag: Option<&str>,
+    options: &HtmlOptions,
+    html: &mut String,
+) {
+    let mut previous_text = false;
+    for node in nodes {
+        let is_text = matches!(node, VNode::Text { .. });
+        // Keeps adjacent text nodes apart when the HTML is parsed back
+        // (not inside <script>/<style>, where a comment would be content)
+        if is_text && previous_text && options.hydration_markers && !is_raw_text(parent_tag) {
+            html.push_str("<!---->");
+        }
+        previous_text = is_text;
+        render_node(node, parent_tag, options, html);
+    }
+}
+
+fn is_raw_text(tag: Option<&str>) -> bool {
+    matches!(tag, Some("script" | "style"))
+}
+
+fn render_node(node: &VNode, parent_tag: Option<&str>, options: &HtmlOptions, html: &mut String) {
+    match node {
+        VNode::Element {
+            tag,
+            attributes,
+            styles,
+            children,
+            semantic_id,
+            key,
+            ..
+        } => {
+            let tag = tag.to_ascii_lowercase();
+            if !is_valid_name(&tag) {
+                html.push_str(&format!("<!-- invalid tag {} -->", escape_comment(&tag)));
+                return;
+            }
+
+            html.push('<');
+            html.push_str(&tag);
+
+            let mut attrs: BTreeMap<&str, String> = attributes
+                .iter()
+                .filter(|(name, _)| is_valid_name(name))
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect();
+
+            if !styles.is_empty() {
+                let inline = styles
+                    .iter()
+                    .collect::<BTreeMap<_, _>>()
+                    .into_iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                let style = match attrs.get("style") {
+                    Some(existing) if !existing.is_empty() => {
+                        format!("{}; {}", existing.trim_end_matches(';'), inline)
+                    }
+                    _ => inline,
+                };
+                attrs.insert("style", style);
+            }
+
+            if options.hydration_markers {
+                attrs.insert("data-pc-id", semantic_id.to_selector());
+                if let Some(key) = key {
+                    attrs.insert("data-pc-key", key.clone());
+                }
+            }
+
+            for (name, value) in attrs {
+                html.push(' ');
+                html.push_str(name);
+                html.push_str("=\"");
+                html.push_str(&escape_attribute(&value));
+                html.push('"');
+            }
+            html.push('>');
+
+            // Void elements can't have children or a closing tag
+            if is_void_element(&tag) {
+                return;
+            }
+
+            render_children(children, Some(&tag), options, html);
+
+            html.push_str("</");
+            html.push_str(&tag);
+            html.push('>');
+        }
+        VNode::LiveComponent {
+            src,
+            export_name,
+            target,
+            props,
+            children,
+            semantic_id,
+            key,
+            ..
+        } => {
+            if !options.hydration_markers {
+                render_children(children, parent_tag, options, html);
+                return;
+            }
+
+            // A layout-neutral host keeps patch paths aligned with the VDOM
+            // and tells the client what to mount in place of the fallback
+            let mut attrs = BTreeMap::from([
+                ("data-pc-id", semantic_id.to_selector()),
+                ("data-pc-live-export", export_name.clone()),
+                ("data-pc-live-props", props.clone()),
+                ("data-pc-live-src", src.clone()),
+                ("data-pc-live-target", target.clone()),
+                ("style", "display: contents".to_string()),
+            ]);
+            if let Some(key) = key {
+                attrs.insert("data-pc-key", key.clone());
+            }
+
+            html.push_str("<div");
+            for (name, value) in attrs {
+                html.push(' ');
+                html.push_str(name);
+                html.push_str("=\"");
+                html.push_str(&escape_attribute(&value));
+                html.push('"');
+            }
+            html.push('>');
+            render_children(children, Some("div"), options, html);
+            html.push_str("</div>");
+        }
+        VNode::Text { content } => match parent_tag {
+            Some(tag @ ("script" | "style")) => html.push_str(&escape_raw_text(content, tag)),
+            _ => html.push_str(&escape_text(content)),
+        },
+        VNode::Comment { content } => {
+            html.push_str("<!--");
+            html.push_str(&escape_comment(content));
+            html.push_str("-->");
+        }
+        VNode::Error { message, .. } => {
+            html.push_str("<!-- paperclip error: ");
+            html.push_str(&escape_comment(message));
+            html.push_str(" -->");
+        }
+    }
+}
+
+/// Serialize rules in document order, with sorted properties. With `layered`,
+/// rules are grouped into `@layer` blocks after the layer order statement.
+fn rules_to_css(rules: &[CssRule], layered: bool) -> String {
+    let mut css = String::new();
+
+    if !layered {
+        for rule in rules {
+            write_rule(&mut css, rule, "");
+        }
+        return css;
+    }
+
+    css.push_str(&layer_order_statement());
+    for layer in CssLayer::ALL {
+        let mut layer_rules = rules.iter().filter(|rule| rule.layer == layer).peekable();
+        if layer_rules.peek().is_none() {
+            continue;
+        }
+
+        css.push_str(&format!("@layer {} {{\n", layer));
+        for rule in layer_rules {
+            write_rule(&mut css, rule, "  ");
+        }
+        css.push_str("}\n");
+    }
+
+    css
+}
+
+fn write_rule(css: &mut String, rule: &CssRule, indent: &str) {
+    let inner = open_at_rules(css, &rule.at_rules, indent);
+
+    css.push_str(&inner);
+    css.push_str(&rule.selector);
+    css.push_str(" {\n");
+    for (name, value) in rule.properties.iter().collect::<BTreeMap<_, _>>() {
+        css.push_str(&inner);
+        css.push_str(&format!("  {}: {};\n", name, value));
+    }
+    css.push_str(&inner);
+    css.push_str("}\n");
+    close_at_rules(css, &rule.at_rules, indent);
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_attribute(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Contents of `<script>`/`<style>` aren't entity-decoded, so only the
+/// closing tag sequence needs breaking up
+fn escape_raw_text(text: &str, tag: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let lower = text.to_ascii_lowercase();
+    let closing = format!("</{}", tag);
+    let mut last = 0;
+    for (i, _) in lower.match_indices(&closing) {
+        escaped.push_str(&text[last..i]);
+        escaped.push_str("<\\/");
+        last = i + 2;
+    }
+    escaped.push_str(&text[last..]);
+    escaped
+}
+
+fn escape_comment(text: &str) -> String {
+    text.replace("--", "- -").replace('>', "&gt;")
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| !c.is_whitespace() && !matches!(c, '"' | '\'' | '<' | '>' | '/' | '='))
+}
+
+fn is_void_element(tag: &str) -> bool {
+    matches!(
+        tag,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "param"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css_at_rules::AtRule;
+    use crate::evaluator::Evaluator;
+    use crate::vdom_differ::{diff_vdocument, v_doc_patch};
+    use paperclip_parser::ast::Properties;
+    use paperclip_parser::parse_with_path;
+    use paperclip_semantics::SemanticID;
+
+    /// DOM node as a browser would build it from the rendered `<body>`
+    #[derive(Debug, PartialEq)]
+    enum Dom {
+        Element(String, Vec<Dom>),
+        Text(String),
+    }
+
+    /// Minimal parser for the markup `to_html` writes, dropping the empty
+    /// comments that separate text nodes the way a hydrating client does
+    fn hydrate(html: &str) -> Vec<Dom> {
+        let body = &html[html.find("<body>").unwrap() + 6..html.rfind("</body>").unwrap()];
+        let mut stack = vec![(String::new(), Vec::new())];
+        let mut rest = body;
+        while !rest.is_empty() {
+            if let Some(comment) = rest.strip_prefix("<!--") {
+                let end = comment.find("-->").unwrap();
+                rest = &comment[end + 3..];
+            } else if let Some(closing) = rest.strip_prefix("</") {
+                let end = closing.find('>').unwrap();
+                let (tag, children) = stack.pop().unwrap();
+                stack
+                    .last_mut()
+                    .unwrap()
+                    .1
+                    .push(Dom::Element(tag, children));
+                rest = &closing[end + 1..];
+            } else if let Some(open) = rest.strip_prefix('<') {
+                let end = open.find('>').unwrap();
+                let tag = open[..end].split(' ').next().unwrap().to_string();
+                if is_void_element(&tag) {
+                    stack.last_mut().unwrap().1.push(Dom::Element(tag, vec![]));
+                } else {
+                    stack.push((tag, vec![]));
+                }
+                rest = &open[end + 1..];
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let text = rest[..end]
+                    .replace("&lt;", "<")
+                    .replace("&gt;", ">")
+                    .replace("&amp;", "&");
+                stack.last_mut().unwrap().1.push(Dom::Text(text));
+                rest = &rest[end..];
+            }
+        }
+        stack.pop().unwrap().1
+    }
+
+    fn dom_of(nodes: &[VNode]) -> Vec<Dom> {
+        nodes
+            .iter()
+            .map(|node| match node {
+                VNode::Element { tag, children, .. } => Dom::Element(tag.clone(), dom_of(children)),
+                VNode::Text { content } => Dom::Text(content.clone()),
+                _ => panic!("Unexpected node {:?}", node),
+            })
+            .collect()
+    }
+
+    fn fragment() -> HtmlOptions {
+        HtmlOptions {
+            fragment: true,
+            ..HtmlOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_escaping_and_void_elements() {
+        let vdom = VirtualDomDocument {
+            nodes: vec![VNode::element("div", SemanticID::root())
+                .with_attr("title", "\"quoted\" & <tagged>")
+                .with_child(VNode::text("1 < 2 & 3 > 2"))
+                .with_child(VNode::element("img", SemanticID::root()).with_attr("src", "a.png"))
+                .with_child(VNode::element("br", SemanticID::root()))],
+            styles: vec![],
+            components: vec![],
+            roots: vec![],
+        };
+
+        assert_eq!(
+            vdom.to_html(&fragment()),
+            "<div title=\"&quot;quoted&quot; &amp; &lt;tagged&gt;\">1 &lt; 2 &amp; 3 &gt; 2<img src=\"a.png\"><br></div>"
+        );
+    }
+
+    #[test]
+    fn test_raw_text_and_styles() {
+        let vdom = VirtualDomDocument {
+            nodes: vec![VNode::element("script", SemanticID::root())
+                .with_child(VNode::text("if (a < b) { x = \"</script>\"; }"))],
+            styles: vec![CssRule {
+                selector: ".card".to_string(),
+                properties: Properties::from([
+                    ("padding".to_string(), "8px".to_string()),
+                    ("color".to_string(), "red".to_string()),
+                ]),
+                at_rules: vec![AtRule::media("(max-width: 600px)")],
+                global: false,
+                layer: CssLayer::Components,
+            }],
+            components: vec![],
+            roots: vec![],
+        };
+
+        assert_eq!(
+            vdom.to_html(&fragment()),
+            "<style>\n@media (max-width: 600px) {\n  .card {\n    color: red;\n    padding: 8px;\n  }\n}\n</style>\n\
+             <script>if (a < b) { x = \"<\\/script>\"; }</script>"
+        );
+    }
+
+    #[test]
+    fn test_cascade_layers() {
+        let rule = |selector: &str, layer| CssRule {
+            selector: selector.to_string(),
+            properties: Properties::from([("color".to_string(), "red".to_string())]),
+            at_rules: vec![],
+            global: false,
+            layer,
+        };
+        let vdom = VirtualDomDocument {
+            nodes: vec![],
+            styles: vec![
+                rule(".card:hover", CssLayer::Variants),
+                rule(".card", CssLayer::Components),
+            ],
+            components: vec![],
+            roots: vec![],
+        };
+
+        assert_eq!(
+            vdom.to_html(&HtmlOptions {
+                cascade_layers: true,
+                ..fragment()
+            }),
+            "<style>\n@layer tokens, globals, mixins, components, variants, overrides;\n\
+             @layer components {\n  .card {\n    color: red;\n  }\n}\n\
+             @layer variants {\n  .card:hover {\n    color: red;\n  }\n}\n</style>\n"
+        );
+    }
+
+    #[test]
+    fn test_evaluated_document_with_hydration_markers() {
+        let source = r#"
+            public component List {
+                render ul {
+                    repeat item in items {
+                        li {
+                            text item
+                        }
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").unwrap();
+        let mut evaluator = Evaluator::with_document_id("/test.pc");
+        evaluator.context.set_variable(
+            "items".to_string(),
+            crate::evaluator::Value::Array(vec![
+                crate::evaluator::Value::String("<one>".to_string()),
+                crate::evaluator::Value::String("two".to_string()),
+            ]),
+        );
+        let vdom = evaluator.evaluate(&doc).unwrap();
+
+        let html = vdom.to_html(&HtmlOptions {
+            hydration_markers: true,
+            ..HtmlOptions::default()
+        });
+
+        assert!(html.starts_with("<!DOCTYPE html>\n<html>\n<head>\n"));
+        assert!(html.ends_with("</body></html>"));
+        assert!(html.contains("&lt;one&gt;</li>"));
+        assert_eq!(html.matches("<li").count(), 2);
+
+        // Every element carries its semantic ID so a client can hydrate
+        let VNode::Element { semantic_id, .. } = &vdom.nodes[0] else {
+            panic!("Expected element");
+        };
+        let marker = format!(
+            "data-pc-id=\"{}\"",
+            escape_attribute(&semantic_id.to_selector())
+        );
+        assert!(html.contains(&marker), "Missing {} in {}", marker, html);
+    }
+
+    #[test]
+    fn test_hydrated_dom_accepts_patches() {
+        let render = |name: &str, label: &str| {
+            let source = format!(
+                r#"
+                public component Greeting {{
+                    render div {{
+                        text "Hello, "
+                        text "{}"
+                        span {{ text "!" }}
+                    }}
+                }}
+                public component Label {{
+                    render p {{ text "{}" }}
+                }}
+            "#,
+                name, label
+            );
+            let doc = parse_with_path(&source, "/test.pc").unwrap();
+            Evaluator::with_document_id("/test.pc")
+                .evaluate(&doc)
+                .unwrap()
+        };
+        let old = render("world", "a");
+        let new = render("there", "b");
+
+        let html = old.to_html(&HtmlOptions {
+            hydration_markers: true,
+            ..HtmlOptions::default()
+        });
+        assert!(html.contains("Hello, <!---->world<span"), "{}", html);
+
+        // Roots and adjacent text nodes keep their own positions
+        let mut dom = hydrate(&html);
+        assert_eq!(dom, dom_of(&old.nodes));
+
+        let patches = diff_vdocument(&old, &new);
+        assert_eq!(patches.len(), 2);
+        for patch in patches {
+            let Some(v_doc_patch::PatchType::UpdateText(update)) = patch.patch_type else {
+                panic!("Expected UpdateText patch");
+            };
+            let (last, parents) = update.path.split_last().unwrap();
+            let mut siblings = &mut dom;
+            for index in parents {
+                let Dom::Element(_, children) = &mut siblings[*index as usize] else {
+                    panic!("Path {:?} runs through a text node", update.path);
+                };
+                siblings = children;
+            }
+            siblings[*last as usize] = Dom::Text(update.content);
+        }
+        assert_eq!(dom, dom_of(&new.nodes));
+    }
+}
@@ -1,10 +1,24 @@
+pub mod class_names;
+pub mod config;
+pub mod css_at_rules;
 pub mod css_evaluator;
+pub mod css_layers;
 pub mod css_optimizer;
 pub mod css_minifier;
+pub mod css_prefixer;
 pub mod css_splitter;
+pub mod css_transforms;
+pub mod css_tree_shaker;
 pub mod css_differ;
+pub mod computed_styles;
+pub mod eval_cache;
 pub mod evaluator;
+pub mod html;
+pub mod limits;
 pub mod override_resolution;
+pub mod raw_css;
+pub mod snapshot;
+pub mod token_resolver;
 pub mod utils;
 pub mod validator;
 pub mod vdom;
@@ -47,13 +61,31 @@ mod tests_expressions;
 #[cfg(test)]
 mod tests_integration;
 
+pub use class_names::{ClassNameCollision, ClassNameStrategy, ClassNames};
+pub use computed_styles::{compute_styles, ComputedProperty, OriginKind};
 pub use css_evaluator::CssRule;
-pub use css_evaluator::{CssError, CssEvaluator, CssResult, VirtualCssDocument};
-pub use evaluator::{EvalContext, EvalError, EvalResult, Evaluator, Value};
+pub use eval_cache::{CacheStats, EvalCache};
+pub use html::HtmlOptions;
+pub use css_at_rules::{AtRule, AtRuleKind};
+pub use css_layers::CssLayer;
+pub use css_evaluator::{CssError, CssEvaluator, CssMapping, CssResult, VirtualCssDocument};
+pub use css_splitter::{ComponentStylesheets, CssManifest};
+pub use css_transforms::{
+    CssTransform, CssTransformConfig, CssTransforms, LogicalProperties, PxToRem, RtlFlip,
+};
+pub use css_tree_shaker::{StyleUsage, TreeShakeReport};
+pub use css_prefixer::{Browser, BrowserTarget, BrowserTargets, PrefixError};
+pub use evaluator::{
+    EvalContext, EvalError, EvalResult, Evaluator, Value, DEFAULT_MAX_RECURSION_DEPTH,
+};
 pub use override_resolution::{OverrideResolver, ResolvedOverride};
+pub use limits::{CancellationToken, EvalLimits};
+pub use raw_css::{RawCssError, RawCssRule};
+pub use snapshot::SnapshotOptions;
+pub use token_resolver::{ResolvedToken, TokenResolver, TokenScope};
 pub use validator::{ValidationLevel, ValidationWarning, Validator};
 pub use vdom::CssRule as VDomCssRule;
-pub use vdom::{VNode, VirtualDomDocument};
+pub use vdom::{FrameMetadata, RootMetadata, VNode, VirtualDomDocument};
 pub use vdom_differ::{diff_vdocument, VDocPatch};
 
 // Re-export proto types as canonical VDOM types (new unified types)
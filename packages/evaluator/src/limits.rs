@@ -0,0 +1,94 @@
+//! # Evaluation Limits
+//!
+//! Budgets that keep a pathological document (huge repeat collections, deep
+//! nesting, runaway templates) from pinning the evaluator, plus a cooperative
+//! cancellation token so callers can abandon stale evaluations.
+//!
+//! Limits are checked in `Evaluator::evaluate_element`. Exceeding one renders a
+//! `VNode::Error` in place of the offending element, so the rest of the
+//! document still previews. Cancellation instead aborts the whole evaluation
+//! with `EvalError::Cancelled`, since the caller no longer wants the result.
+//!
+//! Subtrees reused from the `EvalCache` didn't cost any work, so they don't
+//! count against the node or output budgets.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Resource budget for a single evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EvalLimits {
+    /// Maximum number of elements evaluated across the document
+    pub max_nodes: usize,
+    /// Maximum element nesting depth (including component bodies)
+    pub max_depth: usize,
+    /// Maximum number of items a single `repeat` may iterate
+    pub max_repeat_items: usize,
+    /// Maximum approximate size of the produced tags, attributes and text
+    pub max_output_bytes: usize,
+}
+
+impl Default for EvalLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 100_000,
+            max_depth: 256,
+            max_repeat_items: 10_000,
+            max_output_bytes: 32 * 1024 * 1024,
+        }
+    }
+}
+
+impl EvalLimits {
+    /// No limits (trusted input only)
+    pub fn unlimited() -> Self {
+        Self {
+            max_nodes: usize::MAX,
+            max_depth: usize::MAX,
+            max_repeat_items: usize::MAX,
+            max_output_bytes: usize::MAX,
+        }
+    }
+}
+
+/// Cooperative cancellation flag shared between an evaluation and its caller
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every evaluation holding this token to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Work done so far, shared with the scoped evaluators of nested components
+#[derive(Debug, Default)]
+pub(crate) struct EvalUsage {
+    nodes: AtomicUsize,
+    output_bytes: AtomicUsize,
+}
+
+impl EvalUsage {
+    /// Count one more node, returning the total
+    pub(crate) fn add_node(&self) -> usize {
+        self.nodes.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Count produced bytes, returning the total
+    pub(crate) fn add_output_bytes(&self, bytes: usize) -> usize {
+        self.output_bytes
+            .fetch_add(bytes, Ordering::Relaxed)
+            .saturating_add(bytes)
+    }
+}
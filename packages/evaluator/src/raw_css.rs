@@ -0,0 +1,588 @@
+//! # Raw CSS Blocks
+//!
+//! Parses the contents of `css """ ... """` escape hatches with a real CSS
+//! tokenizer (cssparser) so malformed CSS is reported instead of silently
+//! breaking the stylesheet.
+//!
+//! ## Supported Constructs
+//!
+//! - Style rules (`.card:hover .title { ... }`)
+//! - Grouping at-rules, nested to any depth (`@media`, `@supports`, `@container`, `@layer`)
+//! - `@keyframes` (the name is scoped, keyframe selectors are left alone)
+//! - Declaration-block at-rules (`@font-face`, `@property`, `@page`, `@counter-style`)
+//!
+//! Statement at-rules (`@import`, `@charset`, `@layer a, b;`) and at-rules
+//! inside `@keyframes` are rejected.
+//!
+//! ## Scoping
+//!
+//! Every selector gets the document scope appended to its last compound
+//! (before any pseudo-element), so `.title::after` becomes
+//! `.title[class*="80f4925f-"]::after`. Generated element classes embed the
+//! AST span ID, which is prefixed with the document ID, so the scope matches
+//! exactly the elements rendered from the declaring document.
+//!
+//! `@keyframes` names get the document ID appended like class names do
+//! (`spin` becomes `spin-80f4925f`), and `animation` / `animation-name` values
+//! in the same document are rewritten to match, so two documents can both
+//! declare `spin`.
+
+use crate::css_at_rules::AtRule;
+use cssparser::{ParseError, ParseErrorKind, Parser, ParserInput, SourcePosition, Token};
+use std::collections::HashMap;
+
+/// A style rule produced from a raw CSS block
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawCssRule {
+    /// Enclosing at-rules, outermost first, e.g. `[@supports (display: grid)]`
+    pub at_rules: Vec<AtRule>,
+    pub selector: String,
+    /// Declarations in source order
+    pub declarations: Vec<(String, String)>,
+}
+
+/// Validation error with a location relative to the start of the block
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawCssError {
+    pub message: String,
+    /// 1-based line within the block
+    pub line: u32,
+    /// 1-based column within the line
+    pub column: u32,
+}
+
+type RawResult<'i, T> = Result<T, ParseError<'i, String>>;
+
+/// How selectors inside a block are treated
+#[derive(Clone, Copy, PartialEq)]
+enum RuleContext {
+    /// Regular style rules - scoped to the document
+    Style,
+    /// `@keyframes` children (`from`, `50%`) - left alone
+    Keyframes,
+}
+
+/// Selector that matches elements rendered from the given document
+pub fn document_scope(document_id: &str) -> String {
+    format!("[class*=\"{}-\"]", document_id)
+}
+
+/// `@keyframes` name as emitted for the given document
+pub fn keyframes_name(name: &str, document_id: &str) -> String {
+    format!("{}-{}", name, document_id)
+}
+
+/// Names of the `@keyframes` a raw CSS block declares, at any nesting depth
+pub fn keyframes_names(source: &str) -> Vec<String> {
+    fn walk(input: &mut Parser<'_, '_>, names: &mut Vec<String>) {
+        while let Ok(token) = input.next() {
+            match token.clone() {
+                Token::AtKeyword(name) if is_keyframes(&name) => {
+                    if let Ok(Token::Ident(name)) = input.next() {
+                        names.push(name.to_string());
+                    }
+                }
+                Token::CurlyBracketBlock => {
+                    let _ = input.parse_nested_block(|input| {
+                        walk(input, names);
+                        Ok::<_, ParseError<'_, ()>>(())
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut input = ParserInput::new(source);
+    let mut names = Vec::new();
+    walk(&mut Parser::new(&mut input), &mut names);
+    names
+}
+
+/// Rewrite keyframes names in an `animation` / `animation-name` value using
+/// `keyframes` (declared name -> emitted name). Other properties are returned as-is.
+pub fn scope_animation_names(
+    property: &str,
+    value: &str,
+    keyframes: &HashMap<String, String>,
+) -> String {
+    let unprefixed = unprefixed(property).to_ascii_lowercase();
+    if keyframes.is_empty() || !matches!(unprefixed.as_str(), "animation" | "animation-name") {
+        return value.to_string();
+    }
+
+    let mut output = String::with_capacity(value.len());
+    let mut word = String::new();
+    for c in value.chars().chain(std::iter::once(' ')) {
+        if c.is_whitespace() || c == ',' {
+            output.push_str(keyframes.get(&word).map_or(&word, |name| name));
+            word.clear();
+            output.push(c);
+        } else {
+            word.push(c);
+        }
+    }
+    output.pop();
+    output
+}
+
+/// Parse and validate a raw CSS block, scoping selectors and `@keyframes`
+/// names to the document `document_id`
+pub fn parse_raw_css(source: &str, document_id: &str) -> Result<Vec<RawCssRule>, RawCssError> {
+    let mut input = ParserInput::new(source);
+    let mut parser = Parser::new(&mut input);
+    let mut rules = Vec::new();
+    let scope = Scope {
+        selector: document_scope(document_id),
+        document_id,
+    };
+
+    parse_rule_list(&mut parser, &[], RuleContext::Style, &scope, &mut rules).map_err(
+        |error| RawCssError {
+            message: describe_error(&error.kind),
+            line: error.location.line + 1,
+            column: error.location.column,
+        },
+    )?;
+
+    Ok(rules)
+}
+
+/// What a raw CSS block is scoped to
+struct Scope<'a> {
+    /// Appended to every selector
+    selector: String,
+    /// Appended to `@keyframes` names
+    document_id: &'a str,
+}
+
+fn parse_rule_list<'i>(
+    input: &mut Parser<'i, '_>,
+    at_rules: &[AtRule],
+    context: RuleContext,
+    scope: &Scope<'_>,
+    rules: &mut Vec<RawCssRule>,
+) -> RawResult<'i, ()> {
+    loop {
+        input.skip_whitespace();
+        if input.is_exhausted() {
+            return Ok(());
+        }
+
+        let state = input.state();
+        match input.next()?.clone() {
+            Token::AtKeyword(name) => {
+                let (prelude, has_block) = parse_prelude(input)?;
+                if !has_block {
+                    return Err(input.new_custom_error(format!(
+                        "@{} statements aren't supported in css blocks",
+                        name
+                    )));
+                }
+                if context == RuleContext::Keyframes {
+                    return Err(input.new_custom_error(format!(
+                        "@{} isn't allowed inside @keyframes",
+                        name
+                    )));
+                }
+                parse_at_rule(input, &name, prelude, at_rules, scope, rules)?;
+            }
+            _ => {
+                input.reset(&state);
+                let (prelude, has_block) = parse_prelude(input)?;
+                if !has_block {
+                    return Err(input.new_custom_error(format!(
+                        "Expected '{{' after selector '{}'",
+                        prelude
+                    )));
+                }
+                if prelude.is_empty() {
+                    return Err(input.new_custom_error("Missing selector".to_string()));
+                }
+
+                let selector = match context {
+                    RuleContext::Style => scope_selector(prelude, &scope.selector),
+                    RuleContext::Keyframes => prelude.to_string(),
+                };
+                let declarations = input.parse_nested_block(parse_declarations)?;
+
+                rules.push(RawCssRule {
+                    at_rules: at_rules.to_vec(),
+                    selector,
+                    declarations,
+                });
+            }
+        }
+    }
+}
+
+/// Collect everything up to the next `{` (consumed) or `;`.
+/// Returns the trimmed prelude and whether a block follows.
+fn parse_prelude<'i>(input: &mut Parser<'i, '_>) -> RawResult<'i, (&'i str, bool)> {
+    let start = input.position();
+    loop {
+        let before = input.position();
+        let token = match input.next() {
+            Ok(token) => token.clone(),
+            Err(_) => return Ok((input.slice(start..before).trim(), false)),
+        };
+
+        match token {
+            Token::CurlyBracketBlock => return Ok((input.slice(start..before).trim(), true)),
+            Token::Semicolon => return Ok((input.slice(start..before).trim(), false)),
+            _ => check_token(input, token)?,
+        }
+    }
+}
+
+/// Reject tokens that make the surrounding rule invalid, and step over
+/// nested blocks so `position()` stays accurate for slicing
+fn check_token<'i>(input: &mut Parser<'i, '_>, token: Token<'i>) -> RawResult<'i, ()> {
+    match token {
+        Token::BadString(_) | Token::BadUrl(_) => {
+            Err(input.new_custom_error("Unterminated string or url".to_string()))
+        }
+        Token::CloseCurlyBracket | Token::CloseParenthesis | Token::CloseSquareBracket => {
+            Err(input.new_unexpected_token_error(token))
+        }
+        Token::Function(_)
+        | Token::ParenthesisBlock
+        | Token::SquareBracketBlock
+        | Token::CurlyBracketBlock => input.parse_nested_block(|input| {
+            while let Ok(token) = input.next_including_whitespace_and_comments() {
+                let token = token.clone();
+                check_token(input, token)?;
+            }
+            Ok(())
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn parse_at_rule<'i>(
+    input: &mut Parser<'i, '_>,
+    name: &str,
+    prelude: &str,
+    parents: &[AtRule],
+    scope: &Scope<'_>,
+    rules: &mut Vec<RawCssRule>,
+) -> RawResult<'i, ()> {
+    let at_rule = if is_keyframes(name) {
+        AtRule::new(name, keyframes_name(prelude, scope.document_id))
+    } else {
+        AtRule::new(name, prelude)
+    };
+
+    match unprefixed(name).to_ascii_lowercase().as_str() {
+        "media" | "supports" | "container" | "layer" | "keyframes" => {
+            let context = if is_keyframes(name) {
+                RuleContext::Keyframes
+            } else {
+                RuleContext::Style
+            };
+            let mut stack = parents.to_vec();
+            stack.push(at_rule);
+            input.parse_nested_block(|input| parse_rule_list(input, &stack, context, scope, rules))
+        }
+        "font-face" | "property" | "page" | "counter-style" | "font-palette-values" => {
+            let declarations = input.parse_nested_block(parse_declarations)?;
+            rules.push(RawCssRule {
+                at_rules: parents.to_vec(),
+                selector: at_rule.to_string(),
+                declarations,
+            });
+            Ok(())
+        }
+        _ => Err(input.new_custom_error(format!("Unsupported at-rule '@{}'", name))),
+    }
+}
+
+/// Vendor prefixes (`@-webkit-keyframes`, `-moz-animation`) behave like the unprefixed name
+fn unprefixed(name: &str) -> &str {
+    match name.strip_prefix('-') {
+        Some(rest) => rest.split_once('-').map_or(rest, |(_, name)| name),
+        None => name,
+    }
+}
+
+fn is_keyframes(name: &str) -> bool {
+    unprefixed(name).eq_ignore_ascii_case("keyframes")
+}
+
+fn parse_declarations<'i>(input: &mut Parser<'i, '_>) -> RawResult<'i, Vec<(String, String)>> {
+    let mut declarations = Vec::new();
+
+    loop {
+        input.skip_whitespace();
+        if input.is_exhausted() {
+            return Ok(declarations);
+        }
+
+        let declaration = input.parse_until_after(cssparser::Delimiter::Semicolon, |input| {
+            let name = match input.next()?.clone() {
+                Token::Ident(name) => name.to_string(),
+                token => {
+                    return Err(input.new_custom_error(format!(
+                        "Expected a property declaration, found {}",
+                        describe_token(&token)
+                    )))
+                }
+            };
+            input.expect_colon()?;
+
+            let start = input.position();
+            while let Ok(token) = input.next() {
+                let token = token.clone();
+                check_token(input, token)?;
+            }
+
+            let value = input.slice_from(start).trim();
+            if value.is_empty() {
+                return Err(input.new_custom_error(format!("Missing value for '{}'", name)));
+            }
+            Ok((name, value.to_string()))
+        })?;
+
+        declarations.push(declaration);
+    }
+}
+
+/// Append `scope` to the last compound of every selector in a list
+pub fn scope_selector(selector: &str, scope: &str) -> String {
+    let mut input = ParserInput::new(selector);
+    let mut parser = Parser::new(&mut input);
+
+    let mut scoped = Vec::new();
+    let mut start = 0;
+    let mut pseudo_element: Option<usize> = None;
+    // Start and length of the current run of `:` tokens
+    let mut colons: Option<(SourcePosition, usize)> = None;
+
+    loop {
+        let before = parser.position();
+        let token = match parser.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => break,
+        };
+
+        match &token {
+            Token::Comma => {
+                scoped.push(scope_complex_selector(
+                    &selector[start..before.byte_index()],
+                    pseudo_element.map(|index| index - start),
+                    scope,
+                ));
+                start = parser.position().byte_index();
+                pseudo_element = None;
+            }
+            // `::before`, `::part(name)`
+            Token::Ident(_) | Token::Function(_) if matches!(colons, Some((_, 2))) => {
+                pseudo_element = colons.map(|(position, _)| position.byte_index());
+            }
+            // Legacy single-colon pseudo-elements
+            Token::Ident(name)
+                if matches!(colons, Some((_, 1)))
+                    && matches!(
+                        name.to_ascii_lowercase().as_str(),
+                        "before" | "after" | "first-line" | "first-letter"
+                    ) =>
+            {
+                pseudo_element = colons.map(|(position, _)| position.byte_index());
+            }
+            _ => {}
+        }
+
+        colons = match (&token, colons) {
+            (Token::Colon, Some((position, count))) => Some((position, count + 1)),
+            (Token::Colon, None) => Some((before, 1)),
+            _ => None,
+        };
+
+        // Step over functional pseudo-classes so their commas aren't list separators
+        if matches!(
+            token,
+            Token::Function(_) | Token::ParenthesisBlock | Token::SquareBracketBlock
+        ) {
+            let _ = parser.parse_nested_block(|input| -> RawResult<'_, ()> {
+                while input.next().is_ok() {}
+                Ok(())
+            });
+        }
+    }
+
+    scoped.push(scope_complex_selector(
+        &selector[start..],
+        pseudo_element.map(|index| index - start),
+        scope,
+    ));
+
+    scoped.join(", ")
+}
+
+fn scope_complex_selector(selector: &str, pseudo_element: Option<usize>, scope: &str) -> String {
+    match pseudo_element {
+        Some(index) => format!(
+            "{}{}{}",
+            selector[..index].trim_start(),
+            scope,
+            selector[index..].trim_end()
+        ),
+        None => format!("{}{}", selector.trim(), scope),
+    }
+}
+
+fn describe_token(token: &Token) -> String {
+    match token {
+        Token::Ident(name) => format!("'{}'", name),
+        Token::Delim(c) => format!("'{}'", c),
+        Token::CurlyBracketBlock => "'{'".to_string(),
+        Token::CloseCurlyBracket => "'}'".to_string(),
+        Token::CloseParenthesis => "')'".to_string(),
+        Token::CloseSquareBracket => "']'".to_string(),
+        Token::Colon => "':'".to_string(),
+        Token::Semicolon => "';'".to_string(),
+        Token::IDHash(hash) | Token::Hash(hash) => format!("'#{}'", hash),
+        other => format!("{:?}", other),
+    }
+}
+
+fn describe_error(kind: &ParseErrorKind<String>) -> String {
+    match kind {
+        ParseErrorKind::Custom(message) => message.clone(),
+        ParseErrorKind::Basic(cssparser::BasicParseErrorKind::UnexpectedToken(token)) => {
+            format!("Unexpected {}", describe_token(token))
+        }
+        ParseErrorKind::Basic(cssparser::BasicParseErrorKind::EndOfInput) => {
+            "Unexpected end of input".to_string()
+        }
+        ParseErrorKind::Basic(other) => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCOPE: &str = "[class*=\"abc-\"]";
+    const DOCUMENT_ID: &str = "abc";
+
+    #[test]
+    fn test_scopes_every_selector_in_a_list() {
+        assert_eq!(
+            scope_selector(".card .title, a:hover", SCOPE),
+            ".card .title[class*=\"abc-\"], a:hover[class*=\"abc-\"]"
+        );
+        // Commas inside functional pseudo-classes aren't list separators
+        assert_eq!(
+            scope_selector(":is(.a, .b) > *", SCOPE),
+            ":is(.a, .b) > *[class*=\"abc-\"]"
+        );
+    }
+
+    #[test]
+    fn test_scope_goes_before_pseudo_elements() {
+        assert_eq!(
+            scope_selector(".title::after", SCOPE),
+            ".title[class*=\"abc-\"]::after"
+        );
+        assert_eq!(
+            scope_selector(".title:hover:before", SCOPE),
+            ".title:hover[class*=\"abc-\"]:before"
+        );
+    }
+
+    #[test]
+    fn test_parses_grouping_and_declaration_at_rules() {
+        let rules = parse_raw_css(
+            r#"
+            @supports (display: grid) {
+                .grid { display: grid; gap: 8px }
+            }
+            @property --angle {
+                syntax: "<angle>";
+                inherits: false;
+            }
+            @keyframes spin {
+                from { transform: rotate(0deg); }
+                to { transform: rotate(360deg); }
+            }
+            "#,
+            DOCUMENT_ID,
+        )
+        .unwrap();
+
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[0].at_rules, vec![AtRule::new("supports", "(display: grid)")]);
+        assert_eq!(rules[0].selector, ".grid[class*=\"abc-\"]");
+        assert_eq!(
+            rules[0].declarations,
+            vec![
+                ("display".to_string(), "grid".to_string()),
+                ("gap".to_string(), "8px".to_string()),
+            ]
+        );
+
+        assert_eq!(rules[1].selector, "@property --angle");
+        assert!(rules[1].at_rules.is_empty());
+        assert_eq!(rules[1].declarations[0].1, "\"<angle>\"");
+
+        assert_eq!(rules[2].at_rules, vec![AtRule::new("keyframes", "spin-abc")]);
+        assert_eq!(rules[2].selector, "from");
+        assert_eq!(rules[3].declarations[0].1, "rotate(360deg)");
+    }
+
+    #[test]
+    fn test_reports_invalid_css_with_location() {
+        let error = parse_raw_css(".a {\n  color red;\n}", DOCUMENT_ID).unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.message.contains("Expected colon") || error.message.contains("Unexpected"));
+
+        let error = parse_raw_css(".a { color: red; } }", DOCUMENT_ID).unwrap_err();
+        assert_eq!(error.message, "Unexpected '}'");
+
+        let error = parse_raw_css("@import url(a.css);", DOCUMENT_ID).unwrap_err();
+        assert!(error.message.contains("@import statements"));
+
+        let error = parse_raw_css(
+            "@keyframes spin { @media (min-width: 1px) { from { opacity: 0 } } }",
+            DOCUMENT_ID,
+        )
+        .unwrap_err();
+        assert_eq!(error.message, "@media isn't allowed inside @keyframes");
+    }
+
+    #[test]
+    fn test_nested_at_rules_stack() {
+        let rules = parse_raw_css(
+            "@supports (display: grid) { @container card (min-width: 400px) { .a { color: red } } .b { color: blue } }",
+            DOCUMENT_ID,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules[0].at_rules,
+            vec![
+                AtRule::new("supports", "(display: grid)"),
+                AtRule::new("container", "card (min-width: 400px)"),
+            ]
+        );
+        assert_eq!(rules[1].at_rules, vec![AtRule::new("supports", "(display: grid)")]);
+    }
+
+    #[test]
+    fn test_keyframes_names_and_animation_references() {
+        let source = "@media (min-width: 1px) { @-webkit-keyframes pulse { to { opacity: 0 } } } @keyframes spin { to { opacity: 1 } }";
+        assert_eq!(keyframes_names(source), vec!["pulse", "spin"]);
+
+        let keyframes = HashMap::from([("spin".to_string(), keyframes_name("spin", "abc"))]);
+        assert_eq!(
+            scope_animation_names("animation", "spin 1s linear, fade 2s", &keyframes),
+            "spin-abc 1s linear, fade 2s"
+        );
+        assert_eq!(
+            scope_animation_names("-webkit-animation-name", "spin", &keyframes),
+            "spin-abc"
+        );
+        assert_eq!(scope_animation_names("transition", "spin 1s", &keyframes), "spin 1s");
+    }
+}
@@ -0,0 +1,251 @@
+//! # VDOM Snapshots
+//!
+//! Canonical, human-readable text form of a `VirtualDomDocument` for golden
+//! tests. Unlike the JSON serialization, attributes, inline styles and rule
+//! properties are sorted, so the same document always prints the same text.
+//!
+//! ```text
+//! @frame(x: 0, y: 0, width: 400)
+//! div class="card"
+//!   style color: red
+//!   style padding: 8px
+//!   text "Hello"
+//!   img src="a.png"
+//!
+//! ---
+//!
+//! @media (max-width: 600px) {
+//!   .card {
+//!     color: blue;
+//!   }
+//! }
+//! ```
+//!
+//! Roots with a designer frame are preceded by an `@frame(...)` line.
+//! Script-bound instances print as `live Export from "src" ...` with their
+//! static fallback nested beneath.
+//!
+//! Semantic IDs are omitted by default since they change whenever AST IDs do;
+//! enable `semantic_ids` to pin identity as well.
+
+use crate::css_at_rules::{close_at_rules, open_at_rules};
+use crate::vdom::{CssRule, FrameMetadata, VNode, VirtualDomDocument};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Options for `VirtualDomDocument::to_snapshot`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapshotOptions {
+    /// Print each element's semantic ID after `@`
+    pub semantic_ids: bool,
+}
+
+impl VirtualDomDocument {
+    /// Print the document in the canonical snapshot format
+    pub fn to_snapshot(&self, options: &SnapshotOptions) -> String {
+        let mut out = String::new();
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let Some(frame) = self.root_metadata(index).and_then(|root| root.frame.as_ref()) {
+                print_frame(frame, &mut out);
+            }
+            print_node(node, 0, options, &mut out);
+        }
+
+        if !self.styles.is_empty() {
+            out.push_str("\n---\n\n");
+            for rule in &self.styles {
+                print_rule(rule, &mut out);
+            }
+        }
+
+        out
+    }
+}
+
+fn print_frame(frame: &FrameMetadata, out: &mut String) {
+    let _ = write!(out, "@frame(x: {}, y: {}", frame.x, frame.y);
+    if let Some(width) = frame.width {
+        let _ = write!(out, ", width: {}", width);
+    }
+    if let Some(height) = frame.height {
+        let _ = write!(out, ", height: {}", height);
+    }
+    out.push_str(")\n");
+}
+
+fn print_node(node: &VNode, depth: usize, options: &SnapshotOptions, out: &mut String) {
+    let indent = "  ".repeat(depth);
+
+    match node {
+        VNode::Element {
+            tag,
+            attributes,
+            styles,
+            children,
+            semantic_id,
+            key,
+            ..
+        } => {
+            out.push_str(&indent);
+            out.push_str(tag);
+            for (name, value) in attributes.iter().collect::<BTreeMap<_, _>>() {
+                let _ = write!(out, " {}={:?}", name, value);
+            }
+            if let Some(key) = key {
+                let _ = write!(out, " key={:?}", key);
+            }
+            if options.semantic_ids {
+                let _ = write!(out, " @{}", semantic_id.to_selector());
+            }
+            out.push('\n');
+
+            for (name, value) in styles.iter().collect::<BTreeMap<_, _>>() {
+                let _ = writeln!(out, "{}  style {}: {}", indent, name, value);
+            }
+            for child in children {
+                print_node(child, depth + 1, options, out);
+            }
+        }
+        VNode::LiveComponent {
+            src,
+            export_name,
+            target,
+            props,
+            children,
+            semantic_id,
+            key,
+            ..
+        } => {
+            let _ = write!(
+                out,
+                "{}live {} from {:?} target={:?} props={}",
+                indent, export_name, src, target, props
+            );
+            if let Some(key) = key {
+                let _ = write!(out, " key={:?}", key);
+            }
+            if options.semantic_ids {
+                let _ = write!(out, " @{}", semantic_id.to_selector());
+            }
+            out.push('\n');
+
+            for child in children {
+                print_node(child, depth + 1, options, out);
+            }
+        }
+        VNode::Text { content } => {
+            let _ = writeln!(out, "{}text {:?}", indent, content);
+        }
+        VNode::Comment { content } => {
+            let _ = writeln!(out, "{}comment {:?}", indent, content);
+        }
+        VNode::Error { message, .. } => {
+            let _ = writeln!(out, "{}error {:?}", indent, message);
+        }
+    }
+}
+
+fn print_rule(rule: &CssRule, out: &mut String) {
+    let indent = open_at_rules(out, &rule.at_rules, "");
+
+    let global = if rule.global { " /* global */" } else { "" };
+    let _ = writeln!(out, "{}{} {{{}", indent, rule.selector, global);
+    for (name, value) in rule.properties.iter().collect::<BTreeMap<_, _>>() {
+        let _ = writeln!(out, "{}  {}: {};", indent, name, value);
+    }
+    let _ = writeln!(out, "{}}}", indent);
+
+    close_at_rules(out, &rule.at_rules, "");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::css_at_rules::AtRule;
+    use crate::css_layers::CssLayer;
+    use crate::evaluator::Evaluator;
+    use paperclip_parser::ast::Properties;
+    use paperclip_parser::parse_with_path;
+    use paperclip_semantics::SemanticID;
+
+    #[test]
+    fn test_snapshot_is_sorted() {
+        let vdom = VirtualDomDocument {
+            nodes: vec![VNode::element("div", SemanticID::root())
+                .with_attr("id", "main")
+                .with_attr("class", "card")
+                .with_style("padding", "8px")
+                .with_style("color", "red")
+                .with_child(VNode::text("Hello \"world\""))
+                .with_child(VNode::element("li", SemanticID::root()).with_key("a"))],
+            styles: vec![CssRule {
+                selector: ".card".to_string(),
+                properties: Properties::from([
+                    ("padding".to_string(), "8px".to_string()),
+                    ("color".to_string(), "red".to_string()),
+                ]),
+                at_rules: vec![AtRule::media("(max-width: 600px)")],
+                global: false,
+                layer: CssLayer::Components,
+            }],
+            components: vec![],
+            roots: vec![],
+        };
+
+        assert_eq!(
+            vdom.to_snapshot(&SnapshotOptions::default()),
+            r#"div class="card" id="main"
+  style color: red
+  style padding: 8px
+  text "Hello \"world\""
+  li key="a"
+
+---
+
+@media (max-width: 600px) {
+  .card {
+    color: red;
+    padding: 8px;
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_snapshot_is_stable_across_evaluations() {
+        let source = r#"
+            /**
+             * @frame(x: 0, y: 0, width: 400)
+             */
+            public component Card {
+                render div(class="card", title="Card") {
+                    style {
+                        color: red
+                        padding: 8px
+                        margin: 0
+                    }
+                    text "Hello"
+                }
+            }
+        "#;
+
+        let snapshot = || {
+            let doc = parse_with_path(source, "/card.pc").unwrap();
+            let mut evaluator = Evaluator::with_document_id("/card.pc");
+            evaluator
+                .evaluate(&doc)
+                .unwrap()
+                .to_snapshot(&SnapshotOptions { semantic_ids: true })
+        };
+
+        let first = snapshot();
+        assert!(first.starts_with("@frame(x: 0, y: 0, width: 400)\ndiv "));
+        assert!(first.contains("text \"Hello\""));
+        assert!(first.contains(" @Card{"));
+        for _ in 0..10 {
+            assert_eq!(snapshot(), first);
+        }
+    }
+}
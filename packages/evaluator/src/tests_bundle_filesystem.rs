@@ -301,4 +301,444 @@ mod filesystem_tests {
             1
         );
     }
+
+    #[test]
+    fn test_imported_token_alias_and_math() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        mock_fs.add_file(PathBuf::from("/app/theme.pc"));
+        mock_fs.add_file(PathBuf::from("/app/button.pc"));
+
+        let theme_source = r#"
+            public token primary #3366FF
+            public token spacing 8px
+        "#;
+        let theme_doc = parse_with_path(theme_source, "/app/theme.pc").unwrap();
+        let primary_var = format!("--primary-{}", theme_doc.tokens[0].span.id);
+        bundle.add_document(PathBuf::from("/app/theme.pc"), theme_doc);
+
+        let button_source = r#"
+            import "./theme.pc" as theme
+
+            token buttonBg theme.primary
+            token buttonPadding theme.spacing * 2
+            token buttonHover alpha(theme.primary, 0.8)
+
+            public component Button {
+                render button {
+                    style {
+                        background: var(buttonBg)
+                    }
+                }
+            }
+        "#;
+        let button_doc = parse_with_path(button_source, "/app/button.pc").unwrap();
+        bundle.add_document(PathBuf::from("/app/button.pc"), button_doc);
+
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let mut css_evaluator = CssEvaluator::new();
+        let css_doc = css_evaluator
+            .evaluate_bundle(&bundle, &PathBuf::from("/app/button.pc"))
+            .unwrap();
+
+        let tokens = css_evaluator.tokens();
+        assert_eq!(tokens.get("buttonBg"), Some(&"#3366FF".to_string()));
+        assert_eq!(tokens.get("buttonPadding"), Some(&"16px".to_string()));
+        assert_eq!(
+            tokens.get("buttonHover"),
+            Some(&"rgba(51, 102, 255, 0.8)".to_string())
+        );
+
+        // Imported token is declared once and aliases chain to it
+        let root_rule = css_doc
+            .rules
+            .iter()
+            .find(|r| r.selector == ":root" && r.properties.contains_key(&primary_var))
+            .expect("Imported token should be declared");
+        assert!(root_rule
+            .properties
+            .values()
+            .any(|v| v == &format!("var({}, #3366FF)", primary_var)));
+    }
+
+    #[test]
+    fn test_global_styles_emitted_once_per_bundle() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        mock_fs.add_file(PathBuf::from("/app/reset.pc"));
+        mock_fs.add_file(PathBuf::from("/app/theme.pc"));
+        mock_fs.add_file(PathBuf::from("/app/main.pc"));
+
+        let reset_source = r#"
+            global {
+                "*" { box-sizing: border-box }
+                body { margin: 0 }
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/reset.pc"),
+            parse_with_path(reset_source, "/app/reset.pc").unwrap(),
+        );
+
+        // theme.pc also imports the reset - main.pc reaches it twice
+        let theme_source = r#"
+            import "./reset.pc"
+            global style h1 { font-size: 32px }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/theme.pc"),
+            parse_with_path(theme_source, "/app/theme.pc").unwrap(),
+        );
+
+        let main_source = r#"
+            import "./reset.pc"
+            import "./theme.pc"
+
+            public component App {
+                render div
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/main.pc"),
+            parse_with_path(main_source, "/app/main.pc").unwrap(),
+        );
+
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let globals = |entry: &str, evaluator: CssEvaluator| -> Vec<String> {
+            let mut evaluator = evaluator;
+            evaluator
+                .evaluate_bundle(&bundle, &PathBuf::from(entry))
+                .unwrap()
+                .rules
+                .into_iter()
+                .filter(|r| r.global)
+                .map(|r| r.selector)
+                .collect()
+        };
+
+        // Each file's stylesheet carries only its own globals, so a build that
+        // loads every sheet gets each global once
+        let build: Vec<String> = ["/app/reset.pc", "/app/theme.pc", "/app/main.pc"]
+            .into_iter()
+            .flat_map(|entry| globals(entry, CssEvaluator::new()))
+            .collect();
+        assert_eq!(build, vec!["*", "body", "h1"]);
+
+        // A preview inlines them - reset first (dependency), then theme, each exactly once
+        assert_eq!(
+            globals("/app/main.pc", CssEvaluator::new().with_imported_globals()),
+            vec!["*", "body", "h1"]
+        );
+    }
+
+    #[test]
+    fn test_transitively_imported_component_renders() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        let files = [
+            ("/app/icons.pc", "public component Icon { render i { text \"*\" } }"),
+            (
+                "/app/ds.pc",
+                "import \"./icons.pc\" as icons\npublic component Card { render div { Icon() } }",
+            ),
+            (
+                "/app/main.pc",
+                "import \"./ds.pc\" as ds\npublic component App { render main { Card() } }",
+            ),
+        ];
+        for (path, source) in files {
+            mock_fs.add_file(PathBuf::from(path));
+            bundle.add_document(PathBuf::from(path), parse_with_path(source, path).unwrap());
+        }
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let mut cache = EvalCache::new();
+        let vdom = Evaluator::new()
+            .evaluate_bundle_cached(&bundle, &PathBuf::from("/app/main.pc"), &mut cache)
+            .unwrap();
+
+        let html = serde_json::to_string(&vdom.nodes).unwrap();
+        assert!(!html.contains("\"Error\""), "Icon should resolve: {}", html);
+        assert!(html.contains("\"tag\":\"i\""));
+    }
+
+    #[test]
+    fn test_imported_tokens_resolve_by_document() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        mock_fs.add_file(PathBuf::from("/app/colors.pc"));
+        mock_fs.add_file(PathBuf::from("/app/theme.pc"));
+        mock_fs.add_file(PathBuf::from("/app/main.pc"));
+
+        let colors_source = r#"
+            public token blue #0000FF
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/colors.pc"),
+            parse_with_path(colors_source, "/app/colors.pc").unwrap(),
+        );
+
+        // theme's `primary` is an alias into a file main doesn't import
+        let theme_source = r#"
+            import "./colors.pc" as colors
+
+            public token primary colors.blue
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/theme.pc"),
+            parse_with_path(theme_source, "/app/theme.pc").unwrap(),
+        );
+
+        let main_source = r#"
+            import "./theme.pc" as theme
+
+            token primary theme.primary
+
+            public component Button {
+                render button {
+                    style {
+                        color: var(primary)
+                    }
+                }
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/main.pc"),
+            parse_with_path(main_source, "/app/main.pc").unwrap(),
+        );
+
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let mut css_evaluator = CssEvaluator::new();
+        let css_doc = css_evaluator
+            .evaluate_bundle(&bundle, &PathBuf::from("/app/main.pc"))
+            .unwrap();
+
+        assert_eq!(
+            css_evaluator.tokens().get("primary"),
+            Some(&"#0000FF".to_string())
+        );
+        assert!(css_evaluator.warnings().is_empty());
+
+        let button_rule = css_doc
+            .rules
+            .iter()
+            .find(|r| r.properties.contains_key("color"))
+            .expect("Button rule");
+        assert!(button_rule.properties["color"].ends_with(", #0000FF)"));
+    }
+
+    #[test]
+    fn test_raw_css_keyframes_scoped_per_document() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        mock_fs.add_file(PathBuf::from("/app/fade-in.pc"));
+        mock_fs.add_file(PathBuf::from("/app/main.pc"));
+
+        // Both files declare `spin`
+        let fade_source = r#"
+            css """
+            @keyframes spin { from { opacity: 0 } to { opacity: 1 } }
+            .fade { animation: spin 1s ease-in }
+            """
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/fade-in.pc"),
+            parse_with_path(fade_source, "/app/fade-in.pc").unwrap(),
+        );
+
+        let main_source = r#"
+            import "./fade-in.pc"
+
+            css """
+            @keyframes spin { to { transform: rotate(360deg) } }
+            """
+
+            public component Loader {
+                render div {
+                    style {
+                        animation: spin infinite
+                    }
+                }
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/main.pc"),
+            parse_with_path(main_source, "/app/main.pc").unwrap(),
+        );
+
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let fade_id = bundle.get_document_id(&PathBuf::from("/app/fade-in.pc")).unwrap().to_string();
+        let main_id = bundle.get_document_id(&PathBuf::from("/app/main.pc")).unwrap().to_string();
+
+        let mut css_evaluator = CssEvaluator::new();
+        let css_doc = css_evaluator
+            .evaluate_bundle(&bundle, &PathBuf::from("/app/main.pc"))
+            .unwrap();
+
+        let mut keyframes: Vec<String> = css_doc
+            .rules
+            .iter()
+            .flat_map(|r| &r.at_rules)
+            .filter(|at_rule| at_rule.name == "keyframes")
+            .map(|at_rule| at_rule.prelude.clone())
+            .collect();
+        keyframes.dedup();
+        assert_eq!(
+            keyframes,
+            vec![format!("spin-{}", fade_id), format!("spin-{}", main_id)]
+        );
+
+        // Each document's animations point at its own keyframes
+        let animations: Vec<&str> = css_doc
+            .rules
+            .iter()
+            .filter_map(|r| r.properties.get("animation"))
+            .map(String::as_str)
+            .collect();
+        assert!(animations.contains(&format!("spin-{} 1s ease-in", fade_id).as_str()));
+        assert!(animations.contains(&format!("spin-{} infinite", main_id).as_str()));
+    }
+
+    #[test]
+    fn test_production_drops_unused_styles_and_tokens() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        mock_fs.add_file(PathBuf::from("/app/ds.pc"));
+        mock_fs.add_file(PathBuf::from("/app/main.pc"));
+
+        let ds_source = r#"
+            public token brand #3366FF
+            public token shadow 2px
+            public style base { color: var(brand) }
+            public style loud { font-weight: bold }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/ds.pc"),
+            parse_with_path(ds_source, "/app/ds.pc").unwrap(),
+        );
+
+        let main_source = r#"
+            import "./ds.pc" as ds
+
+            public component App {
+                render div {
+                    style extends ds.base {
+                        padding: 8px
+                    }
+                }
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/main.pc"),
+            parse_with_path(main_source, "/app/main.pc").unwrap(),
+        );
+
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        let entry = PathBuf::from("/app/main.pc");
+        let full_css = CssEvaluator::new()
+            .evaluate_bundle(&bundle, &entry)
+            .unwrap()
+            .to_css();
+        assert!(full_css.contains("--shadow-"));
+        assert!(full_css.contains("--loud-"));
+
+        let usage = StyleUsage::from_entries(&bundle, &[&entry]);
+        let mut css_evaluator = CssEvaluator::new().with_style_usage(usage);
+        let css = css_evaluator
+            .evaluate_bundle(&bundle, &entry)
+            .unwrap()
+            .to_css();
+
+        assert!(css.contains("--brand-"));
+        assert!(css.contains("--base-color-"));
+        assert!(css.contains("padding: 8px"));
+        assert!(!css.contains("--shadow-"));
+        assert!(!css.contains("--loud-"));
+
+        let report = css_evaluator.tree_shake_report();
+        assert_eq!(report.removed_tokens, vec!["shadow"]);
+        assert_eq!(report.removed_styles, vec!["loud"]);
+        assert!(report.removed_components.is_empty());
+    }
+
+    #[test]
+    fn test_production_drops_components_outside_entries() {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+
+        mock_fs.add_file(PathBuf::from("/app/ds.pc"));
+        mock_fs.add_file(PathBuf::from("/app/main.pc"));
+
+        let ds_source = r#"
+            public component Button {
+                render button { style { color: red } }
+            }
+            public component Badge {
+                render span { style { color: blue } }
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/ds.pc"),
+            parse_with_path(ds_source, "/app/ds.pc").unwrap(),
+        );
+
+        let main_source = r#"
+            import "./ds.pc" as ds
+
+            public component App {
+                render div {
+                    style {
+                        color: green
+                    }
+                }
+            }
+        "#;
+        bundle.add_document(
+            PathBuf::from("/app/main.pc"),
+            parse_with_path(main_source, "/app/main.pc").unwrap(),
+        );
+
+        bundle
+            .build_dependencies_with_fs(&PathBuf::from("/app"), &mock_fs)
+            .unwrap();
+
+        // Reachability comes from the app entry; the design system file is
+        // evaluated on its own and none of its components are rendered
+        let usage = StyleUsage::from_entries(&bundle, &["/app/main.pc"]);
+        let mut css_evaluator = CssEvaluator::new().with_style_usage(usage);
+        let css = css_evaluator
+            .evaluate_bundle(&bundle, &PathBuf::from("/app/ds.pc"))
+            .unwrap()
+            .to_css();
+
+        assert!(!css.contains("color: red"));
+        assert!(!css.contains("color: blue"));
+        assert_eq!(
+            css_evaluator.tree_shake_report().removed_components,
+            vec!["Badge", "Button"]
+        );
+    }
 }
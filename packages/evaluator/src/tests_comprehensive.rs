@@ -719,7 +719,7 @@ mod evaluator_comprehensive_tests {
         assert!(tags.contains(&"span"), "AnotherPrivate (span) should render");
     }
 
-    /// Test that top-level render elements with @frame annotations get data-frame-* attributes
+    /// Test that top-level render elements with @frame annotations get a root frame
     #[test]
     fn test_render_frame_attributes_injected() {
         let source = r#"/**
@@ -744,34 +744,17 @@ div {
 
         assert_eq!(vdoc.nodes.len(), 1, "Should have 1 node");
 
-        match &vdoc.nodes[0] {
-            VNode::Element { tag, attributes, .. } => {
-                assert_eq!(tag, "div");
-
-                // Check for data-frame-* attributes
-                assert_eq!(
-                    attributes.get("data-frame-x").map(|s| s.as_str()),
-                    Some("100"),
-                    "Should have data-frame-x attribute"
-                );
-                assert_eq!(
-                    attributes.get("data-frame-y").map(|s| s.as_str()),
-                    Some("200"),
-                    "Should have data-frame-y attribute"
-                );
-                assert_eq!(
-                    attributes.get("data-frame-width").map(|s| s.as_str()),
-                    Some("400"),
-                    "Should have data-frame-width attribute"
-                );
-                assert_eq!(
-                    attributes.get("data-frame-height").map(|s| s.as_str()),
-                    Some("300"),
-                    "Should have data-frame-height attribute"
-                );
-            }
-            _ => panic!("Expected element node"),
-        }
+        let frame = vdoc.root_metadata(0).and_then(|root| root.frame.as_ref());
+        assert_eq!(
+            frame,
+            Some(&FrameMetadata {
+                x: 100.0,
+                y: 200.0,
+                width: Some(400.0),
+                height: Some(300.0),
+            }),
+            "Root metadata should carry the frame"
+        );
     }
 
     /// Test render without @frame annotation has no data-frame-* attributes
@@ -801,9 +784,10 @@ div {
             }
             _ => panic!("Expected element node"),
         }
+        assert_eq!(vdoc.root_metadata(0).and_then(|root| root.frame.as_ref()), None);
     }
 
-    /// Test multiple renders - some with frames, some without
+    /// Test multiple renders - some with frames, some without (root frames stay index-aligned)
     #[test]
     fn test_multiple_renders_frame_attributes() {
         let source = r#"/**
@@ -830,35 +814,24 @@ div {
 
         assert_eq!(vdoc.nodes.len(), 3, "Should have 3 nodes");
 
-        // First node should have frame attributes (x, y only)
-        match &vdoc.nodes[0] {
-            VNode::Element { attributes, .. } => {
-                assert_eq!(attributes.get("data-frame-x").map(|s| s.as_str()), Some("0"));
-                assert_eq!(attributes.get("data-frame-y").map(|s| s.as_str()), Some("0"));
-                assert_eq!(attributes.get("data-frame-width"), None);
-                assert_eq!(attributes.get("data-frame-height"), None);
-            }
-            _ => panic!("Expected element node"),
-        }
-
-        // Second node should NOT have frame attributes
-        match &vdoc.nodes[1] {
-            VNode::Element { attributes, .. } => {
-                let has_frame_attrs = attributes.keys().any(|k| k.starts_with("data-frame-"));
-                assert!(!has_frame_attrs, "Second node should not have frame attrs");
-            }
-            _ => panic!("Expected element node"),
-        }
-
-        // Third node should have frame attributes (x, y, width only)
-        match &vdoc.nodes[2] {
-            VNode::Element { attributes, .. } => {
-                assert_eq!(attributes.get("data-frame-x").map(|s| s.as_str()), Some("500"));
-                assert_eq!(attributes.get("data-frame-y").map(|s| s.as_str()), Some("0"));
-                assert_eq!(attributes.get("data-frame-width").map(|s| s.as_str()), Some("300"));
-                assert_eq!(attributes.get("data-frame-height"), None);
-            }
-            _ => panic!("Expected element node"),
-        }
+        let frames: Vec<_> = vdoc.roots.iter().map(|root| root.frame.clone()).collect();
+        assert_eq!(
+            frames,
+            vec![
+                Some(FrameMetadata {
+                    x: 0.0,
+                    y: 0.0,
+                    width: None,
+                    height: None,
+                }),
+                None,
+                Some(FrameMetadata {
+                    x: 500.0,
+                    y: 0.0,
+                    width: Some(300.0),
+                    height: None,
+                }),
+            ]
+        );
     }
 }
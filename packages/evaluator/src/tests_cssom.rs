@@ -365,4 +365,299 @@ mod cssom_tests {
         );
         assert!(has_public, "Should generate CSS for public components");
     }
+
+    #[test]
+    fn test_raw_css_is_scoped_to_document() {
+        let source = r#"
+            css """
+            @supports (display: grid) {
+                .grid > * { display: grid; }
+            }
+            """
+
+            public component Card {
+                css """.title::after { content: "*"; color: var(accent); }"""
+                render div {
+                    text "Hello"
+                }
+            }
+
+            token accent #FF0000
+        "#;
+
+        let doc = parse_with_path(source, "/card.pc").expect("Failed to parse");
+        let vdom = Evaluator::with_document_id("/card.pc")
+            .evaluate(&doc)
+            .expect("Failed to evaluate DOM");
+
+        let class_name = match &vdom.nodes[0] {
+            VNode::Element { attributes, .. } => attributes["class"].clone(),
+            other => panic!("Expected element, got {:?}", other),
+        };
+        let document_id = paperclip_parser::get_document_id("/card.pc");
+        let scope = format!("[class*=\"{}-\"]", document_id);
+        assert!(class_name.contains(&format!("{}-", document_id)));
+
+        // Raw rules flow into the VDOM stylesheet (and so into CSSOM patches)
+        let grid = vdom
+            .styles
+            .iter()
+            .find(|r| r.at_rules == [AtRule::new("supports", "(display: grid)")])
+            .expect("Should have @supports rule");
+        // (VDOM styles are minified)
+        assert_eq!(grid.selector, format!(".grid>*{}", scope));
+
+        let title = vdom
+            .styles
+            .iter()
+            .find(|r| r.selector.ends_with("::after"))
+            .expect("Should have component-level raw rule");
+        assert_eq!(title.selector, format!(".title{}::after", scope));
+        assert!(title.properties["color"].starts_with("var(--accent-"));
+    }
+
+    #[test]
+    fn test_invalid_raw_css_is_reported() {
+        let source = r#"
+            css """
+            .a {
+                color: red;
+            }}
+            """
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let err = CssEvaluator::with_document_id("/test.pc")
+            .evaluate(&doc)
+            .unwrap_err();
+
+        match err {
+            CssError::InvalidRawCss { line, message, .. } => {
+                assert_eq!(line, 4);
+                assert_eq!(message, "Unexpected '}'");
+            }
+            other => panic!("Expected InvalidRawCss, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_readable_class_names_match_css() {
+        let source = r#"
+            public component Button {
+                render button {
+                    style {
+                        padding: 8px
+                    }
+                    text "Click"
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+
+        let mut dom_evaluator = Evaluator::with_document_id("/test.pc")
+            .with_class_name_strategy(ClassNameStrategy::Readable);
+        let vdom = dom_evaluator
+            .evaluate(&doc)
+            .expect("Failed to evaluate DOM");
+
+        let mut css_evaluator = CssEvaluator::with_document_id("/test.pc")
+            .with_class_name_strategy(ClassNameStrategy::Readable);
+        let css = css_evaluator
+            .evaluate(&doc)
+            .expect("Failed to evaluate CSS");
+
+        let VNode::Element { attributes, .. } = &vdom.nodes[0] else {
+            panic!("Expected Element node");
+        };
+        assert_eq!(
+            attributes.get("class").map(String::as_str),
+            Some("Button__button")
+        );
+        assert!(css.rules.iter().any(|r| r.selector == ".Button__button"));
+    }
+
+    #[test]
+    fn test_preview_styles_are_prefixed_for_targets() {
+        let source = r#"
+            public component Button {
+                render button {
+                    style {
+                        user-select: none
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let targets = BrowserTargets::parse(&["safari >= 15"]).unwrap();
+        let mut evaluator = Evaluator::with_document_id("/test.pc").with_browser_targets(targets);
+        let vdom = evaluator.evaluate(&doc).expect("Failed to evaluate DOM");
+
+        let rule = vdom
+            .styles
+            .iter()
+            .find(|r| r.properties.contains_key("user-select"))
+            .expect("Should have the button rule");
+        assert_eq!(
+            rule.properties.get("-webkit-user-select").map(String::as_str),
+            Some("none")
+        );
+    }
+
+    #[test]
+    fn test_preview_styles_are_transformed() {
+        let source = r#"
+            public component Button {
+                render button {
+                    style {
+                        padding-left: 24px
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+        let transforms = CssTransforms::new()
+            .with(PxToRem::default())
+            .with(LogicalProperties);
+        let mut evaluator =
+            Evaluator::with_document_id("/test.pc").with_css_transforms(transforms);
+        let vdom = evaluator.evaluate(&doc).expect("Failed to evaluate DOM");
+
+        let rule = vdom
+            .styles
+            .iter()
+            .find(|r| r.properties.contains_key("padding-inline-start"))
+            .expect("Should have the button rule");
+        assert_eq!(rule.properties["padding-inline-start"], "1.5rem");
+        assert!(!rule.properties.contains_key("padding-left"));
+
+        // Inline styles on the rendered node go through the same passes
+        let Some(VNode::Element { styles, .. }) = vdom.nodes.first() else {
+            panic!("Should render the button");
+        };
+        assert_eq!(styles["padding-inline-start"], "1.5rem");
+        assert!(!styles.contains_key("padding-left"));
+    }
+
+    #[test]
+    fn test_cached_preview_depends_on_transforms() {
+        use paperclip_bundle::Bundle;
+        use std::path::PathBuf;
+
+        let source = r#"
+            public component Button {
+                render button {
+                    style {
+                        padding-left: 24px
+                    }
+                }
+            }
+        "#;
+        let path = PathBuf::from("/test.pc");
+        let mut bundle = Bundle::new();
+        bundle.add_document(path.clone(), parse_with_path(source, "/test.pc").unwrap());
+
+        let inline_styles = |transforms: CssTransforms, cache: &mut EvalCache| {
+            let vdom = Evaluator::with_document_id("/test.pc")
+                .with_css_transforms(transforms)
+                .evaluate_bundle_cached(&bundle, &path, cache)
+                .expect("Failed to evaluate DOM");
+            match vdom.nodes.first() {
+                Some(VNode::Element { styles, .. }) => styles.clone(),
+                _ => panic!("Should render the button"),
+            }
+        };
+
+        let mut cache = EvalCache::new();
+        let plain = inline_styles(CssTransforms::new(), &mut cache);
+        let rem = inline_styles(CssTransforms::new().with(PxToRem::default()), &mut cache);
+        let rem_10 = inline_styles(
+            CssTransforms::new().with(PxToRem {
+                root_size: 10.0,
+                exclude: Vec::new(),
+            }),
+            &mut cache,
+        );
+
+        assert_eq!(plain["padding-left"], "24px");
+        assert_eq!(rem["padding-left"], "1.5rem");
+        assert_eq!(rem_10["padding-left"], "2.4rem");
+        assert_eq!(cache.stats().hits, 0);
+    }
+
+    // The variant block is declared before the base block on purpose
+    const VARIANT_FIRST: &str = r#"
+        public component Button {
+            variant primary trigger {
+                ":hover"
+            }
+            render button {
+                style variant primary {
+                    color: white
+                }
+                style {
+                    color: black
+                }
+            }
+        }
+    "#;
+
+    #[test]
+    fn test_variant_beats_base_regardless_of_source_order() {
+        let doc = parse_with_path(VARIANT_FIRST, "/test.pc").expect("Failed to parse");
+
+        let mut css_evaluator = CssEvaluator::with_document_id("/test.pc");
+        let css = css_evaluator
+            .evaluate(&doc)
+            .expect("Failed to evaluate CSS");
+
+        let layered = css.to_layered_css();
+        assert!(layered
+            .starts_with("@layer tokens, globals, mixins, components, variants, overrides;\n"));
+        let base = layered.find("@layer components {").expect("components layer");
+        let variant = layered.find("@layer variants {").expect("variants layer");
+        assert!(base < variant);
+        assert!(layered[base..variant].contains("color: black"));
+        assert!(layered[variant..].contains("color: white"));
+
+        // Unlayered output follows layer order too
+        let flat = css.to_css();
+        assert!(flat.find("color: black").unwrap() < flat.find("color: white").unwrap());
+
+        // So do preview styles
+        let mut evaluator = Evaluator::with_document_id("/test.pc");
+        let vdom = evaluator.evaluate(&doc).expect("Failed to evaluate DOM");
+        let layers: Vec<CssLayer> = vdom.styles.iter().map(|r| r.layer).collect();
+        assert!(layers.contains(&CssLayer::Variants));
+        assert!(layers.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn test_added_base_rule_stays_below_variants() {
+        let variant_only = VARIANT_FIRST.replace("color: black", "");
+        let evaluate = |source: &str| {
+            let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
+            Evaluator::with_document_id("/test.pc")
+                .evaluate(&doc)
+                .expect("Failed to evaluate DOM")
+                .styles
+        };
+
+        let mut rules = evaluate(&variant_only);
+        let new_rules = evaluate(VARIANT_FIRST);
+        let diff = css_differ::diff_css_rules(&rules, &new_rules);
+        css_differ::apply_css_patches(&mut rules, &diff.patches);
+
+        let base = rules
+            .iter()
+            .position(|r| r.layer == CssLayer::Components)
+            .expect("Should add the base rule");
+        let variant = rules
+            .iter()
+            .position(|r| r.layer == CssLayer::Variants)
+            .expect("Should keep the variant rule");
+        assert!(base < variant);
+    }
 }
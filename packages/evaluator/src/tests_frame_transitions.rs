@@ -10,7 +10,7 @@ use paperclip_bundle::Bundle;
 use paperclip_parser::parse_with_path;
 use std::path::PathBuf;
 
-/// Helper to evaluate a source and extract frame attributes
+/// Helper to evaluate a source and extract root frames
 fn evaluate_and_get_frames(source: &str) -> Vec<Option<(f64, f64, Option<f64>, Option<f64>)>> {
     let doc = parse_with_path(source, "/test.pc").expect("Failed to parse");
     let mut bundle = Bundle::new();
@@ -21,22 +21,16 @@ fn evaluate_and_get_frames(source: &str) -> Vec<Option<(f64, f64, Option<f64>, O
         .evaluate_bundle(&bundle, std::path::Path::new("/test.pc"))
         .expect("Failed to evaluate");
 
-    vdoc.nodes
-        .iter()
-        .map(|node| {
-            if let VNode::Element { attributes, .. } = node {
-                let x = attributes.get("data-frame-x").and_then(|v| v.parse().ok());
-                let y = attributes.get("data-frame-y").and_then(|v| v.parse().ok());
-                let w = attributes.get("data-frame-width").and_then(|v| v.parse().ok());
-                let h = attributes.get("data-frame-height").and_then(|v| v.parse().ok());
-                if x.is_some() || y.is_some() {
-                    Some((x.unwrap_or(0.0), y.unwrap_or(0.0), w, h))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+    frames_of(&vdoc)
+}
+
+/// Frame of each root node, read from its typed root metadata
+fn frames_of(vdoc: &VirtualDomDocument) -> Vec<Option<(f64, f64, Option<f64>, Option<f64>)>> {
+    (0..vdoc.nodes.len())
+        .map(|index| {
+            vdoc.root_metadata(index)
+                .and_then(|root| root.frame.as_ref())
+                .map(|frame| (frame.x, frame.y, frame.width, frame.height))
         })
         .collect()
 }
@@ -388,24 +382,7 @@ fn try_evaluate_frames(source: &str) -> Result<Vec<Option<(f64, f64, Option<f64>
         }
     }
 
-    Ok(vdoc.nodes
-        .iter()
-        .map(|node| {
-            if let VNode::Element { attributes, .. } = node {
-                let x = attributes.get("data-frame-x").and_then(|v| v.parse().ok());
-                let y = attributes.get("data-frame-y").and_then(|v| v.parse().ok());
-                let w = attributes.get("data-frame-width").and_then(|v| v.parse().ok());
-                let h = attributes.get("data-frame-height").and_then(|v| v.parse().ok());
-                if x.is_some() || y.is_some() {
-                    Some((x.unwrap_or(0.0), y.unwrap_or(0.0), w, h))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .collect())
+    Ok(frames_of(&vdoc))
 }
 
 /// Macro for testing transition sequences with mutations, errors, and recovery
@@ -851,23 +828,11 @@ div {
     println!("Before semantic: {:?}", before_semantic);
     println!("After semantic: {:?}", after_semantic);
 
-    let before_has_frame = vdom_before.nodes.first().and_then(|n| {
-        if let VNode::Element { attributes, .. } = n {
-            attributes.get("data-frame-x")
-        } else {
-            None
-        }
-    });
-    let after_has_frame = vdom_after.nodes.first().and_then(|n| {
-        if let VNode::Element { attributes, .. } = n {
-            attributes.get("data-frame-x")
-        } else {
-            None
-        }
-    });
-
-    assert!(before_has_frame.is_some(), "Before should have frame");
-    assert!(after_has_frame.is_some(), "After should have frame");
+    assert!(
+        frames_of(&vdom_before)[0].is_some(),
+        "Before should have frame"
+    );
+    assert!(frames_of(&vdom_after)[0].is_some(), "After should have frame");
 
     // Generate patches
     let patches = diff_vdocument(&vdom_before, &vdom_after);
@@ -877,25 +842,36 @@ div {
         println!("  Patch {}: {:?}", i, patch.patch_type);
     }
 
-    // If there's a ReplaceNode patch, the new node should have frame attributes
+    // Frames live in root metadata, so node patches never carry them. When
+    // the root is recreated its frame travels in an UpdateFrame patch.
+    use crate::vdom_differ::proto::patches::v_doc_patch::PatchType;
     use crate::vdom_differ::proto::vdom::v_node::NodeType;
+    let root_created = patches.iter().any(|patch| {
+        matches!(&patch.patch_type, Some(PatchType::CreateNode(create)) if create.path.is_empty())
+    });
+    let updated_frame = patches.iter().find_map(|patch| match &patch.patch_type {
+        Some(PatchType::UpdateFrame(update)) => update.metadata.as_ref()?.frame.clone(),
+        _ => None,
+    });
+    if root_created {
+        let frame = updated_frame.expect("Recreated root should receive its frame");
+        assert_eq!((frame.x, frame.y), (100.0, 100.0));
+    } else {
+        assert!(updated_frame.is_none(), "Unchanged frame should not be patched");
+    }
+
     for patch in &patches {
-        if let Some(crate::vdom_differ::proto::patches::v_doc_patch::PatchType::ReplaceNode(
-            replace,
-        )) = &patch.patch_type
-        {
-            if let Some(new_node) = &replace.new_node {
-                println!("ReplaceNode new_node node_type: {:?}", new_node.node_type.is_some());
-                // Check if new node has frame attributes
-                if let Some(NodeType::Element(elem)) = &new_node.node_type {
-                    println!("ReplaceNode element attributes: {:?}", elem.attributes.keys().collect::<Vec<_>>());
-                    assert!(
-                        elem.attributes.contains_key("data-frame-x"),
-                        "ReplaceNode new_node should have data-frame-x attribute, got: {:?}",
-                        elem.attributes.keys().collect::<Vec<_>>()
-                    );
-                }
-            }
+        let new_node = match &patch.patch_type {
+            Some(PatchType::ReplaceNode(replace)) => replace.new_node.as_ref(),
+            Some(PatchType::CreateNode(create)) => create.node.as_ref(),
+            _ => None,
+        };
+        if let Some(NodeType::Element(elem)) = new_node.and_then(|n| n.node_type.as_ref()) {
+            assert!(
+                !elem.attributes.contains_key("data-frame-x"),
+                "Frames should not be node attributes, got: {:?}",
+                elem.attributes.keys().collect::<Vec<_>>()
+            );
         }
     }
 }
@@ -1,8 +1,9 @@
 /// Tests for slot implementation with semantic IDs
-use crate::evaluator::Evaluator;
+use crate::evaluator::{Evaluator, Value};
 use crate::vdom::VNode;
 use paperclip_parser::parse_with_path;
 use paperclip_semantics::{SemanticSegment, SlotVariant};
+use std::collections::HashMap;
 
 #[test]
 fn test_slot_with_default_content() {
@@ -326,3 +327,95 @@ fn test_component_instance_syntax_variants() {
         }
     }
 }
+
+#[test]
+fn test_scoped_slot_passes_data_to_insert() {
+    let source = r#"
+        component List {
+            slot row(item) {
+                li {
+                    text "Default ${item.name}"
+                }
+            }
+
+            render ul {
+                repeat item in items {
+                    row
+                }
+            }
+        }
+
+        public component App {
+            render List(items=users) {
+                insert row(user) {
+                    li {
+                        text user.name
+                    }
+                }
+            }
+        }
+    "#;
+
+    let evaluate = |names: &[&str]| {
+        let doc = parse_with_path(source, "/test.pc").unwrap();
+        let mut evaluator = Evaluator::with_document_id("/test.pc");
+        let users: Vec<Value> = names
+            .iter()
+            .map(|name| {
+                Value::Object(HashMap::from([(
+                    "name".to_string(),
+                    Value::String(name.to_string()),
+                )]))
+            })
+            .collect();
+        evaluator
+            .context
+            .set_variable("users".to_string(), Value::Array(users.clone()));
+        evaluator
+            .context
+            .set_variable("items".to_string(), Value::Array(users));
+        evaluator.evaluate(&doc).unwrap()
+    };
+
+    // Collect (text, semantic ID) of each rendered row
+    fn rows(node: &VNode, out: &mut Vec<(String, String)>) {
+        if let VNode::Element {
+            tag,
+            children,
+            semantic_id,
+            ..
+        } = node
+        {
+            if tag == "li" {
+                if let Some(VNode::Text { content }) = children.first() {
+                    out.push((content.clone(), semantic_id.to_selector()));
+                }
+            }
+            for child in children {
+                rows(child, out);
+            }
+        }
+    }
+
+    let vdom = evaluate(&["Ada", "Grace"]);
+
+    // List's own preview falls back to the default content
+    let mut list_rows = Vec::new();
+    rows(&vdom.nodes[0], &mut list_rows);
+    let list_texts: Vec<_> = list_rows.iter().map(|(text, _)| text.as_str()).collect();
+    assert_eq!(list_texts, vec!["Default Ada", "Default Grace"]);
+
+    // App's insert receives each item under its own name
+    let mut app_rows = Vec::new();
+    rows(&vdom.nodes[1], &mut app_rows);
+    let app_texts: Vec<_> = app_rows.iter().map(|(text, _)| text.as_str()).collect();
+    assert_eq!(app_texts, vec!["Ada", "Grace"]);
+    assert!(app_rows[0].1.contains("::row[inserted]::li"), "{}", app_rows[0].1);
+    assert_ne!(app_rows[0].1, app_rows[1].1);
+
+    // Slot data doesn't leak into semantic IDs
+    let mut renamed = Vec::new();
+    rows(&evaluate(&["Linus", "Barbara"]).nodes[1], &mut renamed);
+    let ids = |rows: &[(String, String)]| rows.iter().map(|(_, id)| id.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&renamed), ids(&app_rows));
+}
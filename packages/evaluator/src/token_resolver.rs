@@ -0,0 +1,893 @@
+//! Token resolution - aliases, compile-time math and color functions
+//!
+//! Tokens can reference other tokens (`token buttonBg primaryColor`), do
+//! arithmetic on compatible units (`token spacingLarge spacing * 2`) and call
+//! color functions (`alpha(primaryColor, 0.5)`, `mix(a, b, 25%)`).
+//!
+//! Every token resolves to two forms:
+//! - `value`: the fully computed literal (used for fallbacks and non-CSS targets)
+//! - `css_value`: the value emitted for the token's custom property. References
+//!   keep their `var()` chain so theme overrides still cascade.
+//!
+//! Tokens are keyed by (document, name), so two imports can declare the same
+//! name, and `theme.x` follows the `theme` import alias of the document the
+//! reference appears in - through as many imports as it takes. A reference
+//! that can't be found is passed through as written and reported in
+//! `ResolvedToken::unresolved`, rather than failing the whole document.
+
+use crate::css_evaluator::{CssError, CssResult};
+use paperclip_bundle::Bundle;
+use paperclip_parser::ast::{BinaryOp, TokenDecl, TokenValue};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// A fully resolved token
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedToken {
+    /// Computed literal value (`16px`, `#3366FF`, `rgba(51, 102, 255, 0.5)`)
+    pub value: String,
+    /// Value emitted for the CSS custom property (may contain `var()`/`calc()`)
+    pub css_value: String,
+    /// Custom property name (`--spacing-abc123-1`)
+    pub var_name: String,
+    /// References (direct or through aliases) that couldn't be found and were
+    /// passed through as written
+    pub unresolved: Vec<String>,
+}
+
+/// CSS custom property name for a token
+pub fn token_var_name(token: &TokenDecl) -> String {
+    format!("--{}-{}", token.name, token.span.id)
+}
+
+/// Per-document token entries, looked up the way references resolve: `name`
+/// in the requesting document (then public tokens of its imports), and
+/// `alias.name` in the public tokens of the aliased import
+#[derive(Debug, Clone)]
+pub struct TokenScope<T> {
+    entries: HashMap<(PathBuf, String), T>,
+    public: HashSet<(PathBuf, String)>,
+    /// (document, import alias) -> imported document
+    aliases: HashMap<(PathBuf, String), PathBuf>,
+    /// Document -> documents it imports
+    imports: HashMap<PathBuf, Vec<PathBuf>>,
+}
+
+impl<T> Default for TokenScope<T> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            public: HashSet::new(),
+            aliases: HashMap::new(),
+            imports: HashMap::new(),
+        }
+    }
+}
+
+impl<'a> TokenScope<&'a TokenDecl> {
+    /// Tokens of a single document, identified by `path`
+    pub fn for_document(path: &Path, tokens: impl IntoIterator<Item = &'a TokenDecl>) -> Self {
+        let mut scope = Self::default();
+        scope.add_document(path, tokens);
+        scope
+    }
+
+    /// Tokens of `entry` and every document it imports, directly or not
+    pub fn for_bundle(bundle: &'a Bundle, entry: &Path) -> Self {
+        let mut scope = Self::default();
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from([entry.to_path_buf()]);
+
+        while let Some(path) = queue.pop_front() {
+            if !visited.insert(path.clone()) {
+                continue;
+            }
+            let Some(doc) = bundle.get_document(&path) else {
+                continue;
+            };
+
+            scope.add_document(&path, &doc.tokens);
+            for alias in doc.imports.iter().filter_map(|import| import.alias.as_ref()) {
+                if let Some(target) = bundle.resolve_alias(&path, alias) {
+                    scope
+                        .aliases
+                        .insert((path.clone(), alias.clone()), target.to_path_buf());
+                }
+            }
+            let deps = bundle.get_dependencies(&path).unwrap_or_default().to_vec();
+            queue.extend(deps.iter().cloned());
+            scope.imports.insert(path, deps);
+        }
+
+        scope
+    }
+
+    fn add_document(&mut self, path: &Path, tokens: impl IntoIterator<Item = &'a TokenDecl>) {
+        for token in tokens {
+            let key = (path.to_path_buf(), token.name.clone());
+            if token.public {
+                self.public.insert(key.clone());
+            }
+            self.entries.insert(key, token);
+        }
+    }
+}
+
+impl<T> TokenScope<T> {
+    /// Find what `reference` refers to from `from`, with the (document, name) it's keyed by
+    pub fn find(&self, reference: &str, from: &Path) -> Option<(&(PathBuf, String), &T)> {
+        if let Some((alias, name)) = reference.split_once('.') {
+            let target = self.aliases.get(&(from.to_path_buf(), alias.to_string()))?;
+            return self.find_public(target, name);
+        }
+
+        self.entries
+            .get_key_value(&(from.to_path_buf(), reference.to_string()))
+            .or_else(|| {
+                self.imports
+                    .get(from)?
+                    .iter()
+                    .find_map(|import| self.find_public(import, reference))
+            })
+    }
+
+    pub fn get(&self, reference: &str, from: &Path) -> Option<&T> {
+        self.find(reference, from).map(|(_, entry)| entry)
+    }
+
+    /// Entry declared as `name` in `path`
+    pub fn declared(&self, path: &Path, name: &str) -> Option<&T> {
+        self.entries.get(&(path.to_path_buf(), name.to_string()))
+    }
+
+    /// Every entry with the document it's declared in and its name
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &str, &T)> {
+        self.entries
+            .iter()
+            .map(|((path, name), entry)| (path.as_path(), name.as_str(), entry))
+    }
+
+    /// Whether `name` is declared public in `path`
+    pub fn is_public(&self, path: &Path, name: &str) -> bool {
+        self.public.contains(&(path.to_path_buf(), name.to_string()))
+    }
+
+    fn find_public(&self, path: &Path, name: &str) -> Option<(&(PathBuf, String), &T)> {
+        let key = (path.to_path_buf(), name.to_string());
+        if !self.public.contains(&key) {
+            return None;
+        }
+        self.entries.get_key_value(&key)
+    }
+
+    /// Same lookup structure with different entries
+    fn with_entries<U>(&self, entries: HashMap<(PathBuf, String), U>) -> TokenScope<U> {
+        TokenScope {
+            entries,
+            public: self.public.clone(),
+            aliases: self.aliases.clone(),
+            imports: self.imports.clone(),
+        }
+    }
+}
+
+/// Resolves token declarations, detecting reference cycles
+pub struct TokenResolver<'a> {
+    decls: TokenScope<&'a TokenDecl>,
+    resolved: HashMap<(PathBuf, String), ResolvedToken>,
+    stack: Vec<(PathBuf, String)>,
+}
+
+impl<'a> TokenResolver<'a> {
+    /// Resolver over a single document's tokens
+    pub fn new(tokens: impl IntoIterator<Item = &'a TokenDecl>) -> Self {
+        Self::with_scope(TokenScope::for_document(Path::new(""), tokens))
+    }
+
+    /// Resolver over `entry` and everything it imports
+    pub fn for_bundle(bundle: &'a Bundle, entry: &Path) -> Self {
+        Self::with_scope(TokenScope::for_bundle(bundle, entry))
+    }
+
+    pub fn with_scope(decls: TokenScope<&'a TokenDecl>) -> Self {
+        Self {
+            decls,
+            resolved: HashMap::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Resolve every token of a single-document resolver, by name
+    pub fn resolve_all(self) -> CssResult<HashMap<String, ResolvedToken>> {
+        Ok(self
+            .resolve_scope()?
+            .entries
+            .into_iter()
+            .map(|((_, name), token)| (name, token))
+            .collect())
+    }
+
+    /// Resolve every token in scope
+    pub fn resolve_scope(mut self) -> CssResult<TokenScope<ResolvedToken>> {
+        let mut keys: Vec<(PathBuf, String)> = self.decls.entries.keys().cloned().collect();
+        keys.sort();
+        for key in keys {
+            let decl = self.decls.entries[&key];
+            self.resolve_decl(&key, decl)?;
+        }
+        Ok(self.decls.with_entries(self.resolved))
+    }
+
+    /// Resolve a token of a single-document resolver by name
+    pub fn resolve(&mut self, name: &str) -> CssResult<ResolvedToken> {
+        self.resolve_reference(name, Path::new(""))?
+            .ok_or_else(|| CssError::TokenNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Resolve `reference` as seen from the document `from` (`None` if it
+    /// isn't a token)
+    pub fn resolve_reference(
+        &mut self,
+        reference: &str,
+        from: &Path,
+    ) -> CssResult<Option<ResolvedToken>> {
+        let Some((key, decl)) = self.decls.find(reference, from) else {
+            return Ok(None);
+        };
+        let (key, decl) = (key.clone(), *decl);
+        self.resolve_decl(&key, decl).map(Some)
+    }
+
+    fn resolve_decl(
+        &mut self,
+        key: &(PathBuf, String),
+        decl: &'a TokenDecl,
+    ) -> CssResult<ResolvedToken> {
+        if let Some(resolved) = self.resolved.get(key) {
+            return Ok(resolved.clone());
+        }
+
+        if let Some(start) = self.stack.iter().position(|k| k == key) {
+            let mut cycle: Vec<&str> = self.stack[start..].iter().map(|(_, n)| n.as_str()).collect();
+            cycle.push(&key.1);
+            return Err(CssError::TokenCycle {
+                cycle: cycle.join(" -> "),
+            });
+        }
+
+        self.stack.push(key.clone());
+        let result = self.evaluate(&decl.expression, &decl.name, &key.0);
+        self.stack.pop();
+
+        let evaluated = result?;
+        let css_value = match (&decl.expression, evaluated.dynamic) {
+            (TokenValue::Binary { .. }, true) => format!("calc({})", evaluated.css),
+            (_, true) => evaluated.css,
+            (_, false) => evaluated.operand.to_css(),
+        };
+
+        let resolved = ResolvedToken {
+            value: evaluated.operand.to_css(),
+            css_value,
+            var_name: token_var_name(decl),
+            unresolved: evaluated.unresolved,
+        };
+        self.resolved.insert(key.clone(), resolved.clone());
+        Ok(resolved)
+    }
+
+    fn evaluate(&mut self, value: &TokenValue, token: &str, document: &Path) -> CssResult<Evaluated> {
+        match value {
+            TokenValue::Literal { value } => Ok(Evaluated::literal(value)),
+
+            TokenValue::Reference { name } => match self.resolve_reference(name, document)? {
+                Some(resolved) => Ok(Evaluated {
+                    operand: Operand::parse(&resolved.value),
+                    css: format!("var({}, {})", resolved.var_name, resolved.value),
+                    dynamic: true,
+                    unresolved: resolved.unresolved,
+                }),
+                // Not a token - treat as a CSS keyword (e.g. `bold`, `auto`)
+                None if !name.contains('.') => Ok(Evaluated::literal(name)),
+                // A missing import - pass it through and let the caller report it
+                None => Ok(Evaluated {
+                    unresolved: vec![name.clone()],
+                    ..Evaluated::literal(name)
+                }),
+            },
+
+            TokenValue::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                let l = self.evaluate(left, token, document)?;
+                let r = self.evaluate(right, token, document)?;
+                if !l.unresolved.is_empty() || !r.unresolved.is_empty() {
+                    // Nothing to compute with - keep the expression for the browser
+                    return Ok(Evaluated::passthrough(
+                        format!(
+                            "{} {} {}",
+                            l.operand_css(left),
+                            operator_symbol(operator),
+                            r.operand_css(right)
+                        ),
+                        [l, r],
+                    ));
+                }
+                let operand = apply_math(&l.operand, operator, &r.operand)
+                    .map_err(|message| invalid(token, message))?;
+                Ok(Evaluated {
+                    operand,
+                    css: format!(
+                        "{} {} {}",
+                        l.operand_css(left),
+                        operator_symbol(operator),
+                        r.operand_css(right)
+                    ),
+                    dynamic: l.dynamic || r.dynamic,
+                    unresolved: Vec::new(),
+                })
+            }
+
+            TokenValue::Call {
+                function,
+                arguments,
+            } => {
+                let mut args = Vec::with_capacity(arguments.len());
+                for argument in arguments {
+                    args.push(self.evaluate(argument, token, document)?);
+                }
+
+                let literal: Vec<String> = args.iter().map(|a| a.operand.to_css()).collect();
+                if args.iter().any(|a| !a.unresolved.is_empty()) {
+                    return Ok(Evaluated::passthrough(
+                        format!("{}({})", function, literal.join(", ")),
+                        args,
+                    ));
+                }
+
+                match function.as_str() {
+                    "alpha" => {
+                        let color = expect_arity(&args, 2, 2, "alpha", token)
+                            .and_then(|_| expect_color(&args[0], "alpha", token))?;
+                        let amount = expect_ratio(&args[1], "alpha", token)?;
+                        Ok(Evaluated::computed(Operand::Color(
+                            color.with_alpha(amount),
+                        )))
+                    }
+                    "mix" => {
+                        expect_arity(&args, 2, 3, "mix", token)?;
+                        let a = expect_color(&args[0], "mix", token)?;
+                        let b = expect_color(&args[1], "mix", token)?;
+                        let weight = match args.get(2) {
+                            Some(arg) => expect_ratio(arg, "mix", token)?,
+                            None => 0.5,
+                        };
+                        Ok(Evaluated::computed(Operand::Color(a.mix(&b, weight))))
+                    }
+                    _ => {
+                        // Unknown functions pass through as plain CSS (e.g. `rgba(...)`)
+                        let css: Vec<String> = args.iter().map(|a| a.css.clone()).collect();
+                        let text = format!("{}({})", function, literal.join(", "));
+                        Ok(Evaluated {
+                            operand: Operand::parse(&text),
+                            css: format!("{}({})", function, css.join(", ")),
+                            dynamic: args.iter().any(|a| a.dynamic),
+                            unresolved: Vec::new(),
+                        })
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn invalid(token: &str, message: impl Into<String>) -> CssError {
+    CssError::InvalidTokenValue {
+        name: token.to_string(),
+        message: message.into(),
+    }
+}
+
+fn operator_symbol(operator: &BinaryOp) -> &'static str {
+    match operator {
+        BinaryOp::Add => "+",
+        BinaryOp::Subtract => "-",
+        BinaryOp::Multiply => "*",
+        BinaryOp::Divide => "/",
+        _ => "?",
+    }
+}
+
+fn expect_arity(
+    args: &[Evaluated],
+    min: usize,
+    max: usize,
+    function: &str,
+    token: &str,
+) -> CssResult<()> {
+    if args.len() < min || args.len() > max {
+        let expected = if min == max {
+            min.to_string()
+        } else {
+            format!("{}-{}", min, max)
+        };
+        return Err(invalid(
+            token,
+            format!(
+                "{}() expects {} arguments, got {}",
+                function,
+                expected,
+                args.len()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn expect_color(arg: &Evaluated, function: &str, token: &str) -> CssResult<Rgba> {
+    match &arg.operand {
+        Operand::Color(color) => Ok(*color),
+        other => Err(invalid(
+            token,
+            format!("{}() expects a color, got '{}'", function, other.to_css()),
+        )),
+    }
+}
+
+fn expect_ratio(arg: &Evaluated, function: &str, token: &str) -> CssResult<f64> {
+    match &arg.operand {
+        Operand::Dimension { value, unit } if unit.is_empty() => Ok(value.clamp(0.0, 1.0)),
+        Operand::Dimension { value, unit } if unit == "%" => Ok((value / 100.0).clamp(0.0, 1.0)),
+        other => Err(invalid(
+            token,
+            format!(
+                "{}() expects a number or percentage, got '{}'",
+                function,
+                other.to_css()
+            ),
+        )),
+    }
+}
+
+/// Intermediate evaluation result
+struct Evaluated {
+    operand: Operand,
+    /// CSS form (may reference other tokens via `var()`)
+    css: String,
+    /// Whether `css` depends on other tokens' custom properties
+    dynamic: bool,
+    /// References that couldn't be found
+    unresolved: Vec<String>,
+}
+
+impl Evaluated {
+    fn computed(operand: Operand) -> Self {
+        let css = operand.to_css();
+        Self {
+            operand,
+            css,
+            dynamic: false,
+            unresolved: Vec::new(),
+        }
+    }
+
+    fn literal(value: &str) -> Self {
+        Self {
+            operand: Operand::parse(value),
+            css: value.to_string(),
+            dynamic: false,
+            unresolved: Vec::new(),
+        }
+    }
+
+    /// An expression that can't be computed because some of `parts` are
+    /// unresolved - kept as written, carrying their unresolved references
+    fn passthrough(text: String, parts: impl IntoIterator<Item = Evaluated>) -> Self {
+        let unresolved = parts.into_iter().flat_map(|part| part.unresolved).collect();
+        Self {
+            operand: Operand::Other(text.clone()),
+            css: text,
+            dynamic: false,
+            unresolved,
+        }
+    }
+
+    /// CSS for use as a binary operand - nested expressions are parenthesized
+    fn operand_css(&self, source: &TokenValue) -> String {
+        match source {
+            TokenValue::Binary { .. } if self.dynamic => format!("({})", self.css),
+            _ if self.dynamic => self.css.clone(),
+            _ => self.operand.to_css(),
+        }
+    }
+}
+
+/// Typed token value used for math and color functions
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Dimension { value: f64, unit: String },
+    Color(Rgba),
+    Other(String),
+}
+
+impl Operand {
+    fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if let Some(color) = Rgba::parse(value) {
+            return Operand::Color(color);
+        }
+        if let Some((number, unit)) = split_dimension(value) {
+            return Operand::Dimension {
+                value: number,
+                unit: unit.to_string(),
+            };
+        }
+        Operand::Other(value.to_string())
+    }
+
+    fn to_css(&self) -> String {
+        match self {
+            Operand::Dimension { value, unit } => format!("{}{}", format_number(*value), unit),
+            Operand::Color(color) => color.to_css(),
+            Operand::Other(value) => value.clone(),
+        }
+    }
+}
+
+fn apply_math(left: &Operand, operator: &BinaryOp, right: &Operand) -> Result<Operand, String> {
+    let (Operand::Dimension { value: a, unit: ua }, Operand::Dimension { value: b, unit: ub }) =
+        (left, right)
+    else {
+        return Err(format!(
+            "cannot apply '{}' to '{}' and '{}'",
+            operator_symbol(operator),
+            left.to_css(),
+            right.to_css()
+        ));
+    };
+
+    let dimension = |value: f64, unit: &str| Operand::Dimension {
+        value,
+        unit: unit.to_string(),
+    };
+
+    match operator {
+        BinaryOp::Add | BinaryOp::Subtract => {
+            if ua != ub {
+                return Err(format!("incompatible units '{}' and '{}'", ua, ub));
+            }
+            let value = if matches!(operator, BinaryOp::Add) {
+                a + b
+            } else {
+                a - b
+            };
+            Ok(dimension(value, ua))
+        }
+        BinaryOp::Multiply => match (ua.is_empty(), ub.is_empty()) {
+            (true, _) => Ok(dimension(a * b, ub)),
+            (_, true) => Ok(dimension(a * b, ua)),
+            _ => Err(format!("cannot multiply '{}' by '{}'", ua, ub)),
+        },
+        BinaryOp::Divide => {
+            if *b == 0.0 {
+                return Err("division by zero".to_string());
+            }
+            if ub.is_empty() {
+                Ok(dimension(a / b, ua))
+            } else if ua == ub {
+                Ok(dimension(a / b, ""))
+            } else {
+                Err(format!("cannot divide '{}' by '{}'", ua, ub))
+            }
+        }
+        _ => Err(format!("unsupported operator '{:?}'", operator)),
+    }
+}
+
+/// Split "16px" into (16.0, "px"). Returns None for non-numeric values.
+fn split_dimension(value: &str) -> Option<(f64, &str)> {
+    let end = value
+        .char_indices()
+        .find(|(i, c)| !(c.is_ascii_digit() || *c == '.' || (*i == 0 && *c == '-')))
+        .map(|(i, _)| i)
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(end);
+    let number: f64 = number.parse().ok()?;
+    if unit == "%" || unit.chars().all(|c| c.is_ascii_alphabetic()) {
+        Some((number, unit))
+    } else {
+        None
+    }
+}
+
+fn format_number(value: f64) -> String {
+    let rounded = (value * 10_000.0).round() / 10_000.0;
+    if rounded == rounded.trunc() {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+/// RGBA color with 0-255 channels and 0-1 alpha
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Rgba {
+    r: f64,
+    g: f64,
+    b: f64,
+    a: f64,
+}
+
+impl Rgba {
+    fn parse(value: &str) -> Option<Self> {
+        if let Some(hex) = value.strip_prefix('#') {
+            return Self::parse_hex(hex);
+        }
+
+        let inner = value
+            .strip_prefix("rgba(")
+            .or_else(|| value.strip_prefix("rgb("))?
+            .strip_suffix(')')?;
+        let parts: Vec<f64> = inner
+            .split(',')
+            .map(|p| p.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+        match parts.as_slice() {
+            [r, g, b] => Some(Self {
+                r: *r,
+                g: *g,
+                b: *b,
+                a: 1.0,
+            }),
+            [r, g, b, a] => Some(Self {
+                r: *r,
+                g: *g,
+                b: *b,
+                a: *a,
+            }),
+            _ => None,
+        }
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        let expanded: String = match hex.len() {
+            3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        let channel = |i: usize| {
+            u8::from_str_radix(&expanded[i..i + 2], 16)
+                .ok()
+                .map(f64::from)
+        };
+        Some(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+            a: if expanded.len() == 8 {
+                channel(6)? / 255.0
+            } else {
+                1.0
+            },
+        })
+    }
+
+    fn with_alpha(self, a: f64) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Mix two colors. `weight` is the proportion of `self` (0-1).
+    fn mix(&self, other: &Rgba, weight: f64) -> Self {
+        let blend = |x: f64, y: f64| x * weight + y * (1.0 - weight);
+        Self {
+            r: blend(self.r, other.r),
+            g: blend(self.g, other.g),
+            b: blend(self.b, other.b),
+            a: blend(self.a, other.a),
+        }
+    }
+
+    fn to_css(self) -> String {
+        let (r, g, b) = (
+            self.r.round().clamp(0.0, 255.0) as u8,
+            self.g.round().clamp(0.0, 255.0) as u8,
+            self.b.round().clamp(0.0, 255.0) as u8,
+        );
+        if (self.a - 1.0).abs() < f64::EPSILON {
+            format!("#{:02X}{:02X}{:02X}", r, g, b)
+        } else {
+            format!("rgba({}, {}, {}, {})", r, g, b, format_number(self.a))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paperclip_parser::parse_with_path;
+
+    fn resolve(source: &str) -> CssResult<HashMap<String, ResolvedToken>> {
+        let doc = parse_with_path(source, "/tokens.pc").expect("Failed to parse");
+        TokenResolver::new(&doc.tokens).resolve_all()
+    }
+
+    #[test]
+    fn test_alias_keeps_var_chain() {
+        let tokens = resolve(
+            r#"
+            token primaryColor #3366FF
+            token buttonBg primaryColor
+        "#,
+        )
+        .unwrap();
+
+        let primary = &tokens["primaryColor"];
+        let button = &tokens["buttonBg"];
+        assert_eq!(button.value, "#3366FF");
+        assert_eq!(
+            button.css_value,
+            format!("var({}, #3366FF)", primary.var_name)
+        );
+    }
+
+    #[test]
+    fn test_math_on_compatible_units() {
+        let tokens = resolve(
+            r#"
+            token spacing 8px
+            token spacingLarge spacing * 2
+            token spacingHalf spacing / 2
+            token gutter 10px + 6px
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(tokens["spacingLarge"].value, "16px");
+        assert_eq!(tokens["spacingHalf"].value, "4px");
+        assert_eq!(tokens["gutter"].value, "16px");
+        assert_eq!(tokens["gutter"].css_value, "16px");
+
+        // References stay live through calc()
+        let spacing_var = &tokens["spacing"].var_name;
+        assert_eq!(
+            tokens["spacingLarge"].css_value,
+            format!("calc(var({}, 8px) * 2)", spacing_var)
+        );
+    }
+
+    #[test]
+    fn test_incompatible_units_error() {
+        let result = resolve(
+            r#"
+            token a 8px
+            token b a + 1em
+        "#,
+        );
+
+        match result {
+            Err(CssError::InvalidTokenValue { name, message }) => {
+                assert_eq!(name, "b");
+                assert!(message.contains("incompatible units"), "{}", message);
+            }
+            other => panic!("Expected InvalidTokenValue, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_color_functions() {
+        let tokens = resolve(
+            r#"
+            token primaryColor #3366FF
+            token overlay alpha(primaryColor, 0.5)
+            token mid mix(#000000, #FFFFFF)
+            token tint mix(primaryColor, #FFFFFF, 25%)
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(tokens["overlay"].value, "rgba(51, 102, 255, 0.5)");
+        assert_eq!(tokens["mid"].value, "#808080");
+        assert_eq!(tokens["tint"].value, "#CCD9FF");
+    }
+
+    #[test]
+    fn test_alpha_requires_color() {
+        let result = resolve("token a alpha(8px, 0.5)");
+        assert!(matches!(result, Err(CssError::InvalidTokenValue { .. })));
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let result = resolve(
+            r#"
+            token a b
+            token b c * 2
+            token c a
+        "#,
+        );
+
+        match result {
+            Err(CssError::TokenCycle { cycle }) => {
+                assert_eq!(cycle, "a -> b -> c -> a");
+            }
+            other => panic!("Expected TokenCycle, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_self_reference_is_cycle() {
+        let result = resolve("token a a");
+        assert!(matches!(result, Err(CssError::TokenCycle { .. })));
+    }
+
+    #[test]
+    fn test_unknown_identifier_is_keyword() {
+        let tokens = resolve("token weight bold").unwrap();
+        assert_eq!(tokens["weight"].value, "bold");
+        assert_eq!(tokens["weight"].css_value, "bold");
+    }
+
+    #[test]
+    fn test_missing_namespaced_reference_passes_through() {
+        let tokens = resolve(
+            r#"
+            token a theme.missing
+            token b a * 2
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(tokens["a"].value, "theme.missing");
+        assert_eq!(tokens["a"].unresolved, vec!["theme.missing"]);
+        // Dependents carry the unresolved reference instead of failing
+        assert_eq!(tokens["b"].unresolved, vec!["theme.missing"]);
+    }
+
+    fn bundle(files: &[(&str, &str)]) -> Bundle {
+        let mut bundle = Bundle::new();
+        let mut mock_fs = paperclip_bundle::MockFileSystem::new();
+        for (path, source) in files {
+            mock_fs.add_file(PathBuf::from(path));
+            bundle.add_document(PathBuf::from(path), parse_with_path(source, path).unwrap());
+        }
+        bundle
+            .build_dependencies_with_fs(Path::new("/app"), &mock_fs)
+            .unwrap();
+        bundle
+    }
+
+    #[test]
+    fn test_references_follow_imports_by_document() {
+        let bundle = bundle(&[
+            ("/app/colors.pc", "public token blue #0000FF\npublic token primary #FF0000"),
+            (
+                "/app/theme.pc",
+                "import \"./colors.pc\" as colors\npublic token primary colors.blue",
+            ),
+            (
+                "/app/main.pc",
+                "import \"./theme.pc\" as theme\ntoken primary theme.primary\ntoken accent primary",
+            ),
+        ]);
+
+        let tokens = TokenResolver::for_bundle(&bundle, Path::new("/app/main.pc"))
+            .resolve_scope()
+            .unwrap();
+
+        // Same name in three documents, each resolved on its own
+        let main = Path::new("/app/main.pc");
+        assert_eq!(tokens.get("primary", main).unwrap().value, "#0000FF");
+        assert_eq!(tokens.get("accent", main).unwrap().value, "#0000FF");
+        assert_eq!(tokens.get("theme.primary", main).unwrap().value, "#0000FF");
+        assert_eq!(
+            tokens.get("primary", Path::new("/app/colors.pc")).unwrap().value,
+            "#FF0000"
+        );
+        assert!(tokens.get("colors.blue", main).is_none());
+    }
+}
@@ -89,6 +89,11 @@ impl Validator {
                 semantic_id,
                 children,
                 ..
+            }
+            | VNode::LiveComponent {
+                semantic_id,
+                children,
+                ..
             } => {
                 // Validate this element
                 self.validate_semantic_id(semantic_id);
@@ -161,6 +166,11 @@ impl Validator {
                 semantic_id,
                 children,
                 ..
+            }
+            | VNode::LiveComponent {
+                semantic_id,
+                children,
+                ..
             } = node
             {
                 let selector = semantic_id.to_selector();
@@ -243,6 +253,9 @@ impl Validator {
 
                 // Recurse into children
                 self.collect_repeat_keys(children, repeat_keys);
+            } else if let VNode::LiveComponent { children, .. } = node {
+                // The fallback render carries the item's key
+                self.collect_repeat_keys(children, repeat_keys);
             }
         }
     }
@@ -267,7 +280,7 @@ mod tests {
             },
         ]);
 
-        let vdom = VirtualDomDocument { components: vec![],
+        let vdom = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "div".to_string(),
                 attributes: HashMap::new(),
@@ -295,7 +308,7 @@ mod tests {
             ast_id: "same-id".to_string(),
         }]);
 
-        let vdom = VirtualDomDocument { components: vec![],
+        let vdom = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![
                 VNode::Element {
                     tag: "div".to_string(),
@@ -333,7 +346,7 @@ mod tests {
             key: "item-0".to_string(),
         }]);
 
-        let vdom = VirtualDomDocument { components: vec![],
+        let vdom = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "div".to_string(),
                 attributes: HashMap::new(),
@@ -379,7 +392,7 @@ mod tests {
             },
         ]);
 
-        let vdom = VirtualDomDocument { components: vec![],
+        let vdom = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "ul".to_string(),
                 attributes: HashMap::new(),
@@ -445,7 +458,7 @@ mod tests {
             key: "item-0".to_string(),         // Same key is OK
         }]);
 
-        let vdom = VirtualDomDocument { components: vec![],
+        let vdom = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![
                 VNode::Element {
                     tag: "li".to_string(),
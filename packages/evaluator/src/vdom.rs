@@ -9,7 +9,7 @@
 //!
 //! ## Core Types
 //!
-//! - **VNode**: Virtual DOM node (Element, Text, Comment, or Error)
+//! - **VNode**: Virtual DOM node (Element, Text, Comment, LiveComponent, or Error)
 //! - **VirtualDomDocument**: Complete VDOM tree with associated CSS rules
 //! - **CssRule**: CSS rule with selector and properties
 //!
@@ -21,6 +21,13 @@
 //! **Key Field**: Elements in repeat blocks should have explicit keys for stable diffing.
 //! Auto-generated keys may not survive data reordering.
 //!
+//! ## Live Components
+//!
+//! Instances of components bound to real code (`script(src: "./counter.tsx")`)
+//! evaluate to `VNode::LiveComponent`. It carries the module path, export name
+//! and JSON props so a hybrid preview client can mount the real component in
+//! place, plus the static render as fallback children for clients that can't.
+//!
 //! ## Error Nodes
 //!
 //! `VNode::Error` nodes represent evaluation errors that don't crash the entire preview.
@@ -38,7 +45,9 @@
 //!     .with_child(VNode::text("Hello"));
 //! ```
 
-use paperclip_parser::ast::Span;
+use crate::css_at_rules::AtRule;
+use crate::css_layers::CssLayer;
+use paperclip_parser::ast::{Properties, Span};
 use paperclip_semantics::SemanticID;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -69,6 +78,25 @@ pub enum VNode {
     /// Comment node
     Comment { content: String },
 
+    /// Instance of a script-bound component, mounted live by capable clients
+    LiveComponent {
+        /// Module path from `script(src: ...)`, as written in the source
+        src: String,
+        /// Export to mount (`script(name: ...)`, defaulting to the component name)
+        export_name: String,
+        /// Framework the module is written for (`script(target: ...)`)
+        target: String,
+        /// Evaluated instance props serialized as a JSON object
+        props: String,
+        /// Static render of the component, shown until (or instead of) the live one
+        children: Vec<VNode>,
+        semantic_id: SemanticID,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+    },
+
     /// Error node (for partial evaluation - shows errors inline instead of crashing)
     Error {
         message: String,
@@ -159,6 +187,10 @@ impl VNode {
         if let VNode::Element {
             key: ref mut node_key,
             ..
+        }
+        | VNode::LiveComponent {
+            key: ref mut node_key,
+            ..
         } = self
         {
             *node_key = Some(key.into());
@@ -208,6 +240,23 @@ pub struct FrameMetadata {
     pub height: Option<f64>,
 }
 
+/// Designer metadata for one root node of a `VirtualDomDocument`
+///
+/// Kept out of the node's attributes so it never reaches the rendered DOM, and
+/// so moving a frame produces an `UpdateFrame` patch rather than attribute churn.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RootMetadata {
+    /// Component rendered by this root (`None` for top-level renders)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+    /// Frame positioning from the @frame annotation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<FrameMetadata>,
+    /// Other annotations from the doc comment
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<AnnotationMetadata>,
+}
+
 /// Generic annotation metadata
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AnnotationMetadata {
@@ -223,15 +272,25 @@ pub struct VirtualDomDocument {
     /// Component metadata for designer (frames, descriptions, annotations)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub components: Vec<ComponentMetadata>,
+    /// Designer metadata for each entry of `nodes` (same index)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<RootMetadata>,
 }
 
 /// CSS Rule
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CssRule {
     pub selector: String,
-    pub properties: HashMap<String, String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub media_query: Option<String>,
+    pub properties: Properties,
+    /// Enclosing at-rules, outermost first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub at_rules: Vec<AtRule>,
+    /// Unscoped rule from a `global style` declaration
+    #[serde(default)]
+    pub global: bool,
+    /// Cascade layer - decides priority between generated rules
+    #[serde(default)]
+    pub layer: CssLayer,
 }
 
 impl VirtualDomDocument {
@@ -240,18 +299,33 @@ impl VirtualDomDocument {
             nodes: Vec::new(),
             styles: Vec::new(),
             components: Vec::new(),
+            roots: Vec::new(),
         }
     }
 
     pub fn add_node(&mut self, node: VNode) {
         self.nodes.push(node);
+        self.roots.push(RootMetadata::default());
+    }
+
+    /// Add a root node along with its designer metadata
+    pub fn add_root(&mut self, node: VNode, metadata: RootMetadata) {
+        self.nodes.push(node);
+        self.roots.push(metadata);
+    }
+
+    /// Designer metadata for the root node at `index`
+    pub fn root_metadata(&self, index: usize) -> Option<&RootMetadata> {
+        self.roots.get(index)
     }
 
-    pub fn add_style(&mut self, selector: impl Into<String>, properties: HashMap<String, String>) {
+    pub fn add_style(&mut self, selector: impl Into<String>, properties: Properties) {
         self.styles.push(CssRule {
             selector: selector.into(),
             properties,
-            media_query: None,
+            at_rules: vec![],
+            global: false,
+            layer: CssLayer::Components,
         });
     }
 }
@@ -21,21 +21,34 @@
 //!
 //! ```text
 //! 1. Match nodes by semantic ID (not position)
-//! 2. For matched nodes:
+//! 2. For unmatched old nodes: Delete (last first)
+//! 3. For matched nodes out of order: Move, keeping the longest
+//!    increasing subsequence in place so the number of moves is minimal
+//! 4. For unmatched new nodes: Insert (first first)
+//! 5. For matched nodes:
 //!    - Update if attributes/styles changed
 //!    - Recursively diff children
-//! 3. For unmatched old nodes: Delete
-//! 4. For unmatched new nodes: Insert
 //! ```
 //!
 //! ## Patch Types
 //!
-//! - **InsertNode**: Add new node at semantic path
-//! - **DeleteNode**: Remove node at semantic path
+//! - **CreateNode**: Add new node under a parent
+//! - **RemoveNode**: Remove node
+//! - **MoveChild**: Reorder an existing child within its parent
 //! - **UpdateAttributes**: Change element attributes
 //! - **UpdateStyles**: Change inline styles
 //! - **ReplaceNode**: Replace entire subtree (type changed)
 //! - **UpdateText**: Change text content
+//! - **UpdateLiveProps**: Re-render a mounted live component with new props
+//! - **UpdateFrame**: Change a root's designer metadata (frame, annotations)
+//! - **AddStyleRule** / **UpdateStyleRule** / **RemoveStyleRule**: CSSOM changes
+//!   from `css_differ::diff_css_rules`, keyed by `(selector, media_query)`;
+//!   updates carry only the properties that changed
+//!
+//! Node patches address their node twice: a positional `path`, valid once the
+//! patches before it have been applied, and a `PatchPath` target holding the
+//! semantic ID when the node is an element with an ID unique among its
+//! siblings (positional otherwise).
 //!
 //! ## Keyed Diffing for Repeat Blocks
 //!
@@ -68,8 +81,11 @@
 //! // Send patches to client for efficient UI update
 //! ```
 
-use crate::vdom::{VNode, VirtualDomDocument};
+use crate::css_at_rules::{legacy_media_query, AtRule};
+use crate::css_differ::{diff_css_rules, CssPatch};
+use crate::vdom::{AnnotationMetadata, RootMetadata, VNode, VirtualDomDocument};
 use paperclip_semantics::SemanticID;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 // Include generated protobuf types
 pub mod proto {
@@ -90,7 +106,15 @@ pub fn diff_vdocument(old: &VirtualDomDocument, new: &VirtualDomDocument) -> Vec
     let mut patches = Vec::new();
 
     // Match root nodes by semantic ID
-    patches.extend(diff_children_by_semantic_id(&old.nodes, &new.nodes, vec![]));
+    patches.extend(diff_children_by_semantic_id(
+        &old.nodes,
+        &new.nodes,
+        vec![],
+        positional_path(vec![]),
+    ));
+
+    // Frames and annotations live beside the nodes, not in their attributes
+    patches.extend(diff_root_metadata(old, new));
 
     // Diff style rules
     patches.extend(diff_style_rules(&old.styles, &new.styles));
@@ -99,119 +123,269 @@ pub fn diff_vdocument(old: &VirtualDomDocument, new: &VirtualDomDocument) -> Vec
 }
 
 /// Diff children using semantic ID matching for stable patches
+///
+/// Patches for one parent are emitted in an order the client can apply
+/// sequentially: removals (descending old index), moves, creates (ascending
+/// new index), then the recursive diffs of matched children at their final
+/// positions.
 fn diff_children_by_semantic_id(
     old_children: &[VNode],
     new_children: &[VNode],
     parent_path: Vec<u32>,
+    parent_target: PatchPath,
 ) -> Vec<VDocPatch> {
-    use std::collections::HashMap;
-
     let mut patches = Vec::new();
 
-    // Separate elements (with semantic IDs) from text/comment nodes
-    let mut old_elements: HashMap<String, (usize, &VNode)> = HashMap::new();
-    let mut new_elements: HashMap<String, (usize, &VNode)> = HashMap::new();
+    let old_targets = child_targets(old_children, &parent_path);
+    let new_targets = child_targets(new_children, &parent_path);
 
-    // For text/comment nodes without semantic IDs, use position-based matching
-    let mut old_simple_nodes: Vec<(usize, &VNode)> = Vec::new();
-    let mut new_simple_nodes: Vec<(usize, &VNode)> = Vec::new();
-
-    for (i, node) in old_children.iter().enumerate() {
-        if let Some(semantic_id) = get_node_semantic_id(node) {
-            old_elements.insert(semantic_id.to_selector(), (i, node));
-        } else {
-            old_simple_nodes.push((i, node));
-        }
+    // For each new child, the old child it was matched to (if any)
+    let matches = match_children(old_children, new_children);
+    let mut matched_old = vec![false; old_children.len()];
+    for old_idx in matches.iter().flatten() {
+        matched_old[*old_idx] = true;
     }
 
-    for (i, node) in new_children.iter().enumerate() {
-        if let Some(semantic_id) = get_node_semantic_id(node) {
-            new_elements.insert(semantic_id.to_selector(), (i, node));
-        } else {
-            new_simple_nodes.push((i, node));
+    // Remove unmatched old children, last first so earlier indices stay valid
+    for old_idx in (0..old_children.len()).rev() {
+        if !matched_old[old_idx] {
+            let mut path = parent_path.clone();
+            path.push(old_idx as u32);
+            patches.push(VDocPatch {
+                patch_type: Some(v_doc_patch::PatchType::RemoveNode(RemoveNodePatch {
+                    path,
+                    target: Some(old_targets[old_idx].clone()),
+                })),
+            });
         }
     }
 
-    // Diff elements by semantic ID
-    // Find removed elements (in old but not in new)
-    for (semantic_key, (old_idx, _)) in &old_elements {
-        if !new_elements.contains_key(semantic_key) {
-            let mut path = parent_path.clone();
-            path.push(*old_idx as u32);
+    // Move survivors that aren't part of the longest run already in order
+    let survivors: Vec<(usize, usize)> = matches
+        .iter()
+        .enumerate()
+        .filter_map(|(new_idx, old_idx)| old_idx.map(|old_idx| (new_idx, old_idx)))
+        .collect();
+    let old_order: Vec<usize> = survivors.iter().map(|(_, old_idx)| *old_idx).collect();
+    let stable = longest_increasing_subsequence(&old_order);
+
+    let mut current: Vec<usize> = (0..old_children.len())
+        .filter(|old_idx| matched_old[*old_idx])
+        .collect();
+    let mut previous: Option<usize> = None;
+    for (i, (_, old_idx)) in survivors.iter().enumerate() {
+        if !stable.contains(&i) {
+            let from_index = position_of(&current, *old_idx);
+            current.remove(from_index);
+            let to_index = previous.map_or(0, |prev| position_of(&current, prev) + 1);
+            current.insert(to_index, *old_idx);
+
             patches.push(VDocPatch {
-                patch_type: Some(v_doc_patch::PatchType::RemoveNode(RemoveNodePatch { path })),
+                patch_type: Some(v_doc_patch::PatchType::MoveChild(MoveChildPatch {
+                    parent: Some(parent_target.clone()),
+                    child_semantic_id: get_node_semantic_id(&old_children[*old_idx])
+                        .map(|id| id.to_selector())
+                        .unwrap_or_default(),
+                    from_index: from_index as u32,
+                    to_index: to_index as u32,
+                    path: parent_path.clone(),
+                })),
             });
         }
+        previous = Some(*old_idx);
     }
 
-    // Find new elements (in new but not in old) and updated elements
-    for (semantic_key, (new_idx, new_node)) in &new_elements {
-        if let Some((_old_idx, old_node)) = old_elements.get(semantic_key) {
-            // Node exists in both - diff it
-            let mut path = parent_path.clone();
-            path.push(*new_idx as u32);
-            patches.extend(diff_vnodes_same_path(old_node, new_node, path));
-        } else {
-            // New node - create it
+    // Create unmatched new children in order, so each index is final
+    for (new_idx, new_node) in new_children.iter().enumerate() {
+        if matches[new_idx].is_none() {
             // Note: path is the PARENT path, index is where to insert in that parent
             patches.push(VDocPatch {
                 patch_type: Some(v_doc_patch::PatchType::CreateNode(CreateNodePatch {
                     path: parent_path.clone(),
                     node: Some(convert_vnode_to_proto(new_node)),
-                    index: *new_idx as u32,
+                    index: new_idx as u32,
+                    parent: Some(parent_target.clone()),
                 })),
             });
         }
     }
 
-    // Diff simple nodes (text/comment) by position
-    let max_simple = old_simple_nodes.len().max(new_simple_nodes.len());
-    for i in 0..max_simple {
-        let old_node = old_simple_nodes.get(i);
-        let new_node = new_simple_nodes.get(i);
+    // Diff matched children at their new positions
+    for (new_idx, old_idx) in survivors {
+        let mut path = parent_path.clone();
+        path.push(new_idx as u32);
+        patches.extend(diff_vnodes_same_path(
+            &old_children[old_idx],
+            &new_children[new_idx],
+            path,
+            new_targets[new_idx].clone(),
+        ));
+    }
 
-        match (old_node, new_node) {
-            (None, Some((new_idx, new_node))) => {
-                // Create new simple node
-                // Note: path is the PARENT path, index is where to insert in that parent
-                patches.push(VDocPatch {
-                    patch_type: Some(v_doc_patch::PatchType::CreateNode(CreateNodePatch {
-                        path: parent_path.clone(),
-                        node: Some(convert_vnode_to_proto(new_node)),
-                        index: *new_idx as u32,
-                    })),
-                });
-            }
-            (Some((old_idx, _)), None) => {
-                // Remove simple node
-                let mut path = parent_path.clone();
-                path.push(*old_idx as u32);
-                patches.push(VDocPatch {
-                    patch_type: Some(v_doc_patch::PatchType::RemoveNode(RemoveNodePatch { path })),
-                });
+    patches
+}
+
+/// Match new children to old ones. Elements match by semantic ID (and key),
+/// duplicates pairing up in order of appearance; text and comment nodes
+/// match by their order among the other simple nodes.
+fn match_children(old_children: &[VNode], new_children: &[VNode]) -> Vec<Option<usize>> {
+    let mut old_elements: HashMap<(String, Option<&str>), VecDeque<usize>> = HashMap::new();
+    let mut old_simple_nodes: VecDeque<usize> = VecDeque::new();
+
+    for (i, node) in old_children.iter().enumerate() {
+        match match_key(node) {
+            Some(key) => old_elements.entry(key).or_default().push_back(i),
+            None => old_simple_nodes.push_back(i),
+        }
+    }
+
+    new_children
+        .iter()
+        .map(|node| match match_key(node) {
+            Some(key) => old_elements.get_mut(&key).and_then(VecDeque::pop_front),
+            None => old_simple_nodes.pop_front(),
+        })
+        .collect()
+}
+
+fn match_key(node: &VNode) -> Option<(String, Option<&str>)> {
+    match node {
+        VNode::Element {
+            semantic_id, key, ..
+        }
+        | VNode::LiveComponent {
+            semantic_id, key, ..
+        } => Some((semantic_id.to_selector(), key.as_deref())),
+        _ => None,
+    }
+}
+
+/// Patch targets for a list of siblings: the semantic ID for elements whose
+/// ID is unique among them, the positional path otherwise
+fn child_targets(children: &[VNode], parent_path: &[u32]) -> Vec<PatchPath> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for node in children {
+        if let Some(semantic_id) = get_node_semantic_id(node) {
+            *counts.entry(semantic_id.to_selector()).or_default() += 1;
+        }
+    }
+
+    children
+        .iter()
+        .enumerate()
+        .map(|(i, node)| match get_node_semantic_id(node) {
+            Some(semantic_id) if counts[&semantic_id.to_selector()] == 1 => {
+                semantic_path(semantic_id.to_selector())
             }
-            (Some((_old_idx, old_node)), Some((new_idx, new_node))) => {
-                // Use the new node's index for path (handles reordering)
-                let mut path = parent_path.clone();
-                path.push(*new_idx as u32);
-                patches.extend(diff_vnodes_same_path(old_node, new_node, path));
+            _ => {
+                let mut path = parent_path.to_vec();
+                path.push(i as u32);
+                positional_path(path)
             }
-            (None, None) => {}
+        })
+        .collect()
+}
+
+fn positional_path(indices: Vec<u32>) -> PatchPath {
+    PatchPath {
+        path_kind: Some(patch_path::PathKind::Positional(PositionalPath { indices })),
+    }
+}
+
+fn semantic_path(selector: String) -> PatchPath {
+    PatchPath {
+        path_kind: Some(patch_path::PathKind::Semantic(selector)),
+    }
+}
+
+fn position_of(list: &[usize], value: usize) -> usize {
+    list.iter()
+        .position(|item| *item == value)
+        .expect("matched child missing from parent")
+}
+
+/// Indices into `values` of one longest strictly increasing subsequence
+/// (patience sorting, O(n log n))
+fn longest_increasing_subsequence(values: &[usize]) -> HashSet<usize> {
+    // tails[k] = index of the smallest tail of an increasing run of length k + 1
+    let mut tails: Vec<usize> = Vec::new();
+    let mut predecessors: Vec<Option<usize>> = vec![None; values.len()];
+
+    for (i, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|tail| values[*tail] < *value);
+        predecessors[i] = length.checked_sub(1).map(|prev| tails[prev]);
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
         }
     }
 
-    patches
+    let mut result = HashSet::new();
+    let mut next = tails.last().copied();
+    while let Some(i) = next {
+        result.insert(i);
+        next = predecessors[i];
+    }
+    result
+}
+
+/// Emit `UpdateFrame` for roots whose designer metadata changed. Runs after the
+/// node patches, so indices refer to the final root positions.
+fn diff_root_metadata(old: &VirtualDomDocument, new: &VirtualDomDocument) -> Vec<VDocPatch> {
+    let empty = RootMetadata::default();
+    let matches = match_children(&old.nodes, &new.nodes);
+
+    new.nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| {
+            let new_meta = new.roots.get(index).unwrap_or(&empty);
+            let old_meta = match matches[index] {
+                Some(old_index) => old.roots.get(old_index).unwrap_or(&empty),
+                // Created roots don't carry metadata
+                None => &empty,
+            };
+            if old_meta == new_meta {
+                return None;
+            }
+
+            Some(VDocPatch {
+                patch_type: Some(v_doc_patch::PatchType::UpdateFrame(UpdateFramePatch {
+                    index: index as u32,
+                    semantic_id: root_semantic_id(node),
+                    metadata: Some(convert_root_metadata_to_proto(new_meta)),
+                })),
+            })
+        })
+        .collect()
+}
+
+fn root_semantic_id(node: &VNode) -> String {
+    match node {
+        VNode::Element { semantic_id, .. }
+        | VNode::LiveComponent { semantic_id, .. }
+        | VNode::Error { semantic_id, .. } => semantic_id.to_selector(),
+        _ => String::new(),
+    }
 }
 
 /// Extract semantic ID from a VNode
 fn get_node_semantic_id(node: &VNode) -> Option<&SemanticID> {
     match node {
-        VNode::Element { semantic_id, .. } => Some(semantic_id),
+        VNode::Element { semantic_id, .. } | VNode::LiveComponent { semantic_id, .. } => {
+            Some(semantic_id)
+        }
         _ => None,
     }
 }
 
-fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPatch> {
+fn diff_vnodes_same_path(
+    old: &VNode,
+    new: &VNode,
+    path: Vec<u32>,
+    target: PatchPath,
+) -> Vec<VDocPatch> {
     let mut patches = Vec::new();
 
     // Check if node types match
@@ -221,6 +395,7 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
             patch_type: Some(v_doc_patch::PatchType::ReplaceNode(ReplaceNodePatch {
                 path,
                 new_node: Some(convert_vnode_to_proto(new)),
+                target: Some(target),
             })),
         });
         return patches;
@@ -250,6 +425,7 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
                     patch_type: Some(v_doc_patch::PatchType::ReplaceNode(ReplaceNodePatch {
                         path: path.clone(),
                         new_node: Some(convert_vnode_to_proto(new)),
+                        target: Some(target),
                     })),
                 });
                 return patches;
@@ -262,6 +438,7 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
                         UpdateAttributesPatch {
                             path: path.clone(),
                             attributes: new_attrs.clone(),
+                            target: Some(target.clone()),
                         },
                     )),
                 });
@@ -273,6 +450,7 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
                     patch_type: Some(v_doc_patch::PatchType::UpdateStyles(UpdateStylesPatch {
                         path: path.clone(),
                         styles: new_styles.clone(),
+                        target: Some(target.clone()),
                     })),
                 });
             }
@@ -282,6 +460,57 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
                 old_children,
                 new_children,
                 path,
+                target,
+            ));
+        }
+        (
+            VNode::LiveComponent {
+                src: old_src,
+                export_name: old_export,
+                target: old_target,
+                props: old_props,
+                children: old_children,
+                ..
+            },
+            VNode::LiveComponent {
+                src: new_src,
+                export_name: new_export,
+                target: new_target,
+                props: new_props,
+                children: new_children,
+                ..
+            },
+        ) => {
+            if (old_src, old_export, old_target) != (new_src, new_export, new_target) {
+                // Bound to different code - remount
+                patches.push(VDocPatch {
+                    patch_type: Some(v_doc_patch::PatchType::ReplaceNode(ReplaceNodePatch {
+                        path,
+                        new_node: Some(convert_vnode_to_proto(new)),
+                        target: Some(target),
+                    })),
+                });
+                return patches;
+            }
+
+            // New props re-render the mounted component in place, keeping its state
+            if old_props != new_props {
+                patches.push(VDocPatch {
+                    patch_type: Some(v_doc_patch::PatchType::UpdateLiveProps(
+                        UpdateLivePropsPatch {
+                            path: path.clone(),
+                            props_json: new_props.clone(),
+                            target: Some(target.clone()),
+                        },
+                    )),
+                });
+            }
+
+            patches.extend(diff_children_by_semantic_id(
+                old_children,
+                new_children,
+                path,
+                target,
             ));
         }
         (VNode::Text { content: old_text }, VNode::Text { content: new_text }) => {
@@ -290,6 +519,7 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
                     patch_type: Some(v_doc_patch::PatchType::UpdateText(UpdateTextPatch {
                         path,
                         content: new_text.clone(),
+                        target: Some(target),
                     })),
                 });
             }
@@ -301,6 +531,7 @@ fn diff_vnodes_same_path(old: &VNode, new: &VNode, path: Vec<u32>) -> Vec<VDocPa
                     patch_type: Some(v_doc_patch::PatchType::ReplaceNode(ReplaceNodePatch {
                         path,
                         new_node: Some(convert_vnode_to_proto(new)),
+                        target: Some(target),
                     })),
                 });
             }
@@ -315,84 +546,156 @@ fn vnodes_same_type(a: &VNode, b: &VNode) -> bool {
     matches!(
         (a, b),
         (VNode::Element { .. }, VNode::Element { .. })
+            | (VNode::LiveComponent { .. }, VNode::LiveComponent { .. })
             | (VNode::Text { .. }, VNode::Text { .. })
             | (VNode::Comment { .. }, VNode::Comment { .. })
     )
 }
 
 fn diff_style_rules(old: &[crate::vdom::CssRule], new: &[crate::vdom::CssRule]) -> Vec<VDocPatch> {
+    let diff = diff_css_rules(old, new);
     let mut patches = Vec::new();
 
-    // Build maps by selector for efficient matching
-    use std::collections::HashMap;
-    let mut old_map: HashMap<&str, &crate::vdom::CssRule> = HashMap::new();
-    let mut new_map: HashMap<&str, &crate::vdom::CssRule> = HashMap::new();
-
-    for rule in old {
-        old_map.insert(&rule.selector, rule);
-    }
-
-    for rule in new {
-        new_map.insert(&rule.selector, rule);
-    }
-
-    // Find removed rules (in old but not in new)
-    for (i, old_rule) in old.iter().enumerate() {
-        if !new_map.contains_key(old_rule.selector.as_str()) {
+    // Removals go last-first so the legacy `index` stays valid when applied in order
+    for patch in diff.patches.iter().rev() {
+        if let CssPatch::Remove {
+            selector,
+            at_rules,
+            layer,
+        } = patch
+        {
+            let index = old
+                .iter()
+                .position(|rule| {
+                    rule.layer == *layer && &rule.selector == selector && &rule.at_rules == at_rules
+                })
+                .unwrap_or_default();
+            #[allow(deprecated)]
             patches.push(VDocPatch {
                 patch_type: Some(v_doc_patch::PatchType::RemoveStyleRule(
-                    RemoveStyleRulePatch { index: i as u32 },
+                    RemoveStyleRulePatch {
+                        index: index as u32,
+                        selector: selector.clone(),
+                        media_query: legacy_media_query(at_rules),
+                        at_rules: convert_at_rules_to_proto(at_rules),
+                        layer: Some(layer.to_string()),
+                    },
                 )),
             });
         }
     }
 
-    // Find new rules (in new but not in old) or updated rules
-    for new_rule in new {
-        if let Some(old_rule) = old_map.get(new_rule.selector.as_str()) {
-            // Rule exists - check if properties changed
-            if old_rule.properties != new_rule.properties {
-                // Properties changed - for now, remove and add
-                // In future, we could add an UpdateStyleRule patch
-                if let Some(old_index) = old.iter().position(|r| r.selector == new_rule.selector) {
-                    patches.push(VDocPatch {
-                        patch_type: Some(v_doc_patch::PatchType::RemoveStyleRule(
-                            RemoveStyleRulePatch {
-                                index: old_index as u32,
-                            },
-                        )),
-                    });
-                }
-                patches.push(VDocPatch {
-                    patch_type: Some(v_doc_patch::PatchType::AddStyleRule(AddStyleRulePatch {
-                        rule: Some(proto_vdom::CssRule {
-                            selector: new_rule.selector.clone(),
-                            properties: new_rule.properties.clone(),
-                            media_query: new_rule.media_query.clone(),
-                            metadata: None,
-                        }),
-                    })),
-                });
-            }
-            // else: no change, no patch needed
-        } else {
-            // New rule - add it
-            patches.push(VDocPatch {
+    for patch in diff.patches {
+        match patch {
+            CssPatch::Add { rule } => patches.push(VDocPatch {
                 patch_type: Some(v_doc_patch::PatchType::AddStyleRule(AddStyleRulePatch {
-                    rule: Some(proto_vdom::CssRule {
-                        selector: new_rule.selector.clone(),
-                        properties: new_rule.properties.clone(),
-                        media_query: new_rule.media_query.clone(),
-                        metadata: None,
-                    }),
+                    rule: Some(convert_css_rule_to_proto(&rule)),
                 })),
-            });
+            }),
+            #[allow(deprecated)]
+            CssPatch::Update {
+                selector,
+                at_rules,
+                layer,
+                properties,
+                removed,
+            } => patches.push(VDocPatch {
+                patch_type: Some(v_doc_patch::PatchType::UpdateStyleRule(
+                    UpdateStyleRulePatch {
+                        selector,
+                        media_query: legacy_media_query(&at_rules),
+                        properties: properties.into_iter().collect(),
+                        removed_properties: removed,
+                        at_rules: convert_at_rules_to_proto(&at_rules),
+                        layer: Some(layer.to_string()),
+                    },
+                )),
+            }),
+            CssPatch::Remove { .. } => {}
         }
     }
 
     patches
 }
 
+/// Convert a style rule to its protobuf form
+pub fn convert_css_rule_to_proto(rule: &crate::vdom::CssRule) -> proto_vdom::CssRule {
+    #[allow(deprecated)]
+    proto_vdom::CssRule {
+        selector: rule.selector.clone(),
+        properties: rule.properties.clone().into_iter().collect(),
+        media_query: legacy_media_query(&rule.at_rules),
+        metadata: None,
+        layer: Some(rule.layer.to_string()),
+        at_rules: convert_at_rules_to_proto(&rule.at_rules),
+    }
+}
+
+/// Convert an at-rule stack to its protobuf form (outermost first)
+pub fn convert_at_rules_to_proto(at_rules: &[AtRule]) -> Vec<proto_vdom::AtRule> {
+    at_rules
+        .iter()
+        .map(|at_rule| proto_vdom::AtRule {
+            name: at_rule.name.clone(),
+            prelude: at_rule.prelude.clone(),
+        })
+        .collect()
+}
+
+/// Convert root designer metadata to its protobuf form
+pub fn convert_root_metadata_to_proto(meta: &RootMetadata) -> proto_vdom::RootMetadata {
+    proto_vdom::RootMetadata {
+        component: meta.component.clone(),
+        frame: meta.frame.as_ref().map(|frame| proto_vdom::FrameMetadata {
+            x: frame.x,
+            y: frame.y,
+            width: frame.width,
+            height: frame.height,
+        }),
+        annotations: meta.annotations.iter().map(convert_annotation_to_proto).collect(),
+    }
+}
+
+/// Convert an annotation to its protobuf form
+pub fn convert_annotation_to_proto(annotation: &AnnotationMetadata) -> proto_vdom::AnnotationMetadata {
+    proto_vdom::AnnotationMetadata {
+        name: annotation.name.clone(),
+        params: annotation
+            .params
+            .iter()
+            .map(|(key, value)| (key.clone(), convert_json_to_proto_value(value)))
+            .collect(),
+    }
+}
+
+// Convert JSON Value to proto Value
+fn convert_json_to_proto_value(value: &serde_json::Value) -> proto_vdom::Value {
+    match value {
+        serde_json::Value::Null => proto_vdom::Value {
+            kind: Some(proto_vdom::value::Kind::NullValue(proto_vdom::NullValue::NullValue as i32)),
+        },
+        serde_json::Value::Bool(b) => proto_vdom::Value {
+            kind: Some(proto_vdom::value::Kind::BoolValue(*b)),
+        },
+        serde_json::Value::Number(n) => proto_vdom::Value {
+            kind: Some(proto_vdom::value::Kind::NumberValue(n.as_f64().unwrap_or(0.0))),
+        },
+        serde_json::Value::String(s) => proto_vdom::Value {
+            kind: Some(proto_vdom::value::Kind::StringValue(s.clone())),
+        },
+        serde_json::Value::Array(arr) => proto_vdom::Value {
+            kind: Some(proto_vdom::value::Kind::ListValue(proto_vdom::ListValue {
+                values: arr.iter().map(convert_json_to_proto_value).collect(),
+            })),
+        },
+        serde_json::Value::Object(obj) => proto_vdom::Value {
+            kind: Some(proto_vdom::value::Kind::ObjectValue(proto_vdom::ObjectValue {
+                fields: obj.iter().map(|(k, v)| (k.clone(), convert_json_to_proto_value(v))).collect(),
+            })),
+        },
+    }
+}
+
 // Convert internal VNode to protobuf VNode
 fn convert_vnode_to_proto(vnode: &VNode) -> proto_vdom::VNode {
     match vnode {
@@ -418,6 +721,29 @@ fn convert_vnode_to_proto(vnode: &VNode) -> proto_vdom::VNode {
                 },
             )),
         },
+        VNode::LiveComponent {
+            src,
+            export_name,
+            target,
+            props,
+            children,
+            semantic_id,
+            source_id,
+            key,
+        } => proto_vdom::VNode {
+            node_type: Some(proto_vdom::v_node::NodeType::LiveComponent(
+                proto_vdom::LiveComponentNode {
+                    semantic_id: semantic_id.to_selector(),
+                    src: src.clone(),
+                    export_name: export_name.clone(),
+                    target: target.clone(),
+                    props_json: props.clone(),
+                    children: children.iter().map(convert_vnode_to_proto).collect(),
+                    source_id: source_id.clone(),
+                    key: key.clone(),
+                },
+            )),
+        },
         VNode::Text { content } => proto_vdom::VNode {
             node_type: Some(proto_vdom::v_node::NodeType::Text(proto_vdom::TextNode {
                 content: content.clone(),
@@ -452,16 +778,17 @@ fn convert_vnode_to_proto(vnode: &VNode) -> proto_vdom::VNode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vdom::FrameMetadata;
     use std::collections::HashMap;
 
     #[test]
     fn test_diff_create_node() {
-        let old = VirtualDomDocument { components: vec![],
+        let old = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![],
             styles: vec![],
         };
 
-        let new = VirtualDomDocument { components: vec![],
+        let new = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "div".to_string(),
                 attributes: HashMap::new(),
@@ -489,7 +816,7 @@ mod tests {
 
     #[test]
     fn test_diff_remove_node() {
-        let old = VirtualDomDocument { components: vec![],
+        let old = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "div".to_string(),
                 attributes: HashMap::new(),
@@ -502,7 +829,7 @@ mod tests {
             styles: vec![],
         };
 
-        let new = VirtualDomDocument { components: vec![],
+        let new = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![],
             styles: vec![],
         };
@@ -520,14 +847,14 @@ mod tests {
 
     #[test]
     fn test_diff_update_text() {
-        let old = VirtualDomDocument { components: vec![],
+        let old = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Text {
                 content: "old".to_string(),
             }],
             styles: vec![],
         };
 
-        let new = VirtualDomDocument { components: vec![],
+        let new = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Text {
                 content: "new".to_string(),
             }],
@@ -564,7 +891,7 @@ mod tests {
         }]);
 
         // Old: [elem1, elem2]
-        let old = VirtualDomDocument { components: vec![],
+        let old = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![
                 VNode::Element {
                     tag: "div".to_string(),
@@ -597,7 +924,7 @@ mod tests {
         };
 
         // New: [elem2, elem1] - reordered!
-        let new = VirtualDomDocument { components: vec![],
+        let new = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![
                 VNode::Element {
                     tag: "div".to_string(),
@@ -631,13 +958,19 @@ mod tests {
 
         let patches = diff_vdocument(&old, &new);
 
-        // Should have NO patches - nodes matched by semantic ID, not position
-        // Even though positions changed, the nodes themselves are identical
-        assert_eq!(
-            patches.len(),
-            0,
-            "Reordering nodes with same semantic IDs should produce no patches"
-        );
+        // Nodes are matched by semantic ID, so a reorder is a single move
+        // rather than updates in place
+        assert_eq!(patches.len(), 1, "Expected a single MoveChild: {:?}", patches);
+
+        match &patches[0].patch_type {
+            Some(v_doc_patch::PatchType::MoveChild(patch)) => {
+                assert_eq!(patch.child_semantic_id, elem2_id.to_selector());
+                assert_eq!(patch.from_index, 1);
+                assert_eq!(patch.to_index, 0);
+                assert_eq!(patch.parent, Some(positional_path(vec![])));
+            }
+            _ => panic!("Expected MoveChild patch"),
+        }
     }
 
     #[test]
@@ -648,7 +981,7 @@ mod tests {
         let mut new_attrs = HashMap::new();
         new_attrs.insert("class".to_string(), "new-class".to_string());
 
-        let old = VirtualDomDocument { components: vec![],
+        let old = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "div".to_string(),
                 attributes: old_attrs,
@@ -661,7 +994,7 @@ mod tests {
             styles: vec![],
         };
 
-        let new = VirtualDomDocument { components: vec![],
+        let new = VirtualDomDocument { components: vec![], roots: vec![],
             nodes: vec![VNode::Element {
                 tag: "div".to_string(),
                 attributes: new_attrs,
@@ -685,4 +1018,212 @@ mod tests {
             _ => panic!("Expected UpdateAttributes patch"),
         }
     }
+
+    fn keyed_item(key: &str, children: Vec<VNode>) -> VNode {
+        use paperclip_semantics::SemanticSegment;
+
+        VNode::Element {
+            tag: "li".to_string(),
+            attributes: HashMap::new(),
+            styles: HashMap::new(),
+            children,
+            semantic_id: SemanticID::new(vec![SemanticSegment::RepeatItem {
+                repeat_id: "list".to_string(),
+                key: key.to_string(),
+            }]),
+            source_id: None,
+            key: Some(key.to_string()),
+        }
+    }
+
+    fn list(keys: &[&str]) -> VirtualDomDocument {
+        VirtualDomDocument {
+            components: vec![],
+            roots: vec![],
+            nodes: vec![VNode::Element {
+                tag: "ul".to_string(),
+                attributes: HashMap::new(),
+                styles: HashMap::new(),
+                children: keys.iter().map(|key| keyed_item(key, vec![])).collect(),
+                semantic_id: SemanticID::root(),
+                source_id: None,
+                key: None,
+            }],
+            styles: vec![],
+        }
+    }
+
+    /// Apply structural patches to a list of keys, the way a client would
+    fn apply_to_keys(keys: &[&str], patches: &[VDocPatch]) -> Vec<String> {
+        let mut keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        for patch in patches {
+            match &patch.patch_type {
+                Some(v_doc_patch::PatchType::RemoveNode(remove)) => {
+                    keys.remove(remove.path[1] as usize);
+                }
+                Some(v_doc_patch::PatchType::MoveChild(patch)) => {
+                    let key = keys.remove(patch.from_index as usize);
+                    keys.insert(patch.to_index as usize, key);
+                }
+                Some(v_doc_patch::PatchType::CreateNode(create)) => {
+                    let Some(proto_vdom::v_node::NodeType::Element(element)) =
+                        create.node.as_ref().and_then(|node| node.node_type.as_ref())
+                    else {
+                        panic!("Expected element");
+                    };
+                    keys.insert(create.index as usize, element.key.clone().unwrap());
+                }
+                other => panic!("Unexpected patch {:?}", other),
+            }
+        }
+        keys
+    }
+
+    #[test]
+    fn test_keyed_reorder_emits_minimal_moves() {
+        let patches = diff_vdocument(&list(&["a", "b", "c", "d"]), &list(&["b", "c", "d", "a"]));
+
+        assert_eq!(patches.len(), 1, "Rotating a list needs one move: {:?}", patches);
+        match &patches[0].patch_type {
+            Some(v_doc_patch::PatchType::MoveChild(patch)) => {
+                assert_eq!(patch.path, vec![0]);
+                assert_eq!(patch.parent, Some(semantic_path(SemanticID::root().to_selector())));
+                assert_eq!((patch.from_index, patch.to_index), (0, 3));
+            }
+            _ => panic!("Expected MoveChild patch"),
+        }
+
+        let patches = diff_vdocument(&list(&["a", "b", "c", "d"]), &list(&["d", "c", "b", "a"]));
+        assert_eq!(patches.len(), 3, "Reversing four items needs three moves");
+    }
+
+    #[test]
+    fn test_keyed_patches_apply_in_order() {
+        let cases: [(&[&str], &[&str]); 4] = [
+            (&["a", "b", "c", "d", "e"], &["e", "x", "c", "a", "y"]),
+            (&["a", "b", "c"], &["c", "b", "a", "d"]),
+            (&["a", "b", "c", "d"], &["d", "b"]),
+            (&[], &["a", "b"]),
+        ];
+
+        for (before, after) in cases {
+            let patches = diff_vdocument(&list(before), &list(after));
+            assert_eq!(apply_to_keys(before, &patches), after, "{:?} -> {:?}", before, after);
+        }
+    }
+
+    #[test]
+    fn test_diff_is_deterministic() {
+        let text = |content: &str| VNode::Text {
+            content: content.to_string(),
+        };
+        let old = list(&["a", "b", "c", "d", "e", "f"]);
+        let mut new = list(&["f", "c", "g", "a", "e", "h"]);
+        if let VNode::Element { children, .. } = &mut new.nodes[0] {
+            children[1] = keyed_item("c", vec![text("changed")]);
+            children.push(text("footer"));
+        }
+
+        let first = diff_vdocument(&old, &new);
+        for _ in 0..20 {
+            assert_eq!(diff_vdocument(&old, &new), first);
+        }
+    }
+
+    #[test]
+    fn test_patch_targets_use_semantic_ids() {
+        let old = list(&["a"]);
+        let mut new = list(&["a"]);
+        if let VNode::Element { children, .. } = &mut new.nodes[0] {
+            children[0] = keyed_item(
+                "a",
+                vec![VNode::Text {
+                    content: "hi".to_string(),
+                }],
+            );
+        }
+
+        let patches = diff_vdocument(&old, &new);
+        assert_eq!(patches.len(), 1);
+        match &patches[0].patch_type {
+            Some(v_doc_patch::PatchType::CreateNode(create)) => {
+                assert_eq!(create.path, vec![0, 0]);
+                assert_eq!(
+                    create.parent,
+                    Some(semantic_path(r#"repeat[list]{"a"}"#.to_string()))
+                );
+            }
+            _ => panic!("Expected CreateNode patch"),
+        }
+    }
+
+    #[test]
+    fn test_moving_frame_sends_one_update_frame() {
+        let framed = |x: f64| {
+            let mut vdom = list(&["a", "b"]);
+            vdom.roots = vec![RootMetadata {
+                component: Some("List".to_string()),
+                frame: Some(FrameMetadata {
+                    x,
+                    y: 0.0,
+                    width: Some(400.0),
+                    height: None,
+                }),
+                annotations: vec![],
+            }];
+            vdom
+        };
+
+        assert!(diff_vdocument(&framed(0.0), &framed(0.0)).is_empty());
+
+        let patches = diff_vdocument(&framed(0.0), &framed(120.0));
+        assert_eq!(patches.len(), 1, "Expected only UpdateFrame, got {:?}", patches);
+        match &patches[0].patch_type {
+            Some(v_doc_patch::PatchType::UpdateFrame(update)) => {
+                assert_eq!(update.index, 0);
+                assert_eq!(update.semantic_id, SemanticID::root().to_selector());
+                let metadata = update.metadata.as_ref().unwrap();
+                assert_eq!(metadata.component.as_deref(), Some("List"));
+                let frame = metadata.frame.as_ref().unwrap();
+                assert_eq!((frame.x, frame.width, frame.height), (120.0, Some(400.0), None));
+            }
+            other => panic!("Expected UpdateFrame patch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_live_component_props_update_in_place() {
+        let live = |props: &str, label: &str| VirtualDomDocument {
+            components: vec![],
+            roots: vec![],
+            nodes: vec![VNode::LiveComponent {
+                src: "./counter.tsx".to_string(),
+                export_name: "Counter".to_string(),
+                target: "react".to_string(),
+                props: props.to_string(),
+                children: vec![VNode::text(label)],
+                semantic_id: SemanticID::root(),
+                source_id: None,
+                key: None,
+            }],
+            styles: vec![],
+        };
+
+        assert!(diff_vdocument(&live("{}", "0"), &live("{}", "0")).is_empty());
+
+        let patches = diff_vdocument(&live(r#"{"count":0}"#, "0"), &live(r#"{"count":1}"#, "1"));
+        assert_eq!(patches.len(), 2, "Unexpected patches: {:?}", patches);
+        match &patches[0].patch_type {
+            Some(v_doc_patch::PatchType::UpdateLiveProps(update)) => {
+                assert_eq!(update.path, vec![0]);
+                assert_eq!(update.props_json, r#"{"count":1}"#);
+            }
+            other => panic!("Expected UpdateLiveProps patch, got {:?}", other),
+        }
+        // The fallback is diffed like any other subtree
+        assert!(matches!(
+            &patches[1].patch_type,
+            Some(v_doc_patch::PatchType::UpdateText(update)) if update.path == vec![0, 0]
+        ));
+    }
 }
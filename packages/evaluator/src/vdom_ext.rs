@@ -20,6 +20,8 @@
 //! }
 //! ```
 
+use crate::css_at_rules::{legacy_media_query, AtRule};
+use crate::vdom_differ::convert_at_rules_to_proto;
 use crate::vdom_differ::proto::vdom as proto;
 use std::collections::HashMap;
 
@@ -435,6 +437,7 @@ impl VDocumentExt for proto::VDocument {
             styles: Vec::new(),
             components: Vec::new(),
             metadata: None,
+            roots: Vec::new(),
         }
     }
 
@@ -465,15 +468,20 @@ pub trait CssRuleExt {
         properties: HashMap<String, String>,
         media_query: impl Into<String>,
     ) -> proto::CssRule;
+    /// Nest the rule in at-rules (outermost first)
+    fn with_at_rules(self, at_rules: &[AtRule]) -> proto::CssRule;
 }
 
 impl CssRuleExt for proto::CssRule {
     fn new(selector: impl Into<String>, properties: HashMap<String, String>) -> proto::CssRule {
+        #[allow(deprecated)]
         proto::CssRule {
             selector: selector.into(),
             properties,
             media_query: None,
             metadata: None,
+            layer: None,
+            at_rules: vec![],
         }
     }
 
@@ -482,12 +490,19 @@ impl CssRuleExt for proto::CssRule {
         properties: HashMap<String, String>,
         media_query: impl Into<String>,
     ) -> proto::CssRule {
-        proto::CssRule {
-            selector: selector.into(),
-            properties,
-            media_query: Some(media_query.into()),
-            metadata: None,
+        // Accept `@media screen` as well as a bare `screen`
+        let media_query = media_query.into();
+        let at_rule = AtRule::parse(&media_query).unwrap_or_else(|| AtRule::media(media_query));
+        proto::CssRule::new(selector, properties).with_at_rules(&[at_rule])
+    }
+
+    fn with_at_rules(mut self, at_rules: &[AtRule]) -> proto::CssRule {
+        #[allow(deprecated)]
+        {
+            self.media_query = legacy_media_query(at_rules);
         }
+        self.at_rules = convert_at_rules_to_proto(at_rules);
+        self
     }
 }
 
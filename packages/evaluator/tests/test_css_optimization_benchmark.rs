@@ -89,7 +89,7 @@ fn test_optimization_reduces_rules() {
     println!("Total nodes: {}", vdom.nodes.len());
 
     // Count media query rules vs regular rules
-    let media_rules = vdom.styles.iter().filter(|r| r.media_query.is_some()).count();
+    let media_rules = vdom.styles.iter().filter(|r| !r.at_rules.is_empty()).count();
     let regular_rules = vdom.styles.len() - media_rules;
 
     println!("Regular rules: {}", regular_rules);
@@ -112,8 +112,8 @@ fn test_optimization_reduces_rules() {
             style.selector,
             style.properties.len()
         );
-        if let Some(ref mq) = style.media_query {
-            println!("    {}", mq);
+        for at_rule in &style.at_rules {
+            println!("    {}", at_rule);
         }
     }
 
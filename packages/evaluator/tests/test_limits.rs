@@ -0,0 +1,150 @@
+//! Test evaluation resource limits and cancellation
+
+use paperclip_evaluator::evaluator::{EvalError, Evaluator, Value};
+use paperclip_evaluator::{CancellationToken, EvalLimits, VNode};
+use paperclip_parser::parse_with_path;
+
+fn find_error(node: &VNode) -> Option<&str> {
+    match node {
+        VNode::Error { message, .. } => Some(message),
+        VNode::Element { children, .. } => children.iter().find_map(find_error),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_repeat_items_limit() {
+    let source = r#"
+        public component List {
+            render ul {
+                repeat item in items {
+                    li {
+                        text item
+                    }
+                }
+            }
+        }
+    "#;
+
+    let doc = parse_with_path(source, "/test.pc").unwrap();
+    let mut evaluator = Evaluator::with_document_id("/test.pc").with_limits(EvalLimits {
+        max_repeat_items: 10,
+        ..EvalLimits::default()
+    });
+    let items = (0..11).map(|i| Value::Number(i as f64)).collect();
+    evaluator
+        .context
+        .set_variable("items".to_string(), Value::Array(items));
+
+    let vdom = evaluator.evaluate(&doc).unwrap();
+
+    let message = find_error(&vdom.nodes[0]).expect("Expected a limit error node");
+    assert_eq!(message, "Repeat over 11 items exceeds the limit of 10");
+}
+
+#[test]
+fn test_depth_limit() {
+    let source = r#"
+        public component Deep {
+            render div {
+                div {
+                    div {
+                        div {
+                            text "too deep"
+                        }
+                    }
+                }
+            }
+        }
+    "#;
+
+    let doc = parse_with_path(source, "/test.pc").unwrap();
+    let mut evaluator = Evaluator::with_document_id("/test.pc").with_limits(EvalLimits {
+        max_depth: 3,
+        ..EvalLimits::default()
+    });
+
+    let vdom = evaluator.evaluate(&doc).unwrap();
+
+    let message = find_error(&vdom.nodes[0]).expect("Expected a limit error node");
+    assert_eq!(message, "Maximum nesting depth (3) exceeded");
+}
+
+#[test]
+fn test_node_limit_spans_component_instances() {
+    let source = r#"
+        component Item {
+            render li {
+                text "item"
+            }
+        }
+
+        public component List {
+            render ul {
+                Item()
+                Item()
+                Item()
+            }
+        }
+    "#;
+
+    let doc = parse_with_path(source, "/test.pc").unwrap();
+
+    // Each Item costs 3 nodes (instance, li, text), so nodes inside the scoped
+    // evaluators of the instances must count toward the same budget
+    let mut evaluator = Evaluator::with_document_id("/test.pc").with_limits(EvalLimits {
+        max_nodes: 8,
+        ..EvalLimits::default()
+    });
+
+    let vdom = evaluator.evaluate(&doc).unwrap();
+
+    let list = vdom
+        .nodes
+        .iter()
+        .find(|node| matches!(node, VNode::Element { tag, .. } if tag == "ul"))
+        .expect("Expected List to render");
+    let message = find_error(list).expect("Expected a limit error node");
+    assert_eq!(message, "Maximum node count (8) exceeded");
+}
+
+#[test]
+fn test_output_bytes_limit() {
+    let source = r#"
+        public component Big {
+            render div {
+                text "0123456789012345678901234567890123456789"
+            }
+        }
+    "#;
+
+    let doc = parse_with_path(source, "/test.pc").unwrap();
+    let mut evaluator = Evaluator::with_document_id("/test.pc").with_limits(EvalLimits {
+        max_output_bytes: 16,
+        ..EvalLimits::default()
+    });
+
+    let vdom = evaluator.evaluate(&doc).unwrap();
+
+    let message = find_error(&vdom.nodes[0]).expect("Expected a limit error node");
+    assert_eq!(message, "Maximum output size (16 bytes) exceeded");
+}
+
+#[test]
+fn test_cancelled_evaluation() {
+    let source = r#"
+        public component Card {
+            render div {
+                text "Hello"
+            }
+        }
+    "#;
+
+    let doc = parse_with_path(source, "/test.pc").unwrap();
+    let token = CancellationToken::new();
+    let mut evaluator = Evaluator::with_document_id("/test.pc").with_cancellation(token.clone());
+
+    token.cancel();
+
+    assert!(matches!(evaluator.evaluate(&doc), Err(EvalError::Cancelled)));
+}
@@ -1,6 +1,7 @@
 //! Test recursive component rendering
 
 use paperclip_evaluator::evaluator::{Evaluator, EvalError, Value};
+use paperclip_evaluator::VNode;
 use paperclip_parser::parse_with_path;
 use std::collections::HashMap;
 
@@ -88,7 +89,7 @@ fn test_conditional_recursion_without_data_change() {
     "#;
 
     let doc = parse_with_path(source, "/test.pc").unwrap();
-    let mut evaluator = Evaluator::with_document_id("/test.pc");
+    let mut evaluator = Evaluator::with_document_id("/test.pc").with_max_recursion_depth(4);
 
     // Set initial count
     evaluator.context.set_variable(
@@ -96,19 +97,62 @@ fn test_conditional_recursion_without_data_change() {
         paperclip_evaluator::evaluator::Value::Number(3.0)
     );
 
-    // Should detect recursion even though it's conditional
-    // (count never changes, so it recurses infinitely)
-    let result = evaluator.evaluate(&doc);
-
-    match result {
-        Ok(_) => panic!("Expected RecursiveComponent error but got Ok result"),
-        Err(EvalError::RecursiveComponent { component, call_stack, .. }) => {
-            assert_eq!(component, "Countdown");
-            assert_eq!(call_stack, vec!["Countdown", "Countdown"]);
-            println!("✓ Conditional recursion without data change detected correctly");
+    // count never changes, so the guarded recursion runs until the depth cap,
+    // which renders an error node instead of failing the document
+    let vdom = evaluator
+        .evaluate(&doc)
+        .expect("Guarded recursion should not fail the document");
+
+    let mut depth = 0;
+    let mut node = &vdom.nodes[0];
+    loop {
+        match node {
+            VNode::Element { children, .. } => {
+                depth += 1;
+                node = children.last().expect("Expected nested instance");
+            }
+            VNode::Error { message, .. } => {
+                assert!(message.contains("Maximum recursion depth (4)"));
+                assert!(message.contains("Countdown"));
+                break;
+            }
+            other => panic!("Unexpected node: {:?}", other),
         }
-        Err(e) => panic!("Expected RecursiveComponent error but got: {:?}", e),
     }
+    assert_eq!(depth, 4);
+}
+
+#[test]
+fn test_unguarded_cycle_through_guarded_component_is_allowed() {
+    // A renders B unconditionally, B renders A under a conditional:
+    // the cycle is guarded, so it terminates on data
+    let source = r#"
+        component A {
+            render div {
+                B()
+            }
+        }
+
+        component B {
+            render div {
+                if show {
+                    A()
+                }
+            }
+        }
+
+        public component App {
+            render A()
+        }
+    "#;
+
+    let doc = parse_with_path(source, "/test.pc").unwrap();
+    let mut evaluator = Evaluator::with_document_id("/test.pc");
+    evaluator
+        .context
+        .set_variable("show".to_string(), Value::Boolean(false));
+
+    assert!(evaluator.evaluate(&doc).is_ok());
 }
 
 #[test]
@@ -210,16 +254,9 @@ fn test_non_recursive_component_reuse() {
 }
 
 #[test]
-#[ignore = "Valid recursion with data-dependent termination - future enhancement"]
 fn test_valid_tree_recursion_with_props() {
-    // This test demonstrates the VALID pattern that should be allowed
-    // TreeNode(node=child) where each child is structurally smaller
-    //
-    // Current implementation will still catch this as a cycle because
-    // we check component name equality, not data equality.
-    //
-    // Future enhancement: Track prop changes and allow recursion if
-    // props are demonstrably different (e.g., array is smaller)
+    // TreeNode(node=child) sits under a repeat over data, so recursion is
+    // bounded by the depth of the tree
 
     let source = r#"
         component TreeNode {
@@ -265,22 +302,22 @@ fn test_valid_tree_recursion_with_props() {
 
     evaluator.context.set_variable("root".to_string(), root);
 
-    // This SHOULD work because:
-    // 1. Each TreeNode call has different node prop
-    // 2. Recursion depth is bounded by tree depth
-    // 3. Leaf nodes have no children, terminating recursion
-    //
-    // But current implementation will detect cycle: TreeNode → TreeNode
+    let vdom = evaluator.evaluate(&doc).expect("Tree recursion should evaluate");
 
-    let result = evaluator.evaluate(&doc);
+    // App is the second root: div(text "Root", repeat wrapper(div(Leaf 1), div(Leaf 2)))
+    let mut labels = Vec::new();
+    collect_text(&vdom.nodes[1], &mut labels);
+    assert_eq!(labels, vec!["Root", "Leaf 1", "Leaf 2"]);
+}
 
-    // Currently fails with RecursiveComponent error
-    // Future: Should succeed and render tree
-    match result {
-        Ok(_) => println!("✓ Valid tree recursion works!"),
-        Err(EvalError::RecursiveComponent { .. }) => {
-            println!("⚠ Valid tree recursion blocked by cycle detection (expected for now)");
+fn collect_text(node: &VNode, out: &mut Vec<String>) {
+    match node {
+        VNode::Text { content } => out.push(content.clone()),
+        VNode::Element { children, .. } => {
+            for child in children {
+                collect_text(child, out);
+            }
         }
-        Err(e) => panic!("Unexpected error: {:?}", e),
+        _ => {}
     }
 }
@@ -55,8 +55,8 @@ fn test_trigger_reference() {
     println!("\nVDOM styles ({} total):", vdom.styles.len());
     for (i, style) in vdom.styles.iter().enumerate() {
         println!("  [{}] selector: {}", i, style.selector);
-        if let Some(ref mq) = style.media_query {
-            println!("      media: {}", mq);
+        for at_rule in &style.at_rules {
+            println!("      at-rule: {}", at_rule);
         }
         println!("      properties: {:?}", style.properties);
     }
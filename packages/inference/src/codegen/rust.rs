@@ -75,6 +75,11 @@ impl CodeGenerator for RustGenerator {
                 // Could generate a struct, but for now use serde_json::Value
                 "serde_json::Value /* Object */".to_string()
             }
+
+            Type::PropRef { .. } => {
+                // Recursive references would need boxing in a generated struct
+                "serde_json::Value /* PropRef */".to_string()
+            }
         }
     }
 
@@ -45,7 +45,10 @@ impl TypeScriptGenerator {
             .params
             .iter()
             .enumerate()
-            .map(|(i, t)| format!("arg{}: {}", i, self.generate_type(t)))
+            .map(|(i, t)| match func.param_names.get(i) {
+                Some(name) => format!("{}: {}", name, self.generate_type(t)),
+                None => format!("arg{}: {}", i, self.generate_type(t)),
+            })
             .collect();
 
         format!(
@@ -106,6 +109,8 @@ impl CodeGenerator for TypeScriptGenerator {
             Type::Element(elem) => self.generate_element_type(elem),
 
             Type::Object(obj) => self.generate_object_type(obj),
+
+            Type::PropRef { component, prop } => format!("{}Props[\"{}\"]", component, prop),
         }
     }
 
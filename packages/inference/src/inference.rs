@@ -1,9 +1,9 @@
 use crate::error::InferenceResult;
 use crate::options::InferenceOptions;
 use crate::scope::Scope;
-use crate::types::{LiteralType, ObjectType, PropertyType, Type};
+use crate::types::{FunctionType, LiteralType, ObjectType, PropertyType, Type};
 use paperclip_parser::ast::*;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 /// Multi-pass type inference engine for Paperclip components
@@ -33,10 +33,259 @@ impl InferenceEngine {
             self.infer_from_element(body, &mut scope)?;
         }
 
-        // Pass 3: Convert scope to props and finalize types
+        // Pass 3: Type data that recursive components pass back into themselves
+        if let Some(body) = &component.body {
+            let mut items = HashMap::new();
+            self.infer_self_references(&component.name, body, &mut items, &mut scope);
+        }
+
+        // Pass 4: Type scoped slot parameters from their render sites
+        self.infer_scoped_slots(component, &mut scope)?;
+
+        // Pass 5: Convert scope to props and finalize types
         Ok(self.scope_to_props(&scope))
     }
 
+    /// Pass 3: Find self-instances (`TreeNode(node=child)`) and give the data they're
+    /// passed a reference to the prop type, so `node.children` becomes an array of `node`.
+    /// `items` maps repeat item names to the path of their collection.
+    fn infer_self_references(
+        &self,
+        component_name: &str,
+        element: &Element,
+        items: &mut HashMap<String, Vec<String>>,
+        scope: &mut Scope,
+    ) {
+        match element {
+            Element::Instance {
+                name,
+                props,
+                children,
+                ..
+            } => {
+                if name == component_name {
+                    for (prop, expr) in props {
+                        let Some(path) = Self::member_path(expr, items) else {
+                            continue;
+                        };
+
+                        // Passing a prop straight through doesn't tell us anything
+                        if path.len() < 2 {
+                            continue;
+                        }
+
+                        if let Some(root) = scope.get_local_mut(&path[0]) {
+                            root.set_path(
+                                &path[1..],
+                                Type::PropRef {
+                                    component: component_name.to_string(),
+                                    prop: prop.clone(),
+                                },
+                            );
+                        }
+                    }
+                }
+
+                for child in children {
+                    self.infer_self_references(component_name, child, items, scope);
+                }
+            }
+
+            Element::Tag { children, .. } => {
+                for child in children {
+                    self.infer_self_references(component_name, child, items, scope);
+                }
+            }
+
+            Element::Conditional {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                for child in then_branch.iter().chain(else_branch.iter().flatten()) {
+                    self.infer_self_references(component_name, child, items, scope);
+                }
+            }
+
+            Element::Repeat {
+                item_name,
+                collection,
+                body,
+                ..
+            } => {
+                let shadowed = items.remove(item_name);
+                if let Some(mut path) = Self::member_path(collection, items) {
+                    path.push("[]".to_string());
+                    items.insert(item_name.clone(), path);
+                }
+
+                for child in body {
+                    self.infer_self_references(component_name, child, items, scope);
+                }
+
+                items.remove(item_name);
+                if let Some(path) = shadowed {
+                    items.insert(item_name.clone(), path);
+                }
+            }
+
+            Element::Insert { content, .. } => {
+                for child in content {
+                    self.infer_self_references(component_name, child, items, scope);
+                }
+            }
+
+            Element::Text { .. } | Element::SlotInsert { .. } => {}
+        }
+    }
+
+    /// Pass 4: A scoped slot (`slot row(item)`) is a render prop. Each parameter
+    /// gets the shape its default content uses, unified with the prop it's read
+    /// from where the slot is rendered (usually a repeat item). That shape flows
+    /// back into the prop, so `items` becomes an array of what `row` expects.
+    fn infer_scoped_slots(&self, component: &Component, scope: &mut Scope) -> InferenceResult<()> {
+        let Some(body) = &component.body else {
+            return Ok(());
+        };
+
+        for slot in component.slots.iter().filter(|slot| slot.is_scoped()) {
+            let mut slot_scope = Scope::with_parent(Rc::new(scope.clone()));
+            for param in &slot.params {
+                slot_scope.bind(param.clone(), Type::Unknown);
+            }
+            for child in &slot.default_content {
+                self.infer_from_element(child, &mut slot_scope)?;
+            }
+            let mut params: Vec<Type> = slot
+                .params
+                .iter()
+                .map(|param| slot_scope.lookup(param).unwrap_or(Type::Unknown))
+                .collect();
+
+            let mut sites = Vec::new();
+            Self::find_slot_sites(&slot.name, body, &mut HashMap::new(), &mut sites);
+
+            for items in &sites {
+                for (param, param_type) in slot.params.iter().zip(params.iter_mut()) {
+                    let path = items
+                        .get(param)
+                        .cloned()
+                        .unwrap_or_else(|| vec![param.clone()]);
+                    if !scope.contains(&path[0]) {
+                        scope.bind(path[0].clone(), Type::Unknown);
+                    }
+                    let Some(root) = scope.get_local_mut(&path[0]) else {
+                        continue;
+                    };
+
+                    if let Some(existing) = root.get_path(&path[1..]) {
+                        *param_type = Type::unify(param_type, existing);
+                    }
+                    if !matches!(param_type, Type::Unknown | Type::Any) {
+                        root.set_path(&path[1..], param_type.clone());
+                    }
+                }
+            }
+
+            let render_prop = Type::Function(FunctionType {
+                params,
+                param_names: slot.params.clone(),
+                return_type: Box::new(Type::Slot),
+            });
+            if let Some(slot_type) = scope.get_local_mut(&slot.name) {
+                *slot_type = if slot.default_content.is_empty() {
+                    render_prop
+                } else {
+                    Type::Optional(Box::new(render_prop))
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collect the repeat items in scope at each place `slot_name` is rendered
+    fn find_slot_sites(
+        slot_name: &str,
+        element: &Element,
+        items: &mut HashMap<String, Vec<String>>,
+        sites: &mut Vec<HashMap<String, Vec<String>>>,
+    ) {
+        match element {
+            Element::SlotInsert { name, .. } => {
+                if name == slot_name {
+                    sites.push(items.clone());
+                }
+            }
+
+            Element::Tag { children, .. } | Element::Instance { children, .. } => {
+                for child in children {
+                    Self::find_slot_sites(slot_name, child, items, sites);
+                }
+            }
+
+            Element::Conditional {
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                for child in then_branch.iter().chain(else_branch.iter().flatten()) {
+                    Self::find_slot_sites(slot_name, child, items, sites);
+                }
+            }
+
+            Element::Repeat {
+                item_name,
+                collection,
+                body,
+                ..
+            } => {
+                let shadowed = items.remove(item_name);
+                if let Some(mut path) = Self::member_path(collection, items) {
+                    path.push("[]".to_string());
+                    items.insert(item_name.clone(), path);
+                }
+
+                for child in body {
+                    Self::find_slot_sites(slot_name, child, items, sites);
+                }
+
+                items.remove(item_name);
+                if let Some(path) = shadowed {
+                    items.insert(item_name.clone(), path);
+                }
+            }
+
+            Element::Insert { content, .. } => {
+                for child in content {
+                    Self::find_slot_sites(slot_name, child, items, sites);
+                }
+            }
+
+            Element::Text { .. } => {}
+        }
+    }
+
+    /// Resolve `a.b.c` (through repeat items) to a path rooted at a prop
+    fn member_path(expr: &Expression, items: &HashMap<String, Vec<String>>) -> Option<Vec<String>> {
+        match expr {
+            Expression::Variable { name, .. } => Some(
+                items
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| vec![name.clone()]),
+            ),
+            Expression::Member {
+                object, property, ..
+            } => {
+                let mut path = Self::member_path(object, items)?;
+                path.push(property.clone());
+                Some(path)
+            }
+            _ => None,
+        }
+    }
+
     /// Pass 1: Collect explicit component signature (variants and slots)
     fn collect_component_signature(&self, component: &Component, scope: &mut Scope) {
         // Variants → Boolean props (always optional)
@@ -502,14 +751,14 @@ impl InferenceEngine {
 
             // Determine if optional
             // - Boolean (variants) are always optional
-            // - Slot wrapped in Optional is optional
+            // - Slot (or scoped slot) wrapped in Optional is optional
             // - Optional types are optional
             // - Everything else is required
             let optional = matches!(finalized_type, Type::Boolean | Type::Optional(_));
 
             // Unwrap Optional for Slot types
             let unwrapped_type = if let Type::Optional(inner) = finalized_type {
-                if matches!(*inner, Type::Slot) {
+                if matches!(*inner, Type::Slot | Type::Function(_)) {
                     *inner
                 } else {
                     Type::Optional(inner)
@@ -634,4 +883,74 @@ public component Card {
         assert!(props.contains_key("header"));
         assert_eq!(props["header"].type_, Type::Slot);
     }
+
+    #[test]
+    fn test_infer_scoped_slot() {
+        let source = r#"
+public component List {
+    slot row(item) {
+        li {
+            text item.name
+        }
+    }
+    render ul {
+        repeat item in items {
+            row
+        }
+    }
+}
+"#;
+
+        let doc = parse(source).unwrap();
+        let engine = InferenceEngine::new(InferenceOptions::default());
+        let props = engine.infer_component_props(&doc.components[0]).unwrap();
+
+        // A scoped slot is a render prop, optional because it has default content
+        assert!(props["row"].optional);
+        let Type::Function(row) = &props["row"].type_ else {
+            panic!("Expected function, got {:?}", props["row"].type_);
+        };
+        assert_eq!(row.param_names, vec!["item"]);
+        assert_eq!(*row.return_type, Type::Slot);
+
+        // The parameter has the shape the slot content uses...
+        let Type::Object(item) = &row.params[0] else {
+            panic!("Expected object param, got {:?}", row.params[0]);
+        };
+        assert!(item.properties.contains_key("name"));
+
+        // ...and the collection it's read from is an array of that shape
+        assert_eq!(props["items"].type_, Type::Array(Box::new(row.params[0].clone())));
+    }
+
+    #[test]
+    fn test_infer_recursive_prop_reference() {
+        let source = r#"
+public component TreeNode {
+    render div {
+        text {node.label}
+        repeat child in node.children {
+            TreeNode(node=child)
+        }
+    }
+}
+"#;
+
+        let doc = parse(source).unwrap();
+        let engine = InferenceEngine::new(InferenceOptions::default());
+        let props = engine.infer_component_props(&doc.components[0]).unwrap();
+
+        let Type::Object(node) = &props["node"].type_ else {
+            panic!("Expected Object type");
+        };
+        assert!(node.properties.contains_key("label"));
+        assert_eq!(
+            node.properties["children"].type_,
+            Type::Array(Box::new(Type::PropRef {
+                component: "TreeNode".to_string(),
+                prop: "node".to_string(),
+            }))
+        );
+        assert!(!props.contains_key("child"));
+    }
 }
@@ -48,6 +48,10 @@ pub enum Type {
 
     /// Object/map type with known properties
     Object(ObjectType),
+
+    /// Reference to a prop of a component, for data a recursive component
+    /// passes back into itself (e.g. `TreeNode(node=child)`)
+    PropRef { component: String, prop: String },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -66,6 +70,9 @@ pub struct PropertyType {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FunctionType {
     pub params: Vec<Type>,
+    /// Parameter names, when known (e.g. scoped slot parameters)
+    #[serde(default)]
+    pub param_names: Vec<String>,
     pub return_type: Box<Type>,
 }
 
@@ -129,6 +136,23 @@ impl Type {
             _ => {}
         }
 
+        // Function unification - same arity unifies parameter by parameter
+        match (t1, t2) {
+            (Function(f1), Function(f2)) if f1.params.len() == f2.params.len() => {
+                return Function(FunctionType {
+                    params: f1
+                        .params
+                        .iter()
+                        .zip(&f2.params)
+                        .map(|(a, b)| Type::unify(a, b))
+                        .collect(),
+                    param_names: f1.param_names.clone(),
+                    return_type: Box::new(Type::unify(&f1.return_type, &f2.return_type)),
+                });
+            }
+            _ => {}
+        }
+
         // Array unification
         match (t1, t2) {
             (Array(inner1), Array(inner2)) => {
@@ -217,6 +241,56 @@ impl Type {
         matches!(self, Type::Boolean | Type::Literal(LiteralType::Boolean(_)))
     }
 
+    /// Type at a property path (see `set_path`), if it has been inferred
+    pub fn get_path(&self, path: &[String]) -> Option<&Type> {
+        let Some((first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+
+        match self {
+            Type::Array(inner) if first == "[]" => inner.get_path(rest),
+            Type::Object(obj) => obj.properties.get(first)?.type_.get_path(rest),
+            _ => None,
+        }
+    }
+
+    /// Replace the type at a property path, creating intermediate objects.
+    /// A `"[]"` step descends into array items. Unlike `unify`, this can
+    /// narrow `Any`.
+    pub fn set_path(&mut self, path: &[String], type_: Type) {
+        let Some((first, rest)) = path.split_first() else {
+            *self = type_;
+            return;
+        };
+
+        if first == "[]" {
+            if !matches!(self, Type::Array(_)) {
+                *self = Type::Array(Box::new(Type::Unknown));
+            }
+            if let Type::Array(inner) = self {
+                inner.set_path(rest, type_);
+            }
+            return;
+        }
+
+        if !matches!(self, Type::Object(_)) {
+            *self = Type::Object(ObjectType {
+                properties: BTreeMap::new(),
+                index_signature: Some(Box::new(Type::Any)),
+            });
+        }
+        if let Type::Object(obj) = self {
+            let prop = obj
+                .properties
+                .entry(first.clone())
+                .or_insert_with(|| PropertyType {
+                    type_: Type::Unknown,
+                    optional: false,
+                });
+            prop.type_.set_path(rest, type_);
+        }
+    }
+
     /// Convert Unknown types to Any (for final output)
     pub fn finalize(self) -> Type {
         match self {
@@ -226,6 +300,11 @@ impl Type {
             }
             Type::Array(inner) => Type::Array(Box::new(inner.finalize())),
             Type::Optional(inner) => Type::Optional(Box::new(inner.finalize())),
+            Type::Function(func) => Type::Function(FunctionType {
+                params: func.params.into_iter().map(|t| t.finalize()).collect(),
+                param_names: func.param_names,
+                return_type: Box::new(func.return_type.finalize()),
+            }),
             Type::Object(obj) => Type::Object(ObjectType {
                 properties: obj
                     .properties
@@ -359,4 +438,28 @@ mod tests {
         assert!(merged.properties.contains_key("name"));
         assert!(merged.properties.contains_key("age"));
     }
+
+    #[test]
+    fn test_set_path_narrows_any() {
+        let mut obj = ObjectType {
+            properties: BTreeMap::new(),
+            index_signature: None,
+        };
+        obj.add_property("children".to_string(), Type::Any, false);
+        let mut node = Type::Object(obj);
+
+        let node_ref = Type::PropRef {
+            component: "TreeNode".to_string(),
+            prop: "node".to_string(),
+        };
+        node.set_path(&["children".to_string(), "[]".to_string()], node_ref.clone());
+
+        let Type::Object(obj) = node else {
+            panic!("Expected object");
+        };
+        assert_eq!(
+            obj.properties["children"].type_,
+            Type::Array(Box::new(node_ref))
+        );
+    }
 }
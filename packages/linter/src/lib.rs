@@ -4,4 +4,4 @@ mod rules;
 
 pub use diagnostic::{Diagnostic, DiagnosticLevel};
 pub use linter::{lint_document, LintOptions};
-pub use rules::{LintRule, RuleRegistry};
+pub use rules::{GlobalStylesRule, LintRule, RuleRegistry, DEFAULT_GLOBAL_STYLE_FILES};
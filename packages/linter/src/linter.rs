@@ -1,17 +1,24 @@
 use crate::diagnostic::Diagnostic;
 use crate::rules::{A11yRule, RuleRegistry};
 use paperclip_parser::ast::{Component, Document, Element};
+use std::path::PathBuf;
 
 /// Options for configuring the linter
 #[derive(Debug)]
 pub struct LintOptions {
     /// Custom rule registry (uses default if None)
     pub registry: Option<RuleRegistry>,
+
+    /// Path of the document being linted (enables path-based rules)
+    pub path: Option<PathBuf>,
 }
 
 impl Default for LintOptions {
     fn default() -> Self {
-        Self { registry: None }
+        Self {
+            registry: None,
+            path: None,
+        }
     }
 }
 
@@ -32,6 +39,11 @@ pub fn lint_document(document: &Document, options: LintOptions) -> Vec<Diagnosti
         diagnostics.extend(lint_component(component, &registry));
     }
 
+    // Document-wide rules
+    for rule in registry.rules() {
+        diagnostics.extend(rule.check_document(document, options.path.as_deref()));
+    }
+
     diagnostics
 }
 
@@ -0,0 +1,148 @@
+use crate::diagnostic::Diagnostic;
+use crate::rules::LintRule;
+use paperclip_parser::ast::Document;
+use std::path::Path;
+
+/// Files allowed to declare global styles when none are configured
+pub const DEFAULT_GLOBAL_STYLE_FILES: &[&str] = &["global.pc", "globals.pc", "reset.pc"];
+
+/// Lint rule that keeps `global style` declarations in designated files.
+///
+/// Global styles leak into every document that imports them, so they should
+/// live in a small number of well-known files (resets, base typography).
+#[derive(Debug, Default)]
+pub struct GlobalStylesRule {
+    /// Path suffixes allowed to declare global styles (e.g. `"reset.pc"`,
+    /// `"styles/base.pc"`). Empty means [`DEFAULT_GLOBAL_STYLE_FILES`].
+    designated_files: Vec<String>,
+}
+
+impl GlobalStylesRule {
+    /// Allow global styles only in `designated_files` (path suffixes)
+    pub fn new(designated_files: Vec<String>) -> Self {
+        Self { designated_files }
+    }
+}
+
+impl LintRule for GlobalStylesRule {
+    fn name(&self) -> &'static str {
+        "global-styles-location"
+    }
+
+    fn description(&self) -> &'static str {
+        "Keep global styles in designated global styles files"
+    }
+
+    fn check_document(&self, document: &Document, path: Option<&Path>) -> Vec<Diagnostic> {
+        // Without a path there's nothing to check the location against
+        let Some(path) = path else {
+            return Vec::new();
+        };
+        if document.global_styles.is_empty() || is_designated(path, &self.designated_files) {
+            return Vec::new();
+        }
+
+        let allowed = if self.designated_files.is_empty() {
+            DEFAULT_GLOBAL_STYLE_FILES.join(", ")
+        } else {
+            self.designated_files.join(", ")
+        };
+
+        document
+            .global_styles
+            .iter()
+            .map(|global| {
+                Diagnostic::warning(
+                    "global-styles-location",
+                    format!(
+                        "Global style '{}' is declared outside a designated global styles file. It will affect every document that imports this file.",
+                        global.selector
+                    ),
+                    global.span.clone(),
+                )
+                .with_suggestion(format!(
+                    "Move global styles to one of: {} (configurable with 'globalStyleFiles')",
+                    allowed
+                ))
+            })
+            .collect()
+    }
+}
+
+fn is_designated(path: &Path, designated_files: &[String]) -> bool {
+    if designated_files.is_empty() {
+        DEFAULT_GLOBAL_STYLE_FILES
+            .iter()
+            .any(|file| path.ends_with(file))
+    } else {
+        designated_files.iter().any(|file| path.ends_with(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use paperclip_parser::parse;
+
+    const SOURCE: &str = r#"
+        global style body {
+            margin: 0
+        }
+    "#;
+
+    #[test]
+    fn test_warns_outside_designated_file() {
+        let document = parse(SOURCE).unwrap();
+        let diagnostics =
+            GlobalStylesRule::default().check_document(&document, Some(Path::new("/app/button.pc")));
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, "global-styles-location");
+    }
+
+    #[test]
+    fn test_allows_default_designated_files() {
+        let document = parse(SOURCE).unwrap();
+        let diagnostics =
+            GlobalStylesRule::default().check_document(&document, Some(Path::new("/app/reset.pc")));
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_allows_configured_files() {
+        let document = parse(SOURCE).unwrap();
+        let rule = GlobalStylesRule::new(vec!["styles/base.pc".to_string()]);
+
+        let diagnostics = rule.check_document(&document, Some(Path::new("/app/styles/base.pc")));
+        assert!(diagnostics.is_empty());
+
+        // Defaults no longer apply once configured
+        let diagnostics = rule.check_document(&document, Some(Path::new("/app/reset.pc")));
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_registered_and_removable() {
+        use crate::{lint_document, LintOptions, RuleRegistry};
+
+        let document = parse(SOURCE).unwrap();
+        let options = || LintOptions {
+            path: Some("/app/button.pc".into()),
+            ..LintOptions::default()
+        };
+        let diagnostics = lint_document(&document, options());
+        assert!(diagnostics.iter().any(|d| d.rule == "global-styles-location"));
+
+        let mut registry = RuleRegistry::new();
+        registry.remove_rule("global-styles-location");
+        let diagnostics = lint_document(
+            &document,
+            LintOptions {
+                registry: Some(registry),
+                ..options()
+            },
+        );
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -1,15 +1,20 @@
 mod a11y;
+mod global_styles;
 mod no_important;
 mod no_negative_spacing;
 mod no_viewport_units;
+mod valid_css;
 
 pub use a11y::A11yRule;
+pub use global_styles::{GlobalStylesRule, DEFAULT_GLOBAL_STYLE_FILES};
 pub use no_important::NoImportantRule;
 pub use no_negative_spacing::NoNegativeSpacingRule;
 pub use no_viewport_units::NoViewportUnitsRule;
+pub use valid_css::ValidCssRule;
 
 use crate::diagnostic::Diagnostic;
-use paperclip_parser::ast::{StyleBlock, StyleDecl};
+use paperclip_parser::ast::{Document, StyleBlock, StyleDecl};
+use std::path::Path;
 
 /// Trait for implementing lint rules
 pub trait LintRule {
@@ -28,6 +33,11 @@ pub trait LintRule {
     fn check_style_block(&self, _style: &StyleBlock) -> Vec<Diagnostic> {
         Vec::new()
     }
+
+    /// Check the document as a whole. `path` is set when the linted file is known.
+    fn check_document(&self, _document: &Document, _path: Option<&Path>) -> Vec<Diagnostic> {
+        Vec::new()
+    }
 }
 
 /// Registry of all available lint rules
@@ -43,6 +53,8 @@ impl RuleRegistry {
                 Box::new(NoImportantRule),
                 Box::new(NoViewportUnitsRule),
                 Box::new(NoNegativeSpacingRule),
+                Box::new(ValidCssRule),
+                Box::new(GlobalStylesRule::default()),
             ],
         }
     }
@@ -61,6 +73,20 @@ impl RuleRegistry {
     pub fn add_rule(&mut self, rule: Box<dyn LintRule>) {
         self.rules.push(rule);
     }
+
+    /// Add a rule, replacing a registered rule with the same name
+    /// (e.g. to reconfigure a built-in rule)
+    pub fn set_rule(&mut self, rule: Box<dyn LintRule>) {
+        match self.rules.iter().position(|r| r.name() == rule.name()) {
+            Some(index) => self.rules[index] = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    /// Remove the rule named `name`
+    pub fn remove_rule(&mut self, name: &str) {
+        self.rules.retain(|rule| rule.name() != name);
+    }
 }
 
 impl Default for RuleRegistry {
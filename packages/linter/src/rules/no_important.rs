@@ -24,7 +24,7 @@ impl LintRule for NoImportantRule {
 }
 
 fn check_properties(
-    properties: &std::collections::HashMap<String, String>,
+    properties: &paperclip_parser::ast::Properties,
     span: &paperclip_parser::ast::Span,
 ) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
@@ -55,12 +55,12 @@ fn check_properties(
 mod tests {
     use super::*;
     use paperclip_parser::ast::Span;
-    use std::collections::HashMap;
+    use paperclip_parser::ast::Properties;
 
     #[test]
     fn test_detects_important_in_style_decl() {
         let rule = NoImportantRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("color".to_string(), "red !important".to_string());
 
         let style = StyleDecl {
@@ -79,7 +79,7 @@ mod tests {
     #[test]
     fn test_allows_normal_styles() {
         let rule = NoImportantRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("color".to_string(), "red".to_string());
 
         let style = StyleDecl {
@@ -1,7 +1,7 @@
 use crate::diagnostic::Diagnostic;
 use crate::rules::LintRule;
 use paperclip_parser::ast::{StyleBlock, StyleDecl};
-use regex::Regex;
+use paperclip_parser::{parse_css_value, ValuePart};
 
 /// Lint rule that prevents negative margins and padding
 pub struct NoNegativeSpacingRule;
@@ -25,15 +25,11 @@ impl LintRule for NoNegativeSpacingRule {
 }
 
 fn check_properties(
-    properties: &std::collections::HashMap<String, String>,
+    properties: &paperclip_parser::ast::Properties,
     span: &paperclip_parser::ast::Span,
 ) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
-    // Regex to match negative values (e.g., -10px, -1rem, -5%)
-    let re =
-        Regex::new(r"-\d+(\.\d+)?(%|px|rem|em|vh|vw|pt|pc|in|cm|mm|ex|ch|vmin|vmax)\b").unwrap();
-
     // Properties to check for negative values
     let spacing_properties = [
         "margin",
@@ -53,7 +49,7 @@ fn check_properties(
 
         // Check if this is a spacing property
         if spacing_properties.contains(&property_lower.as_str()) {
-            if re.is_match(value) {
+            if has_negative_length(value) {
                 let is_margin = property_lower.starts_with("margin");
                 let property_type = if is_margin { "margin" } else { "padding" };
 
@@ -78,16 +74,31 @@ fn check_properties(
     diagnostics
 }
 
+/// Whether the value contains a negative length or percentage (e.g. -10px, -5%)
+fn has_negative_length(value: &str) -> bool {
+    let Ok(value) = parse_css_value(value) else {
+        return false;
+    };
+    let mut negative = false;
+    value.walk(|part| {
+        negative |= matches!(
+            part,
+            ValuePart::Dimension { value, .. } | ValuePart::Percentage(value) if *value < 0.0
+        );
+    });
+    negative
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use paperclip_parser::ast::Span;
-    use std::collections::HashMap;
+    use paperclip_parser::ast::Properties;
 
     #[test]
     fn test_detects_negative_margin() {
         let rule = NoNegativeSpacingRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("margin-top".to_string(), "-10px".to_string());
 
         let style = StyleDecl {
@@ -107,7 +118,7 @@ mod tests {
     #[test]
     fn test_detects_negative_padding() {
         let rule = NoNegativeSpacingRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("padding".to_string(), "-5px".to_string());
 
         let style = StyleDecl {
@@ -127,7 +138,7 @@ mod tests {
     #[test]
     fn test_allows_positive_spacing() {
         let rule = NoNegativeSpacingRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("margin".to_string(), "10px".to_string());
         properties.insert("padding".to_string(), "20px".to_string());
 
@@ -146,7 +157,7 @@ mod tests {
     #[test]
     fn test_allows_negative_in_non_spacing_properties() {
         let rule = NoNegativeSpacingRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("top".to_string(), "-10px".to_string());
         properties.insert("left".to_string(), "-5px".to_string());
 
@@ -1,7 +1,7 @@
 use crate::diagnostic::Diagnostic;
 use crate::rules::LintRule;
 use paperclip_parser::ast::{StyleBlock, StyleDecl};
-use regex::Regex;
+use paperclip_parser::{parse_css_value, ValuePart};
 
 /// Lint rule that prevents use of vw and vh units
 pub struct NoViewportUnitsRule;
@@ -25,21 +25,13 @@ impl LintRule for NoViewportUnitsRule {
 }
 
 fn check_properties(
-    properties: &std::collections::HashMap<String, String>,
+    properties: &paperclip_parser::ast::Properties,
     span: &paperclip_parser::ast::Span,
 ) -> Vec<Diagnostic> {
     let mut diagnostics = Vec::new();
 
-    // Regex to match vw or vh units (with word boundaries to avoid false matches)
-    let re = Regex::new(r"\b\d+(\.\d+)?(vw|vh)\b").unwrap();
-
     for (property, value) in properties {
-        if let Some(matched) = re.find(value) {
-            let unit = if matched.as_str().contains("vw") {
-                "vw"
-            } else {
-                "vh"
-            };
+        if let Some(unit) = viewport_unit(value) {
 
             diagnostics.push(
                 Diagnostic::error(
@@ -61,16 +53,32 @@ fn check_properties(
     diagnostics
 }
 
+/// First `vw` or `vh` unit used in the value, including inside `calc()` and friends
+fn viewport_unit(value: &str) -> Option<&'static str> {
+    let value = parse_css_value(value).ok()?;
+    let mut found = None;
+    value.walk(|part| {
+        if let ValuePart::Dimension { unit, .. } = part {
+            if found.is_none() && unit.eq_ignore_ascii_case("vw") {
+                found = Some("vw");
+            } else if found.is_none() && unit.eq_ignore_ascii_case("vh") {
+                found = Some("vh");
+            }
+        }
+    });
+    found
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use paperclip_parser::ast::Span;
-    use std::collections::HashMap;
+    use paperclip_parser::ast::Properties;
 
     #[test]
     fn test_detects_vw_units() {
         let rule = NoViewportUnitsRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("width".to_string(), "50vw".to_string());
 
         let style = StyleDecl {
@@ -90,7 +98,7 @@ mod tests {
     #[test]
     fn test_detects_vh_units() {
         let rule = NoViewportUnitsRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("height".to_string(), "100vh".to_string());
 
         let style = StyleDecl {
@@ -110,7 +118,7 @@ mod tests {
     #[test]
     fn test_allows_other_units() {
         let rule = NoViewportUnitsRule;
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
         properties.insert("width".to_string(), "50%".to_string());
         properties.insert("height".to_string(), "100px".to_string());
         properties.insert("font-size".to_string(), "1.5rem".to_string());
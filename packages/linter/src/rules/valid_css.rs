@@ -0,0 +1,117 @@
+use crate::diagnostic::Diagnostic;
+use crate::rules::LintRule;
+use paperclip_parser::ast::{StyleBlock, StyleDecl};
+use paperclip_parser::{validate_declaration, DeclarationError};
+
+/// Lint rule that flags unknown properties and values that don't fit the property
+pub struct ValidCssRule;
+
+impl LintRule for ValidCssRule {
+    fn name(&self) -> &'static str {
+        "valid-css"
+    }
+
+    fn description(&self) -> &'static str {
+        "Disallow unknown CSS properties and invalid values"
+    }
+
+    fn check_style_decl(&self, style: &StyleDecl) -> Vec<Diagnostic> {
+        check_properties(&style.properties, &style.span)
+    }
+
+    fn check_style_block(&self, style: &StyleBlock) -> Vec<Diagnostic> {
+        check_properties(&style.properties, &style.span)
+    }
+}
+
+fn check_properties(
+    properties: &paperclip_parser::ast::Properties,
+    span: &paperclip_parser::ast::Span,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // Sorted so diagnostics come out in a stable order
+    let mut properties: Vec<_> = properties.iter().collect();
+    properties.sort();
+
+    for (property, value) in properties {
+        match validate_declaration(property, value) {
+            Ok(_) => {}
+            Err(DeclarationError::UnknownProperty { suggestion, .. }) => {
+                let diagnostic = Diagnostic::warning(
+                    "valid-css",
+                    format!("Unknown property '{}'", property),
+                    span.clone(),
+                );
+                diagnostics.push(match suggestion {
+                    Some(suggestion) => {
+                        diagnostic.with_suggestion(format!("Did you mean '{}'?", suggestion))
+                    }
+                    None => diagnostic,
+                });
+            }
+            Err(DeclarationError::InvalidValue { reason, .. }) => {
+                diagnostics.push(Diagnostic::error(
+                    "valid-css",
+                    format!("Invalid value '{}' for '{}': {}", value, property, reason),
+                    span.clone(),
+                ));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::DiagnosticLevel;
+    use paperclip_parser::ast::Span;
+    use paperclip_parser::ast::Properties;
+
+    fn style(properties: &[(&str, &str)]) -> StyleDecl {
+        StyleDecl {
+            public: false,
+            name: "test".to_string(),
+            extends: vec![],
+            properties: properties
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<Properties>(),
+            span: Span::new(0, 10, "test".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_detects_unknown_property() {
+        let diagnostics = ValidCssRule.check_style_decl(&style(&[("colr", "red")]));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, DiagnosticLevel::Warning);
+        assert_eq!(diagnostics[0].message, "Unknown property 'colr'");
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("Did you mean 'color'?")
+        );
+    }
+
+    #[test]
+    fn test_detects_invalid_unit() {
+        // `8pz` reaches the AST as `8 pz`
+        let diagnostics = ValidCssRule.check_style_decl(&style(&[("padding", "8 pz")]));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level, DiagnosticLevel::Error);
+        assert!(diagnostics[0].message.contains("unknown unit 'pz'"));
+    }
+
+    #[test]
+    fn test_allows_valid_styles() {
+        let diagnostics = ValidCssRule.check_style_decl(&style(&[
+            ("padding", "8px 16px"),
+            ("color", "var(theme.primary)"),
+            ("display", "flex"),
+            ("--accent", "tomato"),
+        ]));
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+}
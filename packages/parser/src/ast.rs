@@ -1,6 +1,10 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// CSS declarations, in the order they were written
+pub type Properties = IndexMap<String, String>;
+
 /// Span information for source location tracking
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Span {
@@ -22,6 +26,12 @@ pub struct Document {
     pub tokens: Vec<TokenDecl>,
     pub triggers: Vec<TriggerDecl>,
     pub styles: Vec<StyleDecl>,
+    /// Unscoped style rules (`global style body { ... }`)
+    #[serde(default)]
+    pub global_styles: Vec<GlobalStyle>,
+    /// Raw CSS escape hatches (`css """ ... """`)
+    #[serde(default)]
+    pub raw_css: Vec<RawCssBlock>,
     pub components: Vec<Component>,
     /// Top-level render elements (text, div, etc.)
     pub renders: Vec<Element>,
@@ -47,10 +57,82 @@ pub struct Import {
 pub struct TokenDecl {
     pub public: bool,
     pub name: String,
+    /// Canonical source text of the value (used by serializers)
     pub value: String,
+    /// Structured value - literals, references to other tokens, math and color functions
+    pub expression: TokenValue,
     pub span: Span,
 }
 
+/// Token value expression
+///
+/// Examples:
+/// - `#3366FF`, `16px`, `"Inter"` -> Literal
+/// - `primaryColor`, `theme.primary` -> Reference
+/// - `spacing * 2` -> Binary
+/// - `alpha(primaryColor, 0.5)` -> Call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TokenValue {
+    /// Plain CSS value (color, number, dimension, string, keyword list)
+    Literal { value: String },
+
+    /// Reference to another token, possibly namespaced (`theme.primary`).
+    /// Bare names that don't match a token are treated as CSS keywords.
+    Reference { name: String },
+
+    /// Arithmetic between token values (`spacing * 2`)
+    Binary {
+        left: Box<TokenValue>,
+        operator: BinaryOp,
+        right: Box<TokenValue>,
+    },
+
+    /// Function call (`alpha(primaryColor, 0.5)`, `mix(a, b, 25%)`)
+    Call {
+        function: String,
+        arguments: Vec<TokenValue>,
+    },
+}
+
+impl TokenValue {
+    /// Render the expression back to Paperclip source
+    pub fn to_source(&self) -> String {
+        match self {
+            TokenValue::Literal { value } => value.clone(),
+            TokenValue::Reference { name } => name.clone(),
+            TokenValue::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                let op = match operator {
+                    BinaryOp::Add => "+",
+                    BinaryOp::Subtract => "-",
+                    BinaryOp::Multiply => "*",
+                    BinaryOp::Divide => "/",
+                    _ => "?",
+                };
+                format!("{} {} {}", left.to_operand_source(), op, right.to_operand_source())
+            }
+            TokenValue::Call {
+                function,
+                arguments,
+            } => {
+                let args: Vec<String> = arguments.iter().map(|a| a.to_source()).collect();
+                format!("{}({})", function, args.join(", "))
+            }
+        }
+    }
+
+    fn to_operand_source(&self) -> String {
+        match self {
+            TokenValue::Binary { .. } => format!("({})", self.to_source()),
+            _ => self.to_source(),
+        }
+    }
+}
+
 /// Trigger declaration (reusable CSS selectors/media queries)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TriggerDecl {
@@ -66,7 +148,30 @@ pub struct StyleDecl {
     pub public: bool,
     pub name: String,
     pub extends: Vec<String>,
-    pub properties: HashMap<String, String>,
+    pub properties: Properties,
+    pub span: Span,
+}
+
+/// Global style rule - emitted without component/document scoping
+///
+/// Declared as `global style body { margin: 0 }` or grouped:
+/// `global { body { margin: 0 } "a:hover" { color: blue } }`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalStyle {
+    pub selector: String,
+    pub properties: Properties,
+    pub span: Span,
+}
+
+/// Raw CSS block for features the style syntax can't express
+///
+/// `css """ @supports (display: grid) { .grid { display: grid } } """`
+///
+/// The source is kept verbatim; it's validated and scoped to the document
+/// during CSS evaluation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawCssBlock {
+    pub source: String,
     pub span: Span,
 }
 
@@ -81,6 +186,9 @@ pub struct Component {
     pub variants: Vec<Variant>,
     pub slots: Vec<Slot>,
     pub overrides: Vec<Override>,
+    /// Raw CSS blocks declared inside the component
+    #[serde(default)]
+    pub raw_css: Vec<RawCssBlock>,
     pub body: Option<Element>,
     pub span: Span,
 }
@@ -141,10 +249,21 @@ pub struct Variant {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Slot {
     pub name: String,
+    /// Scoped slot parameters (`slot row(item)`), read from the scope where
+    /// the slot is rendered and passed to the inserted content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<String>,
     pub default_content: Vec<Element>,
     pub span: Span,
 }
 
+impl Slot {
+    /// Whether the slot passes data back to its inserted content
+    pub fn is_scoped(&self) -> bool {
+        !self.params.is_empty()
+    }
+}
+
 /// Override definition (target nested instances)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Override {
@@ -177,7 +296,12 @@ pub enum Element {
 
     /// Text node
     Text {
+        /// Plain content. For rich text this is the runs flattened into a
+        /// template, so consumers that don't understand runs still see the text.
         content: Expression,
+        /// Inline formatting runs (`text { "Hi " strong "there" }`) - empty for plain text
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        runs: Vec<TextRun>,
         styles: Vec<StyleBlock>,
         span: Span,
     },
@@ -212,17 +336,54 @@ pub enum Element {
     /// Insert directive (explicit slot content)
     Insert {
         slot_name: String,
+        /// Names bound to a scoped slot's parameters (`insert row(item) { ... }`)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        params: Vec<String>,
         content: Vec<Element>,
         span: Span,
     },
 }
 
+/// Inline tags allowed as rich text runs
+pub const RICH_TEXT_TAGS: &[&str] = &["strong", "em", "a", "span"];
+
+/// A run of rich text inside a `text { ... }` block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextRun {
+    /// Inline tag (`strong`, `em`, `a`, `span`) - `None` for a plain run
+    pub tag: Option<String>,
+    pub content: Expression,
+    #[serde(default)]
+    pub attributes: HashMap<String, Expression>,
+    #[serde(default)]
+    pub styles: Vec<StyleBlock>,
+    pub span: Span,
+}
+
+impl TextRun {
+    /// Flatten runs into a single template expression (formatting dropped)
+    pub fn plain_content(runs: &[TextRun], span: &Span) -> Expression {
+        let parts = runs
+            .iter()
+            .map(|run| match &run.content {
+                Expression::Literal { value, .. } => TemplatePart::Literal(value.clone()),
+                other => TemplatePart::Expression(other.clone()),
+            })
+            .collect();
+
+        Expression::Template {
+            parts,
+            span: span.clone(),
+        }
+    }
+}
+
 /// Style block (inline styles)
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StyleBlock {
     pub variants: Vec<String>,
     pub extends: Vec<String>,
-    pub properties: HashMap<String, String>,
+    pub properties: Properties,
     pub span: Span,
 }
 
@@ -302,6 +463,8 @@ impl Document {
             tokens: Vec::new(),
             triggers: Vec::new(),
             styles: Vec::new(),
+            global_styles: Vec::new(),
+            raw_css: Vec::new(),
             components: Vec::new(),
             renders: Vec::new(),
             render_doc_comments: Vec::new(),
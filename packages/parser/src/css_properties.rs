@@ -0,0 +1,533 @@
+//! Known CSS properties and what their values may contain
+//!
+//! The table is deliberately loose for properties with complex grammars (`grid-template-*`,
+//! `transition`, ...): those accept any well-formed value. The point is catching typos like
+//! `colr: red` or `padding: 8pz`, not implementing the CSS spec.
+
+use crate::css_value::{
+    parse_css_value, CssValue, UnitKind, ValueError, ValuePart, CSS_WIDE_KEYWORDS,
+    IMAGE_FUNCTIONS, MATH_FUNCTIONS,
+};
+use thiserror::Error;
+use ValueKind::{Angle, Any, Image, Keywords, Length, Number, Percentage, Text, Time};
+
+/// A kind of value a property accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    Length,
+    Percentage,
+    Number,
+    Color,
+    Angle,
+    Time,
+    Image,
+    /// Quoted string
+    Text,
+    Keywords(&'static [&'static str]),
+    /// Anything well-formed
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyInfo {
+    pub name: &'static str,
+    pub accepts: &'static [ValueKind],
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DeclarationError {
+    #[error("Unknown property '{property}'")]
+    UnknownProperty {
+        property: String,
+        /// Closest known property, if one is a likely typo fix
+        suggestion: Option<&'static str>,
+    },
+
+    #[error("Invalid value '{value}' for '{property}': {reason}")]
+    InvalidValue {
+        property: String,
+        value: String,
+        reason: String,
+    },
+}
+
+
+const AUTO: &[&str] = &["auto"];
+const NONE: &[&str] = &["none"];
+const NORMAL: &[&str] = &["normal"];
+const SIZE: &[&str] = &[
+    "auto", "none", "min-content", "max-content", "fit-content", "-moz-fit-content", "stretch",
+    "-webkit-fill-available",
+];
+const BORDER_STYLE: &[&str] = &[
+    "none", "hidden", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset",
+    "thin", "medium", "thick",
+];
+const ALIGN: &[&str] = &[
+    "normal", "stretch", "center", "start", "end", "flex-start", "flex-end", "self-start",
+    "self-end", "left", "right", "baseline", "first", "last", "space-between", "space-around",
+    "space-evenly", "safe", "unsafe", "auto", "legacy", "anchor-center",
+];
+const DISPLAY: &[&str] = &[
+    "none", "block", "inline", "inline-block", "flex", "inline-flex", "grid", "inline-grid",
+    "flow-root", "contents", "table", "table-row", "table-cell", "table-row-group",
+    "table-header-group", "table-footer-group", "table-column", "table-column-group",
+    "table-caption", "inline-table", "list-item", "run-in", "flow", "ruby", "math",
+    "-webkit-box", "-webkit-inline-box",
+];
+const FONT_SIZE: &[&str] = &[
+    "xx-small", "x-small", "small", "medium", "large", "x-large", "xx-large", "xxx-large",
+    "smaller", "larger", "math",
+];
+const FONT_WEIGHT: &[&str] = &["normal", "bold", "bolder", "lighter"];
+const OVERFLOW: &[&str] = &["visible", "hidden", "clip", "scroll", "auto", "overlay"];
+const TEXT_DECORATION: &[&str] = &[
+    "none", "underline", "overline", "line-through", "blink", "solid", "double", "dotted",
+    "dashed", "wavy", "auto", "from-font", "spelling-error", "grammar-error",
+];
+const BLEND: &[&str] = &[
+    "normal", "multiply", "screen", "overlay", "darken", "lighten", "color-dodge", "color-burn",
+    "hard-light", "soft-light", "difference", "exclusion", "hue", "saturation", "color",
+    "luminosity", "plus-darker", "plus-lighter",
+];
+const CURSOR: &[&str] = &[
+    "auto", "default", "none", "context-menu", "help", "pointer", "progress", "wait", "cell",
+    "crosshair", "text", "vertical-text", "alias", "copy", "move", "no-drop", "not-allowed",
+    "grab", "grabbing", "all-scroll", "col-resize", "row-resize", "n-resize", "e-resize",
+    "s-resize", "w-resize", "ne-resize", "nw-resize", "se-resize", "sw-resize", "ew-resize",
+    "ns-resize", "nesw-resize", "nwse-resize", "zoom-in", "zoom-out",
+];
+
+const LENGTH: &[ValueKind] = &[Length, Percentage];
+const LENGTH_AUTO: &[ValueKind] = &[Length, Percentage, Keywords(AUTO)];
+const SIZING: &[ValueKind] = &[Length, Percentage, Keywords(SIZE)];
+const COLOR: &[ValueKind] = &[ValueKind::Color];
+const BORDER: &[ValueKind] = &[Length, ValueKind::Color, Keywords(BORDER_STYLE)];
+const BORDER_WIDTH: &[ValueKind] = &[Length, Keywords(BORDER_STYLE)];
+const ANY: &[ValueKind] = &[Any];
+
+const fn p(name: &'static str, accepts: &'static [ValueKind]) -> PropertyInfo {
+    PropertyInfo { name, accepts }
+}
+
+#[rustfmt::skip]
+const PROPERTIES: &[PropertyInfo] = &[
+    // Layout
+    p("display", &[Keywords(DISPLAY)]),
+    p("position", &[Keywords(&["static", "relative", "absolute", "fixed", "sticky", "-webkit-sticky"])]),
+    p("top", LENGTH_AUTO), p("right", LENGTH_AUTO), p("bottom", LENGTH_AUTO), p("left", LENGTH_AUTO),
+    p("inset", LENGTH_AUTO), p("inset-inline", LENGTH_AUTO), p("inset-block", LENGTH_AUTO),
+    p("inset-inline-start", LENGTH_AUTO), p("inset-inline-end", LENGTH_AUTO),
+    p("inset-block-start", LENGTH_AUTO), p("inset-block-end", LENGTH_AUTO),
+    p("z-index", &[Number, Keywords(AUTO)]),
+    p("float", &[Keywords(&["left", "right", "none", "inline-start", "inline-end"])]),
+    p("clear", &[Keywords(&["left", "right", "both", "none", "inline-start", "inline-end"])]),
+    p("box-sizing", &[Keywords(&["content-box", "border-box"])]),
+    p("visibility", &[Keywords(&["visible", "hidden", "collapse"])]),
+    p("overflow", &[Keywords(OVERFLOW)]), p("overflow-x", &[Keywords(OVERFLOW)]),
+    p("overflow-y", &[Keywords(OVERFLOW)]),
+    p("overflow-wrap", &[Keywords(&["normal", "break-word", "anywhere"])]),
+    p("word-wrap", &[Keywords(&["normal", "break-word", "anywhere"])]),
+    p("overscroll-behavior", &[Keywords(&["auto", "contain", "none"])]),
+    p("aspect-ratio", &[Number, Keywords(AUTO)]),
+    p("object-fit", &[Keywords(&["fill", "contain", "cover", "none", "scale-down"])]),
+    p("object-position", ANY),
+    p("contain", ANY), p("content-visibility", ANY),
+    p("container", ANY), p("container-name", ANY),
+    p("container-type", &[Keywords(&["normal", "size", "inline-size"])]),
+    p("isolation", &[Keywords(&["auto", "isolate"])]),
+
+    // Sizing
+    p("width", SIZING), p("height", SIZING), p("min-width", SIZING), p("min-height", SIZING),
+    p("max-width", SIZING), p("max-height", SIZING), p("inline-size", SIZING),
+    p("block-size", SIZING), p("min-inline-size", SIZING), p("min-block-size", SIZING),
+    p("max-inline-size", SIZING), p("max-block-size", SIZING),
+
+    // Spacing
+    p("margin", LENGTH_AUTO), p("margin-top", LENGTH_AUTO), p("margin-right", LENGTH_AUTO),
+    p("margin-bottom", LENGTH_AUTO), p("margin-left", LENGTH_AUTO),
+    p("margin-inline", LENGTH_AUTO), p("margin-block", LENGTH_AUTO),
+    p("margin-inline-start", LENGTH_AUTO), p("margin-inline-end", LENGTH_AUTO),
+    p("margin-block-start", LENGTH_AUTO), p("margin-block-end", LENGTH_AUTO),
+    p("padding", LENGTH), p("padding-top", LENGTH), p("padding-right", LENGTH),
+    p("padding-bottom", LENGTH), p("padding-left", LENGTH),
+    p("padding-inline", LENGTH), p("padding-block", LENGTH),
+    p("padding-inline-start", LENGTH), p("padding-inline-end", LENGTH),
+    p("padding-block-start", LENGTH), p("padding-block-end", LENGTH),
+    p("gap", &[Length, Percentage, Keywords(NORMAL)]),
+    p("row-gap", &[Length, Percentage, Keywords(NORMAL)]),
+    p("column-gap", &[Length, Percentage, Keywords(NORMAL)]),
+
+    // Flexbox and grid
+    p("flex", &[Number, Length, Percentage, Keywords(&["auto", "none", "content"])]),
+    p("flex-direction", &[Keywords(&["row", "row-reverse", "column", "column-reverse"])]),
+    p("flex-wrap", &[Keywords(&["nowrap", "wrap", "wrap-reverse"])]),
+    p("flex-flow", &[Keywords(&["row", "row-reverse", "column", "column-reverse", "nowrap", "wrap", "wrap-reverse"])]),
+    p("flex-grow", &[Number]), p("flex-shrink", &[Number]),
+    p("flex-basis", &[Length, Percentage, Keywords(&["auto", "content", "min-content", "max-content", "fit-content"])]),
+    p("order", &[Number]),
+    p("align-items", &[Keywords(ALIGN)]), p("align-self", &[Keywords(ALIGN)]),
+    p("align-content", &[Keywords(ALIGN)]), p("justify-content", &[Keywords(ALIGN)]),
+    p("justify-items", &[Keywords(ALIGN)]), p("justify-self", &[Keywords(ALIGN)]),
+    p("place-items", &[Keywords(ALIGN)]), p("place-self", &[Keywords(ALIGN)]),
+    p("place-content", &[Keywords(ALIGN)]),
+    p("grid", ANY), p("grid-template", ANY), p("grid-template-columns", ANY),
+    p("grid-template-rows", ANY), p("grid-template-areas", ANY), p("grid-area", ANY),
+    p("grid-column", ANY), p("grid-row", ANY), p("grid-column-start", ANY),
+    p("grid-column-end", ANY), p("grid-row-start", ANY), p("grid-row-end", ANY),
+    p("grid-auto-columns", ANY), p("grid-auto-rows", ANY), p("grid-auto-flow", ANY),
+
+    // Typography
+    p("color", COLOR),
+    p("font", ANY), p("font-family", ANY),
+    p("font-size", &[Length, Percentage, Keywords(FONT_SIZE)]),
+    p("font-weight", &[Number, Keywords(FONT_WEIGHT)]),
+    p("font-style", &[Angle, Keywords(&["normal", "italic", "oblique"])]),
+    p("font-variant", ANY), p("font-feature-settings", ANY), p("font-variation-settings", ANY),
+    p("font-display", &[Keywords(&["auto", "block", "swap", "fallback", "optional"])]),
+    p("font-stretch", &[Percentage, Keywords(&["normal", "ultra-condensed", "extra-condensed", "condensed", "semi-condensed", "semi-expanded", "expanded", "extra-expanded", "ultra-expanded"])]),
+    p("line-height", &[Number, Length, Percentage, Keywords(NORMAL)]),
+    p("letter-spacing", &[Length, Keywords(NORMAL)]),
+    p("word-spacing", &[Length, Percentage, Keywords(NORMAL)]),
+    p("text-align", &[Keywords(&["left", "right", "center", "justify", "start", "end", "match-parent"])]),
+    p("text-decoration", &[Length, ValueKind::Color, Keywords(TEXT_DECORATION)]),
+    p("text-decoration-line", &[Keywords(TEXT_DECORATION)]),
+    p("text-decoration-style", &[Keywords(TEXT_DECORATION)]),
+    p("text-decoration-color", COLOR),
+    p("text-decoration-thickness", &[Length, Percentage, Keywords(&["auto", "from-font"])]),
+    p("text-underline-offset", LENGTH_AUTO),
+    p("text-transform", &[Keywords(&["none", "capitalize", "uppercase", "lowercase", "full-width", "full-size-kana"])]),
+    p("text-overflow", &[Text, Keywords(&["clip", "ellipsis"])]),
+    p("text-indent", LENGTH),
+    p("text-shadow", &[Length, ValueKind::Color, Keywords(NONE)]),
+    p("text-wrap", &[Keywords(&["wrap", "nowrap", "balance", "pretty", "stable"])]),
+    p("text-rendering", &[Keywords(&["auto", "optimizeSpeed", "optimizeLegibility", "geometricPrecision"])]),
+    p("text-size-adjust", &[Percentage, Keywords(&["auto", "none"])]),
+    p("white-space", &[Keywords(&["normal", "nowrap", "pre", "pre-wrap", "pre-line", "break-spaces"])]),
+    p("word-break", &[Keywords(&["normal", "break-all", "keep-all", "break-word", "auto-phrase"])]),
+    p("hyphens", &[Keywords(&["none", "manual", "auto"])]),
+    p("vertical-align", &[Length, Percentage, Keywords(&["baseline", "sub", "super", "text-top", "text-bottom", "middle", "top", "bottom"])]),
+    p("direction", &[Keywords(&["ltr", "rtl"])]),
+    p("writing-mode", &[Keywords(&["horizontal-tb", "vertical-rl", "vertical-lr", "sideways-rl", "sideways-lr"])]),
+    p("unicode-bidi", ANY),
+    p("tab-size", &[Number, Length]),
+    p("content", ANY), p("quotes", ANY),
+    p("list-style", ANY), p("list-style-type", ANY), p("list-style-position", ANY),
+    p("list-style-image", &[Image, Keywords(NONE)]),
+    p("counter-reset", ANY), p("counter-increment", ANY), p("counter-set", ANY),
+    p("columns", ANY), p("column-count", &[Number, Keywords(AUTO)]), p("column-width", LENGTH_AUTO),
+    p("column-rule", BORDER),
+
+    // Backgrounds and borders
+    p("background", ANY),
+    p("background-color", COLOR),
+    p("background-image", &[Image, Keywords(NONE)]),
+    p("background-position", ANY), p("background-size", ANY),
+    p("background-repeat", &[Keywords(&["repeat", "no-repeat", "repeat-x", "repeat-y", "space", "round"])]),
+    p("background-clip", &[Keywords(&["border-box", "padding-box", "content-box", "text"])]),
+    p("background-origin", &[Keywords(&["border-box", "padding-box", "content-box"])]),
+    p("background-attachment", &[Keywords(&["scroll", "fixed", "local"])]),
+    p("background-blend-mode", &[Keywords(BLEND)]),
+    p("border", BORDER), p("border-top", BORDER), p("border-right", BORDER),
+    p("border-bottom", BORDER), p("border-left", BORDER),
+    p("border-inline", BORDER), p("border-block", BORDER),
+    p("border-inline-start", BORDER), p("border-inline-end", BORDER),
+    p("border-block-start", BORDER), p("border-block-end", BORDER),
+    p("border-width", BORDER_WIDTH), p("border-top-width", BORDER_WIDTH),
+    p("border-right-width", BORDER_WIDTH), p("border-bottom-width", BORDER_WIDTH),
+    p("border-left-width", BORDER_WIDTH),
+    p("border-style", &[Keywords(BORDER_STYLE)]), p("border-top-style", &[Keywords(BORDER_STYLE)]),
+    p("border-right-style", &[Keywords(BORDER_STYLE)]), p("border-bottom-style", &[Keywords(BORDER_STYLE)]),
+    p("border-left-style", &[Keywords(BORDER_STYLE)]),
+    p("border-color", COLOR), p("border-top-color", COLOR), p("border-right-color", COLOR),
+    p("border-bottom-color", COLOR), p("border-left-color", COLOR),
+    p("border-radius", LENGTH), p("border-top-left-radius", LENGTH),
+    p("border-top-right-radius", LENGTH), p("border-bottom-left-radius", LENGTH),
+    p("border-bottom-right-radius", LENGTH),
+    p("border-start-start-radius", LENGTH), p("border-start-end-radius", LENGTH),
+    p("border-end-start-radius", LENGTH), p("border-end-end-radius", LENGTH),
+    p("border-collapse", &[Keywords(&["collapse", "separate"])]),
+    p("border-spacing", &[Length]),
+    p("border-image", ANY),
+    p("outline", &[Length, ValueKind::Color, Keywords(&["auto", "none", "dotted", "dashed", "solid", "double", "groove", "ridge", "inset", "outset", "thin", "medium", "thick"])]),
+    p("outline-width", BORDER_WIDTH), p("outline-style", &[Keywords(BORDER_STYLE), Keywords(AUTO)]),
+    p("outline-color", COLOR), p("outline-offset", &[Length]),
+    p("box-shadow", &[Length, ValueKind::Color, Keywords(&["inset", "none"])]),
+    p("table-layout", &[Keywords(&["auto", "fixed"])]),
+
+    // Effects
+    p("opacity", &[Number, Percentage]),
+    p("filter", ANY), p("backdrop-filter", ANY),
+    p("mix-blend-mode", &[Keywords(BLEND)]),
+    p("transform", ANY), p("transform-origin", ANY), p("transform-style", ANY),
+    p("translate", ANY), p("rotate", ANY), p("scale", ANY),
+    p("perspective", &[Length, Keywords(NONE)]), p("perspective-origin", ANY),
+    p("backface-visibility", &[Keywords(&["visible", "hidden"])]),
+    p("clip-path", ANY), p("mask", ANY), p("mask-image", ANY),
+    p("transition", ANY), p("transition-property", ANY),
+    p("transition-duration", &[Time]), p("transition-delay", &[Time]),
+    p("transition-timing-function", ANY), p("transition-behavior", ANY),
+    p("animation", ANY), p("animation-name", ANY),
+    p("animation-duration", &[Time, Keywords(AUTO)]), p("animation-delay", &[Time]),
+    p("animation-timing-function", ANY), p("animation-iteration-count", &[Number, Keywords(&["infinite"])]),
+    p("animation-direction", &[Keywords(&["normal", "reverse", "alternate", "alternate-reverse"])]),
+    p("animation-fill-mode", &[Keywords(&["none", "forwards", "backwards", "both"])]),
+    p("animation-play-state", &[Keywords(&["running", "paused"])]),
+    p("will-change", ANY),
+
+    // Interaction
+    p("cursor", &[Image, Number, Keywords(CURSOR)]),
+    p("pointer-events", &[Keywords(&["auto", "none", "all", "visible", "visiblepainted", "visiblefill", "visiblestroke", "painted", "fill", "stroke", "bounding-box"])]),
+    p("user-select", &[Keywords(&["auto", "text", "none", "contain", "all"])]),
+    p("touch-action", ANY),
+    p("resize", &[Keywords(&["none", "both", "horizontal", "vertical", "block", "inline"])]),
+    p("scroll-behavior", &[Keywords(&["auto", "smooth"])]),
+    p("scroll-snap-type", ANY), p("scroll-snap-align", ANY),
+    p("scroll-margin", LENGTH), p("scroll-padding", LENGTH_AUTO),
+    p("appearance", ANY),
+    p("accent-color", &[ValueKind::Color, Keywords(AUTO)]), p("caret-color", &[ValueKind::Color, Keywords(AUTO)]),
+
+    // SVG
+    p("fill", &[ValueKind::Color, Image, Keywords(&["none", "context-fill", "context-stroke"])]),
+    p("stroke", &[ValueKind::Color, Image, Keywords(&["none", "context-fill", "context-stroke"])]),
+    p("stroke-width", &[Length, Percentage, Number]),
+    p("stroke-linecap", &[Keywords(&["butt", "round", "square"])]),
+    p("stroke-linejoin", &[Keywords(&["miter", "round", "bevel", "arcs", "miter-clip"])]),
+    p("stroke-dasharray", &[Length, Percentage, Number, Keywords(NONE)]),
+    p("stroke-dashoffset", &[Length, Percentage, Number]),
+];
+
+/// What a known property accepts (`None` for unknown properties)
+pub fn property_info(property: &str) -> Option<&'static PropertyInfo> {
+    let property = property.to_ascii_lowercase();
+    PROPERTIES.iter().find(|info| info.name == property)
+}
+
+/// Whether `property` is known. Custom properties (`--x`) and vendor-prefixed ones always are.
+pub fn is_known_property(property: &str) -> bool {
+    property.starts_with("--") || property.starts_with('-') || property_info(property).is_some()
+}
+
+/// Closest known property name, for "did you mean" hints
+pub fn suggest_property(property: &str) -> Option<&'static str> {
+    let property = property.to_ascii_lowercase();
+    let max_distance = if property.len() <= 4 { 1 } else { 2 };
+    PROPERTIES
+        .iter()
+        .map(|info| (edit_distance(&property, info.name), info.name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Parse `value` and check it against what `property` accepts
+pub fn validate_declaration(property: &str, value: &str) -> Result<CssValue, DeclarationError> {
+    let invalid = |reason: String| DeclarationError::InvalidValue {
+        property: property.to_string(),
+        value: value.to_string(),
+        reason,
+    };
+
+    let parsed = parse_css_value(value).map_err(|err| invalid(describe(&err)))?;
+
+    if !is_known_property(property) {
+        return Err(DeclarationError::UnknownProperty {
+            property: property.to_string(),
+            suggestion: suggest_property(property),
+        });
+    }
+    let Some(info) = property_info(property) else {
+        return Ok(parsed);
+    };
+
+    if let [ValuePart::Keyword(keyword)] = parsed.parts.as_slice() {
+        if is_css_wide(keyword) {
+            return Ok(parsed);
+        }
+    }
+
+    for (i, part) in parsed.parts.iter().enumerate() {
+        if accepts(info.accepts, part) {
+            continue;
+        }
+
+        // `8 pz`: the number lost its unit to a typo
+        if let (ValuePart::Number(_), Some(ValuePart::Keyword(unit))) = (part, parsed.parts.get(i + 1)) {
+            return Err(invalid(format!("unknown unit '{}'", unit)));
+        }
+        return Err(invalid(match part {
+            ValuePart::Number(n) if accepts(info.accepts, &ValuePart::Dimension {
+                value: *n,
+                unit: "px".to_string(),
+                kind: UnitKind::Length,
+            }) => format!("'{}' needs a unit", part),
+            ValuePart::Keyword(keyword) => format!("unexpected keyword '{}'", keyword),
+            _ => format!("'{}' isn't allowed here", part),
+        }));
+    }
+
+    Ok(parsed)
+}
+
+fn describe(err: &ValueError) -> String {
+    match err {
+        ValueError::UnknownUnit { unit, .. } => format!("unknown unit '{}'", unit),
+        other => other.to_string(),
+    }
+}
+
+fn is_css_wide(keyword: &str) -> bool {
+    CSS_WIDE_KEYWORDS
+        .iter()
+        .any(|k| k.eq_ignore_ascii_case(keyword))
+}
+
+fn accepts(kinds: &[ValueKind], part: &ValuePart) -> bool {
+    if kinds.contains(&Any) {
+        return true;
+    }
+
+    match part {
+        ValuePart::Comma | ValuePart::Slash | ValuePart::Operator(_) | ValuePart::Var { .. } => true,
+        ValuePart::Number(n) => {
+            kinds.contains(&Number) || (*n == 0.0 && kinds.contains(&Length))
+        }
+        ValuePart::Percentage(_) => kinds.contains(&Percentage),
+        ValuePart::Dimension { kind, .. } => match kind {
+            UnitKind::Length => kinds.contains(&Length),
+            UnitKind::Angle => kinds.contains(&Angle),
+            UnitKind::Time => kinds.contains(&Time),
+            _ => false,
+        },
+        ValuePart::Color(_) => kinds.contains(&ValueKind::Color),
+        ValuePart::String(_) => kinds.contains(&Text),
+        ValuePart::Keyword(keyword) => kinds.iter().any(|kind| match kind {
+            Keywords(keywords) => keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword)),
+            _ => false,
+        }),
+        ValuePart::Function { name, .. } => {
+            let name = name.to_ascii_lowercase();
+            if MATH_FUNCTIONS.contains(&name.as_str()) {
+                kinds
+                    .iter()
+                    .any(|kind| matches!(kind, Length | Percentage | Number | Angle | Time))
+            } else if IMAGE_FUNCTIONS.contains(&name.as_str()) {
+                kinds.contains(&Image)
+            } else {
+                false
+            }
+        }
+    }
+}
+
+impl ValueKind {
+    /// Short name for messages and tooling (`length`, `color`, ...)
+    pub fn label(&self) -> &'static str {
+        match self {
+            Length => "length",
+            Percentage => "percentage",
+            Number => "number",
+            ValueKind::Color => "color",
+            Angle => "angle",
+            Time => "time",
+            Image => "image",
+            Text => "string",
+            Keywords(_) => "keyword",
+            Any => "any",
+        }
+    }
+}
+
+impl PropertyInfo {
+    /// Keywords the property accepts (for completions)
+    pub fn keywords(&self) -> Vec<&'static str> {
+        self.accepts
+            .iter()
+            .flat_map(|kind| match kind {
+                Keywords(keywords) => keywords.to_vec(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_declarations() {
+        for (property, value) in [
+            ("color", "red"),
+            ("padding", "8px 16px"),
+            ("margin", "0 auto"),
+            ("border", "1px solid #ddd"),
+            ("width", "calc(100% - 8px)"),
+            ("background", "url(./a.png) no-repeat"),
+            ("font-weight", "600"),
+            ("display", "inherit"),
+            ("padding", "var(spacing)"),
+            ("--brand", "anything at all"),
+            ("-webkit-line-clamp", "3"),
+            ("transition", "opacity 0.3 s ease"),
+        ] {
+            assert!(
+                validate_declaration(property, value).is_ok(),
+                "{}: {} should be valid: {:?}",
+                property,
+                value,
+                validate_declaration(property, value)
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_property_suggests_fix() {
+        assert_eq!(
+            validate_declaration("colr", "red"),
+            Err(DeclarationError::UnknownProperty {
+                property: "colr".to_string(),
+                suggestion: Some("color"),
+            })
+        );
+        assert_eq!(suggest_property("backgrond-color"), Some("background-color"));
+        assert_eq!(suggest_property("zzzzzz"), None);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let reason = |property: &str, value: &str| match validate_declaration(property, value) {
+            Err(DeclarationError::InvalidValue { reason, .. }) => reason,
+            other => panic!("expected invalid value, got {:?}", other),
+        };
+
+        assert_eq!(reason("padding", "8 pz"), "unknown unit 'pz'");
+        assert_eq!(reason("padding", "8pz"), "unknown unit 'pz'");
+        assert_eq!(reason("padding", "8"), "'8' needs a unit");
+        assert_eq!(reason("display", "flexbox"), "unexpected keyword 'flexbox'");
+        assert_eq!(reason("color", "10px"), "'10px' isn't allowed here");
+    }
+
+    #[test]
+    fn test_property_info_for_tooling() {
+        let info = property_info("Display").unwrap();
+        assert!(info.keywords().contains(&"flex"));
+        assert_eq!(property_info("color").unwrap().accepts[0].label(), "color");
+    }
+}
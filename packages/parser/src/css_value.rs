@@ -0,0 +1,718 @@
+//! Typed CSS values
+//!
+//! Style values stay strings in the AST; `parse_css_value` turns one into lengths, colors,
+//! keywords, functions and `var()` references for the linter and style tooling.
+//! See `css_properties` for the per-property rules.
+
+use std::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValueError {
+    #[error("Empty value")]
+    Empty,
+
+    #[error("Unknown unit '{unit}' in '{value}'")]
+    UnknownUnit { unit: String, value: String },
+
+    #[error("Invalid color '{color}'")]
+    InvalidColor { color: String },
+
+    #[error("Unexpected '{found}' at {position}")]
+    Unexpected { found: char, position: usize },
+
+    #[error("Missing ')' after '{name}('")]
+    UnclosedFunction { name: String },
+
+    #[error("Unterminated string")]
+    UnclosedString,
+}
+
+/// What a unit measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitKind {
+    Length,
+    Angle,
+    Time,
+    Frequency,
+    Resolution,
+    Flex,
+}
+
+const UNITS: &[(&str, UnitKind)] = &[
+    ("px", UnitKind::Length),
+    ("em", UnitKind::Length),
+    ("rem", UnitKind::Length),
+    ("ex", UnitKind::Length),
+    ("ch", UnitKind::Length),
+    ("cap", UnitKind::Length),
+    ("ic", UnitKind::Length),
+    ("lh", UnitKind::Length),
+    ("rlh", UnitKind::Length),
+    ("vw", UnitKind::Length),
+    ("vh", UnitKind::Length),
+    ("vi", UnitKind::Length),
+    ("vb", UnitKind::Length),
+    ("vmin", UnitKind::Length),
+    ("vmax", UnitKind::Length),
+    ("svw", UnitKind::Length),
+    ("svh", UnitKind::Length),
+    ("lvw", UnitKind::Length),
+    ("lvh", UnitKind::Length),
+    ("dvw", UnitKind::Length),
+    ("dvh", UnitKind::Length),
+    ("cqw", UnitKind::Length),
+    ("cqh", UnitKind::Length),
+    ("cqi", UnitKind::Length),
+    ("cqb", UnitKind::Length),
+    ("cqmin", UnitKind::Length),
+    ("cqmax", UnitKind::Length),
+    ("cm", UnitKind::Length),
+    ("mm", UnitKind::Length),
+    ("q", UnitKind::Length),
+    ("in", UnitKind::Length),
+    ("pt", UnitKind::Length),
+    ("pc", UnitKind::Length),
+    ("deg", UnitKind::Angle),
+    ("grad", UnitKind::Angle),
+    ("rad", UnitKind::Angle),
+    ("turn", UnitKind::Angle),
+    ("s", UnitKind::Time),
+    ("ms", UnitKind::Time),
+    ("hz", UnitKind::Frequency),
+    ("khz", UnitKind::Frequency),
+    ("dpi", UnitKind::Resolution),
+    ("dpcm", UnitKind::Resolution),
+    ("dppx", UnitKind::Resolution),
+    ("x", UnitKind::Resolution),
+    ("fr", UnitKind::Flex),
+];
+
+/// Kind of a CSS unit (case-insensitive), if it is one
+pub fn unit_kind(unit: &str) -> Option<UnitKind> {
+    let unit = unit.to_ascii_lowercase();
+    UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, kind)| *kind)
+}
+
+#[rustfmt::skip]
+const NAMED_COLORS: &[&str] = &[
+    "aliceblue", "antiquewhite", "aqua", "aquamarine", "azure", "beige", "bisque", "black",
+    "blanchedalmond", "blue", "blueviolet", "brown", "burlywood", "cadetblue", "chartreuse",
+    "chocolate", "coral", "cornflowerblue", "cornsilk", "crimson", "cyan", "darkblue", "darkcyan",
+    "darkgoldenrod", "darkgray", "darkgreen", "darkgrey", "darkkhaki", "darkmagenta",
+    "darkolivegreen", "darkorange", "darkorchid", "darkred", "darksalmon", "darkseagreen",
+    "darkslateblue", "darkslategray", "darkslategrey", "darkturquoise", "darkviolet", "deeppink",
+    "deepskyblue", "dimgray", "dimgrey", "dodgerblue", "firebrick", "floralwhite", "forestgreen",
+    "fuchsia", "gainsboro", "ghostwhite", "gold", "goldenrod", "gray", "green", "greenyellow",
+    "grey", "honeydew", "hotpink", "indianred", "indigo", "ivory", "khaki", "lavender",
+    "lavenderblush", "lawngreen", "lemonchiffon", "lightblue", "lightcoral", "lightcyan",
+    "lightgoldenrodyellow", "lightgray", "lightgreen", "lightgrey", "lightpink", "lightsalmon",
+    "lightseagreen", "lightskyblue", "lightslategray", "lightslategrey", "lightsteelblue",
+    "lightyellow", "lime", "limegreen", "linen", "magenta", "maroon", "mediumaquamarine",
+    "mediumblue", "mediumorchid", "mediumpurple", "mediumseagreen", "mediumslateblue",
+    "mediumspringgreen", "mediumturquoise", "mediumvioletred", "midnightblue", "mintcream",
+    "mistyrose", "moccasin", "navajowhite", "navy", "oldlace", "olive", "olivedrab", "orange",
+    "orangered", "orchid", "palegoldenrod", "palegreen", "paleturquoise", "palevioletred",
+    "papayawhip", "peachpuff", "peru", "pink", "plum", "powderblue", "purple", "rebeccapurple",
+    "red", "rosybrown", "royalblue", "saddlebrown", "salmon", "sandybrown", "seagreen",
+    "seashell", "sienna", "silver", "skyblue", "slateblue", "slategray", "slategrey", "snow",
+    "springgreen", "steelblue", "tan", "teal", "thistle", "tomato", "turquoise", "violet",
+    "wheat", "white", "whitesmoke", "yellow", "yellowgreen", "transparent", "currentcolor",
+];
+
+const COLOR_FUNCTIONS: &[&str] = &[
+    "rgb", "rgba", "hsl", "hsla", "hwb", "lab", "lch", "oklab", "oklch", "color", "color-mix",
+    "light-dark",
+];
+
+/// Functions whose result is a number, length, percentage, angle or time
+pub const MATH_FUNCTIONS: &[&str] = &[
+    "calc", "min", "max", "clamp", "round", "mod", "rem", "abs", "sign", "env",
+];
+
+/// Functions that produce an image
+pub const IMAGE_FUNCTIONS: &[&str] = &[
+    "url",
+    "image-set",
+    "-webkit-image-set",
+    "cross-fade",
+    "element",
+    "linear-gradient",
+    "radial-gradient",
+    "conic-gradient",
+    "repeating-linear-gradient",
+    "repeating-radial-gradient",
+    "repeating-conic-gradient",
+];
+
+/// Keywords every property accepts
+pub const CSS_WIDE_KEYWORDS: &[&str] = &["inherit", "initial", "unset", "revert", "revert-layer"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Color {
+    /// `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+    Hex(String),
+    /// `red`, `transparent`, `currentColor`, ...
+    Named(String),
+    /// `rgb(...)`, `oklch(...)`, ...
+    Function { name: String, args: Vec<ValuePart> },
+}
+
+/// One component of a declaration value
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValuePart {
+    Number(f64),
+    Percentage(f64),
+    Dimension {
+        value: f64,
+        unit: String,
+        kind: UnitKind,
+    },
+    Color(Color),
+    Keyword(String),
+    String(String),
+    Function {
+        name: String,
+        args: Vec<ValuePart>,
+    },
+    /// `var(--custom)` or a token reference `var(theme.primary)`
+    Var {
+        name: String,
+        fallback: Option<Vec<ValuePart>>,
+    },
+    Comma,
+    Slash,
+    /// `+`, `-` or `*` inside math functions
+    Operator(char),
+}
+
+impl ValuePart {
+    /// Numeric value of numbers, percentages and dimensions
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            ValuePart::Number(value) | ValuePart::Percentage(value) => Some(*value),
+            ValuePart::Dimension { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Visit this part and every part nested in functions and `var()` fallbacks
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a ValuePart)) {
+        visit(self);
+        match self {
+            ValuePart::Function { args, .. }
+            | ValuePart::Color(Color::Function { args, .. }) => {
+                args.iter().for_each(|arg| arg.walk(visit));
+            }
+            ValuePart::Var {
+                fallback: Some(fallback),
+                ..
+            } => fallback.iter().for_each(|part| part.walk(visit)),
+            _ => {}
+        }
+    }
+}
+
+/// Parsed declaration value
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssValue {
+    pub parts: Vec<ValuePart>,
+    pub important: bool,
+}
+
+impl CssValue {
+    /// Visit every part, including those nested in functions
+    pub fn walk<'a>(&'a self, mut visit: impl FnMut(&'a ValuePart)) {
+        for part in &self.parts {
+            part.walk(&mut visit);
+        }
+    }
+
+    /// Whether the value references a custom property or token (so its type isn't known)
+    pub fn has_var(&self) -> bool {
+        let mut found = false;
+        self.walk(|part| found |= matches!(part, ValuePart::Var { .. }));
+        found
+    }
+}
+
+/// Parse a declaration value such as `1px solid var(border)` or `calc(100% - 8px)`.
+///
+/// A number followed by a separate unit (`45 deg`, as the `.pc` parser joins tokens)
+/// is read as one dimension.
+pub fn parse_css_value(value: &str) -> Result<CssValue, ValueError> {
+    let mut parser = ValueParser {
+        source: value,
+        chars: value.char_indices().collect(),
+        pos: 0,
+    };
+    let (parts, important) = parser.parse_top_level()?;
+    if parts.is_empty() {
+        return Err(ValueError::Empty);
+    }
+    Ok(CssValue { parts, important })
+}
+
+struct ValueParser<'a> {
+    source: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+}
+
+impl ValueParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|(_, c)| *c)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).map(|(_, c)| *c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn unexpected(&self) -> ValueError {
+        match self.chars.get(self.pos) {
+            Some((position, found)) => ValueError::Unexpected {
+                found: *found,
+                position: *position,
+            },
+            None => ValueError::Unexpected {
+                found: ' ',
+                position: self.source.len(),
+            },
+        }
+    }
+
+    fn parse_top_level(&mut self) -> Result<(Vec<ValuePart>, bool), ValueError> {
+        let parts = self.parse_parts(None)?;
+        self.skip_whitespace();
+
+        let important = if self.peek() == Some('!') {
+            self.pos += 1;
+            self.skip_whitespace();
+            let word = self.read_ident();
+            if !word.eq_ignore_ascii_case("important") {
+                return Err(self.unexpected());
+            }
+            true
+        } else {
+            false
+        };
+
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return Err(self.unexpected());
+        }
+        Ok((parts, important))
+    }
+
+    /// Parse parts until `)` (inside `function`) or the end / `!important`
+    fn parse_parts(&mut self, function: Option<&str>) -> Result<Vec<ValuePart>, ValueError> {
+        let mut parts = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                return match function {
+                    Some(name) => Err(ValueError::UnclosedFunction {
+                        name: name.to_string(),
+                    }),
+                    None => Ok(parts),
+                };
+            };
+
+            match c {
+                ')' if function.is_some() => {
+                    self.pos += 1;
+                    return Ok(parts);
+                }
+                '!' if function.is_none() => return Ok(parts),
+                ',' => {
+                    self.pos += 1;
+                    parts.push(ValuePart::Comma);
+                }
+                '/' => {
+                    self.pos += 1;
+                    parts.push(ValuePart::Slash);
+                }
+                '+' | '*' => {
+                    self.pos += 1;
+                    parts.push(ValuePart::Operator(c));
+                }
+                '-' if self.starts_number() => parts.push(self.parse_numeric()?),
+                '-' if self.starts_ident() => parts.push(self.parse_ident_or_function()?),
+                '-' => {
+                    self.pos += 1;
+                    parts.push(ValuePart::Operator('-'));
+                }
+                '#' => parts.push(self.parse_hex()?),
+                '"' | '\'' => parts.push(ValuePart::String(self.read_string(c)?)),
+                c if c.is_ascii_digit() || (c == '.' && self.starts_number()) => {
+                    parts.push(self.parse_numeric()?);
+                }
+                c if c.is_alphabetic() || c == '_' => parts.push(self.parse_ident_or_function()?),
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn starts_number(&self) -> bool {
+        let mut offset = 0;
+        if matches!(self.peek(), Some('-' | '+')) {
+            offset += 1;
+        }
+        match self.peek_at(offset) {
+            Some(c) if c.is_ascii_digit() => true,
+            Some('.') => self.peek_at(offset + 1).is_some_and(|c| c.is_ascii_digit()),
+            _ => false,
+        }
+    }
+
+    fn starts_ident(&self) -> bool {
+        match self.peek_at(1) {
+            Some('-') => true,
+            Some(c) => c.is_alphabetic() || c == '_',
+            None => false,
+        }
+    }
+
+    fn read_ident(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().map(|(_, c)| c).collect()
+    }
+
+    fn parse_numeric(&mut self) -> Result<ValuePart, ValueError> {
+        let start = self.pos;
+        if matches!(self.peek(), Some('-' | '+')) {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().map(|(_, c)| c).collect();
+        let value: f64 = text.parse().map_err(|_| ValueError::Unexpected {
+            found: text.chars().last().unwrap_or(' '),
+            position: self.chars[start].0,
+        })?;
+
+        if self.peek() == Some('%') {
+            self.pos += 1;
+            return Ok(ValuePart::Percentage(value));
+        }
+
+        if self.peek().is_some_and(char::is_alphabetic) {
+            let unit = self.read_ident();
+            return match unit_kind(&unit) {
+                Some(kind) => Ok(ValuePart::Dimension { value, unit, kind }),
+                None => Err(ValueError::UnknownUnit {
+                    value: format!("{}{}", text, unit),
+                    unit,
+                }),
+            };
+        }
+
+        // `45 deg`: the .pc tokenizer splits units it doesn't know and joins with a space
+        let checkpoint = self.pos;
+        self.skip_whitespace();
+        if self.peek().is_some_and(char::is_alphabetic) {
+            let unit = self.read_ident();
+            if let Some(kind) = unit_kind(&unit) {
+                return Ok(ValuePart::Dimension { value, unit, kind });
+            }
+        }
+        self.pos = checkpoint;
+
+        Ok(ValuePart::Number(value))
+    }
+
+    fn parse_hex(&mut self) -> Result<ValuePart, ValueError> {
+        let start = self.pos;
+        self.pos += 1;
+        while self.peek().is_some_and(|c| c.is_alphanumeric()) {
+            self.pos += 1;
+        }
+        let color: String = self.chars[start..self.pos].iter().map(|(_, c)| c).collect();
+        let digits = &color[1..];
+        let valid = matches!(digits.len(), 3 | 4 | 6 | 8)
+            && digits.chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            return Err(ValueError::InvalidColor { color });
+        }
+        Ok(ValuePart::Color(Color::Hex(color)))
+    }
+
+    fn read_string(&mut self, quote: char) -> Result<String, ValueError> {
+        self.pos += 1;
+        let mut content = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '\\' => {
+                    if let Some(escaped) = self.peek() {
+                        content.push(escaped);
+                        self.pos += 1;
+                    }
+                }
+                c if c == quote => return Ok(content),
+                c => content.push(c),
+            }
+        }
+        Err(ValueError::UnclosedString)
+    }
+
+    fn parse_ident_or_function(&mut self) -> Result<ValuePart, ValueError> {
+        let name = self.read_ident();
+        if self.peek() != Some('(') {
+            let lower = name.to_ascii_lowercase();
+            return Ok(if NAMED_COLORS.contains(&lower.as_str()) {
+                ValuePart::Color(Color::Named(name))
+            } else {
+                ValuePart::Keyword(name)
+            });
+        }
+        self.pos += 1;
+
+        let lower = name.to_ascii_lowercase();
+        match lower.as_str() {
+            "url" => Ok(ValuePart::Function {
+                args: vec![ValuePart::String(self.read_url()?)],
+                name,
+            }),
+            "var" => {
+                let mut args = self.parse_parts(Some(&name))?;
+                let fallback = args
+                    .iter()
+                    .position(|p| *p == ValuePart::Comma)
+                    .map(|comma| args.split_off(comma + 1));
+                match args.first() {
+                    Some(ValuePart::Keyword(var)) => Ok(ValuePart::Var {
+                        name: var.clone(),
+                        fallback,
+                    }),
+                    _ => Err(ValueError::UnclosedFunction { name }),
+                }
+            }
+            _ => {
+                let args = self.parse_parts(Some(&name))?;
+                if COLOR_FUNCTIONS.contains(&lower.as_str()) {
+                    Ok(ValuePart::Color(Color::Function { name, args }))
+                } else {
+                    Ok(ValuePart::Function { name, args })
+                }
+            }
+        }
+    }
+
+    /// `url(...)` contents may be quoted or a bare path
+    fn read_url(&mut self) -> Result<String, ValueError> {
+        self.skip_whitespace();
+        let url = match self.peek() {
+            Some(quote @ ('"' | '\'')) => self.read_string(quote)?,
+            _ => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c != ')') {
+                    self.pos += 1;
+                }
+                let raw: String = self.chars[start..self.pos].iter().map(|(_, c)| c).collect();
+                raw.trim().to_string()
+            }
+        };
+        self.skip_whitespace();
+        if self.peek() != Some(')') {
+            return Err(ValueError::UnclosedFunction {
+                name: "url".to_string(),
+            });
+        }
+        self.pos += 1;
+        Ok(url)
+    }
+}
+
+fn fmt_number(f: &mut fmt::Formatter<'_>, value: f64) -> fmt::Result {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        write!(f, "{}", value as i64)
+    } else {
+        write!(f, "{}", value)
+    }
+}
+
+fn fmt_parts(f: &mut fmt::Formatter<'_>, parts: &[ValuePart]) -> fmt::Result {
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 && *part != ValuePart::Comma {
+            f.write_str(" ")?;
+        }
+        write!(f, "{}", part)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ValuePart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValuePart::Number(value) => fmt_number(f, *value),
+            ValuePart::Percentage(value) => {
+                fmt_number(f, *value)?;
+                f.write_str("%")
+            }
+            ValuePart::Dimension { value, unit, .. } => {
+                fmt_number(f, *value)?;
+                f.write_str(unit)
+            }
+            ValuePart::Color(Color::Hex(color)) | ValuePart::Color(Color::Named(color)) => {
+                f.write_str(color)
+            }
+            ValuePart::Keyword(keyword) => f.write_str(keyword),
+            ValuePart::String(string) => write!(f, "\"{}\"", string.replace('"', "\\\"")),
+            ValuePart::Color(Color::Function { name, args })
+            | ValuePart::Function { name, args } => {
+                write!(f, "{}(", name)?;
+                fmt_parts(f, args)?;
+                f.write_str(")")
+            }
+            ValuePart::Var { name, fallback } => {
+                write!(f, "var({}", name)?;
+                if let Some(fallback) = fallback {
+                    f.write_str(", ")?;
+                    fmt_parts(f, fallback)?;
+                }
+                f.write_str(")")
+            }
+            ValuePart::Comma => f.write_str(","),
+            ValuePart::Slash => f.write_str("/"),
+            ValuePart::Operator(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+impl fmt::Display for CssValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_parts(f, &self.parts)?;
+        if self.important {
+            f.write_str(" !important")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(value: f64) -> ValuePart {
+        ValuePart::Dimension {
+            value,
+            unit: "px".to_string(),
+            kind: UnitKind::Length,
+        }
+    }
+
+    #[test]
+    fn test_parse_lengths_colors_keywords() {
+        let value = parse_css_value("1px solid #FF0000").unwrap();
+        assert_eq!(
+            value.parts,
+            vec![
+                px(1.0),
+                ValuePart::Keyword("solid".to_string()),
+                ValuePart::Color(Color::Hex("#FF0000".to_string())),
+            ]
+        );
+
+        let value = parse_css_value("-8px 50% 0 red").unwrap();
+        assert_eq!(value.parts[0], px(-8.0));
+        assert_eq!(value.parts[1], ValuePart::Percentage(50.0));
+        assert_eq!(value.parts[2], ValuePart::Number(0.0));
+        assert_eq!(value.parts[3], ValuePart::Color(Color::Named("red".to_string())));
+    }
+
+    #[test]
+    fn test_parse_functions_and_vars() {
+        let value = parse_css_value("calc(100% - 8px)").unwrap();
+        assert_eq!(
+            value.parts,
+            vec![ValuePart::Function {
+                name: "calc".to_string(),
+                args: vec![ValuePart::Percentage(100.0), ValuePart::Operator('-'), px(8.0)],
+            }]
+        );
+
+        let value = parse_css_value("var(theme.primary, #fff)").unwrap();
+        assert_eq!(
+            value.parts,
+            vec![ValuePart::Var {
+                name: "theme.primary".to_string(),
+                fallback: Some(vec![ValuePart::Color(Color::Hex("#fff".to_string()))]),
+            }]
+        );
+        assert!(value.has_var());
+
+        let value = parse_css_value("url(./image.png)").unwrap();
+        assert_eq!(value.to_string(), "url(\"./image.png\")");
+
+        let value = parse_css_value("rgba(0, 0, 0, 0.5)").unwrap();
+        assert!(matches!(value.parts[0], ValuePart::Color(Color::Function { .. })));
+    }
+
+    #[test]
+    fn test_split_units_are_joined() {
+        // How `rotate(45deg)` arrives from the .pc parser
+        let value = parse_css_value("rotate( 45 deg)").unwrap();
+        let ValuePart::Function { args, .. } = &value.parts[0] else {
+            panic!("expected a function");
+        };
+        assert!(matches!(
+            args[0],
+            ValuePart::Dimension {
+                kind: UnitKind::Angle,
+                ..
+            }
+        ));
+
+        // Not a unit: stays a number and a keyword
+        let value = parse_css_value("8 pz").unwrap();
+        assert_eq!(value.parts[0], ValuePart::Number(8.0));
+        assert_eq!(value.parts[1], ValuePart::Keyword("pz".to_string()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_css_value("8pz"),
+            Err(ValueError::UnknownUnit {
+                unit: "pz".to_string(),
+                value: "8pz".to_string()
+            })
+        );
+        assert!(matches!(
+            parse_css_value("#ggg"),
+            Err(ValueError::InvalidColor { .. })
+        ));
+        assert!(matches!(
+            parse_css_value("calc(1px"),
+            Err(ValueError::UnclosedFunction { .. })
+        ));
+        assert_eq!(parse_css_value("  "), Err(ValueError::Empty));
+    }
+
+    #[test]
+    fn test_important_and_display() {
+        let value = parse_css_value("red !important").unwrap();
+        assert!(value.important);
+        assert_eq!(value.to_string(), "red !important");
+
+        let value = parse_css_value("Inter,sans-serif").unwrap();
+        assert_eq!(value.to_string(), "Inter, sans-serif");
+    }
+}
@@ -1,4 +1,37 @@
 use crc32fast::Hasher;
+use std::path::Path;
+
+/// How a document's identity is derived from its file path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DocumentIdMode {
+    /// Path relative to the project root, so IDs match across checkouts
+    #[default]
+    Relative,
+    /// Absolute path as given (legacy; IDs change when the repo moves)
+    Absolute,
+}
+
+/// Path that identifies a document - feed it to `parse_with_path` and `get_document_id`.
+///
+/// In `Relative` mode this is the path below `project_root` with `/` separators and a
+/// leading `/` (e.g. `/src/button.pc`). Files outside the root keep their path as given.
+pub fn document_path(path: &Path, project_root: &Path, mode: DocumentIdMode) -> String {
+    let relative = match mode {
+        DocumentIdMode::Relative => path.strip_prefix(project_root).ok(),
+        DocumentIdMode::Absolute => None,
+    };
+
+    match relative {
+        Some(relative) => {
+            let parts: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect();
+            format!("/{}", parts.join("/"))
+        }
+        None => path.to_string_lossy().into_owned(),
+    }
+}
 
 /// Generate document ID from file path using CRC32
 pub fn get_document_id(path: &str) -> String {
@@ -60,6 +93,36 @@ mod tests {
         assert_ne!(id1, id3);
     }
 
+    #[test]
+    fn test_document_path_is_checkout_independent() {
+        let a = document_path(
+            Path::new("/home/alice/app/src/button.pc"),
+            Path::new("/home/alice/app"),
+            DocumentIdMode::Relative,
+        );
+        let b = document_path(
+            Path::new("/ci/build/123/src/button.pc"),
+            Path::new("/ci/build/123"),
+            DocumentIdMode::Relative,
+        );
+        assert_eq!(a, "/src/button.pc");
+        assert_eq!(a, b);
+
+        // Outside the root, and in legacy mode, the path is used as-is
+        let outside = document_path(
+            Path::new("/elsewhere/x.pc"),
+            Path::new("/home/alice/app"),
+            DocumentIdMode::Relative,
+        );
+        assert_eq!(outside, "/elsewhere/x.pc");
+        let legacy = document_path(
+            Path::new("/home/alice/app/src/button.pc"),
+            Path::new("/home/alice/app"),
+            DocumentIdMode::Absolute,
+        );
+        assert_eq!(legacy, "/home/alice/app/src/button.pc");
+    }
+
     #[test]
     fn test_sequential_ids() {
         let mut gen = IDGenerator::new("/test.pc");
@@ -1,5 +1,7 @@
 pub mod annotation_parser;
 pub mod ast;
+pub mod css_properties;
+pub mod css_value;
 mod debug_test;
 pub mod error;
 pub mod id_generator;
@@ -20,8 +22,10 @@ mod test_comprehensive_example;
 #[cfg(test)]
 mod tests_serializer;
 
+pub use css_properties::{validate_declaration, DeclarationError, PropertyInfo, ValueKind};
+pub use css_value::{parse_css_value, CssValue, ValueError, ValuePart};
 pub use error::{ParseError, ParseResult};
-pub use id_generator::{get_document_id, IDGenerator};
+pub use id_generator::{document_path, get_document_id, DocumentIdMode, IDGenerator};
 pub use lossless_serializer::LosslessSerializer;
 pub use parser::{parse, parse_with_path, Parser};
 pub use serializer::{serialize, Serializer};
@@ -90,6 +90,14 @@ impl<'src> Parser<'src> {
                     pending_doc_comment = None;
                     doc.styles.push(self.parse_style_decl(false)?);
                 }
+                Some((Token::Ident("global"), _)) if self.is_global_styles() => {
+                    pending_doc_comment = None;
+                    doc.global_styles.extend(self.parse_global_styles()?);
+                }
+                Some((Token::Ident("css"), _)) if self.is_raw_css_block() => {
+                    pending_doc_comment = None;
+                    doc.raw_css.push(self.parse_raw_css_block()?);
+                }
                 Some((Token::Component, _)) => {
                     let mut component = self.parse_component(false)?;
                     // Attach doc comment and extract frame
@@ -180,14 +188,15 @@ impl<'src> Parser<'src> {
         self.expect(Token::TokenKeyword)?;
 
         let name = self.expect_ident()?;
-        let value = self.parse_token_value()?;
+        let expression = self.parse_token_value()?;
 
         let end = self.current_pos();
 
         Ok(TokenDecl {
             public,
             name,
-            value,
+            value: expression.to_source(),
+            expression,
             span: Span::new(start, end, self.id_generator.new_id()),
         })
     }
@@ -223,38 +232,100 @@ impl<'src> Parser<'src> {
         })
     }
 
-    /// Parse a token value (color, number, string, etc.)
-    fn parse_token_value(&mut self) -> ParseResult<String> {
-        match self.peek() {
-            Some((Token::Color(c), _)) => {
-                let val = c.to_string();
-                self.advance();
-                Ok(val)
+    /// Parse a token value: literals, references, arithmetic and function calls
+    ///
+    /// Examples: `#3366FF`, `primaryColor`, `theme.primary`, `spacing * 2`,
+    /// `alpha(primaryColor, 0.5)`, `Inter, sans-serif`
+    fn parse_token_value(&mut self) -> ParseResult<TokenValue> {
+        let value = self.parse_token_additive()?;
+
+        // Support multi-word values like "Inter, sans-serif"
+        if let TokenValue::Reference { name } = &value {
+            if !name.contains('.') && self.match_token(Token::Comma) {
+                let next = self.expect_ident()?;
+                return Ok(TokenValue::Literal {
+                    value: format!("{}, {}", name, next),
+                });
             }
-            Some((Token::Number(n), _)) => {
-                let val = n.to_string();
-                self.advance();
-                Ok(val)
-            }
-            Some((Token::String(s), _)) => {
-                let val = s.to_string();
-                self.advance();
-                Ok(val)
-            }
-            Some((Token::CssUnit(u), _)) => {
-                let val = u.to_string();
+        }
+
+        Ok(value)
+    }
+
+    fn parse_token_additive(&mut self) -> ParseResult<TokenValue> {
+        let mut left = self.parse_token_multiplicative()?;
+
+        while let Some(operator) = self.match_additive_op() {
+            let right = self.parse_token_multiplicative()?;
+            left = TokenValue::Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn parse_token_multiplicative(&mut self) -> ParseResult<TokenValue> {
+        let mut left = self.parse_token_primary()?;
+
+        while let Some(operator) = self.match_multiplicative_op() {
+            let right = self.parse_token_primary()?;
+            left = TokenValue::Binary {
+                left: Box::new(left),
+                operator,
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn parse_token_primary(&mut self) -> ParseResult<TokenValue> {
+        match self.peek() {
+            Some((Token::Color(v), _))
+            | Some((Token::Number(v), _))
+            | Some((Token::String(v), _))
+            | Some((Token::CssUnit(v), _)) => {
+                let value = v.to_string();
                 self.advance();
-                Ok(val)
+                Ok(TokenValue::Literal { value })
             }
             Some((Token::Ident(i), _)) => {
-                let val = i.to_string();
+                let name = i.to_string();
                 self.advance();
-                // Support multi-word values like "Inter, sans-serif"
-                while self.match_token(Token::Comma) {
-                    let next = self.expect_ident()?;
-                    return Ok(format!("{}, {}", val, next));
+
+                if self.match_token(Token::LParen) {
+                    let mut arguments = Vec::new();
+                    while !self.check(Token::RParen) && !self.is_at_end() {
+                        arguments.push(self.parse_token_additive()?);
+                        if !self.match_token(Token::Comma) {
+                            break;
+                        }
+                    }
+                    self.expect(Token::RParen)?;
+                    return Ok(TokenValue::Call {
+                        function: name,
+                        arguments,
+                    });
                 }
-                Ok(val)
+
+                // Namespaced reference like "theme.primary"
+                if self.match_token(Token::Dot) {
+                    let member = self.expect_ident()?;
+                    return Ok(TokenValue::Reference {
+                        name: format!("{}.{}", name, member),
+                    });
+                }
+
+                Ok(TokenValue::Reference { name })
+            }
+            Some((Token::LParen, _)) => {
+                self.advance();
+                let value = self.parse_token_additive()?;
+                self.expect(Token::RParen)?;
+                Ok(value)
             }
             _ => Err(ParseError::invalid_syntax_span(
                 self.peek_span(),
@@ -294,9 +365,93 @@ impl<'src> Parser<'src> {
         })
     }
 
+    /// Parse global (unscoped) styles
+    ///
+    /// `global style body { ... }` declares a single rule, while
+    /// `global { body { ... } "a:hover" { ... } }` groups several.
+    fn parse_global_styles(&mut self) -> ParseResult<Vec<GlobalStyle>> {
+        self.advance(); // consume 'global'
+
+        if self.match_token(Token::Style) {
+            return Ok(vec![self.parse_global_style_rule()?]);
+        }
+
+        self.expect(Token::LBrace)?;
+        let mut rules = Vec::new();
+        while !self.check(Token::RBrace) && !self.is_at_end() {
+            rules.push(self.parse_global_style_rule()?);
+        }
+        self.expect(Token::RBrace)?;
+
+        Ok(rules)
+    }
+
+    /// Parse `selector { properties }` where selector is an element name or a string
+    fn parse_global_style_rule(&mut self) -> ParseResult<GlobalStyle> {
+        let start = self.current_pos();
+
+        let selector = match self.peek() {
+            Some((Token::String(_), _)) => self.expect_string()?,
+            Some((Token::Star, _)) => {
+                self.advance();
+                "*".to_string()
+            }
+            _ => self.expect_ident_or_element_keyword()?,
+        };
+
+        self.expect(Token::LBrace)?;
+        let properties = self.parse_style_properties()?;
+        self.expect(Token::RBrace)?;
+
+        let end = self.current_pos();
+
+        Ok(GlobalStyle {
+            selector,
+            properties,
+            span: Span::new(start, end, self.id_generator.new_id()),
+        })
+    }
+
+    /// `global` is only a keyword before `style` or `{`, so it stays usable as a name
+    fn is_global_styles(&self) -> bool {
+        matches!(
+            self.peek_ahead(1),
+            Some((Token::Style, _)) | Some((Token::LBrace, _))
+        )
+    }
+
+    /// `css` followed by a triple-quoted string starts a raw CSS block
+    /// (anything else named `css` is left to the element parser)
+    fn is_raw_css_block(&self) -> bool {
+        matches!(self.peek_ahead(1), Some((Token::TripleString(_), _)))
+    }
+
+    /// Parse a raw CSS block: css """ ... """
+    fn parse_raw_css_block(&mut self) -> ParseResult<RawCssBlock> {
+        let start = self.current_pos();
+        self.advance(); // consume 'css'
+
+        let source = match self.advance() {
+            Some((Token::TripleString(s), _)) => s[3..s.len() - 3].to_string(),
+            _ => {
+                return Err(ParseError::invalid_syntax_span(
+                    self.current_span(),
+                    "Expected triple-quoted CSS after 'css'",
+                ))
+            }
+        };
+
+        let end = self.current_pos();
+
+        Ok(RawCssBlock {
+            source,
+            span: Span::new(start, end, self.id_generator.new_id()),
+        })
+    }
+
     /// Parse style properties
-    fn parse_style_properties(&mut self) -> ParseResult<HashMap<String, String>> {
-        let mut properties = HashMap::new();
+    fn parse_style_properties(&mut self) -> ParseResult<Properties> {
+        let mut properties = Properties::new();
 
         while !self.check(Token::RBrace) && !self.is_at_end() {
             let prop_name = self.expect_ident()?;
@@ -407,6 +562,7 @@ impl<'src> Parser<'src> {
         let mut variants = Vec::new();
         let mut slots = Vec::new();
         let mut overrides = Vec::new();
+        let mut raw_css = Vec::new();
         let mut body = None;
 
         while !self.check(Token::RBrace) && !self.is_at_end() {
@@ -423,6 +579,9 @@ impl<'src> Parser<'src> {
                 Some((Token::Override, _)) => {
                     overrides.push(self.parse_override()?);
                 }
+                Some((Token::Ident("css"), _)) if self.is_raw_css_block() => {
+                    raw_css.push(self.parse_raw_css_block()?);
+                }
                 Some((Token::Render, _)) => {
                     self.advance();
                     body = Some(self.parse_element()?);
@@ -430,7 +589,7 @@ impl<'src> Parser<'src> {
                 _ => {
                     return Err(ParseError::invalid_syntax_span(
                         self.peek_span(),
-                        "Expected 'script', 'variant', 'slot', 'override', or 'render'",
+                        "Expected 'script', 'variant', 'slot', 'override', 'css', or 'render'",
                     ));
                 }
             }
@@ -449,6 +608,7 @@ impl<'src> Parser<'src> {
             variants,
             slots,
             overrides,
+            raw_css,
             body,
             span: Span::new(start, end, self.id_generator.new_id()),
         })
@@ -559,6 +719,7 @@ impl<'src> Parser<'src> {
         self.expect(Token::Slot)?;
 
         let name = self.expect_ident()?;
+        let params = self.parse_slot_params()?;
 
         let mut default_content = Vec::new();
         if self.match_token(Token::LBrace) {
@@ -572,11 +733,30 @@ impl<'src> Parser<'src> {
 
         Ok(Slot {
             name,
+            params,
             default_content,
             span: Span::new(start, end, self.id_generator.new_id()),
         })
     }
 
+    /// Parse optional scoped slot parameters: (item, index)
+    fn parse_slot_params(&mut self) -> ParseResult<Vec<String>> {
+        let mut params = Vec::new();
+        if !self.match_token(Token::LParen) {
+            return Ok(params);
+        }
+
+        while !self.check(Token::RParen) && !self.is_at_end() {
+            params.push(self.expect_ident()?);
+            if !self.match_token(Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RParen)?;
+
+        Ok(params)
+    }
+
     /// Parse an override: override Button.Icon { style { ... } }
     fn parse_override(&mut self) -> ParseResult<Override> {
         let start = self.current_pos();
@@ -641,6 +821,9 @@ impl<'src> Parser<'src> {
         match self.peek() {
             Some((Token::Text, _)) => {
                 self.advance();
+                if self.is_rich_text() {
+                    return self.parse_rich_text(start);
+                }
                 let content = self.parse_expression()?;
 
                 // Parse optional styles block
@@ -663,6 +846,7 @@ impl<'src> Parser<'src> {
                 let end = self.current_pos();
                 Ok(Element::Text {
                     content,
+                    runs: Vec::new(),
                     styles,
                     span: Span::new(start, end, self.id_generator.new_id()),
                 })
@@ -721,10 +905,11 @@ impl<'src> Parser<'src> {
         }
     }
 
-    /// Parse an insert directive: insert slotName { ... }
+    /// Parse an insert directive: insert slotName { ... } or insert row(item) { ... }
     fn parse_insert(&mut self, start: usize) -> ParseResult<Element> {
         self.expect(Token::Insert)?;
         let slot_name = self.expect_ident()?;
+        let params = self.parse_slot_params()?;
 
         self.expect(Token::LBrace)?;
         let mut content = Vec::new();
@@ -737,6 +922,7 @@ impl<'src> Parser<'src> {
 
         Ok(Element::Insert {
             slot_name,
+            params,
             content,
             span: Span::new(start, end, self.id_generator.new_id()),
         })
@@ -758,19 +944,8 @@ impl<'src> Parser<'src> {
             }
         }
 
-        if self.match_token(Token::LParen) {
-            // Parse attributes
-            while !self.check(Token::RParen) && !self.is_at_end() {
-                let attr_name = self.expect_ident()?;
-                self.expect(Token::Equals)?;
-                let attr_value = self.parse_expression()?;
-                attributes.insert(attr_name, attr_value);
-
-                if !self.match_token(Token::Comma) {
-                    break;
-                }
-            }
-            self.expect(Token::RParen)?;
+        if self.check(Token::LParen) {
+            attributes = self.parse_attribute_list()?;
         }
 
         if self.match_token(Token::LBrace) {
@@ -796,11 +971,119 @@ impl<'src> Parser<'src> {
         })
     }
 
+    /// Parse an attribute list: (name=expr, name=expr)
+    fn parse_attribute_list(&mut self) -> ParseResult<HashMap<String, Expression>> {
+        let mut attributes = HashMap::new();
+
+        self.expect(Token::LParen)?;
+        while !self.check(Token::RParen) && !self.is_at_end() {
+            let attr_name = self.expect_ident()?;
+            self.expect(Token::Equals)?;
+            let attr_value = self.parse_expression()?;
+            attributes.insert(attr_name, attr_value);
+
+            if !self.match_token(Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RParen)?;
+
+        Ok(attributes)
+    }
+
+    /// Check whether `text {` opens rich text rather than an `{expression}`
+    fn is_rich_text(&self) -> bool {
+        if !self.check(Token::LBrace) {
+            return false;
+        }
+
+        match self.peek_ahead(1) {
+            // `text {"..."}` is a braced expression; a lone string isn't rich text
+            Some((Token::String(_), _)) => !matches!(self.peek_ahead(2), Some((Token::RBrace, _))),
+            Some((Token::Style | Token::RBrace, _)) => true,
+            Some((Token::Span, _)) => true,
+            Some((Token::Ident(name), _)) if RICH_TEXT_TAGS.contains(name) => matches!(
+                self.peek_ahead(2),
+                Some((Token::String(_) | Token::LParen | Token::LBrace, _))
+            ),
+            _ => false,
+        }
+    }
+
+    /// Parse rich text: text { "Read the " strong "terms" " first" style { ... } }
+    fn parse_rich_text(&mut self, start: usize) -> ParseResult<Element> {
+        self.expect(Token::LBrace)?;
+
+        let mut runs = Vec::new();
+        let mut styles = Vec::new();
+        while !self.check(Token::RBrace) && !self.is_at_end() {
+            if self.check(Token::Style) {
+                styles.push(self.parse_style_block()?);
+            } else {
+                runs.push(self.parse_text_run()?);
+            }
+        }
+        self.expect(Token::RBrace)?;
+
+        let end = self.current_pos();
+        let span = Span::new(start, end, self.id_generator.new_id());
+
+        Ok(Element::Text {
+            content: TextRun::plain_content(&runs, &span),
+            runs,
+            styles,
+            span,
+        })
+    }
+
+    /// Parse a single rich text run
+    /// Supports: "plain" | strong "bold" | a(href="/x") "link" { style { ... } }
+    fn parse_text_run(&mut self) -> ParseResult<TextRun> {
+        let start = self.current_pos();
+
+        let tag = match self.peek() {
+            Some((Token::Span, _)) => Some("span".to_string()),
+            Some((Token::Ident(name), _)) if RICH_TEXT_TAGS.contains(name) => {
+                Some(name.to_string())
+            }
+            _ => None,
+        };
+
+        let mut attributes = HashMap::new();
+        if tag.is_some() {
+            self.advance();
+            if self.check(Token::LParen) {
+                attributes = self.parse_attribute_list()?;
+            }
+        }
+
+        let content = self.parse_expression()?;
+
+        let mut styles = Vec::new();
+        if tag.is_some() && self.match_token(Token::LBrace) {
+            while !self.check(Token::RBrace) && !self.is_at_end() {
+                styles.push(self.parse_style_block()?);
+            }
+            self.expect(Token::RBrace)?;
+        }
+
+        let end = self.current_pos();
+
+        Ok(TextRun {
+            tag,
+            content,
+            attributes,
+            styles,
+            span: Span::new(start, end, self.id_generator.new_id()),
+        })
+    }
+
     /// Parse a style block
     /// Supports: style variant a + b + c { ... }
     /// Also supports: style extends baseStyle (without body)
     fn parse_style_block(&mut self) -> ParseResult<StyleBlock> {
-        let start = self.current_pos();
+        // Start at the `style` keyword - CSS source maps point here
+        let start = self.peek_span().start;
         self.expect(Token::Style)?;
 
         let mut variants = Vec::new();
@@ -824,7 +1107,7 @@ impl<'src> Parser<'src> {
             }
         }
 
-        let mut properties = HashMap::new();
+        let mut properties = Properties::new();
 
         // Body is optional if we have extends
         if self.match_token(Token::LBrace) {
@@ -1623,6 +1906,60 @@ mod tests {
         assert_eq!(doc.tokens[0].value, "#3366FF");
     }
 
+    #[test]
+    fn test_parse_token_alias_and_math() {
+        let source = r#"
+            token spacing 8px
+            token spacingLarge spacing * 2
+            token buttonBg theme.primary
+            token overlay alpha(primaryColor, 0.5)
+            token fontFamily Inter, sans-serif
+        "#;
+
+        let doc = parse(source).unwrap();
+        assert_eq!(doc.tokens.len(), 5);
+
+        assert_eq!(doc.tokens[1].value, "spacing * 2");
+        assert!(matches!(
+            &doc.tokens[1].expression,
+            TokenValue::Binary {
+                operator: BinaryOp::Multiply,
+                ..
+            }
+        ));
+
+        assert_eq!(
+            doc.tokens[2].expression,
+            TokenValue::Reference {
+                name: "theme.primary".to_string()
+            }
+        );
+
+        match &doc.tokens[3].expression {
+            TokenValue::Call {
+                function,
+                arguments,
+            } => {
+                assert_eq!(function, "alpha");
+                assert_eq!(arguments.len(), 2);
+            }
+            other => panic!("Expected call, got {:?}", other),
+        }
+        assert_eq!(doc.tokens[3].value, "alpha(primaryColor, 0.5)");
+
+        assert_eq!(doc.tokens[4].value, "Inter, sans-serif");
+    }
+
+    #[test]
+    fn test_parse_token_math_precedence() {
+        let source = "token gap (base + 2px) * 2 token next 1px";
+        let doc = parse(source).unwrap();
+
+        assert_eq!(doc.tokens.len(), 2);
+        assert_eq!(doc.tokens[0].value, "(base + 2px) * 2");
+        assert_eq!(doc.tokens[1].value, "1px");
+    }
+
     #[test]
     fn test_parse_override_simple() {
         let source = r#"
@@ -1,6 +1,7 @@
 use crate::ast::{
-    AnnotationValue, BinaryOp, Component, DocComment, Document, Element, Expression, Import, Slot,
-    StyleBlock, StyleDecl, TemplatePart, TokenDecl, TriggerDecl,
+    AnnotationValue, BinaryOp, Component, DocComment, Document, Element, Expression, GlobalStyle,
+    Import, RawCssBlock, Slot, StyleBlock, StyleDecl, TemplatePart, TextRun, TokenDecl,
+    TriggerDecl,
 };
 use std::fmt::Write;
 
@@ -78,6 +79,26 @@ impl Serializer {
             output.push('\n');
         }
 
+        // Serialize global styles
+        for global in &doc.global_styles {
+            self.serialize_global_style(global, &mut output);
+            output.push('\n');
+        }
+
+        if !doc.global_styles.is_empty() {
+            output.push('\n');
+        }
+
+        // Serialize raw CSS blocks
+        for raw_css in &doc.raw_css {
+            self.serialize_raw_css(raw_css, &mut output);
+            output.push('\n');
+        }
+
+        if !doc.raw_css.is_empty() {
+            output.push('\n');
+        }
+
         // Serialize components
         for (i, component) in doc.components.iter().enumerate() {
             if i > 0 {
@@ -168,6 +189,44 @@ impl Serializer {
         output.push('}');
     }
 
+    fn serialize_global_style(&mut self, global: &GlobalStyle, output: &mut String) {
+        output.push_str("global style ");
+
+        // Simple element names can be written bare, anything else needs quotes
+        let is_bare = global
+            .selector
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if is_bare {
+            output.push_str(&global.selector);
+        } else {
+            output.push('"');
+            output.push_str(&global.selector);
+            output.push('"');
+        }
+
+        output.push_str(" {\n");
+        self.indent_level += 1;
+
+        for (key, value) in &global.properties {
+            self.write_indent(output);
+            output.push_str(key);
+            output.push_str(": ");
+            output.push_str(value);
+            output.push_str(";\n");
+        }
+
+        self.indent_level -= 1;
+        output.push('}');
+    }
+
+    /// Raw CSS is written back verbatim so its formatting survives roundtrips
+    fn serialize_raw_css(&self, raw_css: &RawCssBlock, output: &mut String) {
+        output.push_str("css \"\"\"");
+        output.push_str(&raw_css.source);
+        output.push_str("\"\"\"");
+    }
+
     fn serialize_component(&mut self, component: &Component, output: &mut String) {
         // Serialize doc comment with annotations if present
         if let Some(doc_comment) = &component.doc_comment {
@@ -224,6 +283,13 @@ impl Serializer {
             self.serialize_slot(slot, output);
         }
 
+        // Raw CSS
+        for raw_css in &component.raw_css {
+            self.write_indent(output);
+            self.serialize_raw_css(raw_css, output);
+            output.push('\n');
+        }
+
         // Body
         if let Some(body) = &component.body {
             self.write_indent(output);
@@ -304,6 +370,7 @@ impl Serializer {
         self.write_indent(output);
         output.push_str("slot ");
         output.push_str(&slot.name);
+        serialize_slot_params(&slot.params, output);
 
         if !slot.default_content.is_empty() {
             output.push_str(" {\n");
@@ -377,6 +444,21 @@ impl Serializer {
                 }
             }
 
+            Element::Text { runs, styles, .. } if !runs.is_empty() => {
+                self.write_indent(output);
+                output.push_str("text {\n");
+                self.indent_level += 1;
+                for run in runs {
+                    self.serialize_text_run(run, output);
+                }
+                for style in styles {
+                    self.serialize_style_block(style, output);
+                }
+                self.indent_level -= 1;
+                self.write_indent(output);
+                output.push_str("}\n");
+            }
+
             Element::Text {
                 content, styles, ..
             } => {
@@ -444,11 +526,15 @@ impl Serializer {
             }
 
             Element::Insert {
-                slot_name, content, ..
+                slot_name,
+                params,
+                content,
+                ..
             } => {
                 self.write_indent(output);
                 output.push_str("insert ");
                 output.push_str(slot_name);
+                serialize_slot_params(params, output);
                 output.push_str(" {\n");
 
                 self.indent_level += 1;
@@ -517,6 +603,44 @@ impl Serializer {
         }
     }
 
+    /// One run per line: `"plain"`, `strong "bold"`, `a (href = "/x") "link" { style ... }`
+    fn serialize_text_run(&mut self, run: &TextRun, output: &mut String) {
+        self.write_indent(output);
+
+        if let Some(tag) = &run.tag {
+            output.push_str(tag);
+            if !run.attributes.is_empty() {
+                output.push_str(" (");
+                let mut first = true;
+                for (key, value) in &run.attributes {
+                    if !first {
+                        output.push_str(", ");
+                    }
+                    first = false;
+                    output.push_str(key);
+                    output.push_str(" = ");
+                    self.serialize_expression(value, output);
+                }
+                output.push(')');
+            }
+            output.push(' ');
+        }
+
+        self.serialize_expression(&run.content, output);
+
+        if !run.styles.is_empty() {
+            output.push_str(" {\n");
+            self.indent_level += 1;
+            for style in &run.styles {
+                self.serialize_style_block(style, output);
+            }
+            self.indent_level -= 1;
+            self.write_indent(output);
+            output.push('}');
+        }
+        output.push('\n');
+    }
+
     fn serialize_style_block(&mut self, style_block: &StyleBlock, output: &mut String) {
         self.write_indent(output);
         output.push_str("style");
@@ -806,6 +930,15 @@ pub fn serialize_component(component: &Component) -> String {
     output
 }
 
+/// Write scoped slot parameters: `(item, index)`
+fn serialize_slot_params(params: &[String], output: &mut String) {
+    if !params.is_empty() {
+        output.push('(');
+        output.push_str(&params.join(", "));
+        output.push(')');
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
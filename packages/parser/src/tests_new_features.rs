@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod new_features_tests {
+    use crate::ast::Element;
     use crate::parse;
 
     #[test]
@@ -72,6 +73,49 @@ mod new_features_tests {
         assert_eq!(doc.components.len(), 1);
     }
 
+    #[test]
+    fn test_parse_scoped_slot() {
+        let source = r#"
+            component List {
+                slot row(item, index) {
+                    text item.name
+                }
+
+                render ul {
+                    repeat item in items {
+                        row
+                    }
+                }
+            }
+
+            component App {
+                render List(items=users) {
+                    insert row(user) {
+                        text user.name
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse(source).expect("Failed to parse");
+        let slot = &doc.components[0].slots[0];
+        assert_eq!(slot.params, vec!["item", "index"]);
+        assert!(slot.is_scoped());
+
+        let Some(Element::Instance { children, .. }) = &doc.components[1].body else {
+            panic!("Expected instance");
+        };
+        match &children[0] {
+            Element::Insert {
+                slot_name, params, ..
+            } => {
+                assert_eq!(slot_name, "row");
+                assert_eq!(params, &vec!["user".to_string()]);
+            }
+            other => panic!("Expected Insert, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_element_with_name() {
         let source = r#"
@@ -769,4 +813,148 @@ mod new_features_tests {
             panic!("Expected Text element");
         }
     }
+
+    #[test]
+    fn test_parse_global_style() {
+        let source = r#"
+            global style body {
+                margin: 0
+                font-family: Inter, sans-serif
+            }
+        "#;
+
+        let doc = parse(source).unwrap();
+        assert_eq!(doc.global_styles.len(), 1);
+        assert_eq!(doc.global_styles[0].selector, "body");
+        assert_eq!(
+            doc.global_styles[0].properties.get("margin"),
+            Some(&"0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_global_block() {
+        let source = r#"
+            global {
+                * { box-sizing: border-box }
+                h1 { font-size: 32px }
+                a { color: blue }
+                "a:hover, a:focus" { color: red }
+            }
+
+            public component Button {
+                render button
+            }
+        "#;
+
+        let doc = parse(source).unwrap();
+        let selectors: Vec<&str> = doc
+            .global_styles
+            .iter()
+            .map(|g| g.selector.as_str())
+            .collect();
+        assert_eq!(selectors, vec!["*", "h1", "a", "a:hover, a:focus"]);
+        assert_eq!(doc.components.len(), 1);
+    }
+
+    #[test]
+    fn test_global_is_usable_as_a_name() {
+        let source = r#"
+            token global 8px
+            style global {
+                padding: var(global)
+            }
+
+            public component Banner {
+                render div {
+                    text {global}
+                }
+            }
+        "#;
+
+        let doc = parse(source).unwrap();
+        assert_eq!(doc.tokens[0].name, "global");
+        assert_eq!(doc.styles[0].name, "global");
+        assert!(doc.global_styles.is_empty());
+        assert_eq!(doc.components.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_raw_css_blocks() {
+        let source = r#"
+            css """
+            @supports (display: grid) {
+                .grid { display: grid; }
+            }
+            """
+
+            public component Card {
+                css """.title:hover { color: red; }"""
+                render div {
+                    css {
+                        text "not a raw block"
+                    }
+                }
+            }
+        "#;
+
+        let doc = parse(source).unwrap();
+        assert_eq!(doc.raw_css.len(), 1);
+        assert!(doc.raw_css[0].source.contains("@supports (display: grid)"));
+
+        let card = &doc.components[0];
+        assert_eq!(card.raw_css.len(), 1);
+        assert_eq!(card.raw_css[0].source, ".title:hover { color: red; }");
+        assert!(card.body.is_some());
+    }
+
+    #[test]
+    fn test_parse_rich_text_runs() {
+        let source = r#"
+            component Notice {
+                render div {
+                    text {
+                        "Read the "
+                        strong "terms"
+                        " and "
+                        a(href="/privacy") "privacy policy" {
+                            style { text-decoration: underline }
+                        }
+                        "."
+                        style { color: gray }
+                    }
+                }
+            }
+        "#;
+
+        use crate::ast::{Element, Expression};
+
+        let doc = parse(source).unwrap();
+        let body = doc.components[0].body.as_ref().unwrap();
+        let text = &body.children().unwrap()[0];
+
+        let (content, runs, styles) = match text {
+            Element::Text {
+                content,
+                runs,
+                styles,
+                ..
+            } => (content, runs, styles),
+            other => panic!("Expected text, got {:?}", other),
+        };
+
+        assert_eq!(runs.len(), 5);
+        assert_eq!(runs[0].tag, None);
+        assert_eq!(runs[1].tag.as_deref(), Some("strong"));
+        assert_eq!(runs[3].tag.as_deref(), Some("a"));
+        assert!(runs[3].attributes.contains_key("href"));
+        assert_eq!(runs[3].styles.len(), 1);
+        assert_eq!(styles.len(), 1);
+
+        // Plain content is the flattened text
+        match content {
+            Expression::Template { parts, .. } => assert_eq!(parts.len(), 5),
+            other => panic!("Expected template content, got {:?}", other),
+        }
+    }
 }
@@ -239,6 +239,30 @@ public component Card {
     }
 }
 
+#[test]
+fn test_roundtrip_scoped_slot() {
+    let source = r#"
+public component List {
+    slot row(item, index)
+
+    render ul {
+        List {
+            insert row(user) {
+                text user.name
+            }
+        }
+    }
+}
+"#;
+    let doc = parse(source).unwrap();
+    let serialized = serialize(&doc);
+    assert!(serialized.contains("slot row(item, index)"));
+    assert!(serialized.contains("insert row(user) {"));
+
+    let reparsed = parse(&serialized).unwrap();
+    assert_eq!(reparsed.components[0].slots[0].params, vec!["item", "index"]);
+}
+
 #[test]
 fn test_roundtrip_complex_expression_with_precedence() {
     let source = r#"public component Test { render div { text {a + b * c > d && e || f} } }"#;
@@ -540,3 +564,86 @@ component Card {
     assert_eq!(frame2.x, -50.0);
     assert_eq!(frame2.y, 100.5);
 }
+
+#[test]
+fn test_roundtrip_global_styles() {
+    let source = r#"
+global {
+  body { margin: 0 }
+  "a:hover" { color: red }
+}
+"#;
+
+    let doc = parse(source).unwrap();
+    let serialized = serialize(&doc);
+    let reparsed = parse(&serialized).expect(&format!("Failed to reparse: {}", serialized));
+
+    assert_eq!(reparsed.global_styles.len(), 2);
+    assert_eq!(reparsed.global_styles[0].selector, "body");
+    assert_eq!(reparsed.global_styles[1].selector, "a:hover");
+    assert_eq!(
+        reparsed.global_styles[1].properties.get("color"),
+        Some(&"red".to_string())
+    );
+}
+
+#[test]
+fn test_roundtrip_raw_css() {
+    let source = r#"
+css """
+@property --angle {
+  syntax: "<angle>";
+  inherits: false;
+}
+"""
+
+component Card {
+  css """.title { color: red; }"""
+  render div
+}
+"#;
+
+    let doc = parse(source).unwrap();
+    let serialized = serialize(&doc);
+    let reparsed = parse(&serialized).expect(&format!("Failed to reparse: {}", serialized));
+
+    assert_eq!(reparsed.raw_css[0].source, doc.raw_css[0].source);
+    assert_eq!(
+        reparsed.components[0].raw_css[0].source,
+        ".title { color: red; }"
+    );
+}
+
+#[test]
+fn test_roundtrip_rich_text() {
+    let source = r#"
+component Notice {
+  render div {
+    text {
+      "Hello, "
+      em name
+      " - "
+      a(href="/docs") "read the docs" {
+        style { color: blue }
+      }
+    }
+  }
+}
+"#;
+
+    let doc = parse(source).unwrap();
+    let serialized = serialize(&doc);
+    assert!(serialized.contains("    text {\n      \"Hello, \"\n      em {name}\n"));
+    assert!(serialized.contains("      a (href = \"/docs\") \"read the docs\" {\n"));
+
+    let reparsed = parse(&serialized).expect(&format!("Failed to reparse: {}", serialized));
+    let body = reparsed.components[0].body.as_ref().unwrap();
+    match &body.children().unwrap()[0] {
+        Element::Text { runs, .. } => {
+            assert_eq!(runs.len(), 4);
+            assert_eq!(runs[1].tag.as_deref(), Some("em"));
+            assert_eq!(runs[3].styles[0].properties["color"], "blue");
+        }
+        other => panic!("Expected text, got {:?}", other),
+    }
+}
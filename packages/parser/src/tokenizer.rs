@@ -98,6 +98,10 @@ pub enum Token<'src> {
     #[regex(r#""([^"\\]|\\.)*""#, |lex| lex.slice())]
     String(&'src str),
 
+    // Triple-quoted raw text (`css """ ... """` blocks)
+    #[regex(r#""""([^"]|"[^"]|""[^"])*""""#, |lex| lex.slice())]
+    TripleString(&'src str),
+
     // Numbers
     #[regex(r"-?[0-9]+(\.[0-9]+)?", |lex| lex.slice())]
     Number(&'src str),
@@ -225,6 +229,7 @@ impl<'src> fmt::Display for Token<'src> {
             Token::Input => write!(f, "input"),
             Token::Ident(s) => write!(f, "identifier '{}'", s),
             Token::String(s) => write!(f, "string {}", s),
+            Token::TripleString(_) => write!(f, "triple-quoted string"),
             Token::Number(n) => write!(f, "number {}", n),
             Token::Color(c) => write!(f, "color {}", c),
             Token::CssUnit(u) => write!(f, "css unit {}", u),
@@ -306,6 +311,20 @@ mod tests {
         assert!(matches!(tokens[1].0, Token::String(_)));
     }
 
+    #[test]
+    fn test_triple_quoted_strings() {
+        let source = "css \"\"\"\n.a { content: \"x\"; } // not a comment\n\"\"\" \"\"";
+        let tokens = tokenize(source);
+
+        assert_eq!(tokens.len(), 3);
+        assert_eq!(tokens[0].0, Token::Ident("css"));
+        assert_eq!(
+            tokens[1].0,
+            Token::TripleString("\"\"\"\n.a { content: \"x\"; } // not a comment\n\"\"\"")
+        );
+        assert_eq!(tokens[2].0, Token::String("\"\""));
+    }
+
     #[test]
     fn test_numbers_and_colors() {
         let source = "42 3.14 -10 #FF0000 #333";
@@ -6,7 +6,7 @@
 
 /* eslint-disable */
 import * as _m0 from "protobufjs/minimal";
-import { CssRule, VDocument, VNode } from "./vdom";
+import { AtRule, CssRule, RootMetadata, VDocument, VNode } from "./vdom";
 
 export const protobufPackage = "paperclip.patches";
 
@@ -20,14 +20,20 @@ export interface VDocPatch {
   updateStyles?: UpdateStylesPatch | undefined;
   updateText?: UpdateTextPatch | undefined;
   addStyleRule?: AddStyleRulePatch | undefined;
-  removeStyleRule?:
-    | RemoveStyleRulePatch
-    | undefined;
-  /** NEW: For semantic reordering */
+  removeStyleRule?: RemoveStyleRulePatch | undefined;
   moveChild?: MoveChildPatch | undefined;
+  updateStyleRule?: UpdateStyleRulePatch | undefined;
+  updateFrame?: UpdateFramePatch | undefined;
+  updateLiveProps?: UpdateLivePropsPatch | undefined;
 }
 
-/** Patch path supporting both positional and semantic IDs */
+/**
+ * Patch path supporting both positional and semantic IDs.
+ * Node patches carry both: `path` is the positional path (valid once the
+ * preceding patches have been applied), `target`/`parent` is the semantic ID
+ * of the element when it has one, falling back to the positional path for
+ * text and comment nodes.
+ */
 export interface PatchPath {
   positional?:
     | PositionalPath
@@ -46,23 +52,28 @@ export interface InitializePatch {
 }
 
 export interface CreateNodePatch {
+  /** Parent path */
   path: number[];
   node?: VNode | undefined;
   index: number;
+  parent?: PatchPath | undefined;
 }
 
 export interface RemoveNodePatch {
   path: number[];
+  target?: PatchPath | undefined;
 }
 
 export interface ReplaceNodePatch {
   path: number[];
   newNode?: VNode | undefined;
+  target?: PatchPath | undefined;
 }
 
 export interface UpdateAttributesPatch {
   path: number[];
   attributes: { [key: string]: string };
+  target?: PatchPath | undefined;
 }
 
 export interface UpdateAttributesPatch_AttributesEntry {
@@ -73,6 +84,7 @@ export interface UpdateAttributesPatch_AttributesEntry {
 export interface UpdateStylesPatch {
   path: number[];
   styles: { [key: string]: string };
+  target?: PatchPath | undefined;
 }
 
 export interface UpdateStylesPatch_StylesEntry {
@@ -83,22 +95,94 @@ export interface UpdateStylesPatch_StylesEntry {
 export interface UpdateTextPatch {
   path: number[];
   content: string;
+  target?: PatchPath | undefined;
 }
 
 export interface AddStyleRulePatch {
   rule?: CssRule | undefined;
 }
 
+/**
+ * Style rule patches are keyed by (layer, selector, at_rules) so they survive
+ * rule reordering
+ */
 export interface RemoveStyleRulePatch {
+  /**
+   * Position in the old rule list
+   *
+   * @deprecated
+   */
   index: number;
+  selector: string;
+  /**
+   * Single at-rule as text
+   *
+   * @deprecated
+   */
+  mediaQuery?:
+    | string
+    | undefined;
+  /** Cascade layer of the rule (see CssRule.layer) */
+  layer?: string | undefined;
+  atRules: AtRule[];
+}
+
+/** Per-property update of an existing style rule */
+export interface UpdateStyleRulePatch {
+  selector: string;
+  /**
+   * Single at-rule as text
+   *
+   * @deprecated
+   */
+  mediaQuery?:
+    | string
+    | undefined;
+  /** Added or changed properties */
+  properties: { [key: string]: string };
+  removedProperties: string[];
+  /** Cascade layer of the rule (see CssRule.layer) */
+  layer?: string | undefined;
+  atRules: AtRule[];
 }
 
-/** NEW: Move child patch for efficient reordering (semantic ID support) */
+export interface UpdateStyleRulePatch_PropertiesEntry {
+  key: string;
+  value: string;
+}
+
+/**
+ * Move an existing child within its parent. `to_index` is the child's index
+ * after the move. `child_semantic_id` is empty for text and comment nodes.
+ */
 export interface MoveChildPatch {
   parent?: PatchPath | undefined;
   childSemanticId: string;
   fromIndex: number;
   toIndex: number;
+  /** Positional parent path */
+  path: number[];
+}
+
+/**
+ * Replace the designer metadata (frame, annotations) of a root node.
+ * `index` is the root's position once the node patches have been applied.
+ */
+export interface UpdateFramePatch {
+  index: number;
+  /** Root node's semantic ID, empty for text/comment roots */
+  semanticId: string;
+  metadata?: RootMetadata | undefined;
+}
+
+/**
+ * Re-render a mounted live component with new props, without remounting it.
+ * The static fallback children are diffed separately.
+ */
+export interface UpdateLivePropsPatch {
+  path: number[];
+  propsJson: string;
+  target?: PatchPath | undefined;
 }
 
 function createBaseVDocPatch(): VDocPatch {
@@ -113,6 +197,9 @@ function createBaseVDocPatch(): VDocPatch {
     addStyleRule: undefined,
     removeStyleRule: undefined,
     moveChild: undefined,
+    updateStyleRule: undefined,
+    updateFrame: undefined,
+    updateLiveProps: undefined,
   };
 }
 
@@ -148,6 +235,15 @@ export const VDocPatch = {
     if (message.moveChild !== undefined) {
       MoveChildPatch.encode(message.moveChild, writer.uint32(82).fork()).ldelim();
     }
+    if (message.updateStyleRule !== undefined) {
+      UpdateStyleRulePatch.encode(message.updateStyleRule, writer.uint32(90).fork()).ldelim();
+    }
+    if (message.updateFrame !== undefined) {
+      UpdateFramePatch.encode(message.updateFrame, writer.uint32(98).fork()).ldelim();
+    }
+    if (message.updateLiveProps !== undefined) {
+      UpdateLivePropsPatch.encode(message.updateLiveProps, writer.uint32(106).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -228,6 +324,27 @@ export const VDocPatch = {
 
           message.moveChild = MoveChildPatch.decode(reader, reader.uint32());
           continue;
+        case 11:
+          if (tag !== 90) {
+            break;
+          }
+
+          message.updateStyleRule = UpdateStyleRulePatch.decode(reader, reader.uint32());
+          continue;
+        case 12:
+          if (tag !== 98) {
+            break;
+          }
+
+          message.updateFrame = UpdateFramePatch.decode(reader, reader.uint32());
+          continue;
+        case 13:
+          if (tag !== 106) {
+            break;
+          }
+
+          message.updateLiveProps = UpdateLivePropsPatch.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -253,6 +370,13 @@ export const VDocPatch = {
         ? RemoveStyleRulePatch.fromJSON(object.removeStyleRule)
         : undefined,
       moveChild: isSet(object.moveChild) ? MoveChildPatch.fromJSON(object.moveChild) : undefined,
+      updateStyleRule: isSet(object.updateStyleRule)
+        ? UpdateStyleRulePatch.fromJSON(object.updateStyleRule)
+        : undefined,
+      updateFrame: isSet(object.updateFrame) ? UpdateFramePatch.fromJSON(object.updateFrame) : undefined,
+      updateLiveProps: isSet(object.updateLiveProps)
+        ? UpdateLivePropsPatch.fromJSON(object.updateLiveProps)
+        : undefined,
     };
   },
 
@@ -288,6 +412,15 @@ export const VDocPatch = {
     if (message.moveChild !== undefined) {
       obj.moveChild = MoveChildPatch.toJSON(message.moveChild);
     }
+    if (message.updateStyleRule !== undefined) {
+      obj.updateStyleRule = UpdateStyleRulePatch.toJSON(message.updateStyleRule);
+    }
+    if (message.updateFrame !== undefined) {
+      obj.updateFrame = UpdateFramePatch.toJSON(message.updateFrame);
+    }
+    if (message.updateLiveProps !== undefined) {
+      obj.updateLiveProps = UpdateLivePropsPatch.toJSON(message.updateLiveProps);
+    }
     return obj;
   },
 
@@ -326,6 +459,15 @@ export const VDocPatch = {
     message.moveChild = (object.moveChild !== undefined && object.moveChild !== null)
       ? MoveChildPatch.fromPartial(object.moveChild)
       : undefined;
+    message.updateStyleRule = (object.updateStyleRule !== undefined && object.updateStyleRule !== null)
+      ? UpdateStyleRulePatch.fromPartial(object.updateStyleRule)
+      : undefined;
+    message.updateFrame = (object.updateFrame !== undefined && object.updateFrame !== null)
+      ? UpdateFramePatch.fromPartial(object.updateFrame)
+      : undefined;
+    message.updateLiveProps = (object.updateLiveProps !== undefined && object.updateLiveProps !== null)
+      ? UpdateLivePropsPatch.fromPartial(object.updateLiveProps)
+      : undefined;
     return message;
   },
 };
@@ -535,7 +677,7 @@ export const InitializePatch = {
 };
 
 function createBaseCreateNodePatch(): CreateNodePatch {
-  return { path: [], node: undefined, index: 0 };
+  return { path: [], node: undefined, index: 0, parent: undefined };
 }
 
 export const CreateNodePatch = {
@@ -551,6 +693,9 @@ export const CreateNodePatch = {
     if (message.index !== 0) {
       writer.uint32(24).uint32(message.index);
     }
+    if (message.parent !== undefined) {
+      PatchPath.encode(message.parent, writer.uint32(34).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -592,6 +737,13 @@ export const CreateNodePatch = {
 
           message.index = reader.uint32();
           continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.parent = PatchPath.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -606,6 +758,7 @@ export const CreateNodePatch = {
       path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [],
       node: isSet(object.node) ? VNode.fromJSON(object.node) : undefined,
       index: isSet(object.index) ? globalThis.Number(object.index) : 0,
+      parent: isSet(object.parent) ? PatchPath.fromJSON(object.parent) : undefined,
     };
   },
 
@@ -620,6 +773,9 @@ export const CreateNodePatch = {
     if (message.index !== 0) {
       obj.index = Math.round(message.index);
     }
+    if (message.parent !== undefined) {
+      obj.parent = PatchPath.toJSON(message.parent);
+    }
     return obj;
   },
 
@@ -631,12 +787,15 @@ export const CreateNodePatch = {
     message.path = object.path?.map((e) => e) || [];
     message.node = (object.node !== undefined && object.node !== null) ? VNode.fromPartial(object.node) : undefined;
     message.index = object.index ?? 0;
+    message.parent = (object.parent !== undefined && object.parent !== null)
+      ? PatchPath.fromPartial(object.parent)
+      : undefined;
     return message;
   },
 };
 
 function createBaseRemoveNodePatch(): RemoveNodePatch {
-  return { path: [] };
+  return { path: [], target: undefined };
 }
 
 export const RemoveNodePatch = {
@@ -646,6 +805,9 @@ export const RemoveNodePatch = {
       writer.uint32(v);
     }
     writer.ldelim();
+    if (message.target !== undefined) {
+      PatchPath.encode(message.target, writer.uint32(18).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -673,6 +835,13 @@ export const RemoveNodePatch = {
           }
 
           break;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.target = PatchPath.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -683,7 +852,10 @@ export const RemoveNodePatch = {
   },
 
   fromJSON(object: any): RemoveNodePatch {
-    return { path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [] };
+    return {
+      path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [],
+      target: isSet(object.target) ? PatchPath.fromJSON(object.target) : undefined,
+    };
   },
 
   toJSON(message: RemoveNodePatch): unknown {
@@ -691,6 +863,9 @@ export const RemoveNodePatch = {
     if (message.path?.length) {
       obj.path = message.path.map((e) => Math.round(e));
     }
+    if (message.target !== undefined) {
+      obj.target = PatchPath.toJSON(message.target);
+    }
     return obj;
   },
 
@@ -700,12 +875,15 @@ export const RemoveNodePatch = {
   fromPartial<I extends Exact<DeepPartial<RemoveNodePatch>, I>>(object: I): RemoveNodePatch {
     const message = createBaseRemoveNodePatch();
     message.path = object.path?.map((e) => e) || [];
+    message.target = (object.target !== undefined && object.target !== null)
+      ? PatchPath.fromPartial(object.target)
+      : undefined;
     return message;
   },
 };
 
 function createBaseReplaceNodePatch(): ReplaceNodePatch {
-  return { path: [], newNode: undefined };
+  return { path: [], newNode: undefined, target: undefined };
 }
 
 export const ReplaceNodePatch = {
@@ -718,6 +896,9 @@ export const ReplaceNodePatch = {
     if (message.newNode !== undefined) {
       VNode.encode(message.newNode, writer.uint32(18).fork()).ldelim();
     }
+    if (message.target !== undefined) {
+      PatchPath.encode(message.target, writer.uint32(26).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -752,6 +933,13 @@ export const ReplaceNodePatch = {
 
           message.newNode = VNode.decode(reader, reader.uint32());
           continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.target = PatchPath.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -765,6 +953,7 @@ export const ReplaceNodePatch = {
     return {
       path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [],
       newNode: isSet(object.newNode) ? VNode.fromJSON(object.newNode) : undefined,
+      target: isSet(object.target) ? PatchPath.fromJSON(object.target) : undefined,
     };
   },
 
@@ -776,6 +965,9 @@ export const ReplaceNodePatch = {
     if (message.newNode !== undefined) {
       obj.newNode = VNode.toJSON(message.newNode);
     }
+    if (message.target !== undefined) {
+      obj.target = PatchPath.toJSON(message.target);
+    }
     return obj;
   },
 
@@ -788,12 +980,15 @@ export const ReplaceNodePatch = {
     message.newNode = (object.newNode !== undefined && object.newNode !== null)
       ? VNode.fromPartial(object.newNode)
       : undefined;
+    message.target = (object.target !== undefined && object.target !== null)
+      ? PatchPath.fromPartial(object.target)
+      : undefined;
     return message;
   },
 };
 
 function createBaseUpdateAttributesPatch(): UpdateAttributesPatch {
-  return { path: [], attributes: {} };
+  return { path: [], attributes: {}, target: undefined };
 }
 
 export const UpdateAttributesPatch = {
@@ -806,6 +1001,9 @@ export const UpdateAttributesPatch = {
     Object.entries(message.attributes).forEach(([key, value]) => {
       UpdateAttributesPatch_AttributesEntry.encode({ key: key as any, value }, writer.uint32(18).fork()).ldelim();
     });
+    if (message.target !== undefined) {
+      PatchPath.encode(message.target, writer.uint32(26).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -843,6 +1041,13 @@ export const UpdateAttributesPatch = {
             message.attributes[entry2.key] = entry2.value;
           }
           continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.target = PatchPath.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -861,6 +1066,7 @@ export const UpdateAttributesPatch = {
           return acc;
         }, {})
         : {},
+      target: isSet(object.target) ? PatchPath.fromJSON(object.target) : undefined,
     };
   },
 
@@ -878,6 +1084,9 @@ export const UpdateAttributesPatch = {
         });
       }
     }
+    if (message.target !== undefined) {
+      obj.target = PatchPath.toJSON(message.target);
+    }
     return obj;
   },
 
@@ -896,6 +1105,9 @@ export const UpdateAttributesPatch = {
       },
       {},
     );
+    message.target = (object.target !== undefined && object.target !== null)
+      ? PatchPath.fromPartial(object.target)
+      : undefined;
     return message;
   },
 };
@@ -979,7 +1191,7 @@ export const UpdateAttributesPatch_AttributesEntry = {
 };
 
 function createBaseUpdateStylesPatch(): UpdateStylesPatch {
-  return { path: [], styles: {} };
+  return { path: [], styles: {}, target: undefined };
 }
 
 export const UpdateStylesPatch = {
@@ -992,6 +1204,9 @@ export const UpdateStylesPatch = {
     Object.entries(message.styles).forEach(([key, value]) => {
       UpdateStylesPatch_StylesEntry.encode({ key: key as any, value }, writer.uint32(18).fork()).ldelim();
     });
+    if (message.target !== undefined) {
+      PatchPath.encode(message.target, writer.uint32(26).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -1029,6 +1244,13 @@ export const UpdateStylesPatch = {
             message.styles[entry2.key] = entry2.value;
           }
           continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.target = PatchPath.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -1047,6 +1269,7 @@ export const UpdateStylesPatch = {
           return acc;
         }, {})
         : {},
+      target: isSet(object.target) ? PatchPath.fromJSON(object.target) : undefined,
     };
   },
 
@@ -1064,6 +1287,9 @@ export const UpdateStylesPatch = {
         });
       }
     }
+    if (message.target !== undefined) {
+      obj.target = PatchPath.toJSON(message.target);
+    }
     return obj;
   },
 
@@ -1079,6 +1305,9 @@ export const UpdateStylesPatch = {
       }
       return acc;
     }, {});
+    message.target = (object.target !== undefined && object.target !== null)
+      ? PatchPath.fromPartial(object.target)
+      : undefined;
     return message;
   },
 };
@@ -1160,7 +1389,7 @@ export const UpdateStylesPatch_StylesEntry = {
 };
 
 function createBaseUpdateTextPatch(): UpdateTextPatch {
-  return { path: [], content: "" };
+  return { path: [], content: "", target: undefined };
 }
 
 export const UpdateTextPatch = {
@@ -1173,6 +1402,9 @@ export const UpdateTextPatch = {
     if (message.content !== "") {
       writer.uint32(18).string(message.content);
     }
+    if (message.target !== undefined) {
+      PatchPath.encode(message.target, writer.uint32(26).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -1207,6 +1439,13 @@ export const UpdateTextPatch = {
 
           message.content = reader.string();
           continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.target = PatchPath.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -1220,6 +1459,7 @@ export const UpdateTextPatch = {
     return {
       path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [],
       content: isSet(object.content) ? globalThis.String(object.content) : "",
+      target: isSet(object.target) ? PatchPath.fromJSON(object.target) : undefined,
     };
   },
 
@@ -1231,6 +1471,9 @@ export const UpdateTextPatch = {
     if (message.content !== "") {
       obj.content = message.content;
     }
+    if (message.target !== undefined) {
+      obj.target = PatchPath.toJSON(message.target);
+    }
     return obj;
   },
 
@@ -1241,6 +1484,9 @@ export const UpdateTextPatch = {
     const message = createBaseUpdateTextPatch();
     message.path = object.path?.map((e) => e) || [];
     message.content = object.content ?? "";
+    message.target = (object.target !== undefined && object.target !== null)
+      ? PatchPath.fromPartial(object.target)
+      : undefined;
     return message;
   },
 };
@@ -1303,7 +1549,7 @@ export const AddStyleRulePatch = {
 };
 
 function createBaseRemoveStyleRulePatch(): RemoveStyleRulePatch {
-  return { index: 0 };
+  return { index: 0, selector: "", mediaQuery: undefined, layer: undefined, atRules: [] };
 }
 
 export const RemoveStyleRulePatch = {
@@ -1311,6 +1557,18 @@ export const RemoveStyleRulePatch = {
     if (message.index !== 0) {
       writer.uint32(8).uint32(message.index);
     }
+    if (message.selector !== "") {
+      writer.uint32(18).string(message.selector);
+    }
+    if (message.mediaQuery !== undefined) {
+      writer.uint32(26).string(message.mediaQuery);
+    }
+    if (message.layer !== undefined) {
+      writer.uint32(34).string(message.layer);
+    }
+    for (const v of message.atRules) {
+      AtRule.encode(v!, writer.uint32(42).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -1328,6 +1586,34 @@ export const RemoveStyleRulePatch = {
 
           message.index = reader.uint32();
           continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.selector = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.mediaQuery = reader.string();
+          continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.layer = reader.string();
+          continue;
+        case 5:
+          if (tag !== 42) {
+            break;
+          }
+
+          message.atRules.push(AtRule.decode(reader, reader.uint32()));
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -1338,7 +1624,13 @@ export const RemoveStyleRulePatch = {
   },
 
   fromJSON(object: any): RemoveStyleRulePatch {
-    return { index: isSet(object.index) ? globalThis.Number(object.index) : 0 };
+    return {
+      index: isSet(object.index) ? globalThis.Number(object.index) : 0,
+      selector: isSet(object.selector) ? globalThis.String(object.selector) : "",
+      mediaQuery: isSet(object.mediaQuery) ? globalThis.String(object.mediaQuery) : undefined,
+      layer: isSet(object.layer) ? globalThis.String(object.layer) : undefined,
+      atRules: globalThis.Array.isArray(object?.atRules) ? object.atRules.map((e: any) => AtRule.fromJSON(e)) : [],
+    };
   },
 
   toJSON(message: RemoveStyleRulePatch): unknown {
@@ -1346,6 +1638,18 @@ export const RemoveStyleRulePatch = {
     if (message.index !== 0) {
       obj.index = Math.round(message.index);
     }
+    if (message.selector !== "") {
+      obj.selector = message.selector;
+    }
+    if (message.mediaQuery !== undefined) {
+      obj.mediaQuery = message.mediaQuery;
+    }
+    if (message.layer !== undefined) {
+      obj.layer = message.layer;
+    }
+    if (message.atRules?.length) {
+      obj.atRules = message.atRules.map((e) => AtRule.toJSON(e));
+    }
     return obj;
   },
 
@@ -1355,12 +1659,252 @@ export const RemoveStyleRulePatch = {
   fromPartial<I extends Exact<DeepPartial<RemoveStyleRulePatch>, I>>(object: I): RemoveStyleRulePatch {
     const message = createBaseRemoveStyleRulePatch();
     message.index = object.index ?? 0;
+    message.selector = object.selector ?? "";
+    message.mediaQuery = object.mediaQuery ?? undefined;
+    message.layer = object.layer ?? undefined;
+    message.atRules = object.atRules?.map((e) => AtRule.fromPartial(e)) || [];
+    return message;
+  },
+};
+
+function createBaseUpdateStyleRulePatch(): UpdateStyleRulePatch {
+  return { selector: "", mediaQuery: undefined, properties: {}, removedProperties: [], layer: undefined, atRules: [] };
+}
+
+export const UpdateStyleRulePatch = {
+  encode(message: UpdateStyleRulePatch, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.selector !== "") {
+      writer.uint32(10).string(message.selector);
+    }
+    if (message.mediaQuery !== undefined) {
+      writer.uint32(18).string(message.mediaQuery);
+    }
+    Object.entries(message.properties).forEach(([key, value]) => {
+      UpdateStyleRulePatch_PropertiesEntry.encode({ key: key as any, value }, writer.uint32(26).fork()).ldelim();
+    });
+    for (const v of message.removedProperties) {
+      writer.uint32(34).string(v!);
+    }
+    if (message.layer !== undefined) {
+      writer.uint32(42).string(message.layer);
+    }
+    for (const v of message.atRules) {
+      AtRule.encode(v!, writer.uint32(50).fork()).ldelim();
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): UpdateStyleRulePatch {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseUpdateStyleRulePatch();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.selector = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.mediaQuery = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          const entry3 = UpdateStyleRulePatch_PropertiesEntry.decode(reader, reader.uint32());
+          if (entry3.value !== undefined) {
+            message.properties[entry3.key] = entry3.value;
+          }
+          continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.removedProperties.push(reader.string());
+          continue;
+        case 5:
+          if (tag !== 42) {
+            break;
+          }
+
+          message.layer = reader.string();
+          continue;
+        case 6:
+          if (tag !== 50) {
+            break;
+          }
+
+          message.atRules.push(AtRule.decode(reader, reader.uint32()));
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): UpdateStyleRulePatch {
+    return {
+      selector: isSet(object.selector) ? globalThis.String(object.selector) : "",
+      mediaQuery: isSet(object.mediaQuery) ? globalThis.String(object.mediaQuery) : undefined,
+      properties: isObject(object.properties)
+        ? Object.entries(object.properties).reduce<{ [key: string]: string }>((acc, [key, value]) => {
+          acc[key] = String(value);
+          return acc;
+        }, {})
+        : {},
+      removedProperties: globalThis.Array.isArray(object?.removedProperties)
+        ? object.removedProperties.map((e: any) => globalThis.String(e))
+        : [],
+      layer: isSet(object.layer) ? globalThis.String(object.layer) : undefined,
+      atRules: globalThis.Array.isArray(object?.atRules) ? object.atRules.map((e: any) => AtRule.fromJSON(e)) : [],
+    };
+  },
+
+  toJSON(message: UpdateStyleRulePatch): unknown {
+    const obj: any = {};
+    if (message.selector !== "") {
+      obj.selector = message.selector;
+    }
+    if (message.mediaQuery !== undefined) {
+      obj.mediaQuery = message.mediaQuery;
+    }
+    if (message.properties) {
+      const entries = Object.entries(message.properties);
+      if (entries.length > 0) {
+        obj.properties = {};
+        entries.forEach(([k, v]) => {
+          obj.properties[k] = v;
+        });
+      }
+    }
+    if (message.removedProperties?.length) {
+      obj.removedProperties = message.removedProperties;
+    }
+    if (message.layer !== undefined) {
+      obj.layer = message.layer;
+    }
+    if (message.atRules?.length) {
+      obj.atRules = message.atRules.map((e) => AtRule.toJSON(e));
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<UpdateStyleRulePatch>, I>>(base?: I): UpdateStyleRulePatch {
+    return UpdateStyleRulePatch.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<UpdateStyleRulePatch>, I>>(object: I): UpdateStyleRulePatch {
+    const message = createBaseUpdateStyleRulePatch();
+    message.selector = object.selector ?? "";
+    message.mediaQuery = object.mediaQuery ?? undefined;
+    message.properties = Object.entries(object.properties ?? {}).reduce<{ [key: string]: string }>(
+      (acc, [key, value]) => {
+        if (value !== undefined) {
+          acc[key] = globalThis.String(value);
+        }
+        return acc;
+      },
+      {},
+    );
+    message.removedProperties = object.removedProperties?.map((e) => e) || [];
+    message.layer = object.layer ?? undefined;
+    message.atRules = object.atRules?.map((e) => AtRule.fromPartial(e)) || [];
+    return message;
+  },
+};
+
+function createBaseUpdateStyleRulePatch_PropertiesEntry(): UpdateStyleRulePatch_PropertiesEntry {
+  return { key: "", value: "" };
+}
+
+export const UpdateStyleRulePatch_PropertiesEntry = {
+  encode(message: UpdateStyleRulePatch_PropertiesEntry, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.key !== "") {
+      writer.uint32(10).string(message.key);
+    }
+    if (message.value !== "") {
+      writer.uint32(18).string(message.value);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): UpdateStyleRulePatch_PropertiesEntry {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseUpdateStyleRulePatch_PropertiesEntry();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.key = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.value = reader.string();
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): UpdateStyleRulePatch_PropertiesEntry {
+    return {
+      key: isSet(object.key) ? globalThis.String(object.key) : "",
+      value: isSet(object.value) ? globalThis.String(object.value) : "",
+    };
+  },
+
+  toJSON(message: UpdateStyleRulePatch_PropertiesEntry): unknown {
+    const obj: any = {};
+    if (message.key !== "") {
+      obj.key = message.key;
+    }
+    if (message.value !== "") {
+      obj.value = message.value;
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<UpdateStyleRulePatch_PropertiesEntry>, I>>(
+    base?: I,
+  ): UpdateStyleRulePatch_PropertiesEntry {
+    return UpdateStyleRulePatch_PropertiesEntry.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<UpdateStyleRulePatch_PropertiesEntry>, I>>(
+    object: I,
+  ): UpdateStyleRulePatch_PropertiesEntry {
+    const message = createBaseUpdateStyleRulePatch_PropertiesEntry();
+    message.key = object.key ?? "";
+    message.value = object.value ?? "";
     return message;
   },
 };
 
 function createBaseMoveChildPatch(): MoveChildPatch {
-  return { parent: undefined, childSemanticId: "", fromIndex: 0, toIndex: 0 };
+  return { parent: undefined, childSemanticId: "", fromIndex: 0, toIndex: 0, path: [] };
 }
 
 export const MoveChildPatch = {
@@ -1377,6 +1921,11 @@ export const MoveChildPatch = {
     if (message.toIndex !== 0) {
       writer.uint32(32).uint32(message.toIndex);
     }
+    writer.uint32(42).fork();
+    for (const v of message.path) {
+      writer.uint32(v);
+    }
+    writer.ldelim();
     return writer;
   },
 
@@ -1415,6 +1964,23 @@ export const MoveChildPatch = {
 
           message.toIndex = reader.uint32();
           continue;
+        case 5:
+          if (tag === 40) {
+            message.path.push(reader.uint32());
+
+            continue;
+          }
+
+          if (tag === 42) {
+            const end2 = reader.uint32() + reader.pos;
+            while (reader.pos < end2) {
+              message.path.push(reader.uint32());
+            }
+
+            continue;
+          }
+
+          break;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -1430,6 +1996,7 @@ export const MoveChildPatch = {
       childSemanticId: isSet(object.childSemanticId) ? globalThis.String(object.childSemanticId) : "",
       fromIndex: isSet(object.fromIndex) ? globalThis.Number(object.fromIndex) : 0,
       toIndex: isSet(object.toIndex) ? globalThis.Number(object.toIndex) : 0,
+      path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [],
     };
   },
 
@@ -1447,6 +2014,9 @@ export const MoveChildPatch = {
     if (message.toIndex !== 0) {
       obj.toIndex = Math.round(message.toIndex);
     }
+    if (message.path?.length) {
+      obj.path = message.path.map((e) => Math.round(e));
+    }
     return obj;
   },
 
@@ -1461,6 +2031,201 @@ export const MoveChildPatch = {
     message.childSemanticId = object.childSemanticId ?? "";
     message.fromIndex = object.fromIndex ?? 0;
     message.toIndex = object.toIndex ?? 0;
+    message.path = object.path?.map((e) => e) || [];
+    return message;
+  },
+};
+
+function createBaseUpdateFramePatch(): UpdateFramePatch {
+  return { index: 0, semanticId: "", metadata: undefined };
+}
+
+export const UpdateFramePatch = {
+  encode(message: UpdateFramePatch, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.index !== 0) {
+      writer.uint32(8).uint32(message.index);
+    }
+    if (message.semanticId !== "") {
+      writer.uint32(18).string(message.semanticId);
+    }
+    if (message.metadata !== undefined) {
+      RootMetadata.encode(message.metadata, writer.uint32(26).fork()).ldelim();
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): UpdateFramePatch {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseUpdateFramePatch();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 8) {
+            break;
+          }
+
+          message.index = reader.uint32();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.semanticId = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.metadata = RootMetadata.decode(reader, reader.uint32());
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): UpdateFramePatch {
+    return {
+      index: isSet(object.index) ? globalThis.Number(object.index) : 0,
+      semanticId: isSet(object.semanticId) ? globalThis.String(object.semanticId) : "",
+      metadata: isSet(object.metadata) ? RootMetadata.fromJSON(object.metadata) : undefined,
+    };
+  },
+
+  toJSON(message: UpdateFramePatch): unknown {
+    const obj: any = {};
+    if (message.index !== 0) {
+      obj.index = Math.round(message.index);
+    }
+    if (message.semanticId !== "") {
+      obj.semanticId = message.semanticId;
+    }
+    if (message.metadata !== undefined) {
+      obj.metadata = RootMetadata.toJSON(message.metadata);
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<UpdateFramePatch>, I>>(base?: I): UpdateFramePatch {
+    return UpdateFramePatch.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<UpdateFramePatch>, I>>(object: I): UpdateFramePatch {
+    const message = createBaseUpdateFramePatch();
+    message.index = object.index ?? 0;
+    message.semanticId = object.semanticId ?? "";
+    message.metadata = (object.metadata !== undefined && object.metadata !== null)
+      ? RootMetadata.fromPartial(object.metadata)
+      : undefined;
+    return message;
+  },
+};
+
+function createBaseUpdateLivePropsPatch(): UpdateLivePropsPatch {
+  return { path: [], propsJson: "", target: undefined };
+}
+
+export const UpdateLivePropsPatch = {
+  encode(message: UpdateLivePropsPatch, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    writer.uint32(10).fork();
+    for (const v of message.path) {
+      writer.uint32(v);
+    }
+    writer.ldelim();
+    if (message.propsJson !== "") {
+      writer.uint32(18).string(message.propsJson);
+    }
+    if (message.target !== undefined) {
+      PatchPath.encode(message.target, writer.uint32(26).fork()).ldelim();
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): UpdateLivePropsPatch {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseUpdateLivePropsPatch();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag === 8) {
+            message.path.push(reader.uint32());
+
+            continue;
+          }
+
+          if (tag === 10) {
+            const end2 = reader.uint32() + reader.pos;
+            while (reader.pos < end2) {
+              message.path.push(reader.uint32());
+            }
+
+            continue;
+          }
+
+          break;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.propsJson = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.target = PatchPath.decode(reader, reader.uint32());
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): UpdateLivePropsPatch {
+    return {
+      path: globalThis.Array.isArray(object?.path) ? object.path.map((e: any) => globalThis.Number(e)) : [],
+      propsJson: isSet(object.propsJson) ? globalThis.String(object.propsJson) : "",
+      target: isSet(object.target) ? PatchPath.fromJSON(object.target) : undefined,
+    };
+  },
+
+  toJSON(message: UpdateLivePropsPatch): unknown {
+    const obj: any = {};
+    if (message.path?.length) {
+      obj.path = message.path.map((e) => Math.round(e));
+    }
+    if (message.propsJson !== "") {
+      obj.propsJson = message.propsJson;
+    }
+    if (message.target !== undefined) {
+      obj.target = PatchPath.toJSON(message.target);
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<UpdateLivePropsPatch>, I>>(base?: I): UpdateLivePropsPatch {
+    return UpdateLivePropsPatch.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<UpdateLivePropsPatch>, I>>(object: I): UpdateLivePropsPatch {
+    const message = createBaseUpdateLivePropsPatch();
+    message.path = object.path?.map((e) => e) || [];
+    message.propsJson = object.propsJson ?? "";
+    message.target = (object.target !== undefined && object.target !== null)
+      ? PatchPath.fromPartial(object.target)
+      : undefined;
     return message;
   },
 };
@@ -73,7 +73,11 @@ export interface VNode {
     | ComponentNode
     | undefined;
   /** Inline error display */
-  error?: ErrorNode | undefined;
+  error?:
+    | ErrorNode
+    | undefined;
+  /** Script-bound component mounted by the client */
+  liveComponent?: LiveComponentNode | undefined;
 }
 
 export interface ElementNode {
@@ -126,6 +130,22 @@ export interface ComponentNode_PropsEntry {
   value: string;
 }
 
+/**
+ * Instance of a component bound to real code with `script(...)`. Clients
+ * that can load `src` mount `export_name` with `props_json`; others render
+ * `children`, the static markup, in its place.
+ */
+export interface LiveComponentNode {
+  semanticId: string;
+  src: string;
+  exportName: string;
+  target: string;
+  propsJson: string;
+  children: VNode[];
+  sourceId?: string | undefined;
+  key?: string | undefined;
+}
+
 export interface ErrorNode {
   message: string;
   semanticId: string;
@@ -136,11 +156,24 @@ export interface ErrorNode {
 export interface CssRule {
   selector: string;
   properties: { [key: string]: string };
+  /**
+   * Single at-rule as text, for clients that predate at_rules
+   *
+   * @deprecated
+   */
   mediaQuery?:
     | string
     | undefined;
   /** Flexible metadata (source info, annotations, etc.) */
-  metadata?: Value | undefined;
+  metadata?:
+    | Value
+    | undefined;
+  /** Cascade layer (tokens, globals, mixins, components, variants, overrides) */
+  layer?:
+    | string
+    | undefined;
+  /** Enclosing at-rules, outermost first */
+  atRules: AtRule[];
 }
 
 export interface CssRule_PropertiesEntry {
@@ -148,6 +181,13 @@ export interface CssRule_PropertiesEntry {
   value: string;
 }
 
+/** Grouping at-rule a style rule is nested in, e.g. @container card (min-width: 400px) */
+export interface AtRule {
+  /** At-keyword without the @ (media, container, supports, layer) */
+  name: string;
+  prelude: string;
+}
+
 /** Virtual CSSOM for CSS-specific operations */
 export interface CssDocument {
   rules: CssRule[];
@@ -180,13 +220,25 @@ export interface AnnotationMetadata_ParamsEntry {
   value?: Value | undefined;
 }
 
+/** Designer metadata for one root node (kept out of the node's attributes) */
+export interface RootMetadata {
+  /** Unset for top-level renders */
+  component?: string | undefined;
+  frame?: FrameMetadata | undefined;
+  annotations: AnnotationMetadata[];
+}
+
 export interface VDocument {
   nodes: VNode[];
   styles: CssRule[];
   /** Component metadata for designer */
   components: ComponentMetadata[];
   /** Document-level metadata */
-  metadata?: Value | undefined;
+  metadata?:
+    | Value
+    | undefined;
+  /** Designer metadata for each of `nodes` (same index) */
+  roots: RootMetadata[];
 }
 
 function createBaseValue(): Value {
@@ -633,7 +685,14 @@ export const Span = {
 };
 
 function createBaseVNode(): VNode {
-  return { element: undefined, text: undefined, comment: undefined, component: undefined, error: undefined };
+  return {
+    element: undefined,
+    text: undefined,
+    comment: undefined,
+    component: undefined,
+    error: undefined,
+    liveComponent: undefined,
+  };
 }
 
 export const VNode = {
@@ -653,6 +712,9 @@ export const VNode = {
     if (message.error !== undefined) {
       ErrorNode.encode(message.error, writer.uint32(42).fork()).ldelim();
     }
+    if (message.liveComponent !== undefined) {
+      LiveComponentNode.encode(message.liveComponent, writer.uint32(50).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -698,6 +760,13 @@ export const VNode = {
 
           message.error = ErrorNode.decode(reader, reader.uint32());
           continue;
+        case 6:
+          if (tag !== 50) {
+            break;
+          }
+
+          message.liveComponent = LiveComponentNode.decode(reader, reader.uint32());
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -714,6 +783,7 @@ export const VNode = {
       comment: isSet(object.comment) ? CommentNode.fromJSON(object.comment) : undefined,
       component: isSet(object.component) ? ComponentNode.fromJSON(object.component) : undefined,
       error: isSet(object.error) ? ErrorNode.fromJSON(object.error) : undefined,
+      liveComponent: isSet(object.liveComponent) ? LiveComponentNode.fromJSON(object.liveComponent) : undefined,
     };
   },
 
@@ -734,6 +804,9 @@ export const VNode = {
     if (message.error !== undefined) {
       obj.error = ErrorNode.toJSON(message.error);
     }
+    if (message.liveComponent !== undefined) {
+      obj.liveComponent = LiveComponentNode.toJSON(message.liveComponent);
+    }
     return obj;
   },
 
@@ -755,6 +828,9 @@ export const VNode = {
     message.error = (object.error !== undefined && object.error !== null)
       ? ErrorNode.fromPartial(object.error)
       : undefined;
+    message.liveComponent = (object.liveComponent !== undefined && object.liveComponent !== null)
+      ? LiveComponentNode.fromPartial(object.liveComponent)
+      : undefined;
     return message;
   },
 };
@@ -1434,6 +1510,179 @@ export const ComponentNode_PropsEntry = {
   },
 };
 
+function createBaseLiveComponentNode(): LiveComponentNode {
+  return {
+    semanticId: "",
+    src: "",
+    exportName: "",
+    target: "",
+    propsJson: "",
+    children: [],
+    sourceId: undefined,
+    key: undefined,
+  };
+}
+
+export const LiveComponentNode = {
+  encode(message: LiveComponentNode, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.semanticId !== "") {
+      writer.uint32(10).string(message.semanticId);
+    }
+    if (message.src !== "") {
+      writer.uint32(18).string(message.src);
+    }
+    if (message.exportName !== "") {
+      writer.uint32(26).string(message.exportName);
+    }
+    if (message.target !== "") {
+      writer.uint32(34).string(message.target);
+    }
+    if (message.propsJson !== "") {
+      writer.uint32(42).string(message.propsJson);
+    }
+    for (const v of message.children) {
+      VNode.encode(v!, writer.uint32(50).fork()).ldelim();
+    }
+    if (message.sourceId !== undefined) {
+      writer.uint32(58).string(message.sourceId);
+    }
+    if (message.key !== undefined) {
+      writer.uint32(66).string(message.key);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): LiveComponentNode {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseLiveComponentNode();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.semanticId = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.src = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.exportName = reader.string();
+          continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.target = reader.string();
+          continue;
+        case 5:
+          if (tag !== 42) {
+            break;
+          }
+
+          message.propsJson = reader.string();
+          continue;
+        case 6:
+          if (tag !== 50) {
+            break;
+          }
+
+          message.children.push(VNode.decode(reader, reader.uint32()));
+          continue;
+        case 7:
+          if (tag !== 58) {
+            break;
+          }
+
+          message.sourceId = reader.string();
+          continue;
+        case 8:
+          if (tag !== 66) {
+            break;
+          }
+
+          message.key = reader.string();
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): LiveComponentNode {
+    return {
+      semanticId: isSet(object.semanticId) ? globalThis.String(object.semanticId) : "",
+      src: isSet(object.src) ? globalThis.String(object.src) : "",
+      exportName: isSet(object.exportName) ? globalThis.String(object.exportName) : "",
+      target: isSet(object.target) ? globalThis.String(object.target) : "",
+      propsJson: isSet(object.propsJson) ? globalThis.String(object.propsJson) : "",
+      children: globalThis.Array.isArray(object?.children) ? object.children.map((e: any) => VNode.fromJSON(e)) : [],
+      sourceId: isSet(object.sourceId) ? globalThis.String(object.sourceId) : undefined,
+      key: isSet(object.key) ? globalThis.String(object.key) : undefined,
+    };
+  },
+
+  toJSON(message: LiveComponentNode): unknown {
+    const obj: any = {};
+    if (message.semanticId !== "") {
+      obj.semanticId = message.semanticId;
+    }
+    if (message.src !== "") {
+      obj.src = message.src;
+    }
+    if (message.exportName !== "") {
+      obj.exportName = message.exportName;
+    }
+    if (message.target !== "") {
+      obj.target = message.target;
+    }
+    if (message.propsJson !== "") {
+      obj.propsJson = message.propsJson;
+    }
+    if (message.children?.length) {
+      obj.children = message.children.map((e) => VNode.toJSON(e));
+    }
+    if (message.sourceId !== undefined) {
+      obj.sourceId = message.sourceId;
+    }
+    if (message.key !== undefined) {
+      obj.key = message.key;
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<LiveComponentNode>, I>>(base?: I): LiveComponentNode {
+    return LiveComponentNode.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<LiveComponentNode>, I>>(object: I): LiveComponentNode {
+    const message = createBaseLiveComponentNode();
+    message.semanticId = object.semanticId ?? "";
+    message.src = object.src ?? "";
+    message.exportName = object.exportName ?? "";
+    message.target = object.target ?? "";
+    message.propsJson = object.propsJson ?? "";
+    message.children = object.children?.map((e) => VNode.fromPartial(e)) || [];
+    message.sourceId = object.sourceId ?? undefined;
+    message.key = object.key ?? undefined;
+    return message;
+  },
+};
+
 function createBaseErrorNode(): ErrorNode {
   return { message: "", semanticId: "", span: undefined };
 }
@@ -1524,7 +1773,7 @@ export const ErrorNode = {
 };
 
 function createBaseCssRule(): CssRule {
-  return { selector: "", properties: {}, mediaQuery: undefined, metadata: undefined };
+  return { selector: "", properties: {}, mediaQuery: undefined, metadata: undefined, layer: undefined, atRules: [] };
 }
 
 export const CssRule = {
@@ -1541,6 +1790,12 @@ export const CssRule = {
     if (message.metadata !== undefined) {
       Value.encode(message.metadata, writer.uint32(34).fork()).ldelim();
     }
+    if (message.layer !== undefined) {
+      writer.uint32(42).string(message.layer);
+    }
+    for (const v of message.atRules) {
+      AtRule.encode(v!, writer.uint32(50).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -1582,6 +1837,20 @@ export const CssRule = {
 
           message.metadata = Value.decode(reader, reader.uint32());
           continue;
+        case 5:
+          if (tag !== 42) {
+            break;
+          }
+
+          message.layer = reader.string();
+          continue;
+        case 6:
+          if (tag !== 50) {
+            break;
+          }
+
+          message.atRules.push(AtRule.decode(reader, reader.uint32()));
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -1602,6 +1871,8 @@ export const CssRule = {
         : {},
       mediaQuery: isSet(object.mediaQuery) ? globalThis.String(object.mediaQuery) : undefined,
       metadata: isSet(object.metadata) ? Value.fromJSON(object.metadata) : undefined,
+      layer: isSet(object.layer) ? globalThis.String(object.layer) : undefined,
+      atRules: globalThis.Array.isArray(object?.atRules) ? object.atRules.map((e: any) => AtRule.fromJSON(e)) : [],
     };
   },
 
@@ -1625,6 +1896,12 @@ export const CssRule = {
     if (message.metadata !== undefined) {
       obj.metadata = Value.toJSON(message.metadata);
     }
+    if (message.layer !== undefined) {
+      obj.layer = message.layer;
+    }
+    if (message.atRules?.length) {
+      obj.atRules = message.atRules.map((e) => AtRule.toJSON(e));
+    }
     return obj;
   },
 
@@ -1647,6 +1924,8 @@ export const CssRule = {
     message.metadata = (object.metadata !== undefined && object.metadata !== null)
       ? Value.fromPartial(object.metadata)
       : undefined;
+    message.layer = object.layer ?? undefined;
+    message.atRules = object.atRules?.map((e) => AtRule.fromPartial(e)) || [];
     return message;
   },
 };
@@ -1725,6 +2004,80 @@ export const CssRule_PropertiesEntry = {
   },
 };
 
+function createBaseAtRule(): AtRule {
+  return { name: "", prelude: "" };
+}
+
+export const AtRule = {
+  encode(message: AtRule, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.name !== "") {
+      writer.uint32(10).string(message.name);
+    }
+    if (message.prelude !== "") {
+      writer.uint32(18).string(message.prelude);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): AtRule {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseAtRule();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.name = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.prelude = reader.string();
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): AtRule {
+    return {
+      name: isSet(object.name) ? globalThis.String(object.name) : "",
+      prelude: isSet(object.prelude) ? globalThis.String(object.prelude) : "",
+    };
+  },
+
+  toJSON(message: AtRule): unknown {
+    const obj: any = {};
+    if (message.name !== "") {
+      obj.name = message.name;
+    }
+    if (message.prelude !== "") {
+      obj.prelude = message.prelude;
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<AtRule>, I>>(base?: I): AtRule {
+    return AtRule.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<AtRule>, I>>(object: I): AtRule {
+    const message = createBaseAtRule();
+    message.name = object.name ?? "";
+    message.prelude = object.prelude ?? "";
+    return message;
+  },
+};
+
 function createBaseCssDocument(): CssDocument {
   return { rules: [], metadata: undefined };
 }
@@ -2197,8 +2550,101 @@ export const AnnotationMetadata_ParamsEntry = {
   },
 };
 
+function createBaseRootMetadata(): RootMetadata {
+  return { component: undefined, frame: undefined, annotations: [] };
+}
+
+export const RootMetadata = {
+  encode(message: RootMetadata, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.component !== undefined) {
+      writer.uint32(10).string(message.component);
+    }
+    if (message.frame !== undefined) {
+      FrameMetadata.encode(message.frame, writer.uint32(18).fork()).ldelim();
+    }
+    for (const v of message.annotations) {
+      AnnotationMetadata.encode(v!, writer.uint32(26).fork()).ldelim();
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): RootMetadata {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseRootMetadata();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.component = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.frame = FrameMetadata.decode(reader, reader.uint32());
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.annotations.push(AnnotationMetadata.decode(reader, reader.uint32()));
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): RootMetadata {
+    return {
+      component: isSet(object.component) ? globalThis.String(object.component) : undefined,
+      frame: isSet(object.frame) ? FrameMetadata.fromJSON(object.frame) : undefined,
+      annotations: globalThis.Array.isArray(object?.annotations)
+        ? object.annotations.map((e: any) => AnnotationMetadata.fromJSON(e))
+        : [],
+    };
+  },
+
+  toJSON(message: RootMetadata): unknown {
+    const obj: any = {};
+    if (message.component !== undefined) {
+      obj.component = message.component;
+    }
+    if (message.frame !== undefined) {
+      obj.frame = FrameMetadata.toJSON(message.frame);
+    }
+    if (message.annotations?.length) {
+      obj.annotations = message.annotations.map((e) => AnnotationMetadata.toJSON(e));
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<RootMetadata>, I>>(base?: I): RootMetadata {
+    return RootMetadata.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<RootMetadata>, I>>(object: I): RootMetadata {
+    const message = createBaseRootMetadata();
+    message.component = object.component ?? undefined;
+    message.frame = (object.frame !== undefined && object.frame !== null)
+      ? FrameMetadata.fromPartial(object.frame)
+      : undefined;
+    message.annotations = object.annotations?.map((e) => AnnotationMetadata.fromPartial(e)) || [];
+    return message;
+  },
+};
+
 function createBaseVDocument(): VDocument {
-  return { nodes: [], styles: [], components: [], metadata: undefined };
+  return { nodes: [], styles: [], components: [], metadata: undefined, roots: [] };
 }
 
 export const VDocument = {
@@ -2215,6 +2661,9 @@ export const VDocument = {
     if (message.metadata !== undefined) {
       Value.encode(message.metadata, writer.uint32(34).fork()).ldelim();
     }
+    for (const v of message.roots) {
+      RootMetadata.encode(v!, writer.uint32(42).fork()).ldelim();
+    }
     return writer;
   },
 
@@ -2253,6 +2702,13 @@ export const VDocument = {
 
           message.metadata = Value.decode(reader, reader.uint32());
           continue;
+        case 5:
+          if (tag !== 42) {
+            break;
+          }
+
+          message.roots.push(RootMetadata.decode(reader, reader.uint32()));
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -2270,6 +2726,7 @@ export const VDocument = {
         ? object.components.map((e: any) => ComponentMetadata.fromJSON(e))
         : [],
       metadata: isSet(object.metadata) ? Value.fromJSON(object.metadata) : undefined,
+      roots: globalThis.Array.isArray(object?.roots) ? object.roots.map((e: any) => RootMetadata.fromJSON(e)) : [],
     };
   },
 
@@ -2287,6 +2744,9 @@ export const VDocument = {
     if (message.metadata !== undefined) {
       obj.metadata = Value.toJSON(message.metadata);
     }
+    if (message.roots?.length) {
+      obj.roots = message.roots.map((e) => RootMetadata.toJSON(e));
+    }
     return obj;
   },
 
@@ -2301,6 +2761,7 @@ export const VDocument = {
     message.metadata = (object.metadata !== undefined && object.metadata !== null)
       ? Value.fromPartial(object.metadata)
       : undefined;
+    message.roots = object.roots?.map((e) => RootMetadata.fromPartial(e)) || [];
     return message;
   },
 };
@@ -8,6 +8,7 @@
 import Long from "long";
 import * as _m0 from "protobufjs/minimal";
 import { VDocPatch } from "./patches";
+import { Span } from "./vdom";
 
 export const protobufPackage = "paperclip.workspace";
 
@@ -69,6 +70,69 @@ export function nodeTypeToJSON(object: NodeType): string {
   }
 }
 
+export enum StyleOrigin {
+  INLINE = 0,
+  EXTENDS = 1,
+  TOKEN = 2,
+  VARIANT = 3,
+  TRIGGER = 4,
+  OVERRIDE = 5,
+  IMPORTED = 6,
+  UNRECOGNIZED = -1,
+}
+
+export function styleOriginFromJSON(object: any): StyleOrigin {
+  switch (object) {
+    case 0:
+    case "INLINE":
+      return StyleOrigin.INLINE;
+    case 1:
+    case "EXTENDS":
+      return StyleOrigin.EXTENDS;
+    case 2:
+    case "TOKEN":
+      return StyleOrigin.TOKEN;
+    case 3:
+    case "VARIANT":
+      return StyleOrigin.VARIANT;
+    case 4:
+    case "TRIGGER":
+      return StyleOrigin.TRIGGER;
+    case 5:
+    case "OVERRIDE":
+      return StyleOrigin.OVERRIDE;
+    case 6:
+    case "IMPORTED":
+      return StyleOrigin.IMPORTED;
+    case -1:
+    case "UNRECOGNIZED":
+    default:
+      return StyleOrigin.UNRECOGNIZED;
+  }
+}
+
+export function styleOriginToJSON(object: StyleOrigin): string {
+  switch (object) {
+    case StyleOrigin.INLINE:
+      return "INLINE";
+    case StyleOrigin.EXTENDS:
+      return "EXTENDS";
+    case StyleOrigin.TOKEN:
+      return "TOKEN";
+    case StyleOrigin.VARIANT:
+      return "VARIANT";
+    case StyleOrigin.TRIGGER:
+      return "TRIGGER";
+    case StyleOrigin.OVERRIDE:
+      return "OVERRIDE";
+    case StyleOrigin.IMPORTED:
+      return "IMPORTED";
+    case StyleOrigin.UNRECOGNIZED:
+    default:
+      return "UNRECOGNIZED";
+  }
+}
+
 /** Request to start preview streaming */
 export interface PreviewRequest {
   rootPath: string;
@@ -269,6 +333,31 @@ export interface OutlineNode {
   label?: string | undefined;
 }
 
+/** Request computed styles for an element */
+export interface ComputeStylesRequest {
+  filePath: string;
+  /** AST node id (VNode source_id) */
+  nodeId: string;
+  activeVariants: string[];
+}
+
+/** Every declaration that applies, grouped by property with the winner first */
+export interface ComputeStylesResponse {
+  properties: ComputedProperty[];
+}
+
+export interface ComputedProperty {
+  name: string;
+  /** As written in the source */
+  value: string;
+  /** Token references substituted */
+  resolvedValue: string;
+  sourceSpan?: Span | undefined;
+  originKind: StyleOrigin;
+  /** Index of the winning declaration */
+  overriddenBy?: number | undefined;
+}
+
 /** Source code location */
 export interface SourceSpan {
   startLine: number;
@@ -364,8 +453,17 @@ export interface CrdtVdomPatch {
 
 /** CSSOM update from server */
 export interface CrdtCssomPatch {
+  /**
+   * Full rule list
+   *
+   * @deprecated
+   */
   rules: CssRule[];
   version: number;
+  /** Keyed AddStyleRule / UpdateStyleRule / RemoveStyleRule patches */
+  patches: VDocPatch[];
+  /** Drop every known rule before applying `patches` (sent on initialize) */
+  reset: boolean;
 }
 
 /** CSS rule for CSSOM */
@@ -2403,6 +2501,294 @@ export const OutlineNode = {
   },
 };
 
+function createBaseComputeStylesRequest(): ComputeStylesRequest {
+  return { filePath: "", nodeId: "", activeVariants: [] };
+}
+
+export const ComputeStylesRequest = {
+  encode(message: ComputeStylesRequest, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.filePath !== "") {
+      writer.uint32(10).string(message.filePath);
+    }
+    if (message.nodeId !== "") {
+      writer.uint32(18).string(message.nodeId);
+    }
+    for (const v of message.activeVariants) {
+      writer.uint32(26).string(v!);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): ComputeStylesRequest {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseComputeStylesRequest();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.filePath = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.nodeId = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.activeVariants.push(reader.string());
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): ComputeStylesRequest {
+    return {
+      filePath: isSet(object.filePath) ? globalThis.String(object.filePath) : "",
+      nodeId: isSet(object.nodeId) ? globalThis.String(object.nodeId) : "",
+      activeVariants: globalThis.Array.isArray(object?.activeVariants)
+        ? object.activeVariants.map((e: any) => globalThis.String(e))
+        : [],
+    };
+  },
+
+  toJSON(message: ComputeStylesRequest): unknown {
+    const obj: any = {};
+    if (message.filePath !== "") {
+      obj.filePath = message.filePath;
+    }
+    if (message.nodeId !== "") {
+      obj.nodeId = message.nodeId;
+    }
+    if (message.activeVariants?.length) {
+      obj.activeVariants = message.activeVariants;
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<ComputeStylesRequest>, I>>(base?: I): ComputeStylesRequest {
+    return ComputeStylesRequest.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<ComputeStylesRequest>, I>>(object: I): ComputeStylesRequest {
+    const message = createBaseComputeStylesRequest();
+    message.filePath = object.filePath ?? "";
+    message.nodeId = object.nodeId ?? "";
+    message.activeVariants = object.activeVariants?.map((e) => e) || [];
+    return message;
+  },
+};
+
+function createBaseComputeStylesResponse(): ComputeStylesResponse {
+  return { properties: [] };
+}
+
+export const ComputeStylesResponse = {
+  encode(message: ComputeStylesResponse, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    for (const v of message.properties) {
+      ComputedProperty.encode(v!, writer.uint32(10).fork()).ldelim();
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): ComputeStylesResponse {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseComputeStylesResponse();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.properties.push(ComputedProperty.decode(reader, reader.uint32()));
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): ComputeStylesResponse {
+    return {
+      properties: globalThis.Array.isArray(object?.properties)
+        ? object.properties.map((e: any) => ComputedProperty.fromJSON(e))
+        : [],
+    };
+  },
+
+  toJSON(message: ComputeStylesResponse): unknown {
+    const obj: any = {};
+    if (message.properties?.length) {
+      obj.properties = message.properties.map((e) => ComputedProperty.toJSON(e));
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<ComputeStylesResponse>, I>>(base?: I): ComputeStylesResponse {
+    return ComputeStylesResponse.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<ComputeStylesResponse>, I>>(object: I): ComputeStylesResponse {
+    const message = createBaseComputeStylesResponse();
+    message.properties = object.properties?.map((e) => ComputedProperty.fromPartial(e)) || [];
+    return message;
+  },
+};
+
+function createBaseComputedProperty(): ComputedProperty {
+  return { name: "", value: "", resolvedValue: "", sourceSpan: undefined, originKind: 0, overriddenBy: undefined };
+}
+
+export const ComputedProperty = {
+  encode(message: ComputedProperty, writer: _m0.Writer = _m0.Writer.create()): _m0.Writer {
+    if (message.name !== "") {
+      writer.uint32(10).string(message.name);
+    }
+    if (message.value !== "") {
+      writer.uint32(18).string(message.value);
+    }
+    if (message.resolvedValue !== "") {
+      writer.uint32(26).string(message.resolvedValue);
+    }
+    if (message.sourceSpan !== undefined) {
+      Span.encode(message.sourceSpan, writer.uint32(34).fork()).ldelim();
+    }
+    if (message.originKind !== 0) {
+      writer.uint32(40).int32(message.originKind);
+    }
+    if (message.overriddenBy !== undefined) {
+      writer.uint32(48).uint32(message.overriddenBy);
+    }
+    return writer;
+  },
+
+  decode(input: _m0.Reader | Uint8Array, length?: number): ComputedProperty {
+    const reader = input instanceof _m0.Reader ? input : _m0.Reader.create(input);
+    let end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseComputedProperty();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1:
+          if (tag !== 10) {
+            break;
+          }
+
+          message.name = reader.string();
+          continue;
+        case 2:
+          if (tag !== 18) {
+            break;
+          }
+
+          message.value = reader.string();
+          continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.resolvedValue = reader.string();
+          continue;
+        case 4:
+          if (tag !== 34) {
+            break;
+          }
+
+          message.sourceSpan = Span.decode(reader, reader.uint32());
+          continue;
+        case 5:
+          if (tag !== 40) {
+            break;
+          }
+
+          message.originKind = reader.int32() as any;
+          continue;
+        case 6:
+          if (tag !== 48) {
+            break;
+          }
+
+          message.overriddenBy = reader.uint32();
+          continue;
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skipType(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): ComputedProperty {
+    return {
+      name: isSet(object.name) ? globalThis.String(object.name) : "",
+      value: isSet(object.value) ? globalThis.String(object.value) : "",
+      resolvedValue: isSet(object.resolvedValue) ? globalThis.String(object.resolvedValue) : "",
+      sourceSpan: isSet(object.sourceSpan) ? Span.fromJSON(object.sourceSpan) : undefined,
+      originKind: isSet(object.originKind) ? styleOriginFromJSON(object.originKind) : 0,
+      overriddenBy: isSet(object.overriddenBy) ? globalThis.Number(object.overriddenBy) : undefined,
+    };
+  },
+
+  toJSON(message: ComputedProperty): unknown {
+    const obj: any = {};
+    if (message.name !== "") {
+      obj.name = message.name;
+    }
+    if (message.value !== "") {
+      obj.value = message.value;
+    }
+    if (message.resolvedValue !== "") {
+      obj.resolvedValue = message.resolvedValue;
+    }
+    if (message.sourceSpan !== undefined) {
+      obj.sourceSpan = Span.toJSON(message.sourceSpan);
+    }
+    if (message.originKind !== 0) {
+      obj.originKind = styleOriginToJSON(message.originKind);
+    }
+    if (message.overriddenBy !== undefined) {
+      obj.overriddenBy = Math.round(message.overriddenBy);
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<ComputedProperty>, I>>(base?: I): ComputedProperty {
+    return ComputedProperty.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<ComputedProperty>, I>>(object: I): ComputedProperty {
+    const message = createBaseComputedProperty();
+    message.name = object.name ?? "";
+    message.value = object.value ?? "";
+    message.resolvedValue = object.resolvedValue ?? "";
+    message.sourceSpan = (object.sourceSpan !== undefined && object.sourceSpan !== null)
+      ? Span.fromPartial(object.sourceSpan)
+      : undefined;
+    message.originKind = object.originKind ?? 0;
+    message.overriddenBy = object.overriddenBy ?? undefined;
+    return message;
+  },
+};
+
 function createBaseSourceSpan(): SourceSpan {
   return { startLine: 0, startCol: 0, endLine: 0, endCol: 0 };
 }
@@ -3183,7 +3569,7 @@ export const CrdtVdomPatch = {
 };
 
 function createBaseCrdtCssomPatch(): CrdtCssomPatch {
-  return { rules: [], version: 0 };
+  return { rules: [], version: 0, patches: [], reset: false };
 }
 
 export const CrdtCssomPatch = {
@@ -3194,6 +3580,12 @@ export const CrdtCssomPatch = {
     if (message.version !== 0) {
       writer.uint32(16).uint64(message.version);
     }
+    for (const v of message.patches) {
+      VDocPatch.encode(v!, writer.uint32(26).fork()).ldelim();
+    }
+    if (message.reset !== false) {
+      writer.uint32(32).bool(message.reset);
+    }
     return writer;
   },
 
@@ -3218,6 +3610,20 @@ export const CrdtCssomPatch = {
 
           message.version = longToNumber(reader.uint64() as Long);
           continue;
+        case 3:
+          if (tag !== 26) {
+            break;
+          }
+
+          message.patches.push(VDocPatch.decode(reader, reader.uint32()));
+          continue;
+        case 4:
+          if (tag !== 32) {
+            break;
+          }
+
+          message.reset = reader.bool();
+          continue;
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -3231,6 +3637,8 @@ export const CrdtCssomPatch = {
     return {
       rules: globalThis.Array.isArray(object?.rules) ? object.rules.map((e: any) => CssRule.fromJSON(e)) : [],
       version: isSet(object.version) ? globalThis.Number(object.version) : 0,
+      patches: globalThis.Array.isArray(object?.patches) ? object.patches.map((e: any) => VDocPatch.fromJSON(e)) : [],
+      reset: isSet(object.reset) ? globalThis.Boolean(object.reset) : false,
     };
   },
 
@@ -3242,6 +3650,12 @@ export const CrdtCssomPatch = {
     if (message.version !== 0) {
       obj.version = Math.round(message.version);
     }
+    if (message.patches?.length) {
+      obj.patches = message.patches.map((e) => VDocPatch.toJSON(e));
+    }
+    if (message.reset !== false) {
+      obj.reset = message.reset;
+    }
     return obj;
   },
 
@@ -3252,6 +3666,8 @@ export const CrdtCssomPatch = {
     const message = createBaseCrdtCssomPatch();
     message.rules = object.rules?.map((e) => CssRule.fromPartial(e)) || [];
     message.version = object.version ?? 0;
+    message.patches = object.patches?.map((e) => VDocPatch.fromPartial(e)) || [];
+    message.reset = object.reset ?? false;
     return message;
   },
 };
@@ -12,6 +12,8 @@ export {
   ErrorNode,
   VDocument,
   CssRule,
+  RootMetadata,
+  FrameMetadata,
 } from './generated/vdom.js';
 
 // Patch types
@@ -29,6 +31,8 @@ export {
   AddStyleRulePatch,
   RemoveStyleRulePatch,
   MoveChildPatch,
+  UpdateFramePatch,
+  UpdateLivePropsPatch,
 } from './generated/patches.js';
 
 // Workspace service types
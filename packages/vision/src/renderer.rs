@@ -120,6 +120,13 @@ fn emit_vnode_html(node: &VNode, is_root: bool) -> String {
 
             html
         }
+        VNode::LiveComponent { children, .. } => {
+            // Screenshots can't run component code, so capture the static fallback
+            children
+                .iter()
+                .map(|child| emit_vnode_html(child, is_root))
+                .collect()
+        }
         VNode::Text { content } => escape_html(content),
         VNode::Comment { .. } => {
             // Skip comments in HTML output
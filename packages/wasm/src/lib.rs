@@ -42,6 +42,7 @@ pub fn compile_to_react_js(
     let options = CompileOptions {
         use_typescript: generate_types,
         include_css_imports: true,
+        ..CompileOptions::default()
     };
 
     let code = compile_to_react(&doc, options.clone())
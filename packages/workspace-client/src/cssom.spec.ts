@@ -0,0 +1,98 @@
+import { describe, it, expect } from 'vitest';
+import { applyCssomPatch, CSSOM } from './cssom';
+
+const media = [{ name: 'media', prelude: '(max-width: 600px)' }];
+
+describe('applyCssomPatch', () => {
+  const initial: CSSOM = {
+    rules: [
+      { selector: '.a', properties: { color: 'red', margin: '0' } },
+      { selector: '.a', properties: { color: 'green' }, atRules: media },
+      { selector: '.b', properties: { padding: '4px' } },
+    ],
+    version: 1,
+  };
+
+  it('updates and removes rules by key rather than position', () => {
+    const cssom = applyCssomPatch(initial, {
+      patches: [
+        {
+          updateStyleRule: {
+            selector: '.a',
+            atRules: media,
+            properties: { color: 'blue' },
+            removedProperties: [],
+          },
+        },
+        { updateStyleRule: { selector: '.a', properties: {}, removedProperties: ['margin'] } },
+        { removeStyleRule: { selector: '.b' } },
+      ],
+      version: 2,
+      reset: false,
+    });
+
+    expect(cssom).toEqual({
+      rules: [
+        { selector: '.a', properties: { color: 'red' } },
+        { selector: '.a', properties: { color: 'blue' }, atRules: media },
+      ],
+      version: 2,
+    });
+  });
+
+  it('replaces an existing rule on add', () => {
+    const cssom = applyCssomPatch(initial, {
+      patches: [{ addStyleRule: { rule: { selector: '.b', properties: { padding: '8px' } } } }],
+      version: 2,
+      reset: false,
+    });
+
+    expect(cssom.rules).toHaveLength(3);
+    expect(cssom.rules[2].properties).toEqual({ padding: '8px' });
+  });
+
+  it('drops known rules on reset', () => {
+    const rule = { selector: '.c', properties: { display: 'flex' } };
+    const cssom = applyCssomPatch(initial, {
+      patches: [{ addStyleRule: { rule } }],
+      version: 2,
+      reset: true,
+    });
+
+    expect(cssom.rules).toEqual([rule]);
+  });
+
+  it('keeps rules with the same selector in different layers apart', () => {
+    const tokens = { selector: ':root', properties: { '--brand': 'red' }, layer: 'tokens' };
+    const mixins = { selector: ':root', properties: { '--base-padding': '8px' }, layer: 'mixins' };
+    const cssom = applyCssomPatch(
+      { rules: [tokens, mixins], version: 1 },
+      {
+        patches: [
+          {
+            updateStyleRule: {
+              selector: ':root',
+              layer: 'tokens',
+              properties: { '--brand': 'blue' },
+              removedProperties: [],
+            },
+          },
+        ],
+        version: 2,
+        reset: false,
+      },
+    );
+
+    expect(cssom.rules).toEqual([{ ...tokens, properties: { '--brand': 'blue' } }, mixins]);
+  });
+
+  it('leaves the previous CSSOM untouched', () => {
+    applyCssomPatch(initial, {
+      patches: [{ removeStyleRule: { selector: '.b' } }],
+      version: 2,
+      reset: false,
+    });
+
+    expect(initial.rules).toHaveLength(3);
+  });
+});
@@ -0,0 +1,85 @@
+/**
+ * Client-side CSSOM kept in sync with the server's keyed style rule patches.
+ *
+ * Rules are addressed by (layer, selector, atRules) rather than position,
+ * matching the server's CSS differ, so patches stay valid when rules are
+ * reordered.
+ */
+
+export interface CssomAtRule {
+  name: string;
+  prelude: string;
+}
+
+export interface CssomRule {
+  selector: string;
+  properties: Record<string, string>;
+  atRules?: CssomAtRule[];
+  /** Cascade layer, "components" when absent */
+  layer?: string;
+  [field: string]: unknown;
+}
+
+/**
+ * CSSOM patch as delivered by the CRDT transport.
+ */
+export interface CssomPatch {
+  /** AddStyleRule / UpdateStyleRule / RemoveStyleRule patches */
+  patches: any[];
+  version: number;
+  /** Drop every known rule before applying `patches` */
+  reset: boolean;
+}
+
+export interface CSSOM {
+  rules: CssomRule[];
+  version: number;
+}
+
+/** A rule, or the key fields of a patch targeting one */
+type RuleTarget = Pick<CssomRule, 'layer' | 'selector' | 'atRules'>;
+
+function ruleKey({ layer, selector, atRules }: RuleTarget): string {
+  const scope = (atRules || []).map((atRule) => `@${atRule.name} ${atRule.prelude}`);
+  return JSON.stringify([layer || 'components', selector, ...scope]);
+}
+
+/**
+ * Apply a CSSOM patch, returning the updated CSSOM. The previous CSSOM is
+ * left untouched.
+ */
+export function applyCssomPatch(cssom: CSSOM | null, patch: CssomPatch): CSSOM {
+  const rules: CssomRule[] = patch.reset || !cssom ? [] : cssom.rules.map((rule) => ({ ...rule }));
+  const indexOf = (target: RuleTarget) => {
+    const key = ruleKey(target);
+    return rules.findIndex((rule) => ruleKey(rule) === key);
+  };
+
+  for (const { addStyleRule, updateStyleRule, removeStyleRule } of patch.patches) {
+    if (addStyleRule?.rule) {
+      const rule: CssomRule = addStyleRule.rule;
+      const index = indexOf(rule);
+      if (index === -1) {
+        rules.push(rule);
+      } else {
+        rules[index] = rule;
+      }
+    } else if (updateStyleRule) {
+      const index = indexOf(updateStyleRule);
+      if (index !== -1) {
+        const properties = { ...rules[index].properties, ...updateStyleRule.properties };
+        for (const name of updateStyleRule.removedProperties || []) {
+          delete properties[name];
+        }
+        rules[index] = { ...rules[index], properties };
+      }
+    } else if (removeStyleRule) {
+      const index = indexOf(removeStyleRule);
+      if (index !== -1) {
+        rules.splice(index, 1);
+      }
+    }
+  }
+
+  return { rules, version: patch.version };
+}
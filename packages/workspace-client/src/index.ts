@@ -13,6 +13,8 @@ export type { TextDelta, ChangeOptions, ChangeHandler } from './crdt.js';
 // Session-based sync (combines CRDT + rendering)
 export { DocumentSession } from './sync.js';
 export type { SyncTransport, VDOMHandler, CSSOMHandler } from './sync.js';
+export { applyCssomPatch } from './cssom.js';
+export type { CSSOM, CssomPatch, CssomRule, CssomAtRule } from './cssom.js';
 
 export { EventEmitter } from './events.js';
 export type {
@@ -57,7 +59,10 @@ export type {
   TextNode,
   CommentNode,
   ComponentNode,
+  LiveComponentNode,
   VDocument,
+  FrameMetadata,
+  RootMetadata,
   CssRule,
   // Patch types
   VDocPatch,
@@ -70,6 +75,9 @@ export type {
   UpdateTextPatch,
   AddStyleRulePatch,
   RemoveStyleRulePatch,
+  UpdateStyleRulePatch,
+  UpdateFramePatch,
+  UpdateLivePropsPatch,
   // Workspace service types
   PreviewRequest,
   PreviewUpdate,
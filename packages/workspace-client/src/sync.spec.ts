@@ -191,21 +191,28 @@ describe('DocumentSession', () => {
       expect(session.getCSSOM()).toBeNull(); // Initially null
     });
 
-    it('updates CSSOM when server sends it', () => {
-      const cssom = { rules: ['.foo { color: red }'] };
-      transport.simulateCSSOM(cssom);
+    it('applies CSSOM patches when server sends them', () => {
+      const rule = { selector: '.foo', properties: { color: 'red' } };
+      transport.simulateCSSOM({ patches: [{ addStyleRule: { rule } }], version: 1, reset: true });
+      transport.simulateCSSOM({
+        patches: [{ updateStyleRule: { selector: '.foo', properties: { color: 'blue' } } }],
+        version: 2,
+        reset: false,
+      });
 
-      expect(session.getCSSOM()).toEqual(cssom);
+      expect(session.getCSSOM()).toEqual({
+        rules: [{ selector: '.foo', properties: { color: 'blue' } }],
+        version: 2,
+      });
     });
 
     it('emits onCSSOMChange when CSSOM updates', () => {
       const handler = vi.fn();
       session.onCSSOMChange(handler);
 
-      const cssom = { rules: [] };
-      transport.simulateCSSOM(cssom);
+      transport.simulateCSSOM({ patches: [], version: 1, reset: true });
 
-      expect(handler).toHaveBeenCalledWith(cssom);
+      expect(handler).toHaveBeenCalledWith({ rules: [], version: 1 });
     });
   });
 
@@ -1,4 +1,5 @@
 import { CRDTDocument, TextDelta, ChangeOptions, ChangeHandler } from './crdt';
+import { applyCssomPatch, type CSSOM, type CssomPatch } from './cssom';
 
 /**
  * Transport interface for server communication.
@@ -21,13 +22,13 @@ export interface SyncTransport {
   onVDOM(callback: (vdom: any) => void): () => void;
 
   /**
-   * Subscribe to CSSOM updates from server.
+   * Subscribe to keyed CSSOM patches from server.
    */
-  onCSSOM(callback: (cssom: any) => void): () => void;
+  onCSSOM(callback: (patch: CssomPatch) => void): () => void;
 }
 
 export type VDOMHandler = (vdom: any) => void;
-export type CSSOMHandler = (cssom: any) => void;
+export type CSSOMHandler = (cssom: CSSOM) => void;
 
 /**
  * A session for editing a single file with CRDT synchronization.
@@ -45,7 +46,7 @@ export type CSSOMHandler = (cssom: any) => void;
 export class DocumentSession {
   private crdt: CRDTDocument;
   private vdom: any = null;
-  private cssom: any = null;
+  private cssom: CSSOM | null = null;
   private vdomHandlers = new Set<VDOMHandler>();
   private cssomHandlers = new Set<CSSOMHandler>();
   private textHandlers = new Set<ChangeHandler>();
@@ -98,8 +99,9 @@ export class DocumentSession {
     this.unsubscribers.push(unsubVdom);
 
     // Subscribe to CSSOM updates
-    const unsubCssom = transport.onCSSOM((cssom) => {
+    const unsubCssom = transport.onCSSOM((patch) => {
       if (this.disposed) return;
+      const cssom = applyCssomPatch(this.cssom, patch);
       this.cssom = cssom;
       for (const handler of this.cssomHandlers) {
         handler(cssom);
@@ -167,7 +169,7 @@ export class DocumentSession {
   /**
    * Get current CSSOM (may be null if not yet received).
    */
-  getCSSOM(): any {
+  getCSSOM(): CSSOM | null {
     return this.cssom;
   }
 
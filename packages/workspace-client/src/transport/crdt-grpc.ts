@@ -4,6 +4,7 @@
  */
 
 import type { SyncTransport } from '../sync.js';
+import type { CssomPatch } from '../cssom.js';
 
 /**
  * CRDT sync messages (matching proto definitions)
@@ -41,6 +42,7 @@ export type CrdtSyncResponse =
   | { type: 'welcome'; welcome: CrdtWelcome }
   | { type: 'remoteUpdate'; update: CrdtUpdate }
   | { type: 'vdomPatch'; patch: CrdtVdomPatch }
+  | { type: 'cssomPatch'; patch: CssomPatch }
   | { type: 'parseError'; error: CrdtParseError };
 
 /**
@@ -168,9 +170,10 @@ export class CrdtGrpcTransport implements SyncTransport {
         handler(vdom);
       }
     } else if (msgType?.cssomPatch) {
-      const cssom = {
-        rules: msgType.cssomPatch.rules || [],
+      const cssom: CssomPatch = {
+        patches: msgType.cssomPatch.patches || [],
         version: msgType.cssomPatch.version,
+        reset: msgType.cssomPatch.reset || false,
       };
       for (const handler of this.cssomHandlers) {
         handler(cssom);
@@ -12,6 +12,7 @@ export interface VNode {
   text?: TextNode;
   comment?: CommentNode;
   component?: ComponentNode;
+  live_component?: LiveComponentNode;
 }
 
 export interface ElementNode {
@@ -37,14 +38,61 @@ export interface ComponentNode {
   id?: string;
 }
 
+// Script-bound component: mount `export_name` from `src` with the JSON
+// props, or render `children` (the static markup) in its place
+export interface LiveComponentNode {
+  semantic_id: string;
+  src: string;
+  export_name: string;
+  target: string;
+  props_json: string;
+  children: VNode[];
+  source_id?: string;
+  key?: string;
+}
+
 export interface VDocument {
   nodes: VNode[];
   styles: CssRule[];
+  /** Designer metadata for each root node (same index as `nodes`) */
+  roots?: RootMetadata[];
+}
+
+export interface FrameMetadata {
+  x: number;
+  y: number;
+  width?: number;
+  height?: number;
+}
+
+export interface RootMetadata {
+  component?: string;
+  frame?: FrameMetadata;
+}
+
+export type CssLayer =
+  | "tokens"
+  | "globals"
+  | "mixins"
+  | "components"
+  | "variants"
+  | "overrides";
+
+/** Grouping at-rule, e.g. { name: "container", prelude: "card (min-width: 400px)" } */
+export interface AtRule {
+  name: string;
+  prelude: string;
 }
 
 export interface CssRule {
   selector: string;
   properties: Record<string, string>;
+  /** @deprecated Use at_rules */
+  media_query?: string;
+  /** Enclosing at-rules, outermost first */
+  at_rules?: AtRule[];
+  /** Cascade layer the rule belongs to (defaults to "components") */
+  layer?: CssLayer;
 }
 
 // ============================================================================
@@ -61,6 +109,9 @@ export interface VDocPatch {
   update_text?: UpdateTextPatch;
   add_style_rule?: AddStyleRulePatch;
   remove_style_rule?: RemoveStyleRulePatch;
+  update_style_rule?: UpdateStyleRulePatch;
+  update_frame?: UpdateFramePatch;
+  update_live_props?: UpdateLivePropsPatch;
 }
 
 export interface InitializePatch {
@@ -101,8 +152,38 @@ export interface AddStyleRulePatch {
   rule: CssRule;
 }
 
+// Style rule patches are keyed by (layer, selector, at_rules)
 export interface RemoveStyleRulePatch {
+  /** @deprecated Use selector and at_rules */
   index: number;
+  selector: string;
+  /** @deprecated Use at_rules */
+  media_query?: string;
+  at_rules?: AtRule[];
+  layer?: CssLayer;
+}
+
+export interface UpdateStyleRulePatch {
+  selector: string;
+  /** @deprecated Use at_rules */
+  media_query?: string;
+  at_rules?: AtRule[];
+  layer?: CssLayer;
+  properties: Record<string, string>;
+  removed_properties: string[];
+}
+
+// Frame moves replace root metadata instead of node attributes
+export interface UpdateFramePatch {
+  index: number;
+  semantic_id: string;
+  metadata: RootMetadata;
+}
+
+// Re-render a mounted live component with new props, without remounting it
+export interface UpdateLivePropsPatch {
+  path: number[];
+  props_json: string;
 }
 
 // ============================================================================
@@ -8,7 +8,7 @@ use axum::{
 use futures::stream::{self, Stream};
 use paperclip_bundle::Bundle;
 use paperclip_evaluator::Evaluator;
-use paperclip_parser::parse_with_path;
+use paperclip_parser::{parse_with_path, DocumentIdMode};
 use paperclip_workspace::{
     convert_vdom_to_proto, Mutation, MutationHandler, WorkspaceServer,
 };
@@ -224,7 +224,8 @@ async fn preview_sse_handler(
     let mut broadcast_rx = state.workspace.subscribe();
 
     // Process initial file state
-    let initial_event = match process_file_to_json(&full_path, &root_dir) {
+    let document_id_mode = state.workspace.workspace_state().lock().unwrap().document_id_mode();
+    let initial_event = match process_file_to_json(&full_path, &root_dir, document_id_mode) {
         Ok(patches) => {
             let event = PreviewEvent {
                 file_path: file_path_str.clone(),
@@ -331,14 +332,19 @@ async fn preview_sse_handler(
 fn process_file_to_json(
     file_path: &std::path::Path,
     root_dir: &std::path::Path,
+    document_id_mode: DocumentIdMode,
 ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error + Send + Sync>> {
+    // Identify the file the way WorkspaceState does, so IDs match its patches
+    let mut bundle = Bundle::new()
+        .with_project_root(root_dir)
+        .with_document_id_mode(document_id_mode);
+    let path_str = bundle.document_path(file_path);
+
     // Read and parse file
     let source = std::fs::read_to_string(file_path)?;
-    let path_str = file_path.to_string_lossy();
     let ast = parse_with_path(&source, &path_str)?;
 
-    // Create bundle and add document
-    let mut bundle = Bundle::new();
+    // Add document to bundle
     bundle.add_document(file_path.to_path_buf(), ast);
 
     // Build dependencies for cross-file imports
@@ -477,7 +483,13 @@ async fn mutation_handler(
         let crdt_doc = &mut session_guard.document;
 
         // Build mutation handler with file path for correct span.id generation
-        let mut handler = MutationHandler::new_with_path(&full_path.to_string_lossy());
+        let document_path = state
+            .workspace
+            .workspace_state()
+            .lock()
+            .unwrap()
+            .document_path(&full_path, &state.root_dir);
+        let mut handler = MutationHandler::new_with_path(&document_path);
         let source = crdt_doc.get_text();
 
         if let Err(e) = handler.rebuild_index(crdt_doc.doc(), &source) {
@@ -187,6 +187,12 @@ pub enum CrdtBroadcast {
         version: u64,
         origin_client_id: String,
     },
+    /// Keyed style rule patches after successful parse
+    CssomPatch {
+        patches_json: String,
+        version: u64,
+        reset: bool,
+    },
     /// Parse error
     ParseError {
         error: String,
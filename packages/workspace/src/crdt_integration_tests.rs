@@ -263,4 +263,47 @@ mod tests {
         manager.remove_session("/test/file.pc");
         assert!(manager.get_session("/test/file.pc").is_none());
     }
+
+    #[test]
+    fn test_cssom_patches_are_keyed_style_rule_patches() {
+        use crate::server::cssom_patches;
+        use crate::state::WorkspaceState;
+        use paperclip_evaluator::vdom_differ::proto::patches::v_doc_patch::PatchType;
+        use std::path::PathBuf;
+
+        let mut state = WorkspaceState::new();
+        let path = PathBuf::from("/test/card.pc");
+        let project_root = PathBuf::from("/test");
+        let source = |color: &str| {
+            format!(
+                "public component Card {{\n  render div {{\n    style {{\n      color: {};\n    }}\n  }}\n}}",
+                color
+            )
+        };
+
+        // The first parse initializes, so the whole stylesheet is re-sent
+        let patches = state
+            .update_file(path.clone(), source("red"), &project_root)
+            .unwrap();
+        let (cssom, reset) = cssom_patches(&patches);
+        assert!(reset);
+        assert!(!cssom.is_empty());
+        assert!(cssom
+            .iter()
+            .all(|patch| matches!(patch.patch_type, Some(PatchType::AddStyleRule(_)))));
+
+        // Later edits only carry the keyed rule patches
+        let patches = state
+            .update_file(path, source("blue"), &project_root)
+            .unwrap();
+        let (cssom, reset) = cssom_patches(&patches);
+        assert!(!reset);
+        assert_eq!(cssom.len(), 1);
+        match &cssom[0].patch_type {
+            Some(PatchType::UpdateStyleRule(update)) => {
+                assert_eq!(update.properties.get("color"), Some(&"blue".to_string()));
+            }
+            other => panic!("Expected UpdateStyleRule patch, got {:?}", other),
+        }
+    }
 }
@@ -2,6 +2,10 @@ use crate::crdt::{CrdtBroadcast, CrdtClient, CrdtSessionManager};
 use crate::mutation_handler::{Mutation, MutationHandler};
 use crate::state::{StateError, WorkspaceState};
 use crate::watcher::FileWatcher;
+use paperclip_evaluator::config::Config;
+use paperclip_evaluator::{
+    span_to_proto, CancellationToken, ComputedProperty, CssTransforms, OriginKind,
+};
 use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
@@ -43,6 +47,8 @@ struct ClientState {
     vdom_size: usize,
     version: u64,
     last_update: Instant,
+    // File the client previews
+    file_path: PathBuf,
 }
 
 // Rate limiter per process
@@ -100,6 +106,61 @@ pub struct WorkspaceServer {
     update_sender: tokio::sync::broadcast::Sender<BroadcastUpdate>,
     // CRDT session manager for collaborative editing
     crdt_sessions: Arc<CrdtSessionManager>,
+    // In-flight evaluation per file, cancelled when a newer version arrives
+    evaluations: Arc<Mutex<HashMap<PathBuf, CancellationToken>>>,
+}
+
+/// Cancel any in-flight evaluation of `path` and register one for the newer version
+fn begin_evaluation(
+    evaluations: &Mutex<HashMap<PathBuf, CancellationToken>>,
+    path: &Path,
+) -> CancellationToken {
+    let token = CancellationToken::new();
+    if let Some(stale) = evaluations
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), token.clone())
+    {
+        stale.cancel();
+    }
+    token
+}
+
+/// Drop the evaluation entry of a file that was closed or deleted. An
+/// in-flight evaluation keeps running (other clients may still want it) -
+/// the returned token lets the caller cancel it.
+fn forget_evaluation(
+    evaluations: &Mutex<HashMap<PathBuf, CancellationToken>>,
+    path: &Path,
+) -> Option<CancellationToken> {
+    evaluations.lock().unwrap().remove(path)
+}
+
+/// Forget the evaluation of a closed client's file unless another client
+/// still previews it
+fn close_client_file(
+    client_states: &HashMap<String, ClientState>,
+    evaluations: &Mutex<HashMap<PathBuf, CancellationToken>>,
+    closed: &ClientState,
+) {
+    if !client_states
+        .values()
+        .any(|client| client.file_path == closed.file_path)
+    {
+        forget_evaluation(evaluations, &closed.file_path);
+    }
+}
+
+/// `cssTransforms` from the project's `paperclip.config.json`, so the preview
+/// matches compiled CSS
+fn load_css_transforms(root_dir: &Path) -> CssTransforms {
+    match Config::load(&root_dir.to_string_lossy()) {
+        Ok(config) => config.css_transforms(),
+        Err(error) => {
+            tracing::warn!(%error, "Ignoring cssTransforms in paperclip.config.json");
+            CssTransforms::default()
+        }
+    }
 }
 
 impl WorkspaceServer {
@@ -108,19 +169,24 @@ impl WorkspaceServer {
             .canonicalize()
             .unwrap_or_else(|_| root_dir.clone());
 
+        let css_transforms = load_css_transforms(&root_dir);
+
         // Create broadcast channel for SSE subscribers (capacity 100 messages)
         let (update_sender, _) = tokio::sync::broadcast::channel(100);
 
         let server = Self {
             root_dir,
             root_dir_canonical,
-            state: Arc::new(Mutex::new(WorkspaceState::new())),
+            state: Arc::new(Mutex::new(
+                WorkspaceState::new().with_css_transforms(css_transforms),
+            )),
             client_states: Arc::new(Mutex::new(HashMap::new())),
             client_heartbeats: Arc::new(Mutex::new(HashMap::new())),
             total_vdom_bytes: Arc::new(AtomicUsize::new(0)),
             rate_limiter: Arc::new(Mutex::new(ProcessRateLimiter::new(RATE_LIMIT_PER_PROCESS))),
             update_sender,
             crdt_sessions: Arc::new(CrdtSessionManager::new()),
+            evaluations: Arc::new(Mutex::new(HashMap::new())),
         };
 
         // Start background cleanup task
@@ -165,6 +231,7 @@ impl WorkspaceServer {
         let states = self.client_states.clone();
         let state = self.state.clone();
         let total_bytes = self.total_vdom_bytes.clone();
+        let evaluations = self.evaluations.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(60));
@@ -185,6 +252,7 @@ impl WorkspaceServer {
                     if is_stale {
                         if let Some(client_state) = client_states.remove(client_id) {
                             total_bytes.fetch_sub(client_state.vdom_size, Ordering::Relaxed);
+                            close_client_file(&client_states, &evaluations, &client_state);
                             tracing::warn!("Removed stale client: {}", client_id);
                         }
                         // Note: workspace state cleanup happens automatically
@@ -206,19 +274,19 @@ impl WorkspaceServer {
             .replace('\u{2215}', "/")  // Division slash
             .replace('\u{2044}', "/"); // Fraction slash
 
-        let path = PathBuf::from(&normalized);
+        let path = self.root_dir.join(&normalized);
 
         // 2. Canonicalize (follows symlinks)
         let canonical = path
             .canonicalize()
             .map_err(|e| Status::invalid_argument(format!("Path error: {}", e)))?;
 
-        // 3. Check within workspace
-        if !canonical.starts_with(&self.root_dir_canonical) {
-            return Err(Status::permission_denied("Path escapes workspace"));
-        }
+        // 3. Check within workspace, re-rooting so state keys match `root_dir.join`
+        let relative = canonical
+            .strip_prefix(&self.root_dir_canonical)
+            .map_err(|_| Status::permission_denied("Path escapes workspace"))?;
 
-        Ok(canonical)
+        Ok(self.root_dir.join(relative))
     }
 
     fn ensure_capacity(&self, new_vdom_size: usize) -> Result<(), Status> {
@@ -432,6 +500,7 @@ impl WorkspaceService for WorkspaceServer {
     ) -> Result<Response<Self::WatchFilesStream>, Status> {
         let req = request.into_inner();
         let watch_path = self.root_dir.join(&req.directory);
+        let evaluations = self.evaluations.clone();
 
         let (tx, rx) = mpsc::channel(100);
 
@@ -454,6 +523,13 @@ impl WorkspaceService for WorkspaceServer {
                             _ => continue,
                         };
 
+                        // Nothing left to evaluate for a deleted file
+                        if event_type == 2 {
+                            if let Some(stale) = forget_evaluation(&evaluations, &path) {
+                                stale.cancel();
+                            }
+                        }
+
                         let file_event = FileEvent {
                             event_type,
                             file_path: path.to_string_lossy().to_string(),
@@ -535,6 +611,13 @@ impl WorkspaceService for WorkspaceServer {
             }
         };
 
+        // Same document path as the state, so span IDs match the previewed ones
+        let document_path = self
+            .state
+            .lock()
+            .unwrap()
+            .document_path(&full_path, &self.root_dir);
+
         // Get or create CRDT session for this file
         let session = match std::fs::read_to_string(&full_path) {
             Ok(content) => self.crdt_sessions.get_or_create_session_with_content(&file_path_str, &content),
@@ -549,7 +632,7 @@ impl WorkspaceService for WorkspaceServer {
             let crdt_doc = &mut session_guard.document;
 
             // Build mutation handler with file path
-            let mut handler = MutationHandler::new_with_path(&full_path.to_string_lossy());
+            let mut handler = MutationHandler::new_with_path(&document_path);
             let source = crdt_doc.get_text();
 
             if let Err(e) = handler.rebuild_index(crdt_doc.doc(), &source) {
@@ -580,11 +663,45 @@ impl WorkspaceService for WorkspaceServer {
                         tracing::info!("[gRPC] File written to: {:?}", full_path);
 
                         // Update WorkspaceState and get patches for broadcast
-                        let patches = {
+                        let cancellation = begin_evaluation(&self.evaluations, &full_path);
+                        let (patches, dependent_patches) = {
                             let mut workspace_state = self.state.lock().unwrap();
-                            workspace_state.update_file(full_path.clone(), new_source, &self.root_dir)
+                            let patches = workspace_state.update_file_cancellable(
+                                full_path.clone(),
+                                new_source,
+                                &self.root_dir,
+                                cancellation,
+                            );
+                            // Files importing this one re-render the edited components
+                            let dependent_patches = workspace_state
+                                .refresh_dependents(&full_path)
+                                .unwrap_or_else(|e| {
+                                    tracing::warn!("[gRPC] Failed to refresh dependents: {:?}", e);
+                                    Vec::new()
+                                })
+                                .into_iter()
+                                .map(|(path, patches)| {
+                                    let version = workspace_state.get_file(&path).map(|s| s.version).unwrap_or(0);
+                                    (path, patches, version)
+                                })
+                                .collect::<Vec<_>>();
+                            (patches, dependent_patches)
                         };
 
+                        for (path, patches, dependent_version) in dependent_patches {
+                            let patches_json: Vec<serde_json::Value> = patches
+                                .iter()
+                                .filter_map(|p| serde_json::to_value(p).ok())
+                                .collect();
+                            let relative = path.strip_prefix(&self.root_dir).unwrap_or(&path);
+                            let _ = self.update_sender.send(crate::BroadcastUpdate {
+                                file_path: relative.to_string_lossy().to_string(),
+                                patches_json: serde_json::to_string(&patches_json).unwrap_or_default(),
+                                error: None,
+                                version: dependent_version,
+                            });
+                        }
+
                         if let Ok(patches) = patches {
                             // Broadcast update to SSE subscribers
                             let patches_json: Vec<serde_json::Value> = patches
@@ -651,7 +768,7 @@ impl WorkspaceService for WorkspaceServer {
         request: Request<proto::OutlineRequest>,
     ) -> Result<Response<proto::OutlineResponse>, Status> {
         let req = request.into_inner();
-        let file_path = self.root_dir.join(&req.file_path);
+        let file_path = self.validate_path(&req.file_path)?;
 
         // Read and parse file
         let source = std::fs::read_to_string(&file_path).map_err(to_status)?;
@@ -698,6 +815,33 @@ impl WorkspaceService for WorkspaceServer {
         Ok(Response::new(proto::OutlineResponse { nodes, version }))
     }
 
+    async fn compute_styles(
+        &self,
+        request: Request<proto::ComputeStylesRequest>,
+    ) -> Result<Response<proto::ComputeStylesResponse>, Status> {
+        let req = request.into_inner();
+        let file_path = self.validate_path(&req.file_path)?;
+
+        let mut state = self.state.lock().unwrap();
+
+        // Load the file if no preview has evaluated it yet
+        if state.get_ast(&file_path).is_none() {
+            let source = std::fs::read_to_string(&file_path).map_err(to_status)?;
+            state
+                .update_file(file_path.clone(), source, &self.root_dir)
+                .map_err(to_status)?;
+        }
+
+        let properties = state
+            .compute_styles(&file_path, &req.node_id, &req.active_variants)
+            .map_err(|e| Status::not_found(e.to_string()))?
+            .iter()
+            .map(computed_property_to_proto)
+            .collect();
+
+        Ok(Response::new(proto::ComputeStylesResponse { properties }))
+    }
+
     // NEW: Production-hardened buffer streaming
     type StreamBufferStream = Pin<Box<dyn Stream<Item = Result<PreviewUpdate, Status>> + Send + 'static>>;
 
@@ -770,9 +914,16 @@ impl WorkspaceService for WorkspaceServer {
         let source = req.content;
         let file_path_for_state = self.root_dir.join(&req.file_path);
 
+        // A newer buffer for the same file cancels this evaluation
+        let cancellation = begin_evaluation(&self.evaluations, &file_path_for_state);
         let result = {
             let mut state_guard = self.state.lock().unwrap();
-            let patches = state_guard.update_file(file_path_for_state.clone(), source, &root_dir);
+            let patches = state_guard.update_file_cancellable(
+                file_path_for_state.clone(),
+                source,
+                &root_dir,
+                cancellation,
+            );
             let version = state_guard
                 .get_file(&file_path_for_state)
                 .map(|s| s.version)
@@ -782,6 +933,13 @@ impl WorkspaceService for WorkspaceServer {
 
         let (patches, version) = match result.0 {
             Ok(p) => (p, result.1),
+            Err(e) if e.is_cancelled() => {
+                // Superseded - the newer buffer's response carries the patches
+                tracing::debug!("Stale buffer evaluation cancelled: {}", req.file_path);
+                return Ok(Response::new(
+                    Box::pin(tokio_stream::empty()) as Self::StreamBufferStream
+                ));
+            }
             Err(e) => {
                 return Ok(Response::new(Box::pin(tokio_stream::once(Ok(
                     PreviewUpdate {
@@ -823,6 +981,7 @@ impl WorkspaceService for WorkspaceServer {
                     vdom_size,
                     version,
                     last_update: Instant::now(),
+                    file_path: file_path_for_state.clone(),
                 },
             );
         }
@@ -871,6 +1030,7 @@ impl WorkspaceService for WorkspaceServer {
             self.total_vdom_bytes
                 .fetch_sub(client_state.vdom_size, Ordering::Relaxed);
             heartbeats.remove(&client_id);
+            close_client_file(&client_states, &self.evaluations, &client_state);
             // Note: workspace state cleanup happens automatically
             tracing::info!("Cleaned up state for client_id: {}", client_id);
             true
@@ -926,6 +1086,7 @@ impl WorkspaceService for WorkspaceServer {
         let state = self.state.clone();
         let root_dir = self.root_dir.clone();
         let update_sender = self.update_sender.clone();
+        let evaluations = self.evaluations.clone();
 
         // Clone out_tx before moving into spawn
         let out_tx_for_handler = out_tx.clone();
@@ -1042,16 +1203,20 @@ impl WorkspaceService for WorkspaceServer {
 
                         // Process through parser/evaluator
                         let file_path_for_state = root_dir.join(&req.file_path);
+                        let cancellation = begin_evaluation(&evaluations, &file_path_for_state);
                         let process_result = {
                             let mut state_guard = state.lock().unwrap();
-                            state_guard.update_file(
+                            state_guard.update_file_cancellable(
                                 file_path_for_state.clone(),
                                 text_content.clone(),
                                 &root_dir,
+                                cancellation,
                             )
                         };
 
                         match process_result {
+                            // A newer update from another client superseded this one
+                            Err(e) if e.is_cancelled() => continue,
                             Ok(patches) => {
                                 // Mark document clean
                                 {
@@ -1073,6 +1238,19 @@ impl WorkspaceService for WorkspaceServer {
                                         },
                                         None, // Send to all including origin
                                     ).await;
+
+                                    let (cssom, reset) = cssom_patches(&patches);
+                                    if reset || !cssom.is_empty() {
+                                        session_guard.broadcast(
+                                            CrdtBroadcast::CssomPatch {
+                                                patches_json: serde_json::to_string(&cssom)
+                                                    .unwrap_or_default(),
+                                                version,
+                                                reset,
+                                            },
+                                            None,
+                                        ).await;
+                                    }
                                 }
 
                                 // Also broadcast via SSE for designer iframe
@@ -1126,6 +1304,7 @@ impl WorkspaceService for WorkspaceServer {
                     if session_guard.client_count() == 0 {
                         drop(session_guard);
                         crdt_sessions.remove_session(&file_path);
+                        forget_evaluation(&evaluations, &root_dir.join(&file_path));
                         tracing::info!("CRDT session removed: {}", file_path);
                     }
                 }
@@ -1163,6 +1342,21 @@ impl WorkspaceService for WorkspaceServer {
                             )),
                         }
                     }
+                    CrdtBroadcast::CssomPatch { patches_json, version, reset } => {
+                        let patches: Vec<paperclip_evaluator::VDocPatch> =
+                            serde_json::from_str(&patches_json).unwrap_or_default();
+
+                        proto::CrdtSyncResponse {
+                            message_type: Some(proto::crdt_sync_response::MessageType::CssomPatch(
+                                proto::CrdtCssomPatch {
+                                    patches,
+                                    version,
+                                    reset,
+                                    ..Default::default()
+                                }
+                            )),
+                        }
+                    }
                     CrdtBroadcast::ParseError { error, line, column } => {
                         proto::CrdtSyncResponse {
                             message_type: Some(proto::crdt_sync_response::MessageType::ParseError(
@@ -1187,6 +1381,40 @@ impl WorkspaceService for WorkspaceServer {
     }
 }
 
+/// Style rule patches for the CSSOM stream. An `Initialize` patch replaces
+/// the whole stylesheet, so its rules are sent as adds with `reset` set.
+pub(crate) fn cssom_patches(
+    patches: &[paperclip_evaluator::VDocPatch],
+) -> (Vec<paperclip_evaluator::VDocPatch>, bool) {
+    use paperclip_evaluator::vdom_differ::proto::patches::{v_doc_patch::PatchType, AddStyleRulePatch};
+
+    let mut cssom = vec![];
+    let mut reset = false;
+
+    for patch in patches {
+        match &patch.patch_type {
+            Some(PatchType::Initialize(init)) => {
+                reset = true;
+                cssom.clear();
+                let styles = init.vdom.iter().flat_map(|vdom| vdom.styles.iter());
+                cssom.extend(styles.map(|rule| paperclip_evaluator::VDocPatch {
+                    patch_type: Some(PatchType::AddStyleRule(AddStyleRulePatch {
+                        rule: Some(rule.clone()),
+                    })),
+                }));
+            }
+            Some(
+                PatchType::AddStyleRule(_)
+                | PatchType::UpdateStyleRule(_)
+                | PatchType::RemoveStyleRule(_),
+            ) => cssom.push(patch.clone()),
+            _ => {}
+        }
+    }
+
+    (cssom, reset)
+}
+
 /// Extract line/column from parse error message (best effort)
 fn parse_error_location(error: &str) -> (u32, u32) {
     // Try to extract line:col from error message
@@ -1202,6 +1430,28 @@ fn parse_error_location(error: &str) -> (u32, u32) {
     (0, 0)
 }
 
+/// Convert a computed style declaration to its proto form
+fn computed_property_to_proto(property: &ComputedProperty) -> proto::ComputedProperty {
+    let origin_kind = match property.origin_kind {
+        OriginKind::Inline => proto::StyleOrigin::Inline,
+        OriginKind::Extends => proto::StyleOrigin::Extends,
+        OriginKind::Token => proto::StyleOrigin::Token,
+        OriginKind::Variant => proto::StyleOrigin::Variant,
+        OriginKind::Trigger => proto::StyleOrigin::Trigger,
+        OriginKind::Override => proto::StyleOrigin::Override,
+        OriginKind::Imported => proto::StyleOrigin::Imported,
+    };
+
+    proto::ComputedProperty {
+        name: property.name.clone(),
+        value: property.value.clone(),
+        resolved_value: property.resolved_value.clone(),
+        source_span: Some(span_to_proto(&property.source_span)),
+        origin_kind: origin_kind as i32,
+        overridden_by: property.overridden_by.map(|index| index as u32),
+    }
+}
+
 /// Convert byte offset span to line/column span
 fn span_to_source_span(span: &paperclip_parser::ast::Span, source: &str) -> proto::SourceSpan {
     let (start_line, start_col) = byte_offset_to_line_col(source, span.start);
@@ -1373,6 +1623,7 @@ fn extract_element_nodes(
             slot_name,
             content,
             span,
+            ..
         } => {
             let node_id = span.id.clone();
             let mut child_ids = vec![];
@@ -1408,3 +1659,49 @@ fn extract_element_nodes(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preview(server: &WorkspaceServer, client_id: &str, file_path: &Path) {
+        server.client_states.lock().unwrap().insert(
+            client_id.to_string(),
+            ClientState {
+                vdom_size: 0,
+                version: 1,
+                last_update: Instant::now(),
+                file_path: file_path.to_path_buf(),
+            },
+        );
+    }
+
+    async fn close(server: &WorkspaceServer, client_id: &str) {
+        let response = server
+            .close_preview(Request::new(proto::ClosePreviewRequest {
+                client_id: client_id.to_string(),
+            }))
+            .await
+            .unwrap();
+        assert!(response.into_inner().success);
+    }
+
+    #[tokio::test]
+    async fn test_closing_a_document_evicts_its_evaluation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let server = WorkspaceServer::new(temp_dir.path().to_path_buf());
+        let file_path = server.root_dir.join("button.pc");
+        preview(&server, "a", &file_path);
+        preview(&server, "b", &file_path);
+
+        // Evaluation registered and still running when the clients go away
+        let token = begin_evaluation(&server.evaluations, &file_path);
+
+        close(&server, "a").await;
+        assert!(server.evaluations.lock().unwrap().contains_key(&file_path));
+
+        close(&server, "b").await;
+        assert!(!server.evaluations.lock().unwrap().contains_key(&file_path));
+        assert!(!token.is_cancelled());
+    }
+}
@@ -1,10 +1,16 @@
 use paperclip_bundle::{AssetReference, AssetType, Bundle};
 use paperclip_evaluator::{
-    diff_vdocument, CssError, CssEvaluator, EvalError,
-    Evaluator, VDocPatch, VDomCssRule, VNode, VirtualCssDocument, VirtualDomDocument,
+    compute_styles, diff_vdocument, CacheStats, CancellationToken, ComputedProperty, CssError,
+    CssEvaluator, CssTransforms, EvalCache, EvalError, Evaluator, VDocPatch, VNode,
+    VirtualCssDocument, VirtualDomDocument,
 };
-use paperclip_parser::{ast::Document, get_document_id, parse_with_path, ParseError};
-use std::collections::HashMap;
+use paperclip_evaluator::vdom_differ::{
+    convert_annotation_to_proto, convert_css_rule_to_proto, convert_root_metadata_to_proto,
+};
+use paperclip_parser::{
+    ast::Document, document_path, get_document_id, parse_with_path, DocumentIdMode, ParseError,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use tracing::{debug, error, info, instrument, warn};
 
@@ -27,6 +33,13 @@ pub enum StateError {
     IoError(#[from] std::io::Error),
 }
 
+impl StateError {
+    /// Whether evaluation was abandoned because a newer version superseded it
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, StateError::EvalError(EvalError::Cancelled))
+    }
+}
+
 // Per-file cached state
 // Note: AST is stored in Bundle.documents, assets in Bundle.assets
 #[derive(Clone)]
@@ -43,6 +56,12 @@ pub struct WorkspaceState {
     files: HashMap<PathBuf, FileState>,
     // Bundle for the workspace - rebuilt when files change
     bundle: Bundle,
+    // Memoized component VNodes - only components whose AST (or dependencies) changed are re-evaluated
+    eval_cache: EvalCache,
+    // Root-relative (default) or legacy absolute document IDs
+    document_id_mode: DocumentIdMode,
+    // Transforms applied to preview styles, matching compiled CSS
+    css_transforms: CssTransforms,
 }
 
 impl WorkspaceState {
@@ -50,16 +69,58 @@ impl WorkspaceState {
         Self {
             files: HashMap::new(),
             bundle: Bundle::new(),
+            eval_cache: EvalCache::new(),
+            document_id_mode: DocumentIdMode::default(),
+            css_transforms: CssTransforms::default(),
         }
     }
 
+    /// Opt back into IDs derived from absolute paths (they change when the repo moves)
+    pub fn with_document_id_mode(mut self, mode: DocumentIdMode) -> Self {
+        self.document_id_mode = mode;
+        self.bundle = Bundle::new().with_document_id_mode(mode);
+        self
+    }
+
+    /// Run the project's `cssTransforms` over preview styles
+    pub fn with_css_transforms(mut self, transforms: CssTransforms) -> Self {
+        self.css_transforms = transforms;
+        self
+    }
+
+    pub fn document_id_mode(&self) -> DocumentIdMode {
+        self.document_id_mode
+    }
+
+    /// Path used for IDs of `path` - what the parser and mutation handler should see
+    pub fn document_path(&self, path: &Path, project_root: &Path) -> String {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let project_root = project_root
+            .canonicalize()
+            .unwrap_or_else(|_| project_root.to_path_buf());
+        document_path(&path, &project_root, self.document_id_mode)
+    }
+
     // Update file and return VirtualDomDocument patches
-    #[instrument(skip(self, new_source, project_root), fields(path = %path.display(), source_len = new_source.len()))]
     pub fn update_file(
         &mut self,
         path: PathBuf,
         new_source: String,
         project_root: &Path,
+    ) -> Result<Vec<VDocPatch>, StateError> {
+        self.update_file_cancellable(path, new_source, project_root, CancellationToken::new())
+    }
+
+    /// Like `update_file`, but gives up with `EvalError::Cancelled` once `cancellation`
+    /// is cancelled. Cached state is left untouched, so the next update diffs
+    /// against the last completed version.
+    #[instrument(skip(self, new_source, project_root, cancellation), fields(path = %path.display(), source_len = new_source.len()))]
+    pub fn update_file_cancellable(
+        &mut self,
+        path: PathBuf,
+        new_source: String,
+        project_root: &Path,
+        cancellation: CancellationToken,
     ) -> Result<Vec<VDocPatch>, StateError> {
         // Canonicalize path to ensure consistent lookups
         // (resolves symlinks like /var -> /private/var on macOS)
@@ -68,8 +129,9 @@ impl WorkspaceState {
         let is_cached = self.files.contains_key(&path);
         info!(is_cached, "Updating file");
 
-        // Get path string for ID generation
-        let path_str = path.to_string_lossy();
+        // IDs come from the root-relative path so they survive moving the checkout
+        self.bundle.set_project_root(project_root);
+        let path_str = self.document_path(&path, project_root);
 
         // Parse new source with file path for proper ID generation
         debug!("Parsing source");
@@ -90,12 +152,15 @@ impl WorkspaceState {
 
         // Evaluate using bundle for cross-file imports
         debug!("Evaluating AST for DOM with bundle");
-        let mut evaluator = Evaluator::with_document_id(&path_str);
-        let new_vdom = evaluator.evaluate_bundle(&self.bundle, &path)?;
+        let mut evaluator = Evaluator::with_document_id(&path_str)
+            .with_cancellation(cancellation)
+            .with_css_transforms(self.css_transforms.clone());
+        let new_vdom = evaluator.evaluate_bundle_cached(&self.bundle, &path, &mut self.eval_cache)?;
 
         debug!("Evaluating AST for CSS with bundle");
-        let mut css_evaluator = CssEvaluator::with_document_id(&path_str);
-        let new_css = css_evaluator.evaluate_bundle(&self.bundle, &path)?;
+        let mut css_evaluator = CssEvaluator::with_document_id(&path_str).with_imported_globals();
+        let mut new_css = css_evaluator.evaluate_bundle(&self.bundle, &path)?;
+        self.css_transforms.apply_to_document(&mut new_css);
         info!(css_rules = new_css.rules.len(), "CSS evaluated");
 
         debug!(assets_count = "extracting", "Extracting assets");
@@ -152,6 +217,83 @@ impl WorkspaceState {
         Ok(patches)
     }
 
+    /// Re-evaluate open files that (transitively) import `path` and return their patches.
+    ///
+    /// Call after `update_file` so previews of importing files pick up the change.
+    /// Only components that instantiate something from the edited file miss the cache.
+    #[instrument(skip(self), fields(path = %path.display()))]
+    pub fn refresh_dependents(
+        &mut self,
+        path: &Path,
+    ) -> Result<Vec<(PathBuf, Vec<VDocPatch>)>, StateError> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        // Breadth-first so closer dependents are refreshed first
+        let mut queue = VecDeque::from([path.clone()]);
+        let mut visited = HashSet::from([path]);
+        let mut dependents = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for dependent in self.bundle.get_dependents(&current).unwrap_or_default() {
+                if visited.insert(dependent.clone()) {
+                    dependents.push(dependent.clone());
+                    queue.push_back(dependent.clone());
+                }
+            }
+        }
+
+        let mut results = Vec::new();
+        for dependent in dependents {
+            let Some(old_state) = self.files.get(&dependent) else {
+                continue;
+            };
+
+            let path_str = self.bundle.document_path(&dependent);
+            let mut evaluator = Evaluator::with_document_id(&path_str)
+                .with_css_transforms(self.css_transforms.clone());
+            let new_vdom =
+                evaluator.evaluate_bundle_cached(&self.bundle, &dependent, &mut self.eval_cache)?;
+            let mut css_evaluator = CssEvaluator::with_document_id(&path_str).with_imported_globals();
+            let mut new_css = css_evaluator.evaluate_bundle(&self.bundle, &dependent)?;
+            self.css_transforms.apply_to_document(&mut new_css);
+
+            let patches = diff_vdocument(&old_state.vdom, &new_vdom);
+            debug!(dependent = %dependent.display(), patch_count = patches.len(), "Refreshed dependent");
+
+            let new_state = FileState {
+                vdom: new_vdom,
+                css: new_css,
+                version: old_state.version + 1,
+                ..old_state.clone()
+            };
+            self.files.insert(dependent.clone(), new_state);
+
+            if !patches.is_empty() {
+                results.push((dependent, patches));
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Computed styles of the element `node_id` with per-property provenance
+    pub fn compute_styles(
+        &self,
+        path: &Path,
+        node_id: &str,
+        active_variants: &[String],
+    ) -> Result<Vec<ComputedProperty>, StateError> {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mut properties = compute_styles(&self.bundle, &path, node_id, active_variants)?;
+        // Same transforms as the preview, so the panel shows what renders
+        self.css_transforms.apply_to_computed(&mut properties);
+        Ok(properties)
+    }
+
+    /// Evaluation cache hit/miss counts (for diagnostics and benchmarks)
+    pub fn eval_cache_stats(&self) -> CacheStats {
+        self.eval_cache.stats()
+    }
+
     // Get current state (for queries)
     pub fn get_file(&self, path: &Path) -> Option<&FileState> {
         // Try canonical path first, fall back to original
@@ -384,6 +526,7 @@ pub fn convert_vdom_to_proto(vdom: &VirtualDomDocument) -> proto_vdom::VDocument
         styles: vdom.styles.iter().map(convert_css_rule_to_proto).collect(),
         components: vdom.components.iter().map(convert_component_metadata_to_proto).collect(),
         metadata: None,
+        roots: vdom.roots.iter().map(convert_root_metadata_to_proto).collect(),
     }
 }
 
@@ -412,6 +555,29 @@ fn convert_vnode_to_proto(node: &VNode) -> proto_vdom::VNode {
                 },
             )),
         },
+        VNode::LiveComponent {
+            src,
+            export_name,
+            target,
+            props,
+            children,
+            semantic_id,
+            source_id,
+            key,
+        } => proto_vdom::VNode {
+            node_type: Some(proto_vdom::v_node::NodeType::LiveComponent(
+                proto_vdom::LiveComponentNode {
+                    semantic_id: semantic_id.to_selector(),
+                    src: src.clone(),
+                    export_name: export_name.clone(),
+                    target: target.clone(),
+                    props_json: props.clone(),
+                    children: children.iter().map(convert_vnode_to_proto).collect(),
+                    source_id: source_id.clone(),
+                    key: key.clone(),
+                },
+            )),
+        },
         VNode::Text { content } => proto_vdom::VNode {
             node_type: Some(proto_vdom::v_node::NodeType::Text(proto_vdom::TextNode {
                 content: content.clone(),
@@ -440,16 +606,6 @@ fn convert_vnode_to_proto(node: &VNode) -> proto_vdom::VNode {
     }
 }
 
-// Convert CssRule to proto CssRule
-fn convert_css_rule_to_proto(rule: &VDomCssRule) -> proto_vdom::CssRule {
-    proto_vdom::CssRule {
-        selector: rule.selector.clone(),
-        properties: rule.properties.clone(),
-        media_query: rule.media_query.clone(),
-        metadata: None,
-    }
-}
-
 // Convert ComponentMetadata to proto ComponentMetadata
 fn convert_component_metadata_to_proto(meta: &paperclip_evaluator::vdom::ComponentMetadata) -> proto_vdom::ComponentMetadata {
     proto_vdom::ComponentMetadata {
@@ -461,44 +617,11 @@ fn convert_component_metadata_to_proto(meta: &paperclip_evaluator::vdom::Compone
             width: f.width,
             height: f.height,
         }),
-        annotations: meta.annotations.iter().map(|a| proto_vdom::AnnotationMetadata {
-            name: a.name.clone(),
-            params: a.params.iter().map(|(k, v)| {
-                (k.clone(), convert_json_to_proto_value(v))
-            }).collect(),
-        }).collect(),
+        annotations: meta.annotations.iter().map(convert_annotation_to_proto).collect(),
         source_id: meta.source_id.clone(),
     }
 }
 
-// Convert JSON Value to proto Value
-fn convert_json_to_proto_value(value: &serde_json::Value) -> proto_vdom::Value {
-    match value {
-        serde_json::Value::Null => proto_vdom::Value {
-            kind: Some(proto_vdom::value::Kind::NullValue(proto_vdom::NullValue::NullValue as i32)),
-        },
-        serde_json::Value::Bool(b) => proto_vdom::Value {
-            kind: Some(proto_vdom::value::Kind::BoolValue(*b)),
-        },
-        serde_json::Value::Number(n) => proto_vdom::Value {
-            kind: Some(proto_vdom::value::Kind::NumberValue(n.as_f64().unwrap_or(0.0))),
-        },
-        serde_json::Value::String(s) => proto_vdom::Value {
-            kind: Some(proto_vdom::value::Kind::StringValue(s.clone())),
-        },
-        serde_json::Value::Array(arr) => proto_vdom::Value {
-            kind: Some(proto_vdom::value::Kind::ListValue(proto_vdom::ListValue {
-                values: arr.iter().map(convert_json_to_proto_value).collect(),
-            })),
-        },
-        serde_json::Value::Object(obj) => proto_vdom::Value {
-            kind: Some(proto_vdom::value::Kind::ObjectValue(proto_vdom::ObjectValue {
-                fields: obj.iter().map(|(k, v)| (k.clone(), convert_json_to_proto_value(v))).collect(),
-            })),
-        },
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -642,4 +765,187 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_raw_css_edit_produces_style_patch() {
+        use paperclip_evaluator::vdom_differ::proto::patches::v_doc_patch::PatchType;
+
+        let mut state = WorkspaceState::new();
+        let path = PathBuf::from("/test/card.pc");
+        let project_root = PathBuf::from("/test");
+
+        let source = |color: &str| {
+            format!(
+                "css \"\"\"@supports (display: grid) {{ .grid {{ color: {}; }} }}\"\"\"\ncomponent Card {{\n  render div\n}}",
+                color
+            )
+        };
+
+        state
+            .update_file(path.clone(), source("red"), &project_root)
+            .unwrap();
+        let patches = state
+            .update_file(path.clone(), source("blue"), &project_root)
+            .unwrap();
+
+        let updated = patches
+            .iter()
+            .find_map(|patch| match &patch.patch_type {
+                Some(PatchType::UpdateStyleRule(update)) => Some(update.clone()),
+                _ => None,
+            })
+            .expect("Raw CSS edit should produce an UpdateStyleRule patch");
+
+        assert!(updated.selector.starts_with(".grid[class*="));
+        assert_eq!(updated.at_rules.len(), 1);
+        assert_eq!(updated.at_rules[0].name, "supports");
+        assert_eq!(updated.at_rules[0].prelude, "(display: grid)");
+        // Older clients still get the single at-rule as text
+        #[allow(deprecated)]
+        let media_query = updated.media_query.as_deref();
+        assert_eq!(media_query, Some("@supports (display: grid)"));
+        assert_eq!(updated.properties.len(), 1);
+        assert_eq!(updated.properties.get("color"), Some(&"blue".to_string()));
+        assert!(updated.removed_properties.is_empty());
+    }
+
+    #[test]
+    fn test_one_property_edit_sends_one_property_patch() {
+        use paperclip_evaluator::vdom_differ::proto::patches::v_doc_patch::PatchType;
+
+        let mut state = WorkspaceState::new();
+        let path = PathBuf::from("/test/card.pc");
+        let project_root = PathBuf::from("/test");
+
+        let source = |color: &str| {
+            format!(
+                "public component Card {{\n  render div {{\n    style {{\n      color: {};\n      padding: 8px;\n      margin: 0;\n    }}\n  }}\n}}",
+                color
+            )
+        };
+
+        state
+            .update_file(path.clone(), source("red"), &project_root)
+            .unwrap();
+        let patches = state
+            .update_file(path.clone(), source("blue"), &project_root)
+            .unwrap();
+
+        let rule_patches: Vec<_> = patches
+            .iter()
+            .filter(|patch| {
+                matches!(
+                    patch.patch_type,
+                    Some(PatchType::AddStyleRule(_))
+                        | Some(PatchType::UpdateStyleRule(_))
+                        | Some(PatchType::RemoveStyleRule(_))
+                )
+            })
+            .collect();
+
+        assert_eq!(rule_patches.len(), 1, "Expected a single style rule patch: {:?}", patches);
+        match &rule_patches[0].patch_type {
+            Some(PatchType::UpdateStyleRule(update)) => {
+                assert_eq!(update.properties.len(), 1);
+                assert_eq!(update.properties.get("color"), Some(&"blue".to_string()));
+                assert!(update.removed_properties.is_empty());
+            }
+            other => panic!("Expected UpdateStyleRule patch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_edit_only_reevaluates_dirty_component() {
+        let mut state = WorkspaceState::new();
+        let path = PathBuf::from("/test/cards.pc");
+        let project_root = PathBuf::from("/test");
+
+        let source = |label: &str| {
+            format!(
+                "component A {{\n  render div {{\n    text \"a\"\n  }}\n}}\ncomponent B {{\n  render div {{\n    text \"{}\"\n  }}\n}}\ncomponent C {{\n  render div {{\n    A()\n  }}\n}}",
+                label
+            )
+        };
+
+        state
+            .update_file(path.clone(), source("b"), &project_root)
+            .unwrap();
+        assert_eq!(state.eval_cache_stats().misses, 3);
+
+        let patches = state
+            .update_file(path.clone(), source("edited"), &project_root)
+            .unwrap();
+        let stats = state.eval_cache_stats();
+        assert_eq!(stats.misses, 4, "Only B should be re-evaluated");
+        assert_eq!(stats.hits, 2);
+        assert!(!patches.is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_update_keeps_previous_state() {
+        let mut state = WorkspaceState::new();
+        let path = PathBuf::from("/test/card.pc");
+        let project_root = PathBuf::from("/test");
+
+        state
+            .update_file(path.clone(), "component A {}".to_string(), &project_root)
+            .unwrap();
+
+        let stale = CancellationToken::new();
+        stale.cancel();
+        let result = state.update_file_cancellable(
+            path.clone(),
+            "component B {}".to_string(),
+            &project_root,
+            stale,
+        );
+
+        assert!(result.unwrap_err().is_cancelled());
+        let file_state = state.get_file(&path).unwrap();
+        assert_eq!(file_state.version, 0);
+        assert_eq!(file_state.source, "component A {}");
+    }
+
+    fn evaluate_checkout(root: &Path, mode: DocumentIdMode) -> (String, String, String) {
+        let source = r#"
+public component Card {
+    render div {
+        style {
+            padding: 16px
+        }
+        text "Hello"
+    }
+}
+"#;
+        let path = root.join("src/card.pc");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, source).unwrap();
+
+        let mut state = WorkspaceState::new().with_document_id_mode(mode);
+        state.update_file(path.clone(), source.to_string(), root).unwrap();
+
+        let file_state = state.get_file(&path.canonicalize().unwrap()).unwrap();
+        let options = paperclip_evaluator::SnapshotOptions { semantic_ids: true };
+        (
+            file_state.document_id.clone(),
+            file_state.vdom.to_snapshot(&options),
+            file_state.css.to_css(),
+        )
+    }
+
+    #[test]
+    fn test_identical_output_across_checkouts() {
+        let laptop = tempfile::tempdir().unwrap();
+        let ci = tempfile::tempdir().unwrap();
+
+        let a = evaluate_checkout(laptop.path(), DocumentIdMode::Relative);
+        let b = evaluate_checkout(ci.path(), DocumentIdMode::Relative);
+        assert_eq!(a, b);
+
+        // Legacy absolute IDs still differ per checkout
+        let a = evaluate_checkout(laptop.path(), DocumentIdMode::Absolute);
+        let b = evaluate_checkout(ci.path(), DocumentIdMode::Absolute);
+        assert_ne!(a.0, b.0);
+        assert_ne!(a.2, b.2);
+    }
 }
@@ -21,6 +21,30 @@ mod workspace_comprehensive_tests {
         fs::remove_dir_all(&temp_dir).ok();
     }
 
+    #[tokio::test]
+    async fn test_compute_styles_rejects_path_traversal() {
+        use crate::proto::workspace_service_server::WorkspaceService;
+
+        let root = std::env::temp_dir().join("paperclip_compute_styles_traversal");
+        let workspace = root.join("workspace");
+        fs::create_dir_all(&workspace).expect("Failed to create temp dir");
+        fs::write(root.join("outside.pc"), "component Outside { render div {} }")
+            .expect("Failed to write file");
+
+        let server = WorkspaceServer::new(workspace.clone());
+        let request = tonic::Request::new(crate::proto::ComputeStylesRequest {
+            file_path: "../outside.pc".to_string(),
+            node_id: "node".to_string(),
+            active_variants: vec![],
+        });
+
+        let status = server.compute_styles(request).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        // Cleanup
+        fs::remove_dir_all(&root).ok();
+    }
+
     #[test]
     fn test_file_watcher_creation() {
         let temp_dir = std::env::temp_dir().join("paperclip_watcher_test_creation");
@@ -11,10 +11,15 @@
 
 use crate::crdt::CrdtDocument;
 use crate::state::WorkspaceState;
-use paperclip_evaluator::{VNode, VDocPatch, diff_vdocument};
+use paperclip_evaluator::{FrameMetadata, VNode, VirtualDomDocument};
 use paperclip_evaluator::vdom_differ::proto::patches::v_doc_patch::PatchType;
 use std::path::PathBuf;
 
+/// Frame of the first root node
+fn first_frame(vdom: &VirtualDomDocument) -> Option<&FrameMetadata> {
+    vdom.root_metadata(0).and_then(|root| root.frame.as_ref())
+}
+
 /// Test harness that simulates the full VSCode -> Server -> Designer pipeline
 struct TypingSimulator {
     /// CRDT document (simulates VSCode editor state)
@@ -145,15 +150,7 @@ impl TypingSimulator {
                 let file_state = self.state.get_file(&self.file_path)
                     .expect("File should be cached after successful update");
 
-                let has_frame = file_state.vdom.nodes.first()
-                    .map(|n| {
-                        if let VNode::Element { attributes, .. } = n {
-                            attributes.contains_key("data-frame-x")
-                        } else {
-                            false
-                        }
-                    })
-                    .unwrap_or(false);
+                let has_frame = first_frame(&file_state.vdom).is_some();
 
                 PatchResult {
                     parse_ok: true,
@@ -168,16 +165,7 @@ impl TypingSimulator {
                 // Get last valid state if available
                 let (node_count, has_frame) = self.state.get_file(&self.file_path)
                     .map(|f| {
-                        let has_frame = f.vdom.nodes.first()
-                            .map(|n| {
-                                if let VNode::Element { attributes, .. } = n {
-                                    attributes.contains_key("data-frame-x")
-                                } else {
-                                    false
-                                }
-                            })
-                            .unwrap_or(false);
-                        (f.vdom.nodes.len(), has_frame)
+                        (f.vdom.nodes.len(), first_frame(&f.vdom).is_some())
                     })
                     .unwrap_or((0, false));
 
@@ -215,33 +203,14 @@ impl TypingSimulator {
 
     /// Check if first node has frame
     fn has_frame(&self) -> bool {
-        self.state.get_file(&self.file_path)
-            .and_then(|f| f.vdom.nodes.first())
-            .map(|n| {
-                if let VNode::Element { attributes, .. } = n {
-                    attributes.contains_key("data-frame-x")
-                } else {
-                    false
-                }
-            })
-            .unwrap_or(false)
+        self.get_frame().is_some()
     }
 
-    /// Get frame attributes from first node
+    /// Get the frame of the first root
     fn get_frame(&self) -> Option<(f64, f64, Option<f64>, Option<f64>)> {
         self.state.get_file(&self.file_path)
-            .and_then(|f| f.vdom.nodes.first())
-            .and_then(|n| {
-                if let VNode::Element { attributes, .. } = n {
-                    let x = attributes.get("data-frame-x")?.parse().ok()?;
-                    let y = attributes.get("data-frame-y")?.parse().ok()?;
-                    let w = attributes.get("data-frame-width").and_then(|v| v.parse().ok());
-                    let h = attributes.get("data-frame-height").and_then(|v| v.parse().ok());
-                    Some((x, y, w, h))
-                } else {
-                    None
-                }
-            })
+            .and_then(|f| first_frame(&f.vdom))
+            .map(|frame| (frame.x, frame.y, frame.width, frame.height))
     }
 
     /// Count how many parse errors occurred
@@ -733,14 +702,9 @@ div {
         // Both should have frames
         let file_state = sim.state.get_file(&sim.file_path).unwrap();
         for (i, node) in file_state.vdom.nodes.iter().enumerate() {
-            if let VNode::Element { attributes, .. } = node {
-                assert!(attributes.contains_key("data-frame-x"),
-                    "Node {} should have data-frame-x", i);
-                assert!(attributes.contains_key("data-frame-y"),
-                    "Node {} should have data-frame-y", i);
-            } else {
-                panic!("Node {} should be Element", i);
-            }
+            assert!(matches!(node, VNode::Element { .. }), "Node {} should be Element", i);
+            assert!(file_state.vdom.roots[i].frame.is_some(),
+                "Node {} should have a frame", i);
         }
     }
 
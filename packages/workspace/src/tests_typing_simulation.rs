@@ -34,16 +34,13 @@ fn get_node_count(state: &WorkspaceState, file_path: &Path) -> Option<usize> {
     state.get_file(file_path).map(|f| f.vdom.nodes.len())
 }
 
-/// Helper to check if frame attributes exist on first node
+/// Helper to check if the first root has a frame
 fn has_frame_on_first_node(state: &WorkspaceState, file_path: &Path) -> bool {
-    if let Some(file_state) = state.get_file(file_path) {
-        if let Some(node) = file_state.vdom.nodes.first() {
-            if let paperclip_evaluator::VNode::Element { attributes, .. } = node {
-                return attributes.contains_key("data-frame-x");
-            }
-        }
-    }
-    false
+    state
+        .get_file(file_path)
+        .and_then(|file_state| file_state.vdom.root_metadata(0))
+        .map(|root| root.frame.is_some())
+        .unwrap_or(false)
 }
 
 #[cfg(test)]
@@ -475,12 +472,9 @@ div {
         assert_eq!(file_state.vdom.nodes.len(), 2,
             "Should have 2 nodes (component + render)");
 
-        // Both should have frame attributes
-        for (i, node) in file_state.vdom.nodes.iter().enumerate() {
-            if let paperclip_evaluator::VNode::Element { attributes, .. } = node {
-                assert!(attributes.contains_key("data-frame-x"),
-                    "Node {} should have data-frame-x", i);
-            }
+        // Both should have frames
+        for (i, root) in file_state.vdom.roots.iter().enumerate() {
+            assert!(root.frame.is_some(), "Node {} should have a frame", i);
         }
     }
 
@@ -543,11 +537,9 @@ div {
         let file_state = state.get_file(&file_path).expect("File should be cached");
         assert_eq!(file_state.vdom.nodes.len(), 2);
 
-        for (i, node) in file_state.vdom.nodes.iter().enumerate() {
-            if let paperclip_evaluator::VNode::Element { attributes, .. } = node {
-                assert!(attributes.contains_key("data-frame-x"),
-                    "Node {} should still have frame after edits", i);
-            }
+        for (i, root) in file_state.vdom.roots.iter().enumerate() {
+            assert!(root.frame.is_some(),
+                "Node {} should still have frame after edits", i);
         }
     }
 
@@ -210,3 +210,128 @@ public component App {
     // Should succeed - bundle dependency building may warn but evaluation continues
     assert!(result.is_ok(), "Should handle missing imports gracefully");
 }
+
+#[test]
+fn test_editing_import_refreshes_dependents() {
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path();
+
+    let button_path = project_root.join("button.pc");
+    let button_source = |label: &str| {
+        format!(
+            "public component Button {{\n  render button {{\n    text \"{}\"\n  }}\n}}",
+            label
+        )
+    };
+    fs::write(&button_path, button_source("Save")).unwrap();
+
+    let app_path = project_root.join("app.pc");
+    let app_source = r#"
+import "./button.pc" as ui
+
+component Toolbar {
+    render div {
+        Button()
+    }
+}
+
+component Footer {
+    render div {
+        text "Footer"
+    }
+}
+"#;
+    fs::write(&app_path, app_source).unwrap();
+
+    let mut workspace = WorkspaceState::new();
+    workspace
+        .update_file(button_path.clone(), button_source("Save"), project_root)
+        .unwrap();
+    workspace
+        .update_file(app_path.clone(), app_source.to_string(), project_root)
+        .unwrap();
+    let app_version = workspace.get_file(&app_path).unwrap().version;
+
+    fs::write(&button_path, button_source("Submit")).unwrap();
+    workspace
+        .update_file(button_path.clone(), button_source("Submit"), project_root)
+        .unwrap();
+    let misses_before = workspace.eval_cache_stats().misses;
+
+    let refreshed = workspace.refresh_dependents(&button_path).unwrap();
+
+    assert_eq!(refreshed.len(), 1, "app.pc should be refreshed");
+    assert_eq!(refreshed[0].0, app_path.canonicalize().unwrap());
+    assert!(!refreshed[0].1.is_empty(), "Label change should produce patches");
+    assert_eq!(
+        workspace.eval_cache_stats().misses - misses_before,
+        1,
+        "Only Toolbar renders the edited Button"
+    );
+    assert_eq!(
+        workspace.get_file(&app_path).unwrap().version,
+        app_version + 1
+    );
+}
+
+#[test]
+fn test_compute_styles_reports_imported_mixins() {
+    use paperclip_evaluator::OriginKind;
+
+    let temp_dir = TempDir::new().unwrap();
+    let project_root = temp_dir.path();
+
+    let theme_path = project_root.join("theme.pc");
+    let theme_source = r#"
+public token primaryColor #3366FF
+
+public style fontBase {
+    font-family: Inter, sans-serif;
+    color: var(primaryColor);
+}
+"#;
+    fs::write(&theme_path, theme_source).unwrap();
+
+    let main_path = project_root.join("main.pc");
+    let main_source = r#"
+import "./theme.pc" as theme
+
+public component Label {
+    render span {
+        style extends theme.fontBase {
+            color: red;
+        }
+    }
+}
+"#;
+    fs::write(&main_path, main_source).unwrap();
+
+    let mut workspace = WorkspaceState::new();
+    workspace
+        .update_file(theme_path.clone(), theme_source.to_string(), project_root)
+        .unwrap();
+    workspace
+        .update_file(main_path.clone(), main_source.to_string(), project_root)
+        .unwrap();
+
+    let node_id = workspace.get_ast(&main_path).unwrap().components[0]
+        .body
+        .as_ref()
+        .unwrap()
+        .span()
+        .id
+        .clone();
+    let computed = workspace.compute_styles(&main_path, &node_id, &[]).unwrap();
+
+    let color: Vec<_> = computed.iter().filter(|p| p.name == "color").collect();
+    assert_eq!(color.len(), 2);
+    assert_eq!(color[0].value, "red");
+    assert_eq!(color[0].origin_kind, OriginKind::Inline);
+    assert_eq!(color[1].origin_kind, OriginKind::Imported);
+    assert_eq!(color[1].resolved_value, "#3366FF");
+    assert!(color[1].overridden_by.is_some());
+
+    let font = computed.iter().find(|p| p.name == "font-family").unwrap();
+    assert_eq!(font.origin_kind, OriginKind::Imported);
+    assert_eq!(font.overridden_by, None);
+}